@@ -1,4 +1,4 @@
-use app::{app, AppArgs, InitialRngSeed};
+use app::{app, AppArgs, InitialRngSeed, NoLeaderboard};
 use chargrid_ansi_terminal::{col_encode, Context};
 use native::NativeCommon;
 use rand::Rng;
@@ -50,6 +50,8 @@ fn main() {
                 initial_rng_seed,
                 omniscient,
                 new_game,
+                stress,
+                wizard,
             },
         col_encode_choice,
     } = Args::parser().with_help_default().parse_env_or_exit();
@@ -70,6 +72,9 @@ fn main() {
         initial_rng_seed: InitialRngSeed::U64(initial_rng_seed),
         omniscient,
         new_game,
+        stress,
+        wizard,
+        leaderboard: Box::new(NoLeaderboard),
     });
     use ColEncodeChoice as C;
     match col_encode_choice {
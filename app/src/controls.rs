@@ -1,18 +1,137 @@
-use chargrid::input::{Input, KeyboardInput};
+use crate::locale::Locale;
+use chargrid::input::{GamepadButton, Input, KeyboardInput};
 use direction::CardinalDirection;
 use maplit::btreemap;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AppInput {
     Direction(CardinalDirection),
     Wait,
+    Search,
+    DestroyCorpse,
+    Drag,
+    Brace,
+    ThrowGrenade,
+    FireWeapon,
+    Interact,
+    /// Keeps walking towards unexplored areas until an npc comes into view
+    AutoExplore,
+    PickUp,
+    /// Lists the items the player is carrying, choosing one to drop
+    Inventory,
+    /// Chooses an item to throw, then aims it
+    ThrowItem,
+    /// Swaps between the held and holstered weapons
+    SwapWeapons,
+    MessageHistory,
+    /// Takes back the most recent turn, if undo is enabled
+    Undo,
+    ToggleControlsOverlay,
+    /// Writes the recent gameplay events to the log for debugging
+    DumpEventLog,
+    /// Shows what npcs are planning. Only available in wizard mode.
+    ToggleAiDebug,
+}
+
+/// Key of the name of a direction in the locale
+pub fn direction_key(direction: CardinalDirection) -> &'static str {
+    match direction {
+        CardinalDirection::North => "direction.north",
+        CardinalDirection::East => "direction.east",
+        CardinalDirection::South => "direction.south",
+        CardinalDirection::West => "direction.west",
+    }
+}
+
+impl AppInput {
+    /// Every input which can be bound to a key or button
+    pub fn all() -> Vec<Self> {
+        let mut all = CardinalDirection::all()
+            .map(Self::Direction)
+            .collect::<Vec<_>>();
+        all.extend([
+            Self::Wait,
+            Self::Search,
+            Self::DestroyCorpse,
+            Self::Drag,
+            Self::Brace,
+            Self::ThrowGrenade,
+            Self::FireWeapon,
+            Self::Interact,
+            Self::AutoExplore,
+            Self::PickUp,
+            Self::Inventory,
+            Self::ThrowItem,
+            Self::SwapWeapons,
+            Self::MessageHistory,
+            Self::Undo,
+            Self::ToggleControlsOverlay,
+            Self::DumpEventLog,
+            Self::ToggleAiDebug,
+        ]);
+        all
+    }
+
+    pub fn description(&self, locale: &Locale) -> String {
+        let key = match self {
+            Self::Direction(direction) => {
+                return locale.format("input.move", &[&locale.get(direction_key(*direction))]);
+            }
+            Self::Wait => "input.wait",
+            Self::Search => "input.search",
+            Self::DestroyCorpse => "input.destroy_corpse",
+            Self::Drag => "input.drag",
+            Self::Brace => "input.brace",
+            Self::ThrowGrenade => "input.throw_grenade",
+            Self::FireWeapon => "input.fire_weapon",
+            Self::Interact => "input.interact",
+            Self::AutoExplore => "input.autoexplore",
+            Self::PickUp => "input.pick_up",
+            Self::Inventory => "input.inventory",
+            Self::ThrowItem => "input.throw_item",
+            Self::SwapWeapons => "input.swap_weapons",
+            Self::MessageHistory => "input.message_history",
+            Self::Undo => "input.undo",
+            Self::ToggleControlsOverlay => "input.toggle_controls",
+            Self::DumpEventLog => "input.dump_event_log",
+            Self::ToggleAiDebug => "input.toggle_ai_debug",
+        };
+        locale.get(key).to_string()
+    }
+}
+
+fn key_name(keyboard_input: &KeyboardInput) -> String {
+    match keyboard_input {
+        KeyboardInput::Char(' ') => "Space".to_string(),
+        KeyboardInput::Char(c) => c.to_string(),
+        other => format!("{:?}", other),
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Controls {
     keys: BTreeMap<KeyboardInput, AppInput>,
+    /// Controls files from before gamepad support was added won't have this field
+    #[serde(default = "default_gamepad")]
+    gamepad: BTreeMap<GamepadButton, AppInput>,
+}
+
+fn default_gamepad() -> BTreeMap<GamepadButton, AppInput> {
+    btreemap![
+        GamepadButton::DPadLeft => AppInput::Direction(CardinalDirection::West),
+        GamepadButton::DPadRight => AppInput::Direction(CardinalDirection::East),
+        GamepadButton::DPadUp => AppInput::Direction(CardinalDirection::North),
+        GamepadButton::DPadDown => AppInput::Direction(CardinalDirection::South),
+        GamepadButton::South => AppInput::Wait,
+        GamepadButton::West => AppInput::Search,
+        GamepadButton::North => AppInput::Interact,
+        GamepadButton::East => AppInput::Drag,
+        GamepadButton::LeftBumper => AppInput::ThrowGrenade,
+        GamepadButton::RightBumper => AppInput::Brace,
+        GamepadButton::Select => AppInput::MessageHistory,
+    ]
 }
 
 impl Default for Controls {
@@ -23,8 +142,28 @@ impl Default for Controls {
             KeyboardInput::Up => AppInput::Direction(CardinalDirection::North),
             KeyboardInput::Down => AppInput::Direction(CardinalDirection::South),
             KeyboardInput::Char(' ') => AppInput::Wait,
+            KeyboardInput::Char('s') => AppInput::Search,
+            KeyboardInput::Char('x') => AppInput::DestroyCorpse,
+            KeyboardInput::Char('g') => AppInput::Drag,
+            KeyboardInput::Char('b') => AppInput::Brace,
+            KeyboardInput::Char('t') => AppInput::ThrowGrenade,
+            KeyboardInput::Char('f') => AppInput::FireWeapon,
+            KeyboardInput::Char('e') => AppInput::Interact,
+            KeyboardInput::Char('o') => AppInput::AutoExplore,
+            KeyboardInput::Char('p') => AppInput::PickUp,
+            KeyboardInput::Char('i') => AppInput::Inventory,
+            KeyboardInput::Char('r') => AppInput::ThrowItem,
+            KeyboardInput::Char('w') => AppInput::SwapWeapons,
+            KeyboardInput::Char('m') => AppInput::MessageHistory,
+            KeyboardInput::Char('u') => AppInput::Undo,
+            KeyboardInput::Char('?') => AppInput::ToggleControlsOverlay,
+            KeyboardInput::Function(12) => AppInput::DumpEventLog,
+            KeyboardInput::Function(11) => AppInput::ToggleAiDebug,
         ];
-        Self { keys }
+        Self {
+            keys,
+            gamepad: default_gamepad(),
+        }
     }
 }
 impl Controls {
@@ -32,6 +171,65 @@ impl Controls {
         match input {
             Input::Keyboard(keyboard_input) => self.keys.get(&keyboard_input).cloned(),
             Input::Mouse(_) => None,
+            Input::Gamepad(gamepad_input) => self.gamepad.get(&gamepad_input.button).cloned(),
         }
     }
+
+    /// Binds a key or button to an app input, replacing any other bindings to the same app input
+    /// on the same device. Returns `false` if the input can't be bound.
+    pub fn bind(&mut self, input: Input, app_input: AppInput) -> bool {
+        match input {
+            Input::Keyboard(keyboard_input) => {
+                self.keys.retain(|_, bound| *bound != app_input);
+                self.keys.insert(keyboard_input, app_input);
+                true
+            }
+            Input::Gamepad(gamepad_input) => {
+                self.gamepad.retain(|_, bound| *bound != app_input);
+                self.gamepad.insert(gamepad_input.button, app_input);
+                true
+            }
+            Input::Mouse(_) => false,
+        }
+    }
+
+    /// Names of the key and gamepad button bound to an app input
+    pub fn binding_names(&self, app_input: &AppInput) -> (Option<String>, Option<String>) {
+        let key = self
+            .keys
+            .iter()
+            .find(|(_, bound)| *bound == app_input)
+            .map(|(keyboard_input, _)| key_name(keyboard_input));
+        let button = self
+            .gamepad
+            .iter()
+            .find(|(_, bound)| *bound == app_input)
+            .map(|(button, _)| format!("{:?}", button));
+        (key, button)
+    }
+
+    /// Name of the key bound to an app input, for telling the player what to press
+    pub fn bound_key_name(&self, app_input: AppInput) -> String {
+        self.binding_names(&app_input)
+            .0
+            .unwrap_or_else(|| "?".to_string())
+    }
+
+    /// Names of the keys bound to walking in each direction
+    pub fn movement_key_names(&self) -> String {
+        CardinalDirection::all()
+            .map(|direction| self.bound_key_name(AppInput::Direction(direction)))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Pairs of key names and descriptions of what they do, for each key binding
+    pub fn descriptions(&self, locale: &Locale) -> Vec<(String, String)> {
+        self.keys
+            .iter()
+            .map(|(keyboard_input, app_input)| {
+                (key_name(keyboard_input), app_input.description(locale))
+            })
+            .collect()
+    }
 }
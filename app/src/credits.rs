@@ -0,0 +1,90 @@
+use crate::game_loop::State;
+use chargrid::{input::Input, prelude::*, text::StyledString};
+use serde::Deserialize;
+use std::time::Duration;
+
+const CREDITS: &str = include_str!("credits.ron");
+/// Time taken to scroll by a single row
+const ROW_DURATION: Duration = Duration::from_millis(400);
+
+#[derive(Deserialize)]
+struct Section {
+    title: String,
+    names: Vec<String>,
+}
+
+/// Credits which scroll up from the bottom of the screen, completing once they have scrolled off
+/// the top or when a key is pressed
+pub struct Credits {
+    lines: Vec<StyledString>,
+    elapsed: Duration,
+}
+
+impl Default for Credits {
+    fn default() -> Self {
+        let sections: Vec<Section> = ron::from_str(CREDITS).expect("failed to parse credits");
+        let mut lines = Vec::new();
+        for section in sections {
+            lines.push(StyledString {
+                string: section.title,
+                style: Style::plain_text().with_bold(true),
+            });
+            lines.extend(section.names.into_iter().map(|name| StyledString {
+                string: name,
+                style: Style::plain_text().with_foreground(Rgba32::new_grey(187)),
+            }));
+            lines.push(StyledString {
+                string: String::new(),
+                style: Style::plain_text(),
+            });
+        }
+        Self {
+            lines,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+impl Credits {
+    fn rows_scrolled(&self) -> i32 {
+        (self.elapsed.as_millis() / ROW_DURATION.as_millis()) as i32
+    }
+}
+
+impl Component for Credits {
+    type Output = Option<()>;
+    type State = State;
+
+    fn render(&self, _state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let size = ctx.bounding_box.size();
+        let top = size.height() as i32 - self.rows_scrolled();
+        for (i, line) in self.lines.iter().enumerate() {
+            let y = top + i as i32;
+            if y < 0 || y >= size.height() as i32 {
+                continue;
+            }
+            let x = (size.width() as i32 - line.string.chars().count() as i32) / 2;
+            line.render(&(), ctx.add_xy(x, y), fb);
+        }
+    }
+
+    fn update(&mut self, _state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
+        match event {
+            Event::Tick(since_previous) => {
+                self.elapsed += since_previous;
+                let height = ctx.bounding_box.size().height() as i32;
+                if self.rows_scrolled() > height + self.lines.len() as i32 {
+                    Some(())
+                } else {
+                    None
+                }
+            }
+            Event::Input(Input::Keyboard(_)) | Event::Input(Input::Gamepad(_)) => Some(()),
+            _ => None,
+        }
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        ctx.bounding_box.size()
+    }
+}
@@ -0,0 +1,142 @@
+use crate::game_loop::State;
+use chargrid::{
+    input::{Input, KeyboardInput, MouseButton, MouseInput},
+    prelude::*,
+    text::StyledString,
+};
+use game::{witness, Brush, LevelDesign};
+
+/// Rows above the level reserved for the key reference and status line
+const HEADER_HEIGHT: i32 = 2;
+
+fn brush_colour(ch: char) -> Rgba32 {
+    match ch {
+        '#' => Rgba32::new_grey(187),
+        '+' => Rgba32::new_rgb(187, 127, 0),
+        ':' => Rgba32::new_grey(63),
+        ',' => Rgba32::new_rgb(63, 63, 127),
+        '>' => Rgba32::new_rgb(255, 255, 0),
+        'z' => Rgba32::new_rgb(255, 63, 63),
+        '@' => Rgba32::new_rgb(63, 255, 63),
+        _ => Rgba32::new_grey(127),
+    }
+}
+
+/// Paints floors, walls, doors and spawns onto a level with the mouse. The level can be saved in
+/// the format read by the terrain module, or played immediately.
+pub struct Editor {
+    design: LevelDesign,
+    brush: Brush,
+    /// The left mouse button is held, so moving the mouse paints
+    painting: bool,
+    status: &'static str,
+}
+
+impl Editor {
+    pub fn new(design: LevelDesign) -> Self {
+        Self {
+            design,
+            brush: Brush::Wall,
+            painting: false,
+            status: "",
+        }
+    }
+
+    fn paint_at_screen_coord(&mut self, screen_coord: Coord, ctx: Ctx) {
+        let coord = screen_coord - ctx.bounding_box.top_left() - Coord::new(0, HEADER_HEIGHT);
+        self.design.paint(coord, self.brush);
+    }
+}
+
+impl Component for Editor {
+    /// The running game when the level is being tested, or `None` when leaving the editor
+    type Output = Option<Option<witness::Running>>;
+    type State = State;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let header = StyledString {
+            string: "Left click: paint  s: save  t: test  escape: exit".to_string(),
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(255)),
+        };
+        header.render(&(), ctx, fb);
+        let status = StyledString {
+            string: self.status.to_string(),
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(187)),
+        };
+        status.render(&(), ctx.add_y(1), fb);
+        let size = self.design.size();
+        for coord in size.coord_iter_row_major() {
+            if let Some(ch) = self.design.get(coord) {
+                let render_cell = RenderCell {
+                    character: Some(ch),
+                    style: Style::new().with_foreground(brush_colour(ch)),
+                };
+                fb.set_cell_relative_to_ctx(
+                    ctx,
+                    coord + Coord::new(0, HEADER_HEIGHT),
+                    0,
+                    render_cell,
+                );
+            }
+        }
+        let x = size.width() as i32 + 2;
+        for (i, &brush) in Brush::ALL.iter().enumerate() {
+            let selected = if brush == self.brush { '>' } else { ' ' };
+            let line = StyledString {
+                string: format!(
+                    "{}({}) {} {}",
+                    selected,
+                    i + 1,
+                    brush.ch(),
+                    state.locale().get(brush.name_key())
+                ),
+                style: Style::plain_text().with_foreground(brush_colour(brush.ch())),
+            };
+            line.render(&(), ctx.add_xy(x, HEADER_HEIGHT + i as i32), fb);
+        }
+    }
+
+    fn update(&mut self, state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
+        match event {
+            Event::Input(Input::Keyboard(keyboard_input)) => match keyboard_input {
+                KeyboardInput::Char('\u{1b}') => return Some(None),
+                KeyboardInput::Char('s') => {
+                    state.save_level_design(&self.design);
+                    self.status = "Saved.";
+                }
+                KeyboardInput::Char('t') => match state.test_level_design(&self.design) {
+                    Some(running) => return Some(Some(running)),
+                    None => self.status = "Place a player start (@) before testing.",
+                },
+                KeyboardInput::Char(c) => {
+                    let brush = c
+                        .to_digit(10)
+                        .and_then(|digit| Brush::ALL.get((digit as usize).checked_sub(1)?));
+                    if let Some(&brush) = brush {
+                        self.brush = brush;
+                    }
+                }
+                _ => (),
+            },
+            Event::Input(Input::Mouse(MouseInput::MousePress {
+                button: MouseButton::Left,
+                coord,
+            })) => {
+                self.painting = true;
+                self.paint_at_screen_coord(coord, ctx);
+            }
+            Event::Input(Input::Mouse(MouseInput::MouseRelease { .. })) => self.painting = false,
+            Event::Input(Input::Mouse(MouseInput::MouseMove { coord, .. })) => {
+                if self.painting {
+                    self.paint_at_screen_coord(coord, ctx);
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        ctx.bounding_box.size()
+    }
+}
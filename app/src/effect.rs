@@ -0,0 +1,89 @@
+use crate::game_instance::{Banner, GameInstance, LEVEL_BANNER_DURATION};
+use chargrid::prelude::*;
+use game::{BossPhase, Entity, ExternalEvent, LevelTheme, Message, SoundEffect};
+
+/// Number of frames the camera shakes for each unit of intensity
+const CAMERA_SHAKE_FRAMES_PER_INTENSITY: u32 = 6;
+/// Sounds further than this from the player can't be heard
+const MAX_SOUND_DISTANCE: u32 = 20;
+/// Number of frames "miss" is shown over a character who dodged an attack
+const MISS_FRAMES: u32 = 30;
+/// Number of frames the cells reached by a melee attack are highlighted for
+const SWING_FRAMES: u32 = 8;
+
+/// Implemented by parts of the app which react to events from the game. Every method does nothing
+/// by default so subscribers only need to implement the events they care about.
+pub trait Subscriber {
+    fn on_sound(&mut self, _effect: SoundEffect, _coord: Coord) {}
+    fn on_message(&mut self, _source: Option<Entity>, _message: &Message) {}
+    fn on_camera_shake(&mut self, _intensity: u32) {}
+    fn on_melee_attack(&mut self, _cells: &[Coord]) {}
+    fn on_miss(&mut self, _coord: Coord) {}
+    fn on_level_start(&mut self, _level: u32, _name: &str, _theme: Option<LevelTheme>) {}
+    fn on_boss_phase_change(&mut self, _phase: BossPhase) {}
+    fn on_boss_defeated(&mut self) {}
+}
+
+/// Passes each event to the corresponding method of the subscriber
+pub fn dispatch<S: Subscriber>(events: &[ExternalEvent], subscriber: &mut S) {
+    for event in events {
+        match event {
+            ExternalEvent::Sound { effect, coord } => subscriber.on_sound(*effect, *coord),
+            ExternalEvent::Message { source, message } => subscriber.on_message(*source, message),
+            ExternalEvent::CameraShake { intensity } => subscriber.on_camera_shake(*intensity),
+            ExternalEvent::MeleeAttack { cells } => subscriber.on_melee_attack(cells),
+            ExternalEvent::Miss { coord } => subscriber.on_miss(*coord),
+            ExternalEvent::LevelStart { level, name, theme } => {
+                subscriber.on_level_start(*level, name, *theme)
+            }
+            ExternalEvent::BossPhaseChange { phase } => subscriber.on_boss_phase_change(*phase),
+            ExternalEvent::BossDefeated => subscriber.on_boss_defeated(),
+        }
+    }
+}
+
+/// Turns events from the game into sounds and visual effects
+pub struct EffectContext<'a> {
+    pub instance: &'a mut GameInstance,
+}
+
+impl<'a> Subscriber for EffectContext<'a> {
+    fn on_sound(&mut self, effect: SoundEffect, coord: Coord) {
+        let delta = coord - self.instance.game.inner_ref().player_coord();
+        let distance = delta.x.unsigned_abs().max(delta.y.unsigned_abs());
+        if distance < MAX_SOUND_DISTANCE {
+            let volume = 1.0 - (distance as f64 / MAX_SOUND_DISTANCE as f64);
+            crate::sfx::play(effect, volume);
+        }
+    }
+
+    fn on_camera_shake(&mut self, intensity: u32) {
+        self.instance.camera_shake = self
+            .instance
+            .camera_shake
+            .max(intensity * CAMERA_SHAKE_FRAMES_PER_INTENSITY);
+    }
+
+    fn on_melee_attack(&mut self, cells: &[Coord]) {
+        self.instance
+            .swings
+            .extend(cells.iter().map(|&coord| (coord, SWING_FRAMES)));
+    }
+
+    fn on_miss(&mut self, coord: Coord) {
+        self.instance.misses.push((coord, MISS_FRAMES));
+    }
+
+    fn on_level_start(&mut self, _level: u32, name: &str, theme: Option<LevelTheme>) {
+        self.instance.banner = Some(Banner::new(name.to_string(), LEVEL_BANNER_DURATION));
+        crate::music::set_theme(theme);
+    }
+
+    fn on_boss_phase_change(&mut self, _phase: BossPhase) {
+        crate::music::play_stinger();
+    }
+
+    fn on_boss_defeated(&mut self) {
+        crate::music::play_stinger();
+    }
+}
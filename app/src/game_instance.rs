@@ -1,13 +1,115 @@
+use crate::{
+    controls::{AppInput, Controls},
+    locale::Locale,
+    palette::{rgba32, Palette},
+    tile_registry::TileRegistry,
+};
 use chargrid::{prelude::*, text};
 use game::{
     witness::{self, Game, RunningGame},
-    CellVisibility, Config, Layer, Tile, Victory,
+    Alertness, CellVisibility, Config, Decal, DecalKind, Gas, GasKind, GhostTrail, Gravity, Layer,
+    LevelDesign, Message, PowerState, StatusEffect, TimerKind, TutorialStep, Victory,
+    MAX_DECAL_INTENSITY, MAX_GAS_DENSITY, MAX_LIQUID_DEPTH,
 };
 use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long the name of a level is shown for when the player arrives on it
+pub const LEVEL_BANNER_DURATION: Duration = Duration::from_millis(2500);
+/// How long the banner takes to fade in, and to fade out again
+const BANNER_FADE_DURATION: Duration = Duration::from_millis(500);
+/// Number of rows from the top of the game view to the banner
+const BANNER_Y: i32 = 5;
+/// Hints are shown just above the message log
+const HINT_Y_FROM_BOTTOM: i32 = 9;
+/// Frames between changes in the brightness of flames
+const FLICKER_FRAMES_PER_STEP: u32 = 4;
+/// Opacity of the ghost of a previous attempt at the same seed
+const GHOST_ALPHA: u8 = 127;
+
+pub fn status_effect_key(effect: StatusEffect) -> &'static str {
+    match effect {
+        StatusEffect::Poison => "status.poisoned",
+        StatusEffect::Stun => "status.stunned",
+        StatusEffect::Burning => "status.burning",
+        StatusEffect::Slowed => "status.slowed",
+        StatusEffect::Hasted => "status.hasted",
+    }
+}
+
+/// The prompt for a tutorial step, naming the keys currently bound to the inputs it asks for
+fn tutorial_prompt(step: TutorialStep, locale: &Locale, controls: &Controls) -> String {
+    match step {
+        TutorialStep::Move => locale.format(step.prompt_key(), &[&controls.movement_key_names()]),
+        TutorialStep::ThrowGrenade => {
+            let throw = controls.bound_key_name(AppInput::ThrowGrenade);
+            locale.format(step.prompt_key(), &[&throw, &throw])
+        }
+        TutorialStep::Melee | TutorialStep::PickUpItem | TutorialStep::Stairs => {
+            locale.get(step.prompt_key()).to_string()
+        }
+    }
+}
+
+/// The text of a message from the game. Tutorial prompts in the log name the keys currently
+/// bound to the inputs they ask for, as they do on the hud.
+pub fn message_text(message: &Message, locale: &Locale, controls: &Controls) -> String {
+    match TutorialStep::ALL
+        .iter()
+        .find(|step| step.prompt_key() == message.key)
+    {
+        Some(&step) => tutorial_prompt(step, locale, controls),
+        None => locale.message(message),
+    }
+}
+
+/// Text shown over the game view for a short time, fading in and out
+pub struct Banner {
+    text: String,
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl Banner {
+    pub fn new(text: String, duration: Duration) -> Self {
+        Self {
+            text,
+            elapsed: Duration::ZERO,
+            duration,
+        }
+    }
+
+    fn tick(&mut self, since_previous: Duration) {
+        self.elapsed += since_previous;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    fn alpha(&self) -> u8 {
+        let remaining = self.duration.saturating_sub(self.elapsed);
+        let fade = self.elapsed.min(remaining).min(BANNER_FADE_DURATION);
+        (255 * fade.as_millis() / BANNER_FADE_DURATION.as_millis()) as u8
+    }
+}
 
 pub struct GameInstance {
     pub game: Game,
+    /// Number of frames for which the camera will keep shaking
+    pub camera_shake: u32,
+    /// Advances every frame to animate flames
+    pub flicker: u32,
+    /// Cells where an attack just missed, with the number of frames left to show it for
+    pub misses: Vec<(Coord, u32)>,
+    /// Cells just swept by a melee attack, with the number of frames left to highlight them for
+    pub swings: Vec<(Coord, u32)>,
+    pub banner: Option<Banner>,
+    /// A tip for the player, shown above the message log
+    pub hint: Option<Banner>,
+    /// The path taken by the player the last time they played this game's seed
+    pub ghost: Option<GhostTrail>,
 }
 
 impl GameInstance {
@@ -17,11 +119,111 @@ impl GameInstance {
         rng: &mut R,
     ) -> (Self, witness::Running) {
         let (game, running) = witness::new_game(config, victories, rng);
-        (GameInstance { game }, running)
+        (
+            GameInstance {
+                game,
+                camera_shake: 0,
+                flicker: 0,
+                misses: Vec::new(),
+                swings: Vec::new(),
+                banner: None,
+                hint: None,
+                ghost: None,
+            },
+            running,
+        )
+    }
+
+    /// Continues with the character from a game the player has just won
+    pub fn new_game_plus<R: Rng>(
+        config: &Config,
+        previous: &Game,
+        rng: &mut R,
+    ) -> (Self, witness::Running) {
+        let (game, running) = witness::new_game_plus(config, previous, rng);
+        (
+            GameInstance {
+                game,
+                camera_shake: 0,
+                flicker: 0,
+                misses: Vec::new(),
+                swings: Vec::new(),
+                banner: None,
+                hint: None,
+                ghost: None,
+            },
+            running,
+        )
+    }
+
+    pub fn new_custom_level<R: Rng>(
+        config: &Config,
+        design: &LevelDesign,
+        rng: &mut R,
+    ) -> Option<(Self, witness::Running)> {
+        let (game, running) = witness::new_custom_level(config, design, rng)?;
+        Some((
+            GameInstance {
+                game,
+                camera_shake: 0,
+                flicker: 0,
+                misses: Vec::new(),
+                swings: Vec::new(),
+                banner: None,
+                hint: None,
+                ghost: None,
+            },
+            running,
+        ))
+    }
+
+    pub fn new_stress_test<R: Rng>(config: &Config, rng: &mut R) -> (Self, witness::Running) {
+        let (game, running) = witness::new_stress_test(config, rng);
+        (
+            GameInstance {
+                game,
+                camera_shake: 0,
+                flicker: 0,
+                misses: Vec::new(),
+                swings: Vec::new(),
+                banner: None,
+                hint: None,
+                ghost: None,
+            },
+            running,
+        )
+    }
+
+    pub fn new_tutorial<R: Rng>(config: &Config, rng: &mut R) -> (Self, witness::Running) {
+        let (game, running) = witness::new_tutorial(config, rng);
+        (
+            GameInstance {
+                game,
+                camera_shake: 0,
+                flicker: 0,
+                misses: Vec::new(),
+                swings: Vec::new(),
+                banner: None,
+                hint: None,
+                ghost: None,
+            },
+            running,
+        )
+    }
+
+    pub fn tick_banners(&mut self, since_previous: Duration) {
+        for slot in [&mut self.banner, &mut self.hint] {
+            if let Some(banner) = slot.as_mut() {
+                banner.tick(since_previous);
+                if banner.is_finished() {
+                    *slot = None;
+                }
+            }
+        }
     }
 
     pub fn into_storable(self, running: witness::Running) -> GameInstanceStorable {
-        let Self { game } = self;
+        let Self { game, .. } = self;
         let running_game = game.into_running_game(running);
         GameInstanceStorable { running_game }
     }
@@ -35,53 +237,83 @@ impl GameInstance {
         }
     }
 
-    fn tile_to_render_cell(tile: Tile) -> RenderCell {
-        let character = match tile {
-            Tile::Player => {
-                return RenderCell {
-                    character: Some('@'),
-                    style: Style::new()
-                        .with_bold(true)
-                        .with_foreground(Rgba32::new_grey(255)),
-                };
-            }
-            Tile::Floor => {
-                return RenderCell {
-                    character: Some('.'),
-                    style: Style::new()
-                        .with_bold(true)
-                        .with_foreground(Rgba32::new_grey(127)),
-                };
-            }
-            Tile::Wall => '#',
-            Tile::DoorClosed => '+',
-            Tile::DoorOpen => '-',
-            Tile::StairsDown => {
-                return RenderCell {
-                    character: Some('>'),
-                    style: Style::new()
-                        .with_bold(true)
-                        .with_foreground(Rgba32::new_grey(255)),
-                };
-            }
+    fn decal_to_background(decal: &Decal, palette: &Palette) -> Rgba32 {
+        let (r, g, b) = match decal.kind {
+            DecalKind::Blood => palette.blood,
+            DecalKind::Scorch => palette.scorch,
+            DecalKind::Oil => palette.oil,
         };
-        RenderCell {
-            character: Some(character),
-            style: Style::new()
-                .with_bold(false)
-                .with_foreground(Rgba32::new_grey(187)),
+        let scale =
+            |c: u8| ((c as u32 * decal.intensity as u32) / MAX_DECAL_INTENSITY as u32) as u8;
+        Rgba32::new_rgb(scale(r), scale(g), scale(b))
+    }
+
+    /// Offsets the view by up to one cell in each direction, varying from frame to frame
+    fn camera_shake_offset(&self) -> Coord {
+        if self.camera_shake == 0 {
+            return Coord::new(0, 0);
         }
+        Coord::new(
+            (self.camera_shake * 7 % 3) as i32 - 1,
+            (self.camera_shake * 5 % 3) as i32 - 1,
+        )
+    }
+
+    /// Liquid gets brighter as it gets deeper
+    fn liquid_background(depth: u8, palette: &Palette) -> Rgba32 {
+        let brightness = 63 + (192 * depth as u32 / MAX_LIQUID_DEPTH as u32);
+        let (r, g, b) = palette.liquid;
+        let scale = |c: u8| ((c as u32 * brightness) / 255) as u8;
+        Rgba32::new_rgb(scale(r), scale(g), scale(b))
     }
 
-    pub fn render_game(&self, ctx: Ctx, fb: &mut FrameBuffer) {
-        let centre_coord_delta =
-            self.game.inner_ref().player_coord() - (ctx.bounding_box.size() / 2);
+    /// Thicker gas is drawn brighter
+    fn gas_background(gas: &Gas, palette: &Palette) -> Rgba32 {
+        let brightness = 63 + (192 * gas.density as u32 / MAX_GAS_DENSITY as u32);
+        let (r, g, b) = match gas.kind {
+            GasKind::Smoke => palette.smoke,
+            GasKind::Poison => palette.poison_gas,
+        };
+        let scale = |c: u8| ((c as u32 * brightness) / 255) as u8;
+        Rgba32::new_rgb(scale(r), scale(g), scale(b))
+    }
+
+    /// Flames flicker between shades of the fire colour, with neighbouring cells out of step
+    fn fire_background(&self, coord: Coord, palette: &Palette) -> Rgba32 {
+        let phase = (self.flicker / FLICKER_FRAMES_PER_STEP)
+            .wrapping_add((coord.x * 7 + coord.y * 13) as u32)
+            % 4;
+        let brightness = 255 - phase * 32;
+        let (r, g, b) = palette.fire;
+        let scale = |c: u8| ((c as u32 * brightness) / 255) as u8;
+        Rgba32::new_rgb(scale(r), scale(g), scale(b))
+    }
+
+    /// Blood is hidden while `gore` is off, including any spilled before it was turned off
+    pub fn render_game(
+        &self,
+        tiles: &TileRegistry,
+        palette: &Palette,
+        gore: bool,
+        ctx: Ctx,
+        fb: &mut FrameBuffer,
+    ) {
+        let centre_coord_delta = self.game.inner_ref().player_coord()
+            - (ctx.bounding_box.size() / 2)
+            + self.camera_shake_offset();
+        let telegraphs = self.game.inner_ref().telegraphs();
+        let pending_attacks = self.game.inner_ref().pending_attacks();
+        let electrified = self.game.inner_ref().electrified_cells();
+        let (palette, light) = palette.themed(self.game.inner_ref().level_theme());
+        let palette = &palette;
+        // the lights are out during a power outage
+        let light = light.filter(|_| self.game.inner_ref().power_state() != PowerState::Off);
         for coord in ctx.bounding_box.size().coord_iter_row_major() {
             let cell = self
                 .game
                 .inner_ref()
                 .cell_visibility_at_coord(coord + centre_coord_delta);
-            let unseen_background = Rgba32::new(0, 0, 0, 255);
+            let unseen_background = rgba32(palette.unseen);
             match cell {
                 CellVisibility::Never => {
                     let render_cell = RenderCell {
@@ -91,45 +323,250 @@ impl GameInstance {
                     fb.set_cell_relative_to_ctx(ctx, coord, 0, render_cell);
                 }
                 CellVisibility::Previous(data) => {
-                    let background = Rgba32::new(0, 0, 0, 255);
+                    let background = rgba32(palette.unseen);
                     data.tiles.for_each_enumerate(|tile, layer| {
+                        // characters have moved on since the cell was last seen
+                        if matches!(layer, Layer::Character) {
+                            return;
+                        }
                         if let Some(&tile) = tile.as_ref() {
-                            let depth = Self::layer_to_depth(layer);
-                            let mut render_cell = Self::tile_to_render_cell(tile);
+                            let depth = tiles
+                                .depth(tile)
+                                .unwrap_or_else(|| Self::layer_to_depth(layer));
+                            let mut render_cell = tiles.render_cell(tile, palette);
                             render_cell.style.background = Some(background);
-                            render_cell.style.foreground = Some(Rgba32::new_grey(63));
+                            let foreground = render_cell
+                                .style
+                                .foreground
+                                .unwrap_or_else(|| rgba32(palette.remembered));
+                            render_cell.style.foreground =
+                                Some(palette.remembered_foreground(foreground));
                             fb.set_cell_relative_to_ctx(ctx, coord, depth, render_cell);
                         }
                     });
                 }
-                CellVisibility::Current { data, .. } => {
+                CellVisibility::Current { data, light_colour } => {
+                    // tiles are lit by the ambient light plus any lights nearby
+                    let light_colour = light_colour.to_rgba32(255);
+                    if let Some(light) = light {
+                        let render_cell = RenderCell::default()
+                            .with_background(rgba32(light).normalised_mul(light_colour));
+                        fb.set_cell_relative_to_ctx(ctx, coord, 0, render_cell);
+                    }
                     data.tiles.for_each_enumerate(|tile, layer| {
                         if let Some(&tile) = tile.as_ref() {
-                            let depth = Self::layer_to_depth(layer);
-                            let render_cell = Self::tile_to_render_cell(tile);
+                            let depth = tiles
+                                .depth(tile)
+                                .unwrap_or_else(|| Self::layer_to_depth(layer));
+                            let mut render_cell = tiles.render_cell(tile, palette);
+                            render_cell.style.foreground = render_cell
+                                .style
+                                .foreground
+                                .map(|foreground| foreground.normalised_mul(light_colour));
                             fb.set_cell_relative_to_ctx(ctx, coord, depth, render_cell);
                         }
                     });
+                    if let Some(turns) = data.respawn_turns {
+                        // show how long until the remains get back up
+                        if let Some(character) = std::char::from_digit(turns, 10) {
+                            let render_cell = RenderCell {
+                                character: Some(character),
+                                style: Style::new()
+                                    .with_bold(true)
+                                    .with_foreground(rgba32(palette.respawn_countdown)),
+                            };
+                            let depth = Self::layer_to_depth(Layer::Item);
+                            fb.set_cell_relative_to_ctx(ctx, coord, depth, render_cell);
+                        }
+                    }
+                    let decal = data
+                        .decal
+                        .as_ref()
+                        .filter(|decal| gore || !decal.kind.is_gore());
+                    if let Some(decal) = decal {
+                        let render_cell = RenderCell::default()
+                            .with_background(Self::decal_to_background(decal, palette));
+                        fb.set_cell_relative_to_ctx(ctx, coord, 0, render_cell);
+                    }
+                    if data.liquid_depth > 0 {
+                        let background = if electrified.contains(&(coord + centre_coord_delta)) {
+                            rgba32(palette.electrified)
+                        } else {
+                            Self::liquid_background(data.liquid_depth, palette)
+                        };
+                        let render_cell = RenderCell::default().with_background(background);
+                        fb.set_cell_relative_to_ctx(ctx, coord, 0, render_cell);
+                    }
+                    if let Some(gas) = data.gas.as_ref() {
+                        let render_cell = RenderCell::default()
+                            .with_background(Self::gas_background(gas, palette));
+                        fb.set_cell_relative_to_ctx(ctx, coord, 0, render_cell);
+                    }
+                    if data.fire {
+                        let world_coord = coord + centre_coord_delta;
+                        let render_cell = RenderCell::default()
+                            .with_background(self.fire_background(world_coord, palette));
+                        fb.set_cell_relative_to_ctx(ctx, coord, 0, render_cell);
+                    }
+                    // warn the player about incoming attacks, making heavy attacks stand out
+                    let world_coord = coord + centre_coord_delta;
+                    let warning = if pending_attacks.contains(&world_coord) {
+                        Some(palette.wind_up)
+                    } else if telegraphs.contains(&world_coord) {
+                        Some(palette.telegraph)
+                    } else {
+                        None
+                    };
+                    if let Some(warning) = warning {
+                        let render_cell = RenderCell::default().with_background(rgba32(warning));
+                        fb.set_cell_relative_to_ctx(ctx, coord, 1, render_cell);
+                    }
+                    // show which npcs have noticed something, just above them
+                    let marker = match data.alertness {
+                        Some(Alertness::Suspicious) => Some(('?', palette.ui_warning)),
+                        Some(Alertness::Hostile) => Some(('!', palette.ui_danger)),
+                        Some(Alertness::Unaware) | None => None,
+                    };
+                    if let Some((character, colour)) = marker {
+                        let render_cell = RenderCell {
+                            character: Some(character),
+                            style: Style::new().with_bold(true).with_foreground(rgba32(colour)),
+                        };
+                        let depth = Self::layer_to_depth(Layer::Character) + 1;
+                        fb.set_cell_relative_to_ctx(
+                            ctx,
+                            coord + Coord::new(0, -1),
+                            depth,
+                            render_cell,
+                        );
+                    }
                 }
             }
         }
+        self.render_ghost(palette, centre_coord_delta, ctx, fb);
+        self.render_swings(palette, centre_coord_delta, ctx, fb);
+        self.render_misses(palette, centre_coord_delta, ctx, fb);
+    }
+
+    /// Highlights the cells reached by the player's latest melee attack
+    fn render_swings(
+        &self,
+        palette: &Palette,
+        centre_coord_delta: Coord,
+        ctx: Ctx,
+        fb: &mut FrameBuffer,
+    ) {
+        for &(coord, _) in &self.swings {
+            let render_cell = RenderCell::default().with_background(rgba32(palette.ui_accent));
+            fb.set_cell_relative_to_ctx(ctx, coord - centre_coord_delta, 1, render_cell);
+        }
     }
 
-    fn render_messages(&self, ctx: Ctx, fb: &mut FrameBuffer) {
+    /// Shows "miss" just above each character who recently dodged an attack
+    fn render_misses(
+        &self,
+        palette: &Palette,
+        centre_coord_delta: Coord,
+        ctx: Ctx,
+        fb: &mut FrameBuffer,
+    ) {
+        let style = Style::new()
+            .with_bold(true)
+            .with_foreground(rgba32(palette.ui_warning));
+        // above characters, so the text isn't hidden by whoever it's floating over
+        let depth = Self::layer_to_depth(Layer::Character) + 1;
+        for &(coord, _) in &self.misses {
+            let start = coord - centre_coord_delta + Coord::new(-1, -1);
+            for (i, character) in "miss".chars().enumerate() {
+                let render_cell = RenderCell {
+                    character: Some(character),
+                    style,
+                };
+                fb.set_cell_relative_to_ctx(
+                    ctx,
+                    start + Coord::new(i as i32, 0),
+                    depth,
+                    render_cell,
+                );
+            }
+        }
+    }
+
+    /// Counts down the frames left for each miss and swing to be shown
+    pub fn tick_animations(&mut self) {
+        for animations in [&mut self.misses, &mut self.swings] {
+            for (_, frames) in animations.iter_mut() {
+                *frames = frames.saturating_sub(1);
+            }
+            animations.retain(|&(_, frames)| frames > 0);
+        }
+    }
+
+    /// Shows where the player was at this turn on their previous attempt at the seed
+    fn render_ghost(
+        &self,
+        palette: &Palette,
+        centre_coord_delta: Coord,
+        ctx: Ctx,
+        fb: &mut FrameBuffer,
+    ) {
+        let game = self.game.inner_ref();
+        let step = match self
+            .ghost
+            .as_ref()
+            .and_then(|ghost| ghost.at_turn(game.turn()))
+        {
+            Some(step) => step,
+            None => return,
+        };
+        if step.level != game.level() {
+            return;
+        }
+        if !matches!(
+            game.cell_visibility_at_coord(step.coord),
+            CellVisibility::Current { .. }
+        ) {
+            return;
+        }
+        let render_cell = RenderCell {
+            character: Some('@'),
+            style: Style::new().with_foreground(rgba32(palette.ghost).with_a(GHOST_ALPHA)),
+        };
+        // below characters so the ghost never hides anything real
+        let depth = Self::layer_to_depth(Layer::Item);
+        fb.set_cell_relative_to_ctx(ctx, step.coord - centre_coord_delta, depth, render_cell);
+    }
+
+    fn render_messages(
+        &self,
+        palette: &Palette,
+        locale: &Locale,
+        controls: &Controls,
+        ctx: Ctx,
+        fb: &mut FrameBuffer,
+    ) {
         use text::*;
         let max = 4;
         let mut messages: Vec<(usize, String)> = Vec::new();
-        for m in self.game.inner_ref().messages().iter().rev() {
+        for m in self
+            .game
+            .inner_ref()
+            .messages()
+            .entries()
+            .iter()
+            .rev()
+            .map(|entry| message_text(&entry.message, locale, controls))
+        {
             if messages.len() >= max {
                 break;
             }
             if let Some((ref mut count, last)) = messages.last_mut() {
-                if last == m {
+                if *last == m {
                     *count += 1;
                     continue;
                 }
             }
-            messages.push((1, m.clone()));
+            messages.push((1, m));
         }
         for (i, (count, m)) in messages.into_iter().enumerate() {
             let string = if count == 1 {
@@ -140,16 +577,189 @@ impl GameInstance {
             let alpha = 255 - (i as u8 * 50);
             let styled_string = StyledString {
                 string,
-                style: Style::plain_text().with_foreground(Rgba32::new_grey(255).with_a(alpha)),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_text).with_a(alpha)),
             };
             let offset = max as i32 - i as i32 - 1;
             styled_string.render(&(), ctx.add_y(offset), fb);
         }
     }
 
-    pub fn render(&self, ctx: Ctx, fb: &mut FrameBuffer) {
-        self.render_game(ctx, fb);
+    fn render_hud(
+        &self,
+        palette: &Palette,
+        locale: &Locale,
+        controls: &Controls,
+        ctx: Ctx,
+        fb: &mut FrameBuffer,
+    ) {
+        use text::*;
+        let (current, max) = self.game.inner_ref().player_hit_points().current_and_max();
+        let styled_string = StyledString {
+            string: locale.format("hud.hit_points", &[&current, &max]),
+            style: Style::plain_text().with_foreground(rgba32(palette.ui_text)),
+        };
+        styled_string.render(&(), ctx, fb);
+        let oxygen = self.game.inner_ref().player_oxygen();
+        if !oxygen.is_full() {
+            let (current, max) = oxygen.current_and_max();
+            let styled_string = StyledString {
+                string: locale.format("hud.oxygen", &[&current, &max]),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_accent)),
+            };
+            styled_string.render(&(), ctx.add_xy(12, 0), fb);
+        }
+        if let Some(rads) = self.game.inner_ref().player_rads() {
+            if rads.exposure > 0 {
+                let styled_string = StyledString {
+                    string: locale.format("hud.radiation", &[&rads.exposure]),
+                    style: Style::plain_text().with_foreground(rgba32(palette.radiation)),
+                };
+                styled_string.render(&(), ctx.add_xy(24, 0), fb);
+            }
+        }
+        if let Some(shield) = self.game.inner_ref().player_shield() {
+            let styled_string = StyledString {
+                string: locale.format("hud.shield", &[&shield.charges, &shield.max_charges]),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_info)),
+            };
+            styled_string.render(&(), ctx.add_xy(36, 0), fb);
+        }
+        if self.game.inner_ref().is_player_swimming() {
+            let styled_string = StyledString {
+                string: locale.get("hud.swimming").to_string(),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_info)),
+            };
+            styled_string.render(&(), ctx.add_y(3), fb);
+        }
+        let status_effects = self.game.inner_ref().player_status_effects();
+        if !status_effects.is_empty() {
+            let string = status_effects
+                .into_iter()
+                .map(|effect| locale.get(status_effect_key(effect)).to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let styled_string = StyledString {
+                string,
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_warning)),
+            };
+            styled_string.render(&(), ctx.add_xy(12, 3), fb);
+        }
+        let styled_string = StyledString {
+            string: locale.format("hud.grenades", &[&self.game.inner_ref().player_grenades()]),
+            style: Style::plain_text().with_foreground(rgba32(palette.ui_text)),
+        };
+        styled_string.render(&(), ctx.add_xy(12, 1), fb);
+        if let Some(weapon) = self.game.inner_ref().player_weapon() {
+            let styled_string = StyledString {
+                string: locale.format("hud.weapon", &[&weapon.description(), &weapon.ammo]),
+                style: Style::plain_text().with_foreground(rgba32(palette.rarity(weapon.rarity))),
+            };
+            styled_string.render(&(), ctx.add_xy(24, 1), fb);
+        }
+        if let Some(weapon) = self.game.inner_ref().player_holstered_weapon() {
+            let styled_string = StyledString {
+                string: locale.format("hud.holstered", &[&weapon.description(), &weapon.ammo]),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_text)),
+            };
+            // below the gravity, continues and status effect rows, which all share the columns
+            // under the held weapon
+            styled_string.render(&(), ctx.add_xy(24, 4), fb);
+        }
+        if let Some(timer) = self.game.inner_ref().timer(TimerKind::SelfDestruct) {
+            let string = locale.format("hud.self_destruct", &[&timer.remaining]);
+            let x = (ctx.bounding_box.size().width() as i32 - string.chars().count() as i32) / 2;
+            let styled_string = StyledString {
+                string,
+                style: Style::plain_text()
+                    .with_bold(true)
+                    .with_foreground(rgba32(palette.ui_danger)),
+            };
+            styled_string.render(&(), ctx.add_xy(x, 0), fb);
+        }
+        if self.game.inner_ref().power_state() == PowerState::Off {
+            let styled_string = StyledString {
+                string: locale.get("hud.power_off").to_string(),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_warning)),
+            };
+            styled_string.render(&(), ctx.add_y(1), fb);
+        }
+        if let Some(step) = self.game.inner_ref().tutorial_step() {
+            let string = tutorial_prompt(step, locale, controls);
+            let x = (ctx.bounding_box.size().width() as i32 - string.chars().count() as i32) / 2;
+            let styled_string = StyledString {
+                string,
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_info)),
+            };
+            styled_string.render(&(), ctx.add_xy(x, 1), fb);
+        }
+        let gravity = match self.game.inner_ref().gravity() {
+            Gravity::Normal => None,
+            Gravity::Low => Some("hud.low_gravity"),
+            Gravity::Zero => Some("hud.zero_gravity"),
+        };
+        if let Some(gravity) = gravity {
+            let styled_string = StyledString {
+                string: locale.get(gravity).to_string(),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_accent)),
+            };
+            styled_string.render(&(), ctx.add_y(2), fb);
+        }
+        if let Some(continues) = self.game.inner_ref().continues_remaining() {
+            let styled_string = StyledString {
+                string: locale.format("hud.continues", &[&continues]),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_text)),
+            };
+            styled_string.render(&(), ctx.add_xy(24, 2), fb);
+        }
+        if let Some(player) = self.game.inner_ref().active_player_number() {
+            let styled_string = StyledString {
+                string: locale.format("hud.active_player", &[&player]),
+                style: Style::plain_text().with_foreground(rgba32(palette.ui_accent)),
+            };
+            styled_string.render(&(), ctx.add_xy(40, 2), fb);
+        }
+    }
+
+    fn render_banner(banner: &Banner, style: Style, y: i32, ctx: Ctx, fb: &mut FrameBuffer) {
+        use text::*;
+        let x = (ctx.bounding_box.size().width() as i32 - banner.text.chars().count() as i32) / 2;
+        let styled_string = StyledString {
+            string: banner.text.clone(),
+            style,
+        };
+        styled_string.render(&(), ctx.add_xy(x, y), fb);
+    }
+
+    fn render_banners(&self, palette: &Palette, ctx: Ctx, fb: &mut FrameBuffer) {
+        if let Some(banner) = self.banner.as_ref() {
+            let style = Style::plain_text()
+                .with_bold(true)
+                .with_foreground(rgba32(palette.ui_text).with_a(banner.alpha()));
+            Self::render_banner(banner, style, BANNER_Y, ctx, fb);
+        }
+        if let Some(hint) = self.hint.as_ref() {
+            let style =
+                Style::plain_text().with_foreground(rgba32(palette.ui_info).with_a(hint.alpha()));
+            let y = ctx.bounding_box.size().height() as i32 - HINT_Y_FROM_BOTTOM;
+            Self::render_banner(hint, style, y, ctx, fb);
+        }
+    }
+
+    /// Everything drawn on top of the game
+    pub fn render_overlay(
+        &self,
+        palette: &Palette,
+        locale: &Locale,
+        controls: &Controls,
+        ctx: Ctx,
+        fb: &mut FrameBuffer,
+    ) {
+        self.render_hud(palette, locale, controls, ctx.add_depth(20), fb);
+        self.render_banners(palette, ctx.add_depth(20), fb);
         self.render_messages(
+            palette,
+            locale,
+            controls,
             ctx.add_xy(1, ctx.bounding_box.size().height() as i32 - 7)
                 .add_depth(20),
             fb,
@@ -166,6 +776,19 @@ impl GameInstanceStorable {
     pub fn into_game_instance(self) -> (GameInstance, witness::Running) {
         let Self { running_game } = self;
         let (game, running) = running_game.into_game();
-        (GameInstance { game }, running)
+        crate::music::set_theme(game.inner_ref().level_theme());
+        (
+            GameInstance {
+                game,
+                camera_shake: 0,
+                flicker: 0,
+                misses: Vec::new(),
+                swings: Vec::new(),
+                banner: None,
+                hint: None,
+                ghost: None,
+            },
+            running,
+        )
     }
 }
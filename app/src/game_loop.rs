@@ -1,13 +1,28 @@
 use crate::{
-    controls::{AppInput, Controls},
-    game_instance::{GameInstance, GameInstanceStorable},
+    controls::{direction_key, AppInput, Controls},
+    credits::Credits,
+    editor::Editor,
+    effect,
+    game_instance::{status_effect_key, Banner, GameInstance, GameInstanceStorable},
+    hints::{Hint, HINT_DURATION},
     image::Images,
+    leaderboard::Leaderboard,
+    locale::{self, Locale},
+    message_history::MessageHistory,
+    palette::{self, Palette},
+    rich_text,
+    stress::StressTest,
     text,
+    tile_registry::TileRegistry,
 };
 use chargrid::{self, border::BorderStyle, control_flow::*, menu, prelude::*};
 use game::{
+    interlude_before_level,
     witness::{self, Witness},
-    Config as GameConfig, GameOverReason, Victory,
+    Aim, Balance, Behaviour, CardinalDirection, CodexEntry, Config as GameConfig, Difficulty,
+    Disposition, Ending, GameOverReason, GhostTrail, IntendedAction, Interlude, LayoutStyle,
+    LevelDesign, MessageLog, Performance, Rarity, RunModifier, RunModifiers, RunOutcome, RunRecord,
+    Split, Victory,
 };
 use general_storage_static::{self as storage, format, StaticStorage as Storage};
 use rand::{Rng, SeedableRng};
@@ -15,12 +30,36 @@ use rand_isaac::Isaac64Rng;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 struct Config {
     music_volume: f32,
     sfx_volume: f32,
     won: bool,
     first_run: bool,
     victories: Vec<Victory>,
+    gore: bool,
+    /// Everything the player has discovered across all games
+    codex: Vec<CodexEntry>,
+    show_timer: bool,
+    /// The fastest time for each level across all games
+    best_splits: Vec<Split>,
+    /// Name of the palette to render with
+    palette: String,
+    /// Used to adjust the difficulty of new games
+    performance: Performance,
+    /// Name of the locale to take player-facing strings from
+    language: String,
+    hints: bool,
+    /// Hints which have already been shown, so they aren't shown again
+    shown_hints: Vec<Hint>,
+    /// Rules applied to new games
+    modifiers: RunModifiers,
+    /// Difficulty of new games
+    difficulty: Difficulty,
+    /// How the levels of new games are laid out
+    layout_style: LayoutStyle,
+    /// Allows the most recent turn to be undone
+    undo: bool,
 }
 
 impl Default for Config {
@@ -31,6 +70,19 @@ impl Default for Config {
             won: false,
             first_run: true,
             victories: Vec::new(),
+            gore: true,
+            codex: Vec::new(),
+            show_timer: false,
+            best_splits: Vec::new(),
+            palette: "Default".to_string(),
+            performance: Performance::default(),
+            language: "English".to_string(),
+            hints: true,
+            shown_hints: Vec::new(),
+            modifiers: RunModifiers::default(),
+            difficulty: Difficulty::default(),
+            layout_style: LayoutStyle::default(),
+            undo: false,
         }
     }
 }
@@ -40,6 +92,9 @@ pub type AppCF<T> = CF<Option<T>, GameLoopData>;
 pub type State = GameLoopData;
 
 const MENU_BACKGROUND: Rgba32 = Rgba32::new_rgb(0, 0, 0);
+/// Time between steps while autoexploring or travelling, so the player can see where they're
+/// going
+const AUTO_MOVE_STEP_DELAY: Duration = Duration::from_millis(60);
 const MENU_FADE_SPEC: menu::identifier::fade_spec::FadeSpec = {
     use menu::identifier::fade_spec::*;
     FadeSpec {
@@ -110,17 +165,33 @@ impl RngSeedSource {
     }
 }
 
+/// Number of runs whose ghosts are kept in storage
+const MAX_GHOSTS: usize = 8;
+
 pub struct AppStorage {
     pub handle: Storage,
     pub save_game_key: String,
     pub config_key: String,
     pub controls_key: String,
+    /// Optional file overriding the game's balance numbers
+    pub balance_key: String,
+    /// Optional file adding or replacing tile definitions, in the format of tiles.ron
+    pub tiles_key: String,
+    /// Records of every completed run, as json
+    pub runs_key: String,
+    /// The paths taken by the player in recent runs, replayed as ghosts in later runs on the same
+    /// seed
+    pub ghosts_key: String,
+    /// The level made in the editor, in the format read by the terrain module
+    pub level_key: String,
 }
 
 impl AppStorage {
     const SAVE_GAME_STORAGE_FORMAT: format::Bincode = format::Bincode;
     const CONFIG_STORAGE_FORMAT: format::JsonPretty = format::JsonPretty;
     const CONTROLS_STORAGE_FORMAT: format::JsonPretty = format::JsonPretty;
+    const RUNS_STORAGE_FORMAT: format::JsonPretty = format::JsonPretty;
+    const GHOSTS_STORAGE_FORMAT: format::Bincode = format::Bincode;
 
     fn save_game(&mut self, instance: &GameInstanceStorable) {
         let result = self.handle.store(
@@ -257,6 +328,177 @@ impl AppStorage {
             Ok(instance) => Some(instance),
         }
     }
+
+    fn load_runs(&self) -> Vec<RunRecord> {
+        let result = self
+            .handle
+            .load::<_, Vec<RunRecord>, _>(&self.runs_key, Self::RUNS_STORAGE_FORMAT);
+        match result {
+            Err(e) => {
+                use storage::{LoadError, LoadRawError};
+                match e {
+                    LoadError::FormatError(e) => log::error!("Failed to parse runs file: {}", e),
+                    LoadError::Raw(e) => match e {
+                        LoadRawError::IoError(e) => {
+                            log::error!("Error while reading runs: {}", e)
+                        }
+                        LoadRawError::NoSuchKey => (),
+                    },
+                }
+                Vec::new()
+            }
+            Ok(runs) => runs,
+        }
+    }
+
+    /// Appends a record to the exported list of completed runs
+    fn save_run(&mut self, record: &RunRecord) {
+        let mut runs = self.load_runs();
+        runs.push(record.clone());
+        let result = self
+            .handle
+            .store(&self.runs_key, &runs, Self::RUNS_STORAGE_FORMAT);
+        if let Err(e) = result {
+            use storage::{StoreError, StoreRawError};
+            match e {
+                StoreError::FormatError(e) => log::error!("Failed to format runs: {}", e),
+                StoreError::Raw(e) => match e {
+                    StoreRawError::IoError(e) => {
+                        log::error!("Error while writing runs: {}", e)
+                    }
+                },
+            }
+        }
+    }
+
+    fn load_ghosts(&self) -> Vec<GhostTrail> {
+        let result = self
+            .handle
+            .load::<_, Vec<GhostTrail>, _>(&self.ghosts_key, Self::GHOSTS_STORAGE_FORMAT);
+        match result {
+            Err(e) => {
+                use storage::{LoadError, LoadRawError};
+                match e {
+                    LoadError::FormatError(e) => log::error!("Failed to parse ghosts file: {}", e),
+                    LoadError::Raw(e) => match e {
+                        LoadRawError::IoError(e) => {
+                            log::error!("Error while reading ghosts: {}", e)
+                        }
+                        LoadRawError::NoSuchKey => (),
+                    },
+                }
+                Vec::new()
+            }
+            Ok(ghosts) => ghosts,
+        }
+    }
+
+    /// The most recent trail recorded on the given seed
+    fn load_ghost(&self, seed: u64) -> Option<GhostTrail> {
+        self.load_ghosts()
+            .into_iter()
+            .find(|ghost| ghost.seed == seed)
+    }
+
+    /// Stores a trail, replacing any earlier trail on the same seed and forgetting the oldest
+    /// trails when there are too many
+    fn save_ghost(&mut self, ghost: &GhostTrail) {
+        let mut ghosts = self.load_ghosts();
+        ghosts.retain(|existing| existing.seed != ghost.seed);
+        ghosts.insert(0, ghost.clone());
+        ghosts.truncate(MAX_GHOSTS);
+        let result = self
+            .handle
+            .store(&self.ghosts_key, &ghosts, Self::GHOSTS_STORAGE_FORMAT);
+        if let Err(e) = result {
+            use storage::{StoreError, StoreRawError};
+            match e {
+                StoreError::FormatError(e) => log::error!("Failed to format ghosts: {}", e),
+                StoreError::Raw(e) => match e {
+                    StoreRawError::IoError(e) => {
+                        log::error!("Error while writing ghosts: {}", e)
+                    }
+                },
+            }
+        }
+    }
+
+    fn load_level_design(&self) -> Option<LevelDesign> {
+        let bytes = match self.handle.load_raw(&self.level_key) {
+            Ok(bytes) => bytes,
+            Err(storage::LoadRawError::NoSuchKey) => return None,
+            Err(storage::LoadRawError::IoError(e)) => {
+                log::error!("Error while reading level file: {}", e);
+                return None;
+            }
+        };
+        let string = match String::from_utf8(bytes) {
+            Ok(string) => string,
+            Err(e) => {
+                log::error!("Level file is not valid utf-8: {}", e);
+                return None;
+            }
+        };
+        let design = LevelDesign::from_text(&string);
+        if design.is_none() {
+            log::error!("Level file rows are not all the same length");
+        }
+        design
+    }
+
+    fn save_level_design(&mut self, design: &LevelDesign) {
+        let result = self.handle.store_raw(&self.level_key, design.to_text());
+        if let Err(storage::StoreRawError::IoError(e)) = result {
+            log::error!("Error while writing level file: {}", e);
+        }
+    }
+
+    fn load_balance(&self) -> Option<Balance> {
+        let bytes = match self.handle.load_raw(&self.balance_key) {
+            Ok(bytes) => bytes,
+            Err(storage::LoadRawError::NoSuchKey) => return None,
+            Err(storage::LoadRawError::IoError(e)) => {
+                log::error!("Error while reading balance file: {}", e);
+                return None;
+            }
+        };
+        let string = match String::from_utf8(bytes) {
+            Ok(string) => string,
+            Err(e) => {
+                log::error!("Balance file is not valid utf-8: {}", e);
+                return None;
+            }
+        };
+        match Balance::with_override(&string) {
+            Ok(balance) => Some(balance),
+            Err(e) => {
+                log::error!("Failed to parse balance file: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Adds the definitions in the tiles file, if there is one, to the registry
+    fn load_tile_overrides(&self, tiles: &mut TileRegistry) {
+        let bytes = match self.handle.load_raw(&self.tiles_key) {
+            Ok(bytes) => bytes,
+            Err(storage::LoadRawError::NoSuchKey) => return,
+            Err(storage::LoadRawError::IoError(e)) => {
+                log::error!("Error while reading tiles file: {}", e);
+                return;
+            }
+        };
+        let string = match String::from_utf8(bytes) {
+            Ok(string) => string,
+            Err(e) => {
+                log::error!("Tiles file is not valid utf-8: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = tiles.extend_from_ron(&string) {
+            log::error!("Failed to parse tiles file: {}", e);
+        }
+    }
 }
 
 fn new_game(
@@ -276,21 +518,52 @@ pub struct GameLoopData {
     rng_seed_source: RngSeedSource,
     config: Config,
     images: Images,
+    tiles: TileRegistry,
+    palettes: Vec<Palette>,
+    locales: Vec<Locale>,
     cursor: Option<Coord>,
+    show_controls_overlay: bool,
+    /// Wizard mode overlay showing what npcs are planning
+    show_ai_debug: bool,
+    /// What the player is aiming and where
+    aim: Option<(Aim, Coord)>,
+    /// The next direction pressed chooses a cell to interact with
+    choosing_interaction: bool,
+    /// What the player is doing automatically, and the time until its next step
+    auto_move: Option<(AutoMove, Duration)>,
+    /// How many inputs an ironman game had taken when it was last saved
+    ironman_saved_input: Option<u64>,
+    leaderboard: Box<dyn Leaderboard>,
 }
 
 impl GameLoopData {
     pub fn new(
-        game_config: GameConfig,
+        mut game_config: GameConfig,
         mut storage: AppStorage,
         initial_rng_seed: InitialRngSeed,
         force_new_game: bool,
+        leaderboard: Box<dyn Leaderboard>,
     ) -> (Self, GameLoopState) {
         let mut rng_seed_source = RngSeedSource::new(initial_rng_seed);
-        let config = storage.load_config().unwrap_or_default();
+        let mut config = storage.load_config().unwrap_or_default();
+        game_config.gore = config.gore;
+        game_config.undo = config.undo;
+        game_config.modifiers = config.modifiers;
+        game_config.difficulty = config.difficulty;
+        game_config.layout_style = config.layout_style;
+        game_config.performance = config.performance;
+        if let Some(balance) = storage.load_balance() {
+            game_config.balance = balance;
+        }
+        let show_controls_overlay = config.first_run;
+        if config.first_run {
+            config.first_run = false;
+            storage.save_config(&config);
+        }
         let (instance, state) = match storage.load_game() {
             Some(instance) => {
-                let (instance, running) = instance.into_game_instance();
+                let (mut instance, running) = instance.into_game_instance();
+                instance.ghost = storage.load_ghost(instance.game.inner_ref().seed());
                 (
                     Some(instance),
                     GameLoopState::Playing(running.into_witness()),
@@ -298,8 +571,9 @@ impl GameLoopData {
             }
             None => {
                 if force_new_game {
-                    let (instance, running) =
+                    let (mut instance, running) =
                         new_game(&mut rng_seed_source, &game_config, config.victories.clone());
+                    instance.ghost = storage.load_ghost(instance.game.inner_ref().seed());
                     (
                         Some(instance),
                         GameLoopState::Playing(running.into_witness()),
@@ -309,6 +583,8 @@ impl GameLoopData {
                 }
             }
         };
+        let mut tiles = TileRegistry::new();
+        storage.load_tile_overrides(&mut tiles);
         let controls = if let Some(controls) = storage.load_controls() {
             controls
         } else {
@@ -325,28 +601,185 @@ impl GameLoopData {
                 rng_seed_source,
                 config,
                 images: Images::new(),
+                tiles,
+                palettes: palette::load_palettes(),
+                locales: locale::load_locales(),
                 cursor: None,
+                show_controls_overlay,
+                show_ai_debug: false,
+                aim: None,
+                choosing_interaction: false,
+                auto_move: None,
+                ironman_saved_input: None,
+                leaderboard,
             },
             state,
         )
     }
 
     fn save_instance(&mut self, running: witness::Running) -> witness::Running {
-        let instance = self.instance.take().unwrap().into_storable(running);
+        let instance = self.instance.take().unwrap();
+        let ghost = instance.ghost.clone();
+        let instance = instance.into_storable(running);
         self.storage.save_game(&instance);
-        let (instance, running) = instance.into_game_instance();
+        let (mut instance, running) = instance.into_game_instance();
+        instance.ghost = ghost;
         self.instance = Some(instance);
         running
     }
 
+    /// Shows the player's previous attempt at the current game's seed, if there was one
+    fn attach_ghost(&mut self) {
+        if let Some(instance) = self.instance.as_mut() {
+            instance.ghost = self.storage.load_ghost(instance.game.inner_ref().seed());
+        }
+    }
+
     fn clear_saved_game(&mut self) {
         self.storage.clear_game();
     }
 
+    fn new_tutorial(&mut self) -> witness::Running {
+        let mut rng = Isaac64Rng::seed_from_u64(self.rng_seed_source.next_seed());
+        let (instance, running) = GameInstance::new_tutorial(&self.game_config, &mut rng);
+        self.instance = Some(instance);
+        running
+    }
+
     fn new_game(&mut self) -> witness::Running {
         let victories = self.config.victories.clone();
         let (instance, running) = new_game(&mut self.rng_seed_source, &self.game_config, victories);
         self.instance = Some(instance);
+        self.ironman_saved_input = None;
+        self.attach_ghost();
+        running
+    }
+
+    fn new_ironman_game(&mut self) -> witness::Running {
+        let victories = self.config.victories.clone();
+        let game_config = GameConfig {
+            ironman: true,
+            ..self.game_config
+        };
+        let (instance, running) = new_game(&mut self.rng_seed_source, &game_config, victories);
+        self.instance = Some(instance);
+        self.ironman_saved_input = None;
+        self.attach_ghost();
+        running
+    }
+
+    /// A game where dying sends the player back to the start of the level a limited number of
+    /// times
+    fn new_casual_game(&mut self) -> witness::Running {
+        let victories = self.config.victories.clone();
+        let game_config = GameConfig {
+            casual: true,
+            ..self.game_config
+        };
+        let (instance, running) = new_game(&mut self.rng_seed_source, &game_config, victories);
+        self.instance = Some(instance);
+        self.ironman_saved_input = None;
+        self.attach_ghost();
+        running
+    }
+
+    /// A game with two characters controlled from the same keyboard, taking turns
+    fn new_hot_seat_game(&mut self) -> witness::Running {
+        let victories = self.config.victories.clone();
+        let game_config = GameConfig {
+            hot_seat: true,
+            ..self.game_config
+        };
+        let (instance, running) = new_game(&mut self.rng_seed_source, &game_config, victories);
+        self.instance = Some(instance);
+        self.ironman_saved_input = None;
+        self.attach_ghost();
+        running
+    }
+
+    /// Replaces any current game with the stress test scene
+    pub fn start_stress_test(&mut self) -> GameLoopState {
+        let mut rng = Isaac64Rng::seed_from_u64(self.rng_seed_source.next_seed());
+        let (instance, _running) = GameInstance::new_stress_test(&self.game_config, &mut rng);
+        self.instance = Some(instance);
+        GameLoopState::StressTest
+    }
+
+    /// Advances the stress test, including the effects of anything that happened such as sounds
+    /// and camera shake
+    pub fn stress_turn(&mut self) {
+        let instance = self.instance.as_mut().unwrap();
+        instance.game.stress_turn(&self.game_config);
+        instance.camera_shake = instance.camera_shake.saturating_sub(1);
+        instance.tick_animations();
+        let events = instance.game.take_external_events();
+        effect::dispatch(&events, &mut effect::EffectContext { instance });
+    }
+
+    /// Plays a level from the editor. Returns `None` if the level has nowhere for the player to
+    /// start.
+    pub fn test_level_design(&mut self, design: &LevelDesign) -> Option<witness::Running> {
+        let mut rng = Isaac64Rng::seed_from_u64(self.rng_seed_source.next_seed());
+        let (instance, running) =
+            GameInstance::new_custom_level(&self.game_config, design, &mut rng)?;
+        self.instance = Some(instance);
+        self.ironman_saved_input = None;
+        Some(running)
+    }
+
+    pub fn save_level_design(&mut self, design: &LevelDesign) {
+        self.storage.save_level_design(design);
+    }
+
+    /// Starts a new game+ with the character from the game the player just won, keeping the
+    /// mode that game was played in
+    fn new_game_plus(&mut self) -> witness::Running {
+        let previous = match self.instance.take() {
+            Some(previous) => previous,
+            None => return self.new_game(),
+        };
+        let game_config = GameConfig {
+            ironman: previous.game.inner_ref().is_ironman(),
+            casual: previous.game.inner_ref().is_casual(),
+            hot_seat: previous.game.inner_ref().is_hot_seat(),
+            difficulty: previous.game.inner_ref().difficulty(),
+            layout_style: previous.game.inner_ref().layout_style(),
+            ..self.game_config
+        };
+        let mut rng = Isaac64Rng::seed_from_u64(self.rng_seed_source.next_seed());
+        let (instance, running) =
+            GameInstance::new_game_plus(&game_config, &previous.game, &mut rng);
+        self.instance = Some(instance);
+        self.ironman_saved_input = None;
+        self.attach_ghost();
+        running
+    }
+
+    /// Ironman games are saved after every action, so there is never an earlier save to go back
+    /// to. Banners and camera shake aren't saved so they are carried over by hand.
+    fn ironman_autosave(&mut self, running: witness::Running) -> witness::Running {
+        let num_inputs = match self.instance.as_ref() {
+            Some(instance) if instance.game.inner_ref().is_ironman() => {
+                instance.game.inner_ref().num_committed_inputs()
+            }
+            _ => return running,
+        };
+        if self.ironman_saved_input == Some(num_inputs) {
+            return running;
+        }
+        let GameInstance {
+            camera_shake,
+            banner,
+            hint,
+            ..
+        } = self.instance.as_mut().unwrap();
+        let (camera_shake, banner, hint) = (*camera_shake, banner.take(), hint.take());
+        let running = self.save_instance(running);
+        let instance = self.instance.as_mut().unwrap();
+        instance.camera_shake = camera_shake;
+        instance.banner = banner;
+        instance.hint = hint;
+        self.ironman_saved_input = Some(num_inputs);
         running
     }
 
@@ -354,18 +787,596 @@ impl GameLoopData {
         self.storage.save_config(&self.config);
     }
 
-    fn render(&self, ctx: Ctx, fb: &mut FrameBuffer) {
+    pub fn message_log(&self) -> Option<&MessageLog> {
+        self.instance
+            .as_ref()
+            .map(|instance| instance.game.inner_ref().messages())
+    }
+
+    /// Adds anything discovered in the current game to the codex
+    fn update_codex(&mut self) {
+        let instance = match self.instance.as_ref() {
+            Some(instance) => instance,
+            None => return,
+        };
+        let mut changed = false;
+        for &entry in instance.game.inner_ref().discovered() {
+            if !self.config.codex.contains(&entry) {
+                self.config.codex.push(entry);
+                changed = true;
+            }
+        }
+        if changed {
+            self.save_config();
+        }
+    }
+
+    fn mark_hint_shown(&mut self, hint: Hint) {
+        if !self.config.shown_hints.contains(&hint) {
+            self.config.shown_hints.push(hint);
+            self.save_config();
+        }
+    }
+
+    /// Shows the first hint which is relevant to the current game and hasn't been shown before
+    fn update_hints(&mut self) {
+        if !self.config.hints {
+            return;
+        }
+        let instance = match self.instance.as_ref() {
+            Some(instance) => instance,
+            None => return,
+        };
+        if instance.hint.is_some() {
+            return;
+        }
+        let hint = Hint::ALL.iter().copied().find(|&hint| {
+            !self.config.shown_hints.contains(&hint) && hint.is_relevant(instance.game.inner_ref())
+        });
+        if let Some(hint) = hint {
+            let text = hint.text(self.locale(), &self.controls);
+            if let Some(instance) = self.instance.as_mut() {
+                instance.hint = Some(Banner::new(text, HINT_DURATION));
+            }
+            self.mark_hint_shown(hint);
+        }
+    }
+
+    fn toggle_undo(&mut self) {
+        self.config.undo = !self.config.undo;
+        self.game_config.undo = self.config.undo;
+        self.save_config();
+    }
+
+    fn toggle_hints(&mut self) {
+        self.config.hints = !self.config.hints;
+        self.save_config();
+    }
+
+    fn bind_control(&mut self, input: chargrid::input::Input, app_input: AppInput) {
+        if self.controls.bind(input, app_input) {
+            self.storage.save_controls(&self.controls);
+        }
+    }
+
+    /// The palette named in the config, falling back to the first palette if there is no palette
+    /// with that name
+    fn palette(&self) -> &Palette {
+        self.palettes
+            .iter()
+            .find(|palette| palette.name == self.config.palette)
+            .unwrap_or(&self.palettes[0])
+    }
+
+    fn cycle_palette(&mut self) {
+        let index = self
+            .palettes
+            .iter()
+            .position(|palette| palette.name == self.config.palette)
+            .map(|index| (index + 1) % self.palettes.len())
+            .unwrap_or(0);
+        self.config.palette = self.palettes[index].name.clone();
+        self.save_config();
+    }
+
+    /// The locale named in the config, falling back to the first locale if there is no locale
+    /// with that name
+    pub fn locale(&self) -> &Locale {
+        self.locales
+            .iter()
+            .find(|locale| locale.name == self.config.language)
+            .unwrap_or(&self.locales[0])
+    }
+
+    pub fn controls(&self) -> &Controls {
+        &self.controls
+    }
+
+    fn cycle_language(&mut self) {
+        let index = self
+            .locales
+            .iter()
+            .position(|locale| locale.name == self.config.language)
+            .map(|index| (index + 1) % self.locales.len())
+            .unwrap_or(0);
+        self.config.language = self.locales[index].name.clone();
+        self.save_config();
+    }
+
+    /// Tutorial games don't count towards the player's performance
+    fn is_tutorial(&self) -> bool {
+        self.instance
+            .as_ref()
+            .map(|instance| instance.game.inner_ref().is_tutorial())
+            .unwrap_or(false)
+    }
+
+    /// Exports the record of the run which just ended and submits it to the leaderboard
+    fn record_run(&mut self, outcome: RunOutcome) {
+        if self.is_tutorial() {
+            return;
+        }
+        let record = match self.instance.as_ref() {
+            Some(instance) => instance.game.inner_ref().run_record(outcome),
+            None => return,
+        };
+        self.storage.save_run(&record);
+        self.leaderboard.submit(&record);
+        if let Some(instance) = self.instance.as_ref() {
+            self.storage
+                .save_ghost(instance.game.inner_ref().ghost_trail());
+        }
+    }
+
+    fn record_death(&mut self) {
+        if self.is_tutorial() {
+            return;
+        }
+        self.config.performance.record_death();
+        self.game_config.performance = self.config.performance;
+        self.save_config();
+    }
+
+    fn record_clear(&mut self) {
+        if let Some(instance) = self.instance.as_ref() {
+            let (current, max) = instance
+                .game
+                .inner_ref()
+                .player_hit_points()
+                .current_and_max();
+            self.config.performance.record_clear(current, max);
+            self.game_config.performance = self.config.performance;
+            self.save_config();
+        }
+    }
+
+    fn toggle_timer(&mut self) {
+        self.config.show_timer = !self.config.show_timer;
+        self.save_config();
+    }
+
+    /// Updates personal bests with the splits from the current game, returning each split paired
+    /// with the previous best for its level
+    fn record_splits(&mut self) -> Vec<(Split, Option<Split>)> {
+        let splits = match self.instance.as_ref() {
+            Some(instance) => instance.game.inner_ref().run_timer().splits().to_vec(),
+            None => return Vec::new(),
+        };
+        let comparison = splits
+            .into_iter()
+            .map(|split| {
+                let best = self
+                    .config
+                    .best_splits
+                    .iter()
+                    .find(|best| best.level == split.level)
+                    .copied();
+                (split, best)
+            })
+            .collect::<Vec<_>>();
+        for &(split, best) in &comparison {
+            match best {
+                None => self.config.best_splits.push(split),
+                Some(best) if split.duration < best.duration => {
+                    if let Some(entry) = self
+                        .config
+                        .best_splits
+                        .iter_mut()
+                        .find(|best| best.level == split.level)
+                    {
+                        *entry = split;
+                    }
+                }
+                Some(_) => (),
+            }
+        }
+        self.save_config();
+        comparison
+    }
+
+    fn toggle_modifier(&mut self, modifier: RunModifier) {
+        self.config.modifiers.toggle(modifier);
+        self.game_config.modifiers = self.config.modifiers;
+        self.save_config();
+    }
+
+    fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.config.difficulty = difficulty;
+        self.game_config.difficulty = difficulty;
+        self.save_config();
+    }
+
+    fn set_layout_style(&mut self, layout_style: LayoutStyle) {
+        self.config.layout_style = layout_style;
+        self.game_config.layout_style = layout_style;
+        self.save_config();
+    }
+
+    fn toggle_gore(&mut self) {
+        self.config.gore = !self.config.gore;
+        self.game_config.gore = self.config.gore;
+        self.save_config();
+    }
+
+    pub fn render(&self, ctx: Ctx, fb: &mut FrameBuffer) {
         let instance = self.instance.as_ref().unwrap();
-        instance.render(ctx, fb);
+        instance.render_game(&self.tiles, self.palette(), self.config.gore, ctx, fb);
+        instance.render_overlay(self.palette(), self.locale(), &self.controls, ctx, fb);
         if let Some(cursor) = self.cursor {
             let cursor_colour = Rgba32::new(255, 255, 255, 127);
             let render_cell = RenderCell::default().with_background(cursor_colour);
             fb.set_cell_relative_to_ctx(ctx, cursor, 50, render_cell);
+            self.render_examine(cursor, ctx, fb);
+        }
+        if let Some((aim, target)) = self.aim {
+            self.render_aim_preview(aim, target, ctx, fb);
+        }
+        if let Some((AutoMove::Travel(_), _)) = self.auto_move {
+            self.render_travel_path(ctx, fb);
+        }
+        if self.show_ai_debug {
+            self.render_ai_debug(ctx, fb);
+        }
+        if self.show_controls_overlay {
+            self.render_controls_overlay(ctx.add_depth(30), fb);
+        }
+        if self.config.show_timer {
+            self.render_timer(ctx.add_depth(20), fb);
+        }
+    }
+
+    /// Describes the npc under the cursor, and marks where it will move or attack on its next
+    /// turn
+    fn render_examine(&self, cursor: Coord, ctx: Ctx, fb: &mut FrameBuffer) {
+        let game = self.instance.as_ref().unwrap().game.inner_ref();
+        let coord = self.screen_to_world_coord(cursor, ctx.bounding_box.size());
+        let examination = match game.examine_npc(coord) {
+            Some(examination) => examination,
+            None => {
+                if let Some(examination) = game.examine_item(coord) {
+                    let foreground = match examination.rarity {
+                        Rarity::Common => Rgba32::new_grey(187),
+                        rarity => palette::rgba32(self.palette().rarity(rarity)),
+                    };
+                    Self::render_examine_panel(
+                        cursor,
+                        vec![self.locale().get(&examination.description).to_string()],
+                        foreground,
+                        ctx,
+                        fb,
+                    );
+                }
+                return;
+            }
+        };
+        let locale = self.locale();
+        let disposition = match examination.disposition {
+            Disposition::Hostile => "examine.hostile",
+        };
+        let behaviour = match examination.behaviour {
+            Behaviour::Attacking => "examine.attacking",
+            Behaviour::Hunting => "examine.hunting",
+            Behaviour::Investigating => "examine.investigating",
+            Behaviour::Fleeing => "examine.fleeing",
+            Behaviour::Searching => "examine.searching",
+            Behaviour::Idle => "examine.idle",
+        };
+        let mut lines = vec![
+            examination.description,
+            format!("{}, {}", locale.get(disposition), locale.get(behaviour)),
+        ];
+        match examination.intended_action {
+            Some(IntendedAction::Move(direction)) => {
+                lines.push(locale.format(
+                    "examine.next_move",
+                    &[&locale.get(direction_key(direction))],
+                ));
+                let arrow = match direction {
+                    CardinalDirection::North => '^',
+                    CardinalDirection::East => '>',
+                    CardinalDirection::South => 'v',
+                    CardinalDirection::West => '<',
+                };
+                let render_cell = RenderCell {
+                    character: Some(arrow),
+                    style: Style::new()
+                        .with_bold(true)
+                        .with_foreground(Rgba32::new_grey(255)),
+                };
+                fb.set_cell_relative_to_ctx(ctx, cursor + direction.coord(), 50, render_cell);
+            }
+            Some(IntendedAction::Attack(target)) => {
+                lines.push(locale.get("examine.next_attack").to_string());
+                let render_cell =
+                    RenderCell::default().with_background(Rgba32::new(255, 0, 0, 127));
+                fb.set_cell_relative_to_ctx(ctx, cursor + (target - coord), 50, render_cell);
+            }
+            None => (),
+        }
+        if !examination.status_effects.is_empty() {
+            let status_effects = examination
+                .status_effects
+                .iter()
+                .map(|&effect| locale.get(status_effect_key(effect)).to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            lines.push(status_effects);
+        }
+        Self::render_examine_panel(cursor, lines, Rgba32::new_grey(187), ctx, fb);
+    }
+
+    /// Draws lines of text next to the cursor
+    fn render_examine_panel(
+        cursor: Coord,
+        lines: Vec<String>,
+        foreground: Rgba32,
+        ctx: Ctx,
+        fb: &mut FrameBuffer,
+    ) {
+        use chargrid::text::StyledString;
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as i32;
+        // keep the description next to the cursor without running off the screen
+        let x = (cursor.x + 2).min(ctx.bounding_box.size().width() as i32 - width);
+        let style = Style::plain_text()
+            .with_foreground(foreground)
+            .with_background(Rgba32::new(0, 0, 0, 191));
+        for (y, line) in lines.into_iter().enumerate() {
+            let styled_string = StyledString {
+                string: format!("{:width$}", line, width = width as usize),
+                style,
+            };
+            styled_string.render(&(), ctx.add_xy(x, cursor.y + y as i32).add_depth(50), fb);
+        }
+    }
+
+    /// Shows the path a grenade or shot would take and the cells its explosion would reach
+    fn render_aim_preview(&self, aim: Aim, target: Coord, ctx: Ctx, fb: &mut FrameBuffer) {
+        let game = self.instance.as_ref().unwrap().game.inner_ref();
+        let centre_coord_delta = game.player_coord() - (ctx.bounding_box.size() / 2);
+        let mut highlight = |coord: Coord, colour: Rgba32| {
+            let render_cell = RenderCell::default().with_background(colour);
+            fb.set_cell_relative_to_ctx(ctx, coord - centre_coord_delta, 50, render_cell);
+        };
+        let preview = game.aim_preview(aim, target);
+        for &coord in &preview.path {
+            highlight(coord, Rgba32::new(255, 255, 0, 63));
+        }
+        for &coord in &preview.blast_area {
+            highlight(coord, Rgba32::new(255, 63, 0, 127));
+        }
+        let target_colour = if preview.in_range {
+            Rgba32::new(255, 255, 255, 127)
+        } else {
+            Rgba32::new(127, 127, 127, 127)
+        };
+        highlight(target, target_colour);
+    }
+
+    fn render_travel_path(&self, ctx: Ctx, fb: &mut FrameBuffer) {
+        let game = self.instance.as_ref().unwrap().game.inner_ref();
+        let centre_coord_delta = game.player_coord() - (ctx.bounding_box.size() / 2);
+        let render_cell = RenderCell::default().with_background(Rgba32::new(0, 255, 255, 63));
+        for coord in game.current_travel_path() {
+            fb.set_cell_relative_to_ctx(ctx, coord - centre_coord_delta, 50, render_cell);
         }
     }
 
+    /// Labels each cell with the number of steps npcs would take from there to reach the player,
+    /// tints each npc's path by how aware it is of the player, and marks its goal
+    fn render_ai_debug(&self, ctx: Ctx, fb: &mut FrameBuffer) {
+        let game = self.instance.as_ref().unwrap().game.inner_ref();
+        let size = ctx.bounding_box.size();
+        let centre_coord_delta = game.player_coord() - (size / 2);
+        // drawn at the same depth as items so that characters are still visible
+        let distance_style = Style::new().with_foreground(Rgba32::new(0, 255, 255, 127));
+        for screen_coord in size.coord_iter_row_major() {
+            let coord = screen_coord + centre_coord_delta;
+            if let Some(distance) = game.ai_distance_at(coord) {
+                let render_cell = RenderCell {
+                    character: std::char::from_digit(distance % 36, 36),
+                    style: distance_style,
+                };
+                fb.set_cell_relative_to_ctx(ctx, screen_coord, 2, render_cell);
+            }
+        }
+        let mut highlight = |coord: Coord, colour: Rgba32| {
+            let render_cell = RenderCell::default().with_background(colour);
+            fb.set_cell_relative_to_ctx(ctx, coord - centre_coord_delta, 50, render_cell);
+        };
+        for agent in game.ai_introspection() {
+            let colour = match agent.awareness {
+                Behaviour::Attacking => Rgba32::new(255, 0, 0, 63),
+                Behaviour::Hunting => Rgba32::new(255, 255, 0, 63),
+                Behaviour::Investigating => Rgba32::new(0, 127, 255, 63),
+                Behaviour::Fleeing => Rgba32::new(255, 0, 255, 63),
+                Behaviour::Searching => Rgba32::new(255, 127, 0, 63),
+                Behaviour::Idle => Rgba32::new(0, 255, 0, 63),
+            };
+            for &coord in &agent.path {
+                highlight(coord, colour);
+            }
+            if let Some(goal) = agent.goal {
+                highlight(goal, colour.with_a(191));
+            }
+        }
+    }
+
+    /// Stops aiming or choosing a cell. Returns whether there was anything to cancel.
+    fn cancel_targeting(&mut self) -> bool {
+        let choosing_interaction = std::mem::replace(&mut self.choosing_interaction, false);
+        self.aim.take().is_some() || choosing_interaction
+    }
+
+    /// Opens the interaction menu for a cell if there's anything to do there
+    fn interact_at(&mut self, coord: Coord, running: witness::Running) -> GameLoopState {
+        let instance = self.instance.as_ref().unwrap();
+        if instance
+            .game
+            .inner_ref()
+            .available_actions_at(coord)
+            .is_empty()
+        {
+            GameLoopState::Playing(running.into_witness())
+        } else {
+            GameLoopState::Interact(running, coord)
+        }
+    }
+
+    /// Converts a position on the screen into a position in the world, given the size of the
+    /// area the game is rendered into
+    fn screen_to_world_coord(&self, screen_coord: Coord, size: Size) -> Coord {
+        let instance = self.instance.as_ref().unwrap();
+        screen_coord + instance.game.inner_ref().player_coord() - (size / 2)
+    }
+
+    fn update_choose_interaction(
+        &mut self,
+        app_input: AppInput,
+        running: witness::Running,
+    ) -> GameLoopState {
+        self.choosing_interaction = false;
+        let player_coord = self
+            .instance
+            .as_ref()
+            .unwrap()
+            .game
+            .inner_ref()
+            .player_coord();
+        match app_input {
+            AppInput::Direction(direction) => {
+                self.interact_at(player_coord + direction.coord(), running)
+            }
+            AppInput::Wait | AppInput::Interact => self.interact_at(player_coord, running),
+            _ => GameLoopState::Playing(running.into_witness()),
+        }
+    }
+
+    /// Moves the target, or fires if the player presses the same input they started aiming with
+    fn update_aim(
+        &mut self,
+        aim: Aim,
+        target: Coord,
+        app_input: AppInput,
+        running: witness::Running,
+    ) -> GameLoopState {
+        let witness = match (aim, app_input) {
+            (_, AppInput::Direction(direction)) => {
+                self.aim = Some((aim, target + direction.coord()));
+                running.into_witness()
+            }
+            (Aim::Grenade, AppInput::ThrowGrenade) => {
+                self.aim = None;
+                // the player has found out how to aim so doesn't need telling
+                self.mark_hint_shown(Hint::Aim);
+                let instance = self.instance.as_mut().unwrap();
+                let (witness, _action_result) =
+                    running.throw_grenade(&mut instance.game, target, &self.game_config);
+                witness
+            }
+            (Aim::Weapon, AppInput::FireWeapon) => {
+                self.aim = None;
+                let instance = self.instance.as_mut().unwrap();
+                let (witness, _action_result) =
+                    running.fire_weapon(&mut instance.game, target, &self.game_config);
+                witness
+            }
+            (Aim::Throw(slot), AppInput::ThrowItem) => {
+                self.aim = None;
+                let instance = self.instance.as_mut().unwrap();
+                let (witness, _action_result) =
+                    running.throw(&mut instance.game, slot, target, &self.game_config);
+                witness
+            }
+            _ => running.into_witness(),
+        };
+        GameLoopState::Playing(witness)
+    }
+
+    /// Lists the current key bindings along the right edge of the screen
+    fn render_controls_overlay(&self, ctx: Ctx, fb: &mut FrameBuffer) {
+        use chargrid::text::StyledString;
+        let lines = self
+            .controls
+            .descriptions(self.locale())
+            .into_iter()
+            .map(|(key, description)| format!("{}: {}", key, description))
+            .collect::<Vec<_>>();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0) as i32;
+        let x = ctx.bounding_box.size().width() as i32 - width - 1;
+        let style = Style::plain_text()
+            .with_foreground(Rgba32::new_grey(187))
+            .with_background(Rgba32::new(0, 0, 0, 191));
+        for (y, line) in lines.into_iter().enumerate() {
+            let styled_string = StyledString {
+                string: format!("{:width$}", line, width = width as usize),
+                style,
+            };
+            styled_string.render(&(), ctx.add_xy(x, y as i32), fb);
+        }
+    }
+
+    fn render_timer(&self, ctx: Ctx, fb: &mut FrameBuffer) {
+        use chargrid::text::StyledString;
+        let game = self.instance.as_ref().unwrap().game.inner_ref();
+        let string = format!(
+            "{} T{}",
+            text::format_duration(game.run_timer().elapsed()),
+            game.turn()
+        );
+        let x = ctx.bounding_box.size().width() as i32 - string.len() as i32;
+        let styled_string = StyledString {
+            string,
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(187)),
+        };
+        styled_string.render(&(), ctx.add_x(x), fb);
+    }
+
     fn update(&mut self, event: Event, running: witness::Running) -> GameLoopState {
+        if let Some((aim, target)) = self.aim {
+            if let Event::Input(input) = &event {
+                if let Some(app_input) = self.controls.get(*input) {
+                    return self.update_aim(aim, target, app_input, running);
+                }
+            }
+        }
+        if self.choosing_interaction {
+            if let Event::Input(input) = &event {
+                if let Some(app_input) = self.controls.get(*input) {
+                    return self.update_choose_interaction(app_input, running);
+                }
+            }
+        }
         let instance = self.instance.as_mut().unwrap();
+        if let Event::Tick(since_previous) = event {
+            instance.camera_shake = instance.camera_shake.saturating_sub(1);
+            instance.flicker = instance.flicker.wrapping_add(1);
+            instance.tick_animations();
+            instance.tick_banners(since_previous);
+        }
         let witness = match event {
             Event::Input(input) => {
                 if let Some(app_input) = self.controls.get(input) {
@@ -374,6 +1385,64 @@ impl GameLoopData {
                             running.walk(&mut instance.game, direction, &self.game_config)
                         }
                         AppInput::Wait => running.wait(&mut instance.game, &self.game_config),
+                        AppInput::Search => running.search(&mut instance.game, &self.game_config),
+                        AppInput::DestroyCorpse => {
+                            running.destroy_corpse(&mut instance.game, &self.game_config)
+                        }
+                        AppInput::Drag => running.drag(&mut instance.game, &self.game_config),
+                        AppInput::Brace => running.brace(&mut instance.game, &self.game_config),
+                        AppInput::AutoExplore => {
+                            // the first step is taken on the next tick
+                            self.auto_move = Some((AutoMove::Explore, Duration::ZERO));
+                            (running.into_witness(), Ok(()))
+                        }
+                        AppInput::ThrowGrenade => {
+                            self.aim =
+                                Some((Aim::Grenade, instance.game.inner_ref().player_coord()));
+                            (running.into_witness(), Ok(()))
+                        }
+                        AppInput::FireWeapon => {
+                            let game = instance.game.inner_ref();
+                            if game.player_weapon().is_some() {
+                                self.aim = Some((Aim::Weapon, game.player_coord()));
+                            }
+                            (running.into_witness(), Ok(()))
+                        }
+                        AppInput::Interact => {
+                            self.choosing_interaction = true;
+                            (running.into_witness(), Ok(()))
+                        }
+                        AppInput::PickUp => running.pick_up(&mut instance.game, &self.game_config),
+                        AppInput::Inventory => {
+                            return GameLoopState::Inventory(running, InventoryAction::Drop);
+                        }
+                        AppInput::ThrowItem => {
+                            return GameLoopState::Inventory(running, InventoryAction::Throw);
+                        }
+                        AppInput::SwapWeapons => {
+                            running.swap_weapons(&mut instance.game, &self.game_config)
+                        }
+                        AppInput::MessageHistory => {
+                            return GameLoopState::MessageHistory(running);
+                        }
+                        AppInput::ToggleControlsOverlay => {
+                            self.show_controls_overlay = !self.show_controls_overlay;
+                            (running.into_witness(), Ok(()))
+                        }
+                        AppInput::Undo => (running.undo(&mut instance.game), Ok(())),
+                        AppInput::ToggleAiDebug => {
+                            if self.game_config.debug {
+                                self.show_ai_debug = !self.show_ai_debug;
+                            }
+                            (running.into_witness(), Ok(()))
+                        }
+                        AppInput::DumpEventLog => {
+                            log::info!(
+                                "Recent events:\n{}",
+                                instance.game.inner_ref().event_log().dump()
+                            );
+                            (running.into_witness(), Ok(()))
+                        }
                     };
                     witness
                 } else {
@@ -381,14 +1450,73 @@ impl GameLoopData {
                 }
             }
             Event::Tick(since_previous) => {
-                running.tick(&mut instance.game, since_previous, &self.game_config)
+                match running.tick(&mut instance.game, since_previous, &self.game_config) {
+                    Witness::Running(running) => match self.auto_move {
+                        Some((auto_move, delay)) if delay > since_previous => {
+                            self.auto_move = Some((auto_move, delay - since_previous));
+                            Witness::Running(running)
+                        }
+                        Some((auto_move, _)) => {
+                            let (witness, keep_going) = auto_move_step(
+                                &mut instance.game,
+                                running,
+                                auto_move,
+                                &self.game_config,
+                            );
+                            self.auto_move =
+                                keep_going.then_some((auto_move, AUTO_MOVE_STEP_DELAY));
+                            witness
+                        }
+                        None => Witness::Running(running),
+                    },
+                    witness => {
+                        self.auto_move = None;
+                        witness
+                    }
+                }
             }
             _ => Witness::Running(running),
         };
+        let radiation = match witness {
+            Witness::Running(_) => instance.game.inner_ref().radiation_at_player(),
+            _ => 0,
+        };
+        crate::sfx::set_radiation_level(radiation);
+        let events = instance.game.take_external_events();
+        effect::dispatch(&events, &mut effect::EffectContext { instance });
+        self.update_codex();
+        self.update_hints();
         GameLoopState::Playing(witness)
     }
 }
 
+/// Movement which the app keeps repeating until it fails or the player presses something
+#[derive(Clone, Copy)]
+enum AutoMove {
+    Explore,
+    Travel(Coord),
+}
+
+/// Takes a step of automatic movement. Returns whether it's safe to keep going.
+fn auto_move_step(
+    game: &mut witness::Game,
+    running: witness::Running,
+    auto_move: AutoMove,
+    config: &GameConfig,
+) -> (Witness, bool) {
+    let (witness, result) = match auto_move {
+        AutoMove::Explore => running.autoexplore(game, config),
+        AutoMove::Travel(destination) => running.travel_to(game, destination, config),
+    };
+    let exploring = matches!(auto_move, AutoMove::Explore);
+    // travelling goes where the player asked, but exploring stops when danger comes into view
+    let keep_going = result.is_ok()
+        && matches!(witness, Witness::Running(_))
+        && !game.inner_ref().is_npc_visible()
+        && !(exploring && game.inner_ref().is_hazard_visible());
+    (witness, keep_going)
+}
+
 struct GameInstanceComponent(Option<witness::Running>);
 
 impl GameInstanceComponent {
@@ -399,8 +1527,14 @@ impl GameInstanceComponent {
 
 pub enum GameLoopState {
     Paused(witness::Running),
+    /// Choosing what to do to the cell at the given coordinate
+    Interact(witness::Running, Coord),
+    MessageHistory(witness::Running),
+    Inventory(witness::Running, InventoryAction),
     Playing(Witness),
     MainMenu,
+    /// Measuring performance with a worst-case scene. The app exits when the test is over.
+    StressTest,
 }
 
 impl Component for GameInstanceComponent {
@@ -411,9 +1545,44 @@ impl Component for GameInstanceComponent {
         state.render(ctx, fb);
     }
 
-    fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+    fn update(&mut self, state: &mut Self::State, ctx: Ctx, event: Event) -> Self::Output {
+        use chargrid::input::{Input, MouseButton, MouseInput};
         let running = witness::Running::cheat(); // XXX
-        if event.is_escape() {
+        if let Event::Input(Input::Mouse(MouseInput::MouseMove { coord, .. })) = event {
+            state.cursor = Some(coord - ctx.bounding_box.top_left());
+        }
+        // pressing anything interrupts autoexplore and travel
+        if let Event::Input(Input::Keyboard(_) | Input::Gamepad(_)) = event {
+            state.auto_move = None;
+        }
+        if let Event::Input(Input::Mouse(MouseInput::MousePress {
+            button: MouseButton::Left,
+            coord,
+        })) = event
+        {
+            let world_coord = state.screen_to_world_coord(
+                coord - ctx.bounding_box.top_left(),
+                ctx.bounding_box.size(),
+            );
+            // the first step is taken on the next tick
+            state.auto_move = Some((AutoMove::Travel(world_coord), Duration::ZERO));
+            return GameLoopState::Playing(running.into_witness());
+        }
+        if let Event::Input(Input::Mouse(MouseInput::MousePress {
+            button: MouseButton::Right,
+            coord,
+        })) = event
+        {
+            let world_coord = state.screen_to_world_coord(
+                coord - ctx.bounding_box.top_left(),
+                ctx.bounding_box.size(),
+            );
+            return state.interact_at(world_coord, running);
+        }
+        if event.is_escape() && state.cancel_targeting() {
+            GameLoopState::Playing(running.into_witness())
+        } else if event.is_escape() {
+            crate::sfx::set_radiation_level(0);
             GameLoopState::Paused(running)
         } else {
             state.update(event, running)
@@ -439,7 +1608,18 @@ fn menu_style<T: 'static>(menu: AppCF<T>) -> AppCF<T> {
 #[derive(Clone)]
 enum MainMenuEntry {
     NewGame,
+    NewIronmanGame,
+    NewCasualGame,
+    NewHotSeatGame,
+    Difficulty,
+    Layout,
+    Modifiers,
+    LevelEditor,
+    Tutorial,
     Help,
+    Codex,
+    Controls,
+    Credits,
     Quit,
 }
 
@@ -457,18 +1637,35 @@ fn title_decorate<T: 'static>(cf: AppCF<T>) -> AppCF<T> {
 fn main_menu() -> AppCF<MainMenuEntry> {
     use menu::builder::*;
     use MainMenuEntry::*;
-    let mut builder = menu_builder().vi_keys();
-    let mut add_item = |entry, name, ch: char| {
-        let identifier =
-            MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
-        builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
-    };
-    add_item(NewGame, "New Game", 'n');
-    add_item(Help, "Help", 'h');
-    if !cfg!(feature = "web") {
-        add_item(Quit, "Quit", 'q');
-    }
-    builder.build_cf()
+    on_state_then(|state: &mut State| {
+        let locale = state.locale();
+        let mut builder = menu_builder().vi_keys();
+        let mut add_item = |entry, key, ch: char| {
+            let name = locale.get(key).to_string();
+            let identifier =
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+            builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
+        };
+        add_item(NewGame, "menu.new_game", 'n');
+        if !cfg!(feature = "web") {
+            add_item(NewIronmanGame, "menu.new_ironman_game", 'i');
+        }
+        add_item(NewCasualGame, "menu.new_casual_game", 'a');
+        add_item(NewHotSeatGame, "menu.new_hot_seat_game", 's');
+        add_item(Difficulty, "menu.difficulty", 'd');
+        add_item(Layout, "menu.layout", 'o');
+        add_item(Modifiers, "menu.modifiers", 'm');
+        add_item(LevelEditor, "menu.level_editor", 'l');
+        add_item(Tutorial, "menu.tutorial", 't');
+        add_item(Help, "menu.help", 'h');
+        add_item(Codex, "menu.codex", 'x');
+        add_item(Controls, "menu.controls", 'k');
+        add_item(Credits, "menu.credits", 'c');
+        if !cfg!(feature = "web") {
+            add_item(Quit, "menu.quit", 'q');
+        }
+        builder.build_cf()
+    })
 }
 
 enum MainMenuOutput {
@@ -498,10 +1695,51 @@ fn main_menu_loop() -> AppCF<MainMenuOutput> {
                 })
             })
             .break_(),
+        NewIronmanGame => text::loading(MAIN_MENU_TEXT_WIDTH)
+            .centre()
+            .overlay(background(), 1)
+            .then(|| {
+                on_state(|state: &mut State| MainMenuOutput::NewGame {
+                    new_running: state.new_ironman_game(),
+                })
+            })
+            .break_(),
+        NewCasualGame => text::loading(MAIN_MENU_TEXT_WIDTH)
+            .centre()
+            .overlay(background(), 1)
+            .then(|| {
+                on_state(|state: &mut State| MainMenuOutput::NewGame {
+                    new_running: state.new_casual_game(),
+                })
+            })
+            .break_(),
+        NewHotSeatGame => text::loading(MAIN_MENU_TEXT_WIDTH)
+            .centre()
+            .overlay(background(), 1)
+            .then(|| {
+                on_state(|state: &mut State| MainMenuOutput::NewGame {
+                    new_running: state.new_hot_seat_game(),
+                })
+            })
+            .break_(),
+        Tutorial => on_state(|state: &mut State| MainMenuOutput::NewGame {
+            new_running: state.new_tutorial(),
+        })
+        .break_(),
         Help => text::help(MAIN_MENU_TEXT_WIDTH)
             .centre()
             .overlay(background(), 1)
             .continue_(),
+        Codex => codex().centre().overlay(background(), 1).continue_(),
+        Controls => controls().centre().overlay(background(), 1).continue_(),
+        Difficulty => difficulty().centre().overlay(background(), 1).continue_(),
+        Layout => layout_style().centre().overlay(background(), 1).continue_(),
+        Modifiers => modifiers().centre().overlay(background(), 1).continue_(),
+        LevelEditor => level_editor().and_then(|running| match running {
+            Some(new_running) => val_once(MainMenuOutput::NewGame { new_running }).break_(),
+            None => val_once(()).continue_(),
+        }),
+        Credits => credits().continue_(),
         Quit => val_once(MainMenuOutput::Quit).break_(),
     })
 }
@@ -513,27 +1751,228 @@ enum PauseMenuEntry {
     Save,
     NewGame,
     Help,
+    Codex,
+    Controls,
+    Gore,
+    Timer,
+    Palette,
+    Language,
+    Hints,
+    Undo,
     Clear,
 }
 
 fn pause_menu() -> AppCF<PauseMenuEntry> {
     use menu::builder::*;
     use PauseMenuEntry::*;
-    let mut builder = menu_builder().vi_keys();
-    let mut add_item = |entry, name, ch: char| {
-        let identifier =
-            MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
-        builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
-    };
-    add_item(Resume, "Resume", 'r');
-    if !cfg!(feature = "web") {
-        add_item(SaveQuit, "Save and Quit", 'q');
-        add_item(Save, "Save", 's');
+    on_state_then(|state: &mut State| {
+        let locale = state.locale();
+        let mut builder = menu_builder().vi_keys();
+        let mut add_item = |entry, key, ch: char| {
+            let name = locale.get(key).to_string();
+            let identifier =
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+            builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
+        };
+        add_item(Resume, "menu.resume", 'r');
+        if !cfg!(feature = "web") {
+            add_item(SaveQuit, "menu.save_and_quit", 'q');
+            add_item(Save, "menu.save", 's');
+        }
+        add_item(NewGame, "menu.new_game", 'n');
+        add_item(Help, "menu.help", 'h');
+        add_item(Codex, "menu.codex", 'x');
+        add_item(Controls, "menu.controls", 'k');
+        add_item(Gore, "menu.toggle_gore", 'g');
+        add_item(Timer, "menu.toggle_timer", 't');
+        add_item(Palette, "menu.cycle_palette", 'p');
+        add_item(Language, "menu.cycle_language", 'l');
+        add_item(Hints, "menu.toggle_hints", 'i');
+        add_item(Undo, "menu.toggle_undo", 'u');
+        add_item(Clear, "menu.clear", 'c');
+        builder.build_cf()
+    })
+}
+
+/// Lists everything discovered so far, showing the details of entries as they are chosen
+fn codex() -> AppCF<()> {
+    use menu::builder::*;
+    on_state_then(|state: &mut State| {
+        let locale = state.locale();
+        let mut builder = menu_builder().vi_keys();
+        for &entry in CodexEntry::ALL {
+            let discovered = state.config.codex.contains(&entry);
+            let name = locale.get(entry.name_key()).to_string();
+            let identifier = MENU_FADE_SPEC.identifier(move |b| {
+                if discovered {
+                    write!(b, "{}", name).unwrap()
+                } else {
+                    write!(b, "???").unwrap()
+                }
+            });
+            builder.add_item_mut(item((entry, discovered), identifier));
+        }
+        builder.build_cf().menu_harness()
+    })
+    .repeat_unit(|entry_or_escape| match entry_or_escape {
+        Ok((entry, true)) => text::codex_entry(MAIN_MENU_TEXT_WIDTH, entry).continue_(),
+        Ok((_, false)) => val_once(()).continue_(),
+        Err(_escape_or_start) => val_once(()).break_(),
+    })
+}
+
+/// Waits for a key or gamepad button to bind to an app input
+struct RebindPrompt(AppInput);
+
+impl Component for RebindPrompt {
+    type Output = Option<()>;
+    type State = State;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let locale = state.locale();
+        let string = locale.format("controls.rebind_prompt", &[&self.0.description(locale)]);
+        let styled_string = chargrid::text::StyledString {
+            string,
+            style: Style::plain_text(),
+        };
+        styled_string.render(&(), ctx, fb);
     }
-    add_item(NewGame, "New Game", 'n');
-    add_item(Help, "Help", 'h');
-    add_item(Clear, "Clear", 'c');
-    builder.build_cf()
+
+    fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        if event.is_escape() {
+            return Some(());
+        }
+        match event {
+            Event::Input(input) => {
+                state.bind_control(input, self.0.clone());
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        ctx.bounding_box.size()
+    }
+}
+
+/// Yields the chosen app input, or `None` if the menu was closed
+fn controls_menu() -> AppCF<Option<AppInput>> {
+    use menu::builder::*;
+    on_state_then(|state: &mut State| {
+        let mut builder = menu_builder().vi_keys();
+        for app_input in AppInput::all() {
+            let (key, button) = state.controls.binding_names(&app_input);
+            let name = format!(
+                "{:24}{:10}{}",
+                app_input.description(state.locale()),
+                key.unwrap_or_default(),
+                button.unwrap_or_default()
+            );
+            let identifier = MENU_FADE_SPEC.identifier(move |b| write!(b, "{}", name).unwrap());
+            builder.add_item_mut(item(app_input, identifier));
+        }
+        builder.build_cf().menu_harness().map(|result| result.ok())
+    })
+}
+
+/// Lists the difficulties with the current one marked, and sets the difficulty of new games to
+/// the one chosen
+fn difficulty() -> AppCF<()> {
+    use menu::builder::*;
+    on_state_then(|state: &mut State| {
+        let locale = state.locale();
+        let mut builder = menu_builder().vi_keys();
+        for &difficulty in Difficulty::ALL {
+            let mark = if state.config.difficulty == difficulty {
+                '*'
+            } else {
+                ' '
+            };
+            let name = locale.get(difficulty.name_key()).to_string();
+            let identifier =
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", mark, name).unwrap());
+            builder.add_item_mut(item(difficulty, identifier));
+        }
+        builder.build_cf().menu_harness()
+    })
+    .and_then_side_effect(|result, state: &mut State| {
+        if let Ok(difficulty) = result {
+            state.set_difficulty(difficulty);
+        }
+        val_once(())
+    })
+}
+
+/// Lists the layout styles with the current one marked, and sets the layout style of new games
+/// to the one chosen
+fn layout_style() -> AppCF<()> {
+    use menu::builder::*;
+    on_state_then(|state: &mut State| {
+        let locale = state.locale();
+        let mut builder = menu_builder().vi_keys();
+        for &layout_style in LayoutStyle::ALL {
+            let mark = if state.config.layout_style == layout_style {
+                '*'
+            } else {
+                ' '
+            };
+            let name = locale.get(layout_style.name_key()).to_string();
+            let identifier =
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", mark, name).unwrap());
+            builder.add_item_mut(item(layout_style, identifier));
+        }
+        builder.build_cf().menu_harness()
+    })
+    .and_then_side_effect(|result, state: &mut State| {
+        if let Ok(layout_style) = result {
+            state.set_layout_style(layout_style);
+        }
+        val_once(())
+    })
+}
+
+/// Yields the chosen run modifier, or `None` if the menu was closed
+fn modifiers_menu() -> AppCF<Option<RunModifier>> {
+    use menu::builder::*;
+    on_state_then(|state: &mut State| {
+        let locale = state.locale();
+        let mut builder = menu_builder().vi_keys();
+        for &modifier in RunModifier::ALL {
+            let check = if state.config.modifiers.contains(modifier) {
+                'x'
+            } else {
+                ' '
+            };
+            let name = locale.get(modifier.name_key()).to_string();
+            let identifier =
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "[{}] {}", check, name).unwrap());
+            builder.add_item_mut(item(modifier, identifier));
+        }
+        builder.build_cf().menu_harness().map(|result| result.ok())
+    })
+}
+
+/// Lists the run modifiers, toggling each one as it's chosen. Modifiers apply to new games.
+fn modifiers() -> AppCF<()> {
+    loop_((), |()| {
+        modifiers_menu().and_then(|modifier_or_escape| match modifier_or_escape {
+            Some(modifier) => {
+                on_state(move |state: &mut State| state.toggle_modifier(modifier)).continue_()
+            }
+            None => val_once(()).break_(),
+        })
+    })
+}
+
+/// Lists the key and gamepad button bound to each app input, allowing them to be rebound
+fn controls() -> AppCF<()> {
+    loop_((), |()| {
+        controls_menu().and_then(|entry_or_escape| match entry_or_escape {
+            Some(app_input) => cf(RebindPrompt(app_input)).continue_(),
+            None => val_once(()).break_(),
+        })
+    })
 }
 
 fn pause_menu_loop(running: witness::Running) -> AppCF<PauseOutput> {
@@ -544,37 +1983,55 @@ fn pause_menu_loop(running: witness::Running) -> AppCF<PauseOutput> {
         .repeat(
             running,
             move |running, entry_or_escape| match entry_or_escape {
-                Ok(entry) => match entry {
-                    Resume => break_(PauseOutput::ContinueGame { running }),
-                    SaveQuit => text::saving(MAIN_MENU_TEXT_WIDTH)
-                        .then(|| {
-                            on_state(|state: &mut State| {
-                                state.save_instance(running);
-                                PauseOutput::Quit
+                Ok(entry) => {
+                    match entry {
+                        Resume => break_(PauseOutput::ContinueGame { running }),
+                        SaveQuit => text::saving(MAIN_MENU_TEXT_WIDTH)
+                            .then(|| {
+                                on_state(|state: &mut State| {
+                                    state.save_instance(running);
+                                    PauseOutput::Quit
+                                })
                             })
-                        })
-                        .break_(),
-                    Save => text::saving(MAIN_MENU_TEXT_WIDTH)
-                        .then(|| {
-                            on_state(|state: &mut State| PauseOutput::ContinueGame {
-                                running: state.save_instance(running),
+                            .break_(),
+                        Save => text::saving(MAIN_MENU_TEXT_WIDTH)
+                            .then(|| {
+                                on_state(|state: &mut State| PauseOutput::ContinueGame {
+                                    running: state.save_instance(running),
+                                })
                             })
-                        })
-                        .break_(),
-                    NewGame => text::loading(MAIN_MENU_TEXT_WIDTH)
-                        .then(|| {
-                            on_state(|state: &mut State| PauseOutput::ContinueGame {
-                                running: state.new_game(),
+                            .break_(),
+                        NewGame => text::loading(MAIN_MENU_TEXT_WIDTH)
+                            .then(|| {
+                                on_state(|state: &mut State| PauseOutput::ContinueGame {
+                                    running: state.new_game(),
+                                })
                             })
+                            .break_(),
+                        Help => text::help(text_width).continue_with(running),
+                        Codex => codex().continue_with(running),
+                        Controls => controls().continue_with(running),
+                        Gore => {
+                            on_state(|state: &mut State| state.toggle_gore()).continue_with(running)
+                        }
+                        Timer => on_state(|state: &mut State| state.toggle_timer())
+                            .continue_with(running),
+                        Palette => on_state(|state: &mut State| state.cycle_palette())
+                            .continue_with(running),
+                        Language => on_state(|state: &mut State| state.cycle_language())
+                            .continue_with(running),
+                        Hints => on_state(|state: &mut State| state.toggle_hints())
+                            .continue_with(running),
+                        Undo => {
+                            on_state(|state: &mut State| state.toggle_undo()).continue_with(running)
+                        }
+                        Clear => on_state(|state: &mut State| {
+                            state.clear_saved_game();
+                            PauseOutput::MainMenu
                         })
                         .break_(),
-                    Help => text::help(text_width).continue_with(running),
-                    Clear => on_state(|state: &mut State| {
-                        state.clear_saved_game();
-                        PauseOutput::MainMenu
-                    })
-                    .break_(),
-                },
+                    }
+                }
                 Err(_escape_or_start) => break_(PauseOutput::ContinueGame { running }),
             },
         )
@@ -590,18 +2047,203 @@ fn pause(running: witness::Running) -> AppCF<PauseOutput> {
     menu_style(pause_menu_loop(running))
 }
 
+fn interaction_menu(running: witness::Running, coord: Coord) -> AppCF<Witness> {
+    use menu::builder::*;
+    menu_style(
+        on_state_then(move |state: &mut State| {
+            let interactions = state
+                .instance
+                .as_ref()
+                .unwrap()
+                .game
+                .inner_ref()
+                .available_actions_at(coord);
+            let locale = state.locale();
+            let mut builder = menu_builder().vi_keys();
+            for (i, interaction) in interactions.into_iter().enumerate() {
+                let ch = std::char::from_digit(i as u32 + 1, 10).unwrap();
+                let name = locale.get(interaction.name_key()).to_string();
+                let identifier =
+                    MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+                builder.add_item_mut(item(interaction, identifier).add_hotkey_char(ch));
+            }
+            builder.build_cf().menu_harness()
+        })
+        .and_then_side_effect(move |result, state: &mut State| {
+            let witness = match result {
+                Ok(interaction) => {
+                    let instance = state.instance.as_mut().unwrap();
+                    let (witness, _action_result) = running.interact(
+                        &mut instance.game,
+                        coord,
+                        interaction,
+                        &state.game_config,
+                    );
+                    witness
+                }
+                Err(_escape_or_start) => running.into_witness(),
+            };
+            val_once(witness)
+        }),
+    )
+}
+
+/// What choosing an item from the inventory menu does
+#[derive(Clone, Copy)]
+pub enum InventoryAction {
+    Drop,
+    /// Starts aiming the item, which is thrown once the player picks a target
+    Throw,
+}
+
+/// Lists the items the player is carrying. Choosing one drops or throws it.
+fn inventory_menu(running: witness::Running, action: InventoryAction) -> AppCF<Witness> {
+    use chargrid::text::*;
+    use menu::builder::*;
+    menu_style(
+        on_state_then(move |state: &mut State| {
+            let game = state.instance.as_ref().unwrap().game.inner_ref();
+            let items = game.player_inventory();
+            let verb = match action {
+                InventoryAction::Drop => "drop",
+                InventoryAction::Throw => "throw",
+            };
+            let title = format!(
+                "Carrying {}/{}. Choose an item to {} it.",
+                items.len(),
+                game.player_inventory_capacity(),
+                verb
+            );
+            let mut builder = menu_builder().vi_keys();
+            for (slot, examination) in items.into_iter().enumerate() {
+                let ch = std::char::from_digit(slot as u32 + 1, 10).unwrap();
+                let name = state.locale().get(&examination.description).to_string();
+                let identifier =
+                    MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+                builder.add_item_mut(item(slot, identifier).add_hotkey_char(ch));
+            }
+            let title = Text::new(rich_text::spans(&title))
+                .wrap_word()
+                .cf::<State>()
+                .set_width(36);
+            builder
+                .build_cf()
+                .menu_harness()
+                .with_title_vertical(title, 2)
+        })
+        .and_then_side_effect(move |result, state: &mut State| {
+            let witness = match result {
+                Ok(slot) => match action {
+                    InventoryAction::Drop => {
+                        let instance = state.instance.as_mut().unwrap();
+                        let (witness, _action_result) =
+                            running.drop_item(&mut instance.game, slot, &state.game_config);
+                        witness
+                    }
+                    InventoryAction::Throw => {
+                        let instance = state.instance.as_ref().unwrap();
+                        state.aim =
+                            Some((Aim::Throw(slot), instance.game.inner_ref().player_coord()));
+                        running.into_witness()
+                    }
+                },
+                Err(_escape_or_start) => running.into_witness(),
+            };
+            val_once(witness)
+        }),
+    )
+}
+
+fn message_history() -> AppCF<()> {
+    cf(MessageHistory::default()).fill(MENU_BACKGROUND)
+}
+
+/// Yields the running game if the level is being tested
+fn level_editor() -> AppCF<Option<witness::Running>> {
+    on_state_then(|state: &mut State| {
+        let design = state.storage.load_level_design().unwrap_or_default();
+        cf(Editor::new(design)).fill(MENU_BACKGROUND)
+    })
+}
+
+fn credits() -> AppCF<()> {
+    cf(Credits::default()).fill(MENU_BACKGROUND)
+}
+
 fn game_instance_component(running: witness::Running) -> AppCF<GameLoopState> {
     cf(GameInstanceComponent::new(running)).some().no_peek()
 }
 
-fn win() -> AppCF<()> {
-    text::win(MAIN_MENU_TEXT_WIDTH)
+fn win(ending: Ending) -> AppCF<()> {
+    on_state_then(move |state: &mut State| {
+        state.record_clear();
+        state.record_run(RunOutcome::Won(ending));
+        text::win(MAIN_MENU_TEXT_WIDTH, ending, state.record_splits())
+    })
+}
+
+/// Shows the story interlude leading into the player's new level, if it has one
+fn level_change(level: u32) -> AppCF<()> {
+    on_state_then(move |state: &mut State| {
+        state.record_clear();
+        match interlude_before_level(level) {
+            Some(Interlude { text, image, .. }) => {
+                rich_text::rich_text(MAIN_MENU_TEXT_WIDTH, &text)
+                    .centre()
+                    .overlay(
+                        render_state(move |state: &State, ctx, fb| {
+                            state.images.image_from_menu_image(image).render(ctx, fb)
+                        }),
+                        1,
+                    )
+            }
+            None => val_once(()),
+        }
+    })
+}
+
+/// Offers to carry the player's character into a new game+ after a win
+fn new_game_plus_offer() -> AppCF<GameLoopState> {
+    use menu::builder::*;
+    on_state_then(|state: &mut State| {
+        let locale = state.locale();
+        let mut builder = menu_builder().vi_keys();
+        let mut add_item = |new_game_plus: bool, key, ch: char| {
+            let name = locale.get(key).to_string();
+            let identifier =
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "({}) {}", ch, name).unwrap());
+            builder.add_item_mut(item(new_game_plus, identifier).add_hotkey_char(ch));
+        };
+        add_item(true, "menu.new_game_plus", 'n');
+        add_item(false, "menu.main_menu", 'm');
+        builder.build_cf().menu_harness()
+    })
+    .and_then(|choice_or_escape| match choice_or_escape {
+        Ok(true) => text::loading(MAIN_MENU_TEXT_WIDTH).then(|| {
+            on_state(|state: &mut State| {
+                GameLoopState::Playing(state.new_game_plus().into_witness())
+            })
+        }),
+        Ok(false) | Err(_) => val_once(GameLoopState::MainMenu),
+    })
+    .centre()
+    .overlay(background(), 1)
+}
+
+fn tutorial_complete() -> AppCF<()> {
+    on_state_then(|state: &mut State| {
+        state.clear_saved_game();
+        text::tutorial_complete(MAIN_MENU_TEXT_WIDTH)
+    })
+    .centre()
+    .overlay(background(), 1)
 }
 
 fn game_over(reason: GameOverReason) -> AppCF<()> {
     on_state_then(move |state: &mut State| {
         state.clear_saved_game();
-        state.save_config();
+        state.record_death();
+        state.record_run(RunOutcome::Died(reason));
         text::game_over(MAIN_MENU_TEXT_WIDTH, reason)
     })
     .centre()
@@ -612,52 +2254,52 @@ fn game_menu(menu_witness: witness::Menu) -> AppCF<Witness> {
     use chargrid::align::*;
     use game::MenuChoice;
     use menu::builder::*;
-    let mut builder = menu_builder();
-    let mut add_item = |entry: MenuChoice, name: String, ch: char| {
-        let identifier = MENU_FADE_SPEC.identifier(move |b| write!(b, "{}. {}", ch, name).unwrap());
-        builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
-    };
-    for (i, choice) in menu_witness.menu.choices.iter().enumerate() {
-        let ch = std::char::from_digit(i as u32 + 1, 10).unwrap();
-        match choice {
-            MenuChoice::Dummy => add_item(choice.clone(), "Dummy".to_string(), ch),
+    let request = menu_witness.request.clone();
+    let menu_cf = on_state_then(move |state: &mut State| {
+        let locale = state.locale();
+        let mut builder = menu_builder();
+        let mut add_item = |entry: MenuChoice, name: String, ch: char| {
+            let identifier =
+                MENU_FADE_SPEC.identifier(move |b| write!(b, "{}. {}", ch, name).unwrap());
+            builder.add_item_mut(item(entry, identifier).add_hotkey_char(ch));
+        };
+        for (i, &choice) in request.choices.iter().enumerate() {
+            let ch = std::char::from_digit(i as u32 + 1, 10).unwrap();
+            add_item(choice, locale.get(choice.label_key()).to_string(), ch);
         }
-    }
-    let title = {
-        use chargrid::text::*;
-        Text::new(vec![StyledString {
-            string: menu_witness.menu.text.clone(),
-            style: Style::plain_text(),
-        }])
-        .wrap_word()
-        .cf::<State>()
-        .set_width(36)
-    };
-    let menu_cf = builder
-        .build_cf()
-        .menu_harness()
-        .add_x(2)
-        .with_title_vertical(title, 2)
-        .align(Alignment {
-            x: AlignmentX::Left,
-            y: AlignmentY::Centre,
-        })
-        .add_x(4)
-        .overlay(
-            render_state(move |state: &State, ctx, fb| {
-                state
-                    .images
-                    .image_from_menu_image(menu_witness.menu.image)
-                    .render(ctx, fb)
-            }),
-            1,
-        );
+        let title = {
+            use chargrid::text::*;
+            Text::new(rich_text::spans(locale.get(request.text_key)))
+                .wrap_word()
+                .cf::<State>()
+                .set_width(36)
+        };
+        builder
+            .build_cf()
+            .menu_harness()
+            .add_x(2)
+            .with_title_vertical(title, 2)
+            .align(Alignment {
+                x: AlignmentX::Left,
+                y: AlignmentY::Centre,
+            })
+            .add_x(4)
+            .overlay(
+                render_state(move |state: &State, ctx, fb| {
+                    state
+                        .images
+                        .image_from_menu_image(request.image)
+                        .render(ctx, fb)
+                }),
+                1,
+            )
+    });
     menu_cf.and_then_side_effect(|result, state: &mut State| {
         let witness = match result {
             Err(Close) => menu_witness.cancel(),
             Ok(choice) => {
                 if let Some(instance) = state.instance.as_mut() {
-                    menu_witness.commit(&mut instance.game, choice.clone())
+                    menu_witness.commit(&mut instance.game, choice, &state.game_config)
                 } else {
                     menu_witness.cancel()
                 }
@@ -674,11 +2316,32 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
         .then(|| {
             loop_(initial_state, |state| match state {
                 Playing(witness) => match witness {
-                    Witness::Running(running) => game_instance_component(running).continue_(),
+                    Witness::Running(running) => on_state_then(move |state: &mut State| {
+                        game_instance_component(state.ironman_autosave(running))
+                    })
+                    .continue_(),
                     Witness::GameOver(reason) => game_over(reason).map_val(|| MainMenu).continue_(),
-                    Witness::Win(_) => win().map_val(|| MainMenu).continue_(),
+                    Witness::Win(win_) => win(win_.ending)
+                        .then(credits)
+                        .then(new_game_plus_offer)
+                        .continue_(),
                     Witness::Menu(menu_) => game_menu(menu_).map(Playing).continue_(),
+                    Witness::TutorialComplete => {
+                        tutorial_complete().map_val(|| MainMenu).continue_()
+                    }
+                    Witness::LevelChange(level_change_) => level_change(level_change_.level)
+                        .map_val(move || Playing(level_change_.into_running().into_witness()))
+                        .continue_(),
                 },
+                MessageHistory(running) => message_history()
+                    .map_val(|| Playing(running.into_witness()))
+                    .continue_(),
+                Interact(running, coord) => {
+                    interaction_menu(running, coord).map(Playing).continue_()
+                }
+                Inventory(running, action) => {
+                    inventory_menu(running, action).map(Playing).continue_()
+                }
                 Paused(running) => pause(running).map(|pause_output| match pause_output {
                     PauseOutput::ContinueGame { running } => {
                         LoopControl::Continue(Playing(running.into_witness()))
@@ -686,6 +2349,7 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
                     PauseOutput::MainMenu => LoopControl::Continue(MainMenu),
                     PauseOutput::Quit => LoopControl::Break(()),
                 }),
+                StressTest => cf(StressTest::default()).map_val(|| LoopControl::Break(())),
                 MainMenu => main_menu_loop().map(|main_menu_output| match main_menu_output {
                     MainMenuOutput::NewGame { new_running } => {
                         LoopControl::Continue(Playing(new_running.into_witness()))
@@ -696,7 +2360,7 @@ pub fn game_loop_component(initial_state: GameLoopState) -> AppCF<()> {
             .bound_size(Size::new_u16(80, 30))
             .on_each_tick({
                 use currawong::signal_player::SignalPlayer;
-                let mut signal = crate::music::signal();
+                let mut signal = crate::music::signal() + crate::sfx::signal();
                 let mut signal_player = SignalPlayer::new().unwrap();
                 signal_player.set_buffer_padding_sample_rate_ratio(0.25);
                 move || {
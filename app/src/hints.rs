@@ -0,0 +1,59 @@
+use crate::{
+    controls::{AppInput, Controls},
+    locale::Locale,
+};
+use game::Game;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long each hint stays on screen
+pub const HINT_DURATION: Duration = Duration::from_secs(6);
+/// Players who haven't aimed a grenade by this turn are reminded that they can
+const AIM_HINT_TURN: u64 = 100;
+
+/// A tip shown to the player the first time it becomes relevant. Each hint is only ever shown
+/// once, across all games.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    LowOxygen,
+    OnCorpse,
+    Aim,
+}
+
+impl Hint {
+    pub const ALL: &'static [Self] = &[Self::LowOxygen, Self::OnCorpse, Self::Aim];
+
+    /// Key of the hint's text in the locale
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::LowOxygen => "hint.low_oxygen",
+            Self::OnCorpse => "hint.on_corpse",
+            Self::Aim => "hint.aim",
+        }
+    }
+
+    /// The hint's text, naming the keys currently bound to the inputs it mentions
+    pub fn text(self, locale: &Locale, controls: &Controls) -> String {
+        match self {
+            Self::LowOxygen => locale.get(self.key()).to_string(),
+            Self::OnCorpse => {
+                locale.format(self.key(), &[&controls.bound_key_name(AppInput::Search)])
+            }
+            Self::Aim => locale.format(
+                self.key(),
+                &[&controls.bound_key_name(AppInput::ThrowGrenade)],
+            ),
+        }
+    }
+
+    pub fn is_relevant(self, game: &Game) -> bool {
+        match self {
+            Self::LowOxygen => {
+                let (current, max) = game.player_oxygen().current_and_max();
+                current * 2 <= max
+            }
+            Self::OnCorpse => game.is_player_on_unsearched_corpse(),
+            Self::Aim => game.turn() >= AIM_HINT_TURN,
+        }
+    }
+}
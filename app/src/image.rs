@@ -51,6 +51,8 @@ impl Images {
     }
 
     pub fn image_from_menu_image(&self, menu_image: MenuImage) -> &Image {
-        match menu_image {}
+        match menu_image {
+            MenuImage::Placeholder => &self.placeholder,
+        }
     }
 }
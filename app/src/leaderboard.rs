@@ -0,0 +1,15 @@
+use game::RunRecord;
+
+/// Somewhere to send the records of completed runs, such as a community leaderboard server.
+/// Records are also exported to storage as json regardless of where (or whether) they are
+/// submitted.
+pub trait Leaderboard {
+    fn submit(&mut self, record: &RunRecord);
+}
+
+/// Keeps records local to the player's own storage
+pub struct NoLeaderboard;
+
+impl Leaderboard for NoLeaderboard {
+    fn submit(&mut self, _record: &RunRecord) {}
+}
@@ -2,13 +2,27 @@ use chargrid::{control_flow::*, core::*};
 use game::Config;
 
 mod controls;
+mod credits;
+mod editor;
+mod effect;
 mod game_instance;
 mod game_loop;
+mod hints;
 mod image;
+mod leaderboard;
+mod locale;
+mod message_history;
 mod music;
+mod palette;
+mod rich_text;
+mod sfx;
+mod stress;
 mod text;
+mod tile_registry;
 
 pub use game_loop::{AppStorage, InitialRngSeed};
+pub use leaderboard::{Leaderboard, NoLeaderboard};
+pub use tile_registry::{TileDef, TileRegistry};
 
 pub const NAME: &'static str = "Placeholder";
 
@@ -21,6 +35,12 @@ pub struct AppArgs {
     pub initial_rng_seed: InitialRngSeed,
     pub omniscient: bool,
     pub new_game: bool,
+    /// Runs the stress test scene and reports timings instead of playing the game
+    pub stress: bool,
+    /// Enables debugging tools
+    pub wizard: bool,
+    /// Where records of completed runs are submitted
+    pub leaderboard: Box<dyn Leaderboard>,
 }
 
 pub fn app(
@@ -29,15 +49,31 @@ pub fn app(
         initial_rng_seed,
         omniscient,
         new_game,
+        stress,
+        wizard,
+        leaderboard,
     }: AppArgs,
 ) -> impl Component<Output = app::Output, State = ()> {
     let config = Config {
         omniscient: if omniscient { Config::OMNISCIENT } else { None },
         demo: false,
-        debug: false,
+        debug: wizard,
+        gore: true,
+        balance: Default::default(),
+        performance: Default::default(),
+        ironman: false,
+        casual: false,
+        hot_seat: false,
+        undo: false,
+        modifiers: Default::default(),
+        difficulty: Default::default(),
+        layout_style: Default::default(),
     };
-    let (game_loop_data, initial_state) =
-        game_loop::GameLoopData::new(config, storage, initial_rng_seed, new_game);
+    let (mut game_loop_data, mut initial_state) =
+        game_loop::GameLoopData::new(config, storage, initial_rng_seed, new_game, leaderboard);
+    if stress {
+        initial_state = game_loop_data.start_stress_test();
+    }
     let state = AppState { game_loop_data };
     game_loop::game_loop_component(initial_state)
         .lens_state(lens!(AppState[game_loop_data]: game_loop::GameLoopData))
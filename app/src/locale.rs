@@ -0,0 +1,66 @@
+use game::Message;
+use serde::Deserialize;
+use std::{collections::HashMap, fmt::Display};
+
+const LOCALES: &str = include_str!("locales.ron");
+
+/// Player-facing strings in a single language, keyed by identifier
+#[derive(Debug, Clone, Deserialize)]
+pub struct Locale {
+    pub name: String,
+    strings: HashMap<String, String>,
+}
+
+impl Locale {
+    /// The string for `key`, or the key itself if there's no string for it so missing strings
+    /// are easy to spot
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings.get(key).map(String::as_str).unwrap_or(key)
+    }
+
+    /// The string for `key` with each "{}" replaced by the next argument
+    pub fn format(&self, key: &str, args: &[&dyn Display]) -> String {
+        let mut parts = self.get(key).split("{}");
+        let mut string = parts.next().unwrap_or("").to_string();
+        let mut args = args.iter();
+        for part in parts {
+            if let Some(arg) = args.next() {
+                string.push_str(&arg.to_string());
+            }
+            string.push_str(part);
+        }
+        string
+    }
+
+    /// A message from the game in this locale. Each argument is looked up too, so arguments
+    /// which are keys are translated and anything else is shown as it is.
+    pub fn message(&self, message: &Message) -> String {
+        let args = message
+            .args
+            .iter()
+            .map(|arg| self.get(arg))
+            .collect::<Vec<_>>();
+        let args = args
+            .iter()
+            .map(|arg| arg as &dyn Display)
+            .collect::<Vec<_>>();
+        self.format(&message.key, &args)
+    }
+}
+
+/// The first locale is the default. Strings missing from other locales are taken from it, so a
+/// partial translation is still playable.
+pub fn load_locales() -> Vec<Locale> {
+    let mut locales: Vec<Locale> = ron::from_str(LOCALES).expect("failed to parse locales");
+    if let Some((default, others)) = locales.split_first_mut() {
+        for locale in others {
+            for (key, string) in default.strings.iter() {
+                locale
+                    .strings
+                    .entry(key.clone())
+                    .or_insert_with(|| string.clone());
+            }
+        }
+    }
+    locales
+}
@@ -0,0 +1,138 @@
+use crate::{game_instance::message_text, game_loop::State, locale::Locale};
+use chargrid::{
+    input::{Input, KeyboardInput},
+    prelude::*,
+    text::StyledString,
+};
+use game::{LogEntry, MessageCategory};
+
+/// Full-screen view of every message logged so far, which can be scrolled, filtered by
+/// category, and searched
+#[derive(Default)]
+pub struct MessageHistory {
+    /// Number of lines scrolled up from the most recent message
+    scroll: usize,
+    filter: Option<MessageCategory>,
+    query: String,
+    /// Keys are appended to the search query rather than controlling the view
+    searching: bool,
+}
+
+const FILTERS: &[(char, Option<MessageCategory>, &str)] = &[
+    ('1', None, "history.all"),
+    ('2', Some(MessageCategory::Combat), "history.combat"),
+    ('3', Some(MessageCategory::Items), "history.items"),
+    ('4', Some(MessageCategory::System), "history.system"),
+];
+
+impl MessageHistory {
+    /// The turn and text of each entry in the current category whose text contains the search
+    /// query, ignoring case
+    fn matching_entries(&self, state: &State, entries: &[LogEntry]) -> Vec<(u64, String)> {
+        let query = self.query.to_lowercase();
+        entries
+            .iter()
+            .filter(|entry| self.filter.is_none_or(|filter| entry.category == filter))
+            .map(|entry| {
+                let text = message_text(&entry.message, state.locale(), state.controls());
+                (entry.turn, text)
+            })
+            .filter(|(_, text)| text.to_lowercase().contains(query.as_str()))
+            .collect()
+    }
+
+    fn header(&self, locale: &Locale) -> String {
+        let filters = FILTERS
+            .iter()
+            .map(|&(key, filter, name)| {
+                let name = locale.get(name);
+                if filter == self.filter {
+                    format!("[{}] *{}*", key, name)
+                } else {
+                    format!("[{}] {}", key, name)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let cursor = if self.searching { "_" } else { "" };
+        format!(
+            "{}  [/] {}: {}{}",
+            filters,
+            locale.get("history.search"),
+            self.query,
+            cursor
+        )
+    }
+}
+
+impl Component for MessageHistory {
+    type Output = Option<()>;
+    type State = State;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        let header = StyledString {
+            string: self.header(state.locale()),
+            style: Style::plain_text().with_foreground(Rgba32::new_grey(255)),
+        };
+        header.render(&(), ctx, fb);
+        let entries = match state.message_log() {
+            Some(message_log) => self.matching_entries(state, message_log.entries()),
+            None => return,
+        };
+        let num_rows = (ctx.bounding_box.size().height() as usize).saturating_sub(2);
+        let end = entries.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(num_rows);
+        for (i, (turn, text)) in entries[start..end].iter().enumerate() {
+            let line = StyledString {
+                string: format!("{:>5} {}", turn, text),
+                style: Style::plain_text().with_foreground(Rgba32::new_grey(187)),
+            };
+            line.render(&(), ctx.add_y(i as i32 + 2), fb);
+        }
+    }
+
+    fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        let keyboard_input = match event {
+            Event::Input(Input::Keyboard(keyboard_input)) => keyboard_input,
+            _ => return None,
+        };
+        if self.searching {
+            match keyboard_input {
+                KeyboardInput::Char('\r') => self.searching = false,
+                KeyboardInput::Char('\u{1b}') => {
+                    self.searching = false;
+                    self.query.clear();
+                }
+                KeyboardInput::Char('\u{8}') | KeyboardInput::Char('\u{7f}') => {
+                    self.query.pop();
+                }
+                KeyboardInput::Char(c) if !c.is_control() => self.query.push(c),
+                _ => (),
+            }
+            self.scroll = 0;
+            return None;
+        }
+        let num_entries = state
+            .message_log()
+            .map(|message_log| self.matching_entries(state, message_log.entries()).len())
+            .unwrap_or(0);
+        match keyboard_input {
+            KeyboardInput::Char('\u{1b}') | KeyboardInput::Char('m') => return Some(()),
+            KeyboardInput::Char('/') => self.searching = true,
+            KeyboardInput::Up => self.scroll = (self.scroll + 1).min(num_entries),
+            KeyboardInput::Down => self.scroll = self.scroll.saturating_sub(1),
+            KeyboardInput::Char(c) => {
+                if let Some(&(_, filter, _)) = FILTERS.iter().find(|&&(key, _, _)| key == c) {
+                    self.filter = filter;
+                    self.scroll = 0;
+                }
+            }
+            _ => (),
+        }
+        None
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        ctx.bounding_box.size()
+    }
+}
@@ -1,5 +1,20 @@
 use currawong::prelude::*;
+use game::LevelTheme;
 use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+/// Length of the stinger played over the music at dramatic moments
+const STINGER_DURATION_S: f64 = 1.5;
+/// Pitch the stinger starts at, before sliding down an octave
+const STINGER_START_HZ: f64 = 220.0;
+
+/// Set by the game loop when a stinger should play, and cleared by the audio signal once it
+/// starts
+static STINGER_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Set by the game loop to one more than the index of the current level's theme in
+/// `LevelTheme::ALL`, or 0 if the level has no theme, and read by the ambient signal
+static AMBIENT_THEME: AtomicU8 = AtomicU8::new(0);
 
 const C_MAJOR_SCALE: &[NoteName] = &[
     NoteName::A,
@@ -166,7 +181,82 @@ fn drum_signal(trigger: Trigger) -> Sf64 {
     }
 }
 
+/// Plays a short falling chord over the music, e.g. when the boss changes phase
+pub fn play_stinger() {
+    STINGER_PENDING.store(true, Ordering::Relaxed);
+}
+
+/// A square wave and its fifth sliding down an octave and fading out
+fn stinger_signal() -> Sf64 {
+    let mut elapsed_s = STINGER_DURATION_S;
+    let mut phases = [0.0_f64; 2];
+    Signal::from_fn_mut(move |ctx| {
+        if STINGER_PENDING.swap(false, Ordering::Relaxed) {
+            elapsed_s = 0.0;
+        }
+        if elapsed_s >= STINGER_DURATION_S {
+            return 0.0;
+        }
+        let progress = elapsed_s / STINGER_DURATION_S;
+        elapsed_s += 1.0 / ctx.sample_rate_hz;
+        let root_hz = STINGER_START_HZ * (1.0 - progress * 0.5);
+        let mut sample = 0.0;
+        for (phase, ratio) in phases.iter_mut().zip([1.0, 1.5]) {
+            *phase = (*phase + root_hz * ratio / ctx.sample_rate_hz).fract();
+            sample += if *phase < 0.5 { 1.0 } else { -1.0 };
+        }
+        sample * (1.0 - progress)
+    })
+}
+
+/// Switches the ambient drone under the music to the one for levels of the given theme, or
+/// silences it
+pub fn set_theme(theme: Option<LevelTheme>) {
+    let index = theme
+        .and_then(|theme| LevelTheme::ALL.iter().position(|&other| other == theme))
+        .map_or(0, |index| index as u8 + 1);
+    AMBIENT_THEME.store(index, Ordering::Relaxed);
+}
+
+/// Pitch of the drone for each theme, and how many times per second its volume swells
+fn ambient_params(theme: LevelTheme) -> (f64, f64) {
+    match theme {
+        LevelTheme::Medbay => (330.0, 0.25),
+        LevelTheme::Engineering => (55.0, 2.0),
+        LevelTheme::Hydroponics => (165.0, 0.125),
+        LevelTheme::Derelict => (41.2, 0.5),
+    }
+}
+
+/// A pair of slightly detuned triangle waves whose volume slowly swells and fades
+fn ambient_signal() -> Sf64 {
+    let mut phases = [0.0_f64; 2];
+    let mut swell_phase = 0.0_f64;
+    Signal::from_fn_mut(move |ctx| {
+        let index = AMBIENT_THEME.load(Ordering::Relaxed) as usize;
+        let theme = match index
+            .checked_sub(1)
+            .and_then(|index| LevelTheme::ALL.get(index))
+        {
+            Some(&theme) => theme,
+            None => return 0.0,
+        };
+        let (base_hz, swell_hz) = ambient_params(theme);
+        swell_phase = (swell_phase + swell_hz / ctx.sample_rate_hz).fract();
+        let swell = 0.5 - 0.5 * (swell_phase * std::f64::consts::TAU).cos();
+        let mut sample = 0.0;
+        for (phase, ratio) in phases.iter_mut().zip([1.0, 1.003]) {
+            *phase = (*phase + base_hz * ratio / ctx.sample_rate_hz).fract();
+            sample += 4.0 * (*phase - 0.5).abs() - 1.0;
+        }
+        sample * swell
+    })
+}
+
 pub fn signal() -> Sf64 {
     let trigger = periodic_trigger_hz(4.0).build();
-    synth_signal(trigger.divide(4)) * 0.15 + drum_signal(trigger.divide(4)) * 0.075
+    synth_signal(trigger.divide(4)) * 0.15
+        + drum_signal(trigger.divide(4)) * 0.075
+        + stinger_signal() * 0.1
+        + ambient_signal() * 0.05
 }
@@ -0,0 +1,121 @@
+use chargrid::prelude::*;
+use game::{LevelTheme, Rarity};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const PALETTES: &str = include_str!("palettes.ron");
+
+pub type Rgb = (u8, u8, u8);
+
+pub fn rgba32((r, g, b): Rgb) -> Rgba32 {
+    Rgba32::new_rgb(r, g, b)
+}
+
+/// Colours for each purpose they are used for when rendering the game, so the game can be
+/// reskinned without changing any rendering code
+#[derive(Debug, Clone, Deserialize)]
+pub struct Palette {
+    pub name: String,
+    /// Background of cells that have never been seen
+    pub unseen: Rgb,
+    /// Foreground of tiles that were seen before but aren't currently visible
+    pub remembered: Rgb,
+    /// How much of a remembered tile's own colour shows through `remembered`, out of 255. At 0
+    /// every remembered tile is drawn in `remembered`.
+    #[serde(default)]
+    pub remembered_blend: u8,
+    pub blood: Rgb,
+    pub scorch: Rgb,
+    pub oil: Rgb,
+    /// Colour of the deepest liquid. Shallower liquid is darker.
+    pub liquid: Rgb,
+    /// Colour of the brightest flames. Fire flickers between this and darker shades.
+    pub fire: Rgb,
+    /// Liquid carrying current from a powered terminal
+    pub electrified: Rgb,
+    /// Colour of the thickest smoke. Thinner smoke is darker.
+    pub smoke: Rgb,
+    pub poison_gas: Rgb,
+    pub respawn_countdown: Rgb,
+    pub ui_text: Rgb,
+    pub ui_accent: Rgb,
+    pub ui_info: Rgb,
+    pub ui_warning: Rgb,
+    pub ui_danger: Rgb,
+    pub radiation: Rgb,
+    /// Background of cells an enemy will attack next turn
+    pub telegraph: Rgb,
+    /// Background of cells where an enemy's heavy attack will land next turn
+    pub wind_up: Rgb,
+    /// The player's path on a previous attempt at the same seed
+    pub ghost: Rgb,
+    /// Names of items rarer than common
+    pub rarity_uncommon: Rgb,
+    pub rarity_rare: Rgb,
+    pub rarity_legendary: Rgb,
+    /// Foreground colours replacing those from the tile registry, keyed by tile id
+    #[serde(default)]
+    pub tiles: HashMap<String, Rgb>,
+    /// Colours for levels of each theme, keyed by theme id
+    #[serde(default)]
+    pub themes: HashMap<String, ThemePalette>,
+}
+
+/// Colours used when rendering levels of one theme
+#[derive(Debug, Clone, Deserialize)]
+pub struct ThemePalette {
+    /// Background of cells the player can currently see, as if lit by the level's lights
+    pub light: Rgb,
+    /// Foreground colours replacing those from the tile registry and the palette, keyed by tile
+    /// id
+    #[serde(default)]
+    pub tiles: HashMap<String, Rgb>,
+}
+
+impl Palette {
+    /// Dims the colour a tile was drawn in when it was last seen, so explored areas out of view
+    /// stay recognisable without being mistaken for what the player can see now
+    pub fn remembered_foreground(&self, foreground: Rgba32) -> Rgba32 {
+        let (r, g, b) = self.remembered;
+        let blend = self.remembered_blend as u32;
+        let mix = |own: u8, remembered: u8| {
+            ((own as u32 * blend + remembered as u32 * (255 - blend)) / 255) as u8
+        };
+        Rgba32::new_rgb(
+            mix(foreground.r, r),
+            mix(foreground.g, g),
+            mix(foreground.b, b),
+        )
+    }
+
+    /// Colour of the name of an item of the given rarity
+    pub fn rarity(&self, rarity: Rarity) -> Rgb {
+        match rarity {
+            Rarity::Common => self.ui_text,
+            Rarity::Uncommon => self.rarity_uncommon,
+            Rarity::Rare => self.rarity_rare,
+            Rarity::Legendary => self.rarity_legendary,
+        }
+    }
+
+    /// The palette with the colours for levels of the given theme applied, and the colour of
+    /// the level's lights if the palette has one
+    pub fn themed(&self, theme: Option<LevelTheme>) -> (Palette, Option<Rgb>) {
+        let mut palette = self.clone();
+        let theme_palette = match theme.and_then(|theme| self.themes.get(theme.id())) {
+            Some(theme_palette) => theme_palette,
+            None => return (palette, None),
+        };
+        palette.tiles.extend(
+            theme_palette
+                .tiles
+                .iter()
+                .map(|(id, &colour)| (id.clone(), colour)),
+        );
+        (palette, Some(theme_palette.light))
+    }
+}
+
+pub fn load_palettes() -> Vec<Palette> {
+    ron::from_str(PALETTES).expect("failed to parse palettes")
+}
@@ -0,0 +1,217 @@
+use crate::game_loop::{AppCF, State};
+use chargrid::{
+    control_flow::*,
+    input::Input,
+    prelude::*,
+    text::{StyledString, Text},
+};
+use std::time::Duration;
+
+/// Time between consecutive characters appearing
+const CHARACTER_DELAY: Duration = Duration::from_millis(15);
+
+enum Segment {
+    Span(StyledString),
+    Pause(Duration),
+}
+
+enum Tag {
+    Bold(bool),
+    Colour(Option<Rgba32>),
+    Pause(Duration),
+}
+
+fn parse_tag(tag: &str) -> Option<Tag> {
+    match tag {
+        "b" => Some(Tag::Bold(true)),
+        "/b" => Some(Tag::Bold(false)),
+        "/c" => Some(Tag::Colour(None)),
+        _ => {
+            if let Some(hex) = tag.strip_prefix("c=") {
+                parse_colour(hex).map(|colour| Tag::Colour(Some(colour)))
+            } else if let Some(ms) = tag.strip_prefix("p=") {
+                ms.parse()
+                    .ok()
+                    .map(|ms| Tag::Pause(Duration::from_millis(ms)))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Parses text containing markup:
+/// - `<b>...</b>` makes text bold
+/// - `<c=rrggbb>...</c>` sets the colour of text, in hex
+/// - `<p=ms>` pauses the typewriter effect for some milliseconds
+///
+/// Anything that isn't a recognised tag is treated as text.
+fn parse(markup: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut bold = false;
+    let mut colour = None;
+    let mut current = String::new();
+    let mut rest = markup;
+    while let Some(ch) = rest.chars().next() {
+        let tag = rest
+            .strip_prefix('<')
+            .and_then(|after| after.find('>').map(|end| &after[..end]))
+            .and_then(|tag| parse_tag(tag).map(|parsed| (tag.len(), parsed)));
+        let (tag_len, tag) = match tag {
+            Some(tag) => tag,
+            None => {
+                current.push(ch);
+                rest = &rest[ch.len_utf8()..];
+                continue;
+            }
+        };
+        if !current.is_empty() {
+            segments.push(Segment::Span(span(
+                std::mem::take(&mut current),
+                bold,
+                colour,
+            )));
+        }
+        match tag {
+            Tag::Bold(new_bold) => bold = new_bold,
+            Tag::Colour(new_colour) => colour = new_colour,
+            Tag::Pause(pause) => segments.push(Segment::Pause(pause)),
+        }
+        // skip the tag and its angle brackets
+        rest = &rest[tag_len + 2..];
+    }
+    if !current.is_empty() {
+        segments.push(Segment::Span(span(current, bold, colour)));
+    }
+    segments
+}
+
+fn parse_colour(hex: &str) -> Option<Rgba32> {
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Rgba32::new_rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+fn span(string: String, bold: bool, colour: Option<Rgba32>) -> StyledString {
+    let mut style = Style::plain_text().with_bold(bold);
+    if let Some(colour) = colour {
+        style = style.with_foreground(colour);
+    }
+    StyledString { string, style }
+}
+
+/// The styled text described by some markup, for displaying all at once
+pub fn spans(markup: &str) -> Vec<StyledString> {
+    parse(markup)
+        .into_iter()
+        .filter_map(|segment| match segment {
+            Segment::Span(span) => Some(span),
+            Segment::Pause(_) => None,
+        })
+        .collect()
+}
+
+/// Word-wrapped text which is revealed a character at a time. Pressing a key reveals the rest of
+/// the text, and pressing a key once all the text is revealed completes the component.
+pub struct RichText {
+    spans: Vec<StyledString>,
+    /// When each character (in the order they appear in `spans`) is revealed
+    reveal_times: Vec<Duration>,
+    elapsed: Duration,
+}
+
+impl RichText {
+    pub fn new(markup: &str) -> Self {
+        let mut spans = Vec::new();
+        let mut reveal_times = Vec::new();
+        let mut time = Duration::ZERO;
+        for segment in parse(markup) {
+            match segment {
+                Segment::Span(span) => {
+                    for ch in span.string.chars() {
+                        if !ch.is_whitespace() {
+                            time += CHARACTER_DELAY;
+                        }
+                        reveal_times.push(time);
+                    }
+                    spans.push(span);
+                }
+                Segment::Pause(pause) => time += pause,
+            }
+        }
+        Self {
+            spans,
+            reveal_times,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    fn total_duration(&self) -> Duration {
+        self.reveal_times.last().copied().unwrap_or(Duration::ZERO)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= self.total_duration()
+    }
+
+    /// Unrevealed characters are replaced with spaces rather than omitted so that words don't
+    /// jump between lines as they are revealed
+    fn visible_spans(&self) -> Vec<StyledString> {
+        let mut reveal_times = self.reveal_times.iter();
+        self.spans
+            .iter()
+            .map(|span| StyledString {
+                string: span
+                    .string
+                    .chars()
+                    .map(|ch| match reveal_times.next() {
+                        Some(&time) if time > self.elapsed && !ch.is_whitespace() => ' ',
+                        _ => ch,
+                    })
+                    .collect(),
+                style: span.style,
+            })
+            .collect()
+    }
+}
+
+impl Component for RichText {
+    type Output = Option<()>;
+    type State = State;
+
+    fn render(&self, _state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        Text::new(self.visible_spans())
+            .wrap_word()
+            .render(&(), ctx, fb);
+    }
+
+    fn update(&mut self, _state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        match event {
+            Event::Tick(since_previous) => {
+                self.elapsed += since_previous;
+                None
+            }
+            Event::Input(Input::Keyboard(_)) | Event::Input(Input::Gamepad(_)) => {
+                if self.is_finished() {
+                    Some(())
+                } else {
+                    self.elapsed = self.total_duration();
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        Text::new(self.spans.clone()).wrap_word().size(&(), ctx)
+    }
+}
+
+/// Reveals the text described by some markup, completing when the player presses a key after
+/// the text is fully revealed
+pub fn rich_text(width: u32, markup: &str) -> AppCF<()> {
+    cf(RichText::new(markup)).set_width(width)
+}
@@ -0,0 +1,65 @@
+use currawong::prelude::*;
+use game::SoundEffect;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// How many times per second the geiger counter clicks for each unit of radiation
+const GEIGER_CLICKS_PER_SECOND_PER_RAD: u32 = 4;
+
+/// The game loop and the audio signal run independently, so the game loop publishes the current
+/// radiation level here for the geiger counter to pick up
+static GEIGER_CLICKS_PER_SECOND: AtomicU32 = AtomicU32::new(0);
+
+/// Fraction of its volume lost by a burst of noise each second
+const NOISE_BURST_DECAY_PER_SECOND: f64 = 8.0;
+
+/// Volume of the next burst of noise in thousandths, or 0 if there is none pending
+static NOISE_BURST_MILLIS: AtomicU32 = AtomicU32::new(0);
+
+/// Plays a sound effect at a volume between 0 and 1
+pub fn play(effect: SoundEffect, volume: f64) {
+    let base_volume = match effect {
+        SoundEffect::Explosion => 1.0,
+        SoundEffect::Breach => 0.5,
+        SoundEffect::Hit => 0.3,
+        SoundEffect::Door => 0.2,
+    };
+    let millis = (base_volume * volume * 1000.0) as u32;
+    NOISE_BURST_MILLIS.fetch_max(millis, Ordering::Relaxed);
+}
+
+pub fn set_radiation_level(radiation: u32) {
+    GEIGER_CLICKS_PER_SECOND.store(
+        radiation * GEIGER_CLICKS_PER_SECOND_PER_RAD,
+        Ordering::Relaxed,
+    );
+}
+
+/// Clicks at random intervals, more often the higher the radiation level
+fn geiger_signal() -> Sf64 {
+    let mut rng = StdRng::from_entropy();
+    Signal::from_fn_mut(move |ctx| {
+        let clicks_per_second = GEIGER_CLICKS_PER_SECOND.load(Ordering::Relaxed) as f64;
+        if rng.gen::<f64>() < clicks_per_second / ctx.sample_rate_hz {
+            1.0
+        } else {
+            0.0
+        }
+    })
+}
+
+/// White noise which starts loud whenever a sound effect is played and quickly fades out
+fn noise_burst_signal() -> Sf64 {
+    let mut rng = StdRng::from_entropy();
+    let mut volume = 0.0;
+    Signal::from_fn_mut(move |ctx| {
+        let millis = NOISE_BURST_MILLIS.swap(0, Ordering::Relaxed);
+        volume = f64::max(volume, millis as f64 / 1000.0);
+        volume *= 1.0 - (NOISE_BURST_DECAY_PER_SECOND / ctx.sample_rate_hz);
+        (rng.gen::<f64>() * 2.0 - 1.0) * volume
+    })
+}
+
+pub fn signal() -> Sf64 {
+    (geiger_signal() * 0.5) + noise_burst_signal()
+}
@@ -0,0 +1,75 @@
+use crate::game_loop::State;
+use chargrid::prelude::*;
+use std::time::{Duration, Instant};
+
+/// How long the stress test runs before reporting its timings
+const STRESS_TEST_DURATION: Duration = Duration::from_secs(30);
+const PERCENTILES: &[u32] = &[50, 90, 99, 100];
+
+/// Runs a turn of the stress test scene every frame, recording how long each frame and each turn
+/// took. Once the test is over the timings are printed and the component completes.
+#[derive(Default)]
+pub struct StressTest {
+    elapsed: Duration,
+    frame_times: Vec<Duration>,
+    tick_times: Vec<Duration>,
+}
+
+/// The value below which the given percent of the (sorted) durations fall
+fn percentile(sorted: &[Duration], percent: u32) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let index = ((sorted.len() - 1) * percent as usize) / 100;
+    sorted[index]
+}
+
+fn summarise(name: &str, durations: &[Duration]) -> String {
+    let mut sorted = durations.to_vec();
+    sorted.sort();
+    let percentiles = PERCENTILES
+        .iter()
+        .map(|&percent| format!("p{}: {:?}", percent, percentile(&sorted, percent)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{} ({} samples) {}", name, sorted.len(), percentiles)
+}
+
+impl StressTest {
+    fn report(&self) -> String {
+        format!(
+            "Stress test results after {:?}\n{}\n{}",
+            self.elapsed,
+            summarise("frame", &self.frame_times),
+            summarise("tick", &self.tick_times),
+        )
+    }
+}
+
+impl Component for StressTest {
+    type Output = Option<()>;
+    type State = State;
+
+    fn render(&self, state: &Self::State, ctx: Ctx, fb: &mut FrameBuffer) {
+        state.render(ctx, fb);
+    }
+
+    fn update(&mut self, state: &mut Self::State, _ctx: Ctx, event: Event) -> Self::Output {
+        if let Event::Tick(since_previous) = event {
+            self.frame_times.push(since_previous);
+            let start = Instant::now();
+            state.stress_turn();
+            self.tick_times.push(start.elapsed());
+            self.elapsed += since_previous;
+            if self.elapsed >= STRESS_TEST_DURATION {
+                println!("{}", self.report());
+                return Some(());
+            }
+        }
+        None
+    }
+
+    fn size(&self, _state: &Self::State, ctx: Ctx) -> Size {
+        ctx.bounding_box.size()
+    }
+}
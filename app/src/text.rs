@@ -1,87 +1,171 @@
-use crate::game_loop::{AppCF, State};
+use crate::{
+    controls::AppInput,
+    game_loop::{AppCF, State},
+    locale::Locale,
+    rich_text::rich_text,
+};
 use chargrid::{
     control_flow::*,
     prelude::*,
     text::{StyledString, Text},
 };
-use game::GameOverReason;
+use game::{CodexEntry, Ending, GameOverReason, Split};
 
 fn text_component(width: u32, text: Vec<StyledString>) -> CF<(), State> {
     Text::new(text).wrap_word().cf().set_width(width)
 }
 
-pub fn help(width: u32) -> AppCF<()> {
-    let t = |s: &str| StyledString {
-        string: s.to_string(),
+fn plain(string: &str) -> StyledString {
+    StyledString {
+        string: string.to_string(),
         style: Style::plain_text(),
-    };
-    let b = |s: &str| StyledString {
-        string: s.to_string(),
+    }
+}
+
+fn bold(string: &str) -> StyledString {
+    StyledString {
+        string: string.to_string(),
         style: Style::plain_text().with_bold(true),
-    };
-    text_component(
-        width,
-        vec![
-            b("Controls:\n\n"),
-            t("Wait: Space\n"),
-            t("Ability: 1-9\n"),
-            t("\n"),
-            b("On Foot\n"),
-            t("Walk: Arrow Keys\n"),
-        ],
-    )
+    }
+}
+
+/// A single line of text looked up in the current locale
+fn localised(width: u32, key: &'static str) -> CF<(), State> {
+    on_state_then(move |state: &mut State| {
+        text_component(width, vec![plain(state.locale().get(key))])
+    })
+}
+
+pub fn help(width: u32) -> AppCF<()> {
+    on_state_then(move |state: &mut State| {
+        let locale = state.locale();
+        let controls = state.controls();
+        let t = |key: &str| plain(&format!("{}\n", locale.get(key)));
+        // names the bound key in each place it appears in the line
+        let k = |key: &str, app_input: AppInput| {
+            let name = controls.bound_key_name(app_input);
+            plain(&format!("{}\n", locale.format(key, &[&name, &name])))
+        };
+        text_component(
+            width,
+            vec![
+                bold(&format!("{}\n\n", locale.get("help.title"))),
+                k("help.wait", AppInput::Wait),
+                t("help.ability"),
+                k("help.search", AppInput::Search),
+                k("help.destroy_corpse", AppInput::DestroyCorpse),
+                k("help.drag", AppInput::Drag),
+                k("help.brace", AppInput::Brace),
+                k("help.throw_grenade", AppInput::ThrowGrenade),
+                k("help.fire_weapon", AppInput::FireWeapon),
+                k("help.interact", AppInput::Interact),
+                k("help.message_history", AppInput::MessageHistory),
+                k("help.controls_overlay", AppInput::ToggleControlsOverlay),
+                t("help.rebind"),
+                plain("\n"),
+                bold(&format!("{}\n", locale.get("help.on_foot"))),
+                plain(&format!(
+                    "{}\n",
+                    locale.format("help.walk", &[&controls.movement_key_names()])
+                )),
+            ],
+        )
+    })
+    .press_any_key()
+}
+
+pub fn codex_entry(width: u32, entry: CodexEntry) -> AppCF<()> {
+    on_state_then(move |state: &mut State| {
+        let locale = state.locale();
+        let mut text = vec![
+            bold(&format!("{}\n", locale.get(entry.name_key()))),
+            plain(&format!("{}\n\n", locale.get(entry.category().name_key()))),
+        ];
+        if let Some(stats) = entry.stats() {
+            text.push(plain(&format!("{}\n\n", locale.message(&stats))));
+        }
+        text.push(plain(locale.get(entry.description_key())));
+        text_component(width, text)
+    })
     .press_any_key()
 }
 
 pub fn press_any_key_to_begin(width: u32) -> CF<(), State> {
-    let t = |s: &str| StyledString {
-        string: s.to_string(),
-        style: Style::plain_text(),
-    };
-    text_component(width, vec![t("Press any key to begin...")])
+    localised(width, "text.press_any_key_to_begin")
 }
 
 pub fn loading(width: u32) -> AppCF<()> {
-    let t = |s: &str| StyledString {
-        string: s.to_string(),
-        style: Style::plain_text(),
-    };
-    text_component(width, vec![t("Generating...")]).delay(Duration::from_millis(100))
+    localised(width, "text.loading").delay(Duration::from_millis(100))
 }
 
 pub fn saving(width: u32) -> AppCF<()> {
-    let t = |s: &str| StyledString {
-        string: s.to_string(),
-        style: Style::plain_text(),
-    };
-    text_component(width, vec![t("Saving...")]).delay(Duration::from_millis(100))
+    localised(width, "text.saving").delay(Duration::from_millis(100))
 }
 
-fn game_over_text(width: u32, _reason: GameOverReason) -> CF<(), State> {
-    let t = |s: &str| StyledString {
-        string: s.to_string(),
-        style: Style::plain_text(),
-    };
-    let text = vec![t("TODO")];
-    text_component(width, text)
+pub fn tutorial_complete(width: u32) -> AppCF<()> {
+    on_state_then(move |state: &mut State| {
+        rich_text(width, state.locale().get("tutorial.complete"))
+    })
 }
 
 pub fn game_over(width: u32, reason: GameOverReason) -> AppCF<()> {
-    game_over_text(width, reason)
-        .delay(Duration::from_secs(2))
-        .then(move || game_over_text(width, reason).press_any_key())
+    let key = match reason {
+        GameOverReason::Killed => "game_over.killed",
+        GameOverReason::SelfDestruct => "game_over.self_destruct",
+        GameOverReason::Suffocated => "game_over.suffocated",
+        GameOverReason::RadiationSickness => "game_over.radiation_sickness",
+    };
+    on_state_then(move |state: &mut State| rich_text(width, state.locale().get(key)))
 }
 
-fn win_text(width: u32) -> CF<(), State> {
-    let t = |s: &str| StyledString {
-        string: s.to_string(),
-        style: Style::plain_text(),
-    };
-    text_component(width, vec![t("You win!")])
+/// Formats a duration as minutes, seconds and tenths of a second
+pub fn format_duration(duration: Duration) -> String {
+    let tenths = duration.as_millis() / 100;
+    format!("{}:{:02}.{}", tenths / 600, (tenths / 10) % 60, tenths % 10)
 }
-pub fn win(width: u32) -> AppCF<()> {
-    // TODO: this is not ergonomic
-    win_text(width)
-        .delay(Duration::from_secs(2))
-        .then(move || win_text(width).press_any_key())
+
+fn splits_text(locale: &Locale, splits: &[(Split, Option<Split>)]) -> String {
+    let mut text = "\n\n".to_string();
+    for (split, best) in splits {
+        let comparison = match best {
+            None => locale.get("splits.new_best").to_string(),
+            Some(best) if split.duration < best.duration => {
+                locale.format("splits.new_best_was", &[&format_duration(best.duration)])
+            }
+            Some(best) => locale.format("splits.best", &[&format_duration(best.duration)]),
+        };
+        text.push_str(&locale.format(
+            "splits.level",
+            &[
+                &split.level,
+                &format_duration(split.duration),
+                &split.turns,
+                &comparison,
+            ],
+        ));
+        if split.ironman {
+            text.push_str(locale.get("splits.ironman"));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+pub fn win(width: u32, ending: Ending, splits: Vec<(Split, Option<Split>)>) -> AppCF<()> {
+    let (title, body) = match ending {
+        Ending::Escaped => ("ending.escaped.title", "ending.escaped.text"),
+        Ending::Scuttled => ("ending.scuttled.title", "ending.scuttled.text"),
+        Ending::Truce => ("ending.truce.title", "ending.truce.text"),
+        Ending::Confrontation => ("ending.confrontation.title", "ending.confrontation.text"),
+    };
+    on_state_then(move |state: &mut State| {
+        let locale = state.locale();
+        let markup = format!(
+            "<b>{}</b><p=500>\n\n{}{}",
+            locale.get(title),
+            locale.get(body),
+            splits_text(locale, &splits)
+        );
+        rich_text(width, &markup)
+    })
 }
@@ -0,0 +1,91 @@
+use crate::palette::{rgba32, Palette};
+use chargrid::prelude::*;
+use game::Tile;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const DEFAULT_TILES: &str = include_str!("tiles.ron");
+
+/// How to draw a tile
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileDef {
+    pub glyph: char,
+    pub foreground: (u8, u8, u8),
+    #[serde(default)]
+    pub background: Option<(u8, u8, u8)>,
+    #[serde(default)]
+    pub bold: bool,
+    /// Overrides the depth at which the tile is drawn, which is otherwise based on its layer
+    #[serde(default)]
+    pub depth: Option<i8>,
+}
+
+impl TileDef {
+    pub fn render_cell(&self) -> RenderCell {
+        let mut style = Style::new()
+            .with_bold(self.bold)
+            .with_foreground(rgba32(self.foreground));
+        if let Some(background) = self.background {
+            style = style.with_background(rgba32(background));
+        }
+        RenderCell {
+            character: Some(self.glyph),
+            style,
+        }
+    }
+}
+
+/// Tile definitions looked up by tile id. The defaults are embedded in the binary. At startup
+/// the app adds the definitions in the tiles file from its storage, if there is one, with
+/// `extend_from_ron`, replacing any defaults with the same id. Which tiles exist is still decided
+/// by the game's `Tile` enum, whose `Tile::id` gives the keys looked up here.
+pub struct TileRegistry {
+    defs: HashMap<String, TileDef>,
+}
+
+impl TileRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self {
+            defs: HashMap::new(),
+        };
+        registry
+            .extend_from_ron(DEFAULT_TILES)
+            .expect("failed to parse default tiles");
+        registry
+    }
+
+    pub fn extend_from_ron(&mut self, ron: &str) -> Result<(), ron::error::SpannedError> {
+        let defs: HashMap<String, TileDef> = ron::from_str(ron)?;
+        self.defs.extend(defs);
+        Ok(())
+    }
+
+    /// Tiles missing from the registry are drawn as a magenta '?' so they stand out
+    pub fn render_cell(&self, tile: Tile, palette: &Palette) -> RenderCell {
+        match self.defs.get(tile.id()) {
+            Some(def) => {
+                let mut render_cell = def.render_cell();
+                if let Some(&foreground) = palette.tiles.get(tile.id()) {
+                    render_cell.style.foreground = Some(rgba32(foreground));
+                }
+                render_cell
+            }
+            None => RenderCell {
+                character: Some('?'),
+                style: Style::new()
+                    .with_bold(true)
+                    .with_foreground(Rgba32::new_rgb(255, 0, 255)),
+            },
+        }
+    }
+
+    pub fn depth(&self, tile: Tile) -> Option<i8> {
+        self.defs.get(tile.id()).and_then(|def| def.depth)
+    }
+}
+
+impl Default for TileRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{telegraph::Behaviour, Coord, Entity, Game, NextAction, NPC_APPROACH_DISTANCE};
+
+/// What an npc is trying to do and how it plans to get there, for tuning npc behaviour. Unlike
+/// `NpcExamination` this includes npcs the player can't see.
+#[derive(Debug, Clone)]
+pub struct AgentIntrospection {
+    pub entity: Entity,
+    pub name: &'static str,
+    pub coord: Coord,
+    /// How aware the npc is of the player
+    pub awareness: Behaviour,
+    /// The cell the npc is trying to reach or attack
+    pub goal: Option<Coord>,
+    /// The cells the npc will step through to reach its goal, not including its own cell
+    pub path: Vec<Coord>,
+}
+
+impl Game {
+    /// Everything the npcs on the current level are planning
+    pub fn ai_introspection(&self) -> Vec<AgentIntrospection> {
+        self.world
+            .components
+            .npc_type
+            .iter()
+            .filter_map(|(entity, npc_type)| {
+                let coord = self.world.spatial_table.coord_of(entity)?;
+                let next_action = self.world.components.next_action.get(entity).copied();
+                let path = self.ai_path(coord);
+                let goal = match next_action {
+                    Some(
+                        NextAction::Attack(target)
+                        | NextAction::WindUp(target)
+                        | NextAction::Shoot(target),
+                    ) => Some(target),
+                    Some(NextAction::Move(_)) | None => path.last().copied(),
+                };
+                Some(AgentIntrospection {
+                    entity,
+                    name: npc_type.name(),
+                    coord,
+                    awareness: self.npc_behaviour(entity, coord),
+                    goal,
+                    path,
+                })
+            })
+            .collect()
+    }
+
+    /// The number of steps an npc at a cell would take to reach the player, following the
+    /// distance map npcs use to approach the player. `None` if the player can't be reached from
+    /// the cell.
+    pub fn ai_distance_at(&self, coord: Coord) -> Option<u32> {
+        if !coord.is_valid(self.world.size()) {
+            return None;
+        }
+        if self.player_coords().contains(&coord) {
+            return Some(0);
+        }
+        match self.ai_path(coord).len() {
+            0 => None,
+            steps => Some(steps as u32),
+        }
+    }
+
+    /// Follows the distance map downhill from a cell
+    fn ai_path(&self, start: Coord) -> Vec<Coord> {
+        let mut path = Vec::new();
+        let mut coord = start;
+        while path.len() < NPC_APPROACH_DISTANCE as usize {
+            match self.world.distance_map.direction_to_best_neighbour(coord) {
+                Some(direction) => {
+                    coord = coord + direction.coord();
+                    path.push(coord);
+                }
+                None => break,
+            }
+        }
+        path
+    }
+}
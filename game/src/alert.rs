@@ -0,0 +1,79 @@
+use crate::{
+    coords_between, world::data::Alertness, Coord, Entity, Game, PowerState, VisibleWorld,
+    POWER_OUTAGE_VISION_DISTANCE_SQUARED, VISION_DISTANCE_SQUARED,
+};
+
+/// Opacity along a line of sight at which an npc can no longer see through it
+const MAX_SIGHT_OPACITY: u32 = 255;
+
+impl Game {
+    /// Npcs without an alertness, e.g. from older saves, behave as they always did
+    pub(crate) fn npc_alertness(&self, entity: Entity) -> Alertness {
+        self.world
+            .components
+            .alertness
+            .get(entity)
+            .copied()
+            .unwrap_or(Alertness::Hostile)
+    }
+
+    /// Npcs see as far as the player does, and are just as blinded by the lights going out
+    pub(crate) fn npc_can_see(&self, coord: Coord, target: Coord) -> bool {
+        let distance_squared = if self.power_state() == PowerState::Off {
+            POWER_OUTAGE_VISION_DISTANCE_SQUARED
+        } else {
+            VISION_DISTANCE_SQUARED
+        };
+        if (target - coord).magnitude2() > distance_squared {
+            return false;
+        }
+        let opacity = coords_between(coord, target)
+            .skip(1)
+            .take_while(|&between| between != target)
+            .map(|between| self.world.get_opacity(between) as u32)
+            .sum::<u32>();
+        opacity < MAX_SIGHT_OPACITY
+    }
+
+    /// Npcs that see or smell the player become hostile, and unaware npcs that hear something become
+    /// suspicious. Suspicious npcs calm down once there's nothing left to investigate. Hostile
+    /// npcs stay hostile until they give up searching for the player.
+    pub(crate) fn update_alertness(&mut self) {
+        let player_coords = self.player_coords();
+        let alarm = self.is_alarm_sounding();
+        let updates = self
+            .world
+            .components
+            .alertness
+            .iter()
+            .filter_map(|(entity, &alertness)| {
+                let coord = self.world.spatial_table.coord_of(entity)?;
+                let next = if alertness == Alertness::Hostile
+                    || alarm
+                    || self.smells_player(entity, coord)
+                    || player_coords
+                        .iter()
+                        .any(|&player_coord| self.npc_can_see(coord, player_coord))
+                {
+                    Alertness::Hostile
+                } else if self.world.components.investigating.contains(entity) {
+                    Alertness::Suspicious
+                } else {
+                    Alertness::Unaware
+                };
+                (next != alertness).then_some((entity, next))
+            })
+            .collect::<Vec<_>>();
+        for (entity, alertness) in updates {
+            self.world.components.alertness.insert(entity, alertness);
+        }
+    }
+
+    /// Being hurt gives the player's position away
+    pub(crate) fn alert_npc(&mut self, entity: Entity) {
+        if let Some(alertness) = self.world.components.alertness.get_mut(entity) {
+            *alertness = Alertness::Hostile;
+            self.remember_player(entity);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    distance_map::{CanEnter, DistanceMap},
+    world::{
+        data::{Atmosphere, GasKind},
+        spatial::Layers,
+        World,
+    },
+    ActionError, CardinalDirection, CellVisibility, Config, Coord, Game, GameControlFlow,
+    VisibilityGrid, VisibleCellData,
+};
+
+/// Cells the player has seen and could safely walk through on the way to somewhere unexplored.
+/// This is also used to find paths for travelling.
+pub(crate) struct Explorable<'a> {
+    pub(crate) world: &'a World,
+    pub(crate) visibility_grid: &'a VisibilityGrid<VisibleCellData>,
+}
+
+impl<'a> Explorable<'a> {
+    fn seen(&self, coord: Coord) -> bool {
+        !matches!(
+            self.visibility_grid.get_visibility(coord),
+            CellVisibility::Never
+        )
+    }
+
+    /// Cells which would hurt or strand the player. Walking around them is always better than
+    /// walking through them.
+    fn is_hazard(&self, coord: Coord) -> bool {
+        self.world.atmosphere_at(coord) == Atmosphere::Vacuum
+            || self.world.liquid_depth_at(coord) > 0
+            || self
+                .world
+                .spatial_table
+                .layers_at(coord)
+                .and_then(|layers| layers.floor)
+                .is_some_and(|floor| self.world.components.fire.contains(floor))
+            || self
+                .world
+                .gas_at(coord)
+                .is_some_and(|gas| gas.kind == GasKind::Poison)
+    }
+
+    /// A seen cell next to one that's never been seen
+    fn is_frontier(&self, coord: Coord) -> bool {
+        let size = self.world.size();
+        self.can_enter(coord)
+            && CardinalDirection::all().any(|direction| {
+                let neighbour = coord + direction.coord();
+                neighbour.is_valid(size) && !self.seen(neighbour)
+            })
+    }
+}
+
+impl<'a> CanEnter for Explorable<'a> {
+    fn can_enter(&self, coord: Coord) -> bool {
+        if !self.seen(coord) || self.is_hazard(coord) {
+            return false;
+        }
+        let components = &self.world.components;
+        match self.world.spatial_table.layers_at(coord) {
+            Some(&Layers {
+                floor: Some(floor),
+                feature,
+                ..
+            }) => {
+                if components.gap.contains(floor) {
+                    return false;
+                }
+                // doors are opened by walking into them, but walking into anything else which
+                // is in the way would use it
+                feature.is_none_or(|feature| {
+                    // walking around a known trap is always better than walking into it
+                    if components
+                        .trap
+                        .get(feature)
+                        .is_some_and(|trap| trap.revealed)
+                    {
+                        return false;
+                    }
+                    components.door_state.contains(feature)
+                        || !(components.solid.contains(feature)
+                            || components.stairs_down.contains(feature)
+                            || components.escape_pod.contains(feature))
+                })
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Game {
+    /// Whether the player can currently see any npcs. Autoexploring stops when this is true.
+    pub fn is_npc_visible(&self) -> bool {
+        self.world
+            .components
+            .npc_type
+            .iter()
+            .filter_map(|(entity, _)| self.world.spatial_table.coord_of(entity))
+            .any(|coord| {
+                matches!(
+                    self.cell_visibility_at_coord(coord),
+                    CellVisibility::Current { .. }
+                )
+            })
+    }
+
+    /// Whether the player can currently see fire or poison gas, which spread and could catch
+    /// them on the way. Autoexploring stops when this is true.
+    pub fn is_hazard_visible(&self) -> bool {
+        self.world
+            .size()
+            .coord_iter_row_major()
+            .filter(|&coord| {
+                matches!(
+                    self.cell_visibility_at_coord(coord),
+                    CellVisibility::Current { .. }
+                )
+            })
+            .any(|coord| {
+                self.is_fire_at(coord)
+                    || self
+                        .world
+                        .gas_at(coord)
+                        .is_some_and(|gas| gas.kind == GasKind::Poison)
+            })
+    }
+
+    /// Takes a step towards the nearest cell next to somewhere the player hasn't seen yet
+    pub(crate) fn player_autoexplore(
+        &mut self,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        if self.is_npc_visible() {
+            return Err(ActionError::NpcInView);
+        }
+        if self.is_hazard_visible() {
+            return Err(ActionError::HazardInView);
+        }
+        let player_coord = self.player_coord();
+        let size = self.world.size();
+        let explorable = Explorable {
+            world: &self.world,
+            visibility_grid: &self.visibility_grid,
+        };
+        self.ai_ctx.distance_map.clear();
+        for coord in size.coord_iter_row_major() {
+            if coord != player_coord && explorable.is_frontier(coord) {
+                self.ai_ctx.distance_map.add(coord);
+            }
+        }
+        let mut explore_map = DistanceMap::new(size);
+        let max_distance = size.width() + size.height();
+        self.ai_ctx
+            .distance_map
+            .populate_approach(&explorable, max_distance, &mut explore_map);
+        let direction = explore_map
+            .direction_to_best_neighbour(player_coord)
+            .ok_or(ActionError::NothingToExplore)?;
+        Ok(self.player_walk(direction, config))
+    }
+}
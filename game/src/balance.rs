@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_BALANCE: &str = include_str!("balance.ron");
+
+/// Declares `Balance` along with `BalanceOverride`, which has the same fields but all optional so
+/// an override file only needs to mention the numbers it changes
+macro_rules! declare_balance {
+    ($($(#[$meta:meta])* $field:ident,)*) => {
+        /// Numbers which tune the difficulty of the game, kept in a data file so they can be
+        /// changed during playtests without recompiling
+        #[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+        pub struct Balance {
+            $($(#[$meta])* pub $field: u32,)*
+        }
+
+        #[derive(Deserialize, Default)]
+        #[serde(default, deny_unknown_fields)]
+        struct BalanceOverride {
+            $($field: Option<u32>,)*
+        }
+
+        impl Balance {
+            fn apply_override(&mut self, balance_override: BalanceOverride) {
+                $(
+                    if let Some(value) = balance_override.$field {
+                        self.$field = value;
+                    }
+                )*
+            }
+        }
+    };
+}
+
+declare_balance! {
+    player_melee_damage,
+    grenade_damage,
+    /// Damage dealt by falling debris during the self destruct sequence
+    debris_damage,
+    /// Damage taken each turn while out of oxygen
+    suffocation_damage,
+    corpse_medkit_healing,
+    oxygen_recovery_per_turn,
+    /// Number of turns an automatic door stays open before closing itself
+    door_close_turns,
+    self_destruct_turns,
+    power_outage_turns,
+}
+
+impl Default for Balance {
+    /// The values from the balance file embedded in the game
+    fn default() -> Self {
+        ron::from_str(DEFAULT_BALANCE).expect("failed to parse default balance")
+    }
+}
+
+impl Balance {
+    /// Applies an override file in the same format as the embedded balance file on top of the
+    /// default values
+    pub fn with_override(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        let balance_override = ron::from_str(ron)?;
+        let mut balance = Self::default();
+        balance.apply_override(balance_override);
+        Ok(balance)
+    }
+}
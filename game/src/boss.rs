@@ -0,0 +1,206 @@
+use crate::{
+    energy::NORMAL_SPEED,
+    names,
+    projectile::Projectile,
+    world::{
+        data::{Alertness, NpcType, OnCollision},
+        World,
+    },
+    CardinalDirection, Config, Coord, Direction, Entity, ExternalEvent, Game, GameControlFlow,
+    Message,
+};
+use serde::{Deserialize, Serialize};
+
+/// Turns between the abomination's special attacks once it has started making them
+const BOSS_SPECIAL_INTERVAL: u32 = 4;
+/// The abomination starts summoning zombies at this percentage of its hit points or fewer
+const BOSS_SUMMON_HIT_POINTS_PERCENT: u32 = 66;
+/// The abomination becomes enraged at this percentage of its hit points or fewer
+const BOSS_ENRAGE_HIT_POINTS_PERCENT: u32 = 33;
+const BOSS_ENRAGED_SPEED: u32 = NORMAL_SPEED * 3 / 2;
+/// Most zombies that answer each time the abomination calls for help
+const BOSS_ADDS_PER_SUMMON: usize = 2;
+/// Distance each projectile in a burst is aimed at, in each of the eight directions
+const BOSS_BURST_RANGE: i32 = 8;
+const BOSS_BURST_DAMAGE: u32 = 2;
+const BOSS_CAMERA_SHAKE: u32 = 3;
+
+/// The stages of the fight against the abomination, in the order they happen
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BossPhase {
+    /// Hasn't noticed the player yet
+    Dormant,
+    /// Fights like any other npc
+    Awakened,
+    /// Periodically summons zombies to its side
+    Summoning,
+    /// Moves faster, and periodically fires projectiles in every direction
+    Enraged,
+}
+
+impl BossPhase {
+    fn announcement(self) -> Option<&'static str> {
+        match self {
+            Self::Dormant => None,
+            Self::Awakened => Some("message.boss_awakened"),
+            Self::Summoning => Some("message.boss_summoning"),
+            Self::Enraged => Some("message.boss_enraged"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Boss {
+    pub phase: BossPhase,
+    turns_until_special: u32,
+}
+
+impl Default for Boss {
+    fn default() -> Self {
+        Self {
+            phase: BossPhase::Dormant,
+            turns_until_special: BOSS_SPECIAL_INTERVAL,
+        }
+    }
+}
+
+/// Places the abomination where the final level's arena says it waits
+pub(crate) fn spawn_boss(world: &mut World, coord: Coord) -> Entity {
+    let entity = world.spawn_npc(coord, NpcType::Abomination);
+    world.components.boss.insert(entity, Boss::default());
+    entity
+}
+
+impl Game {
+    /// Moves the abomination on to its next phase once it notices the player or its hit points
+    /// fall past a threshold. Phases can be skipped by a heavy enough blow, but never repeated.
+    pub(crate) fn update_boss_phase(&mut self, entity: Entity) {
+        let current = match self.world.components.boss.get(entity) {
+            Some(boss) => boss.phase,
+            None => return,
+        };
+        let hit_points_percent = match self.world.components.hit_points.get(entity) {
+            Some(hit_points) => hit_points.current() * 100 / hit_points.max().max(1),
+            None => return,
+        };
+        let phase = if self.npc_alertness(entity) != Alertness::Hostile {
+            BossPhase::Dormant
+        } else if hit_points_percent <= BOSS_ENRAGE_HIT_POINTS_PERCENT {
+            BossPhase::Enraged
+        } else if hit_points_percent <= BOSS_SUMMON_HIT_POINTS_PERCENT {
+            BossPhase::Summoning
+        } else {
+            BossPhase::Awakened
+        };
+        if phase <= current {
+            return;
+        }
+        if let Some(boss) = self.world.components.boss.get_mut(entity) {
+            boss.phase = phase;
+            boss.turns_until_special = 0;
+        }
+        if phase == BossPhase::Enraged {
+            self.world
+                .components
+                .speed
+                .insert(entity, BOSS_ENRAGED_SPEED);
+        }
+        if let Some(announcement) = phase.announcement() {
+            let name = names::capitalise(&self.npc_description(entity));
+            self.messages
+                .combat(Some(entity), Message::with_args(announcement, &[&name]));
+        }
+        self.external_events
+            .push(ExternalEvent::BossPhaseChange { phase });
+        self.emit_camera_shake(BOSS_CAMERA_SHAKE);
+    }
+
+    /// Counts down to the abomination's next special attack, returning its phase if the attack
+    /// is due this turn
+    pub(crate) fn boss_special_due(&mut self, entity: Entity) -> Option<BossPhase> {
+        let boss = self.world.components.boss.get_mut(entity)?;
+        if boss.phase < BossPhase::Summoning {
+            return None;
+        }
+        if boss.turns_until_special > 0 {
+            boss.turns_until_special -= 1;
+            return None;
+        }
+        boss.turns_until_special = BOSS_SPECIAL_INTERVAL;
+        Some(boss.phase)
+    }
+
+    pub(crate) fn boss_special(
+        &mut self,
+        entity: Entity,
+        coord: Coord,
+        phase: BossPhase,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        match phase {
+            BossPhase::Dormant | BossPhase::Awakened => None,
+            BossPhase::Summoning => {
+                self.boss_summon(entity, coord);
+                None
+            }
+            BossPhase::Enraged => self.boss_burst(entity, coord, config),
+        }
+    }
+
+    /// Spawns zombies next to the abomination. They arrive already knowing where the player is.
+    fn boss_summon(&mut self, entity: Entity, coord: Coord) {
+        let cells = CardinalDirection::all()
+            .map(|direction| coord + direction.coord())
+            .filter(|&cell| self.is_free_for_npc(cell))
+            .take(BOSS_ADDS_PER_SUMMON)
+            .collect::<Vec<_>>();
+        if cells.is_empty() {
+            return;
+        }
+        for cell in cells {
+            let add = self.world.spawn_npc(cell, NpcType::Zombie);
+            self.world
+                .components
+                .alertness
+                .insert(add, Alertness::Hostile);
+        }
+        let name = names::capitalise(&self.npc_description(entity));
+        self.messages.combat(
+            Some(entity),
+            Message::with_args("message.boss_summon", &[&name]),
+        );
+    }
+
+    /// Fires a projectile in each of the eight directions at once
+    fn boss_burst(
+        &mut self,
+        entity: Entity,
+        coord: Coord,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        let name = names::capitalise(&self.npc_description(entity));
+        self.messages.combat(
+            Some(entity),
+            Message::with_args("message.boss_barrage", &[&name]),
+        );
+        for direction in Direction::all() {
+            let offset = direction.coord();
+            let target =
+                coord + Coord::new(offset.x * BOSS_BURST_RANGE, offset.y * BOSS_BURST_RANGE);
+            let projectile = Projectile::new(coord, target, BOSS_BURST_DAMAGE, OnCollision::Stop)
+                .with_source(entity);
+            if let Some(game_control_flow) = self.fire_projectile(projectile, config) {
+                return Some(game_control_flow);
+            }
+        }
+        None
+    }
+
+    /// Lets the app mark the end of the fight
+    pub(crate) fn boss_defeated(&mut self, entity: Entity) {
+        if self.world.components.boss.contains(entity) {
+            self.external_events.push(ExternalEvent::BossDefeated);
+            self.emit_camera_shake(BOSS_CAMERA_SHAKE);
+        }
+    }
+}
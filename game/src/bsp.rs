@@ -0,0 +1,360 @@
+use coord_2d::{Coord, Size};
+use rand::{seq::SliceRandom, Rng};
+use std::collections::HashSet;
+
+/// Size of levels made of rooms and corridors, including the hull around the edge
+const BSP_LEVEL_SIZE: Size = Size::new_u16(36, 22);
+/// Areas are only split if both halves would be at least this long on the split axis
+const BSP_MIN_LEAF_LENGTH: i32 = 8;
+/// Smallest room, counting its walls
+const BSP_MIN_ROOM_LENGTH: i32 = 5;
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Rect {
+    fn centre(self) -> Coord {
+        Coord::new(self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    /// Cells inside the walls of a room occupying the rectangle
+    fn interior(self) -> impl Iterator<Item = Coord> {
+        (self.y + 1..self.y + self.height - 1)
+            .flat_map(move |y| (self.x + 1..self.x + self.width - 1).map(move |x| Coord::new(x, y)))
+    }
+
+    /// The four cells inside the corners of a room occupying the rectangle
+    fn interior_corners(self) -> [Coord; 4] {
+        let (left, top) = (self.x + 1, self.y + 1);
+        let (right, bottom) = (self.x + self.width - 2, self.y + self.height - 2);
+        [
+            Coord::new(left, top),
+            Coord::new(right, top),
+            Coord::new(left, bottom),
+            Coord::new(right, bottom),
+        ]
+    }
+}
+
+/// A level in the format of the terrain files, split into rooms and corridors
+struct Cells {
+    rows: Vec<Vec<char>>,
+    /// Every cell a corridor passes through, including inside rooms
+    paths: HashSet<Coord>,
+}
+
+impl Cells {
+    fn get(&self, coord: Coord) -> char {
+        if coord.x < 0 || coord.y < 0 {
+            return ',';
+        }
+        self.rows
+            .get(coord.y as usize)
+            .and_then(|row| row.get(coord.x as usize))
+            .copied()
+            .unwrap_or(',')
+    }
+
+    fn set(&mut self, coord: Coord, ch: char) {
+        if let Some(cell) = self
+            .rows
+            .get_mut(coord.y as usize)
+            .and_then(|row| row.get_mut(coord.x as usize))
+        {
+            *cell = ch;
+        }
+    }
+
+    fn draw_room(&mut self, room: Rect) {
+        for y in room.y..room.y + room.height {
+            for x in room.x..room.x + room.width {
+                let on_edge = x == room.x
+                    || y == room.y
+                    || x == room.x + room.width - 1
+                    || y == room.y + room.height - 1;
+                self.set(Coord::new(x, y), if on_edge { '#' } else { '.' });
+            }
+        }
+    }
+
+    /// Carves an L-shaped corridor between two cells. Corridors become doors where they pass
+    /// through the wall of a room. Returns the cells carved out of the hull.
+    fn carve_corridor(&mut self, start: Coord, end: Coord, horizontal_first: bool) -> Vec<Coord> {
+        let corner = if horizontal_first {
+            Coord::new(end.x, start.y)
+        } else {
+            Coord::new(start.x, end.y)
+        };
+        let mut corridor = Vec::new();
+        for (from, to) in [(start, corner), (corner, end)] {
+            let step = Coord::new((to.x - from.x).signum(), (to.y - from.y).signum());
+            let mut coord = from;
+            loop {
+                self.paths.insert(coord);
+                match self.get(coord) {
+                    ',' => {
+                        self.set(coord, '.');
+                        corridor.push(coord);
+                    }
+                    '#' => self.set(coord, '+'),
+                    _ => (),
+                }
+                if coord == to {
+                    break;
+                }
+                coord = coord + step;
+            }
+        }
+        corridor
+    }
+
+    /// Closes in corridors with walls so they don't open onto the hull
+    fn wall_in(&mut self, corridor: &[Coord]) {
+        for &coord in corridor {
+            for y in -1..=1 {
+                for x in -1..=1 {
+                    let neighbour = coord + Coord::new(x, y);
+                    if self.get(neighbour) == ',' && self.is_inside(neighbour) {
+                        self.set(neighbour, '#');
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_inside(&self, coord: Coord) -> bool {
+        coord.x > 0
+            && coord.y > 0
+            && coord.x < BSP_LEVEL_SIZE.width() as i32 - 1
+            && coord.y < BSP_LEVEL_SIZE.height() as i32 - 1
+    }
+
+    /// Doors only make sense between two walls. Where a corridor runs along a room's wall it
+    /// knocks several door cells through, and those are opened up instead.
+    fn fix_doors(&mut self) {
+        let doors = (0..BSP_LEVEL_SIZE.height() as i32)
+            .flat_map(|y| (0..BSP_LEVEL_SIZE.width() as i32).map(move |x| Coord::new(x, y)))
+            .filter(|&coord| self.get(coord) == '+')
+            .collect::<Vec<_>>();
+        let is_wall = |cells: &Self, coord: Coord| cells.get(coord) == '#';
+        let is_open = |cells: &Self, coord: Coord| matches!(cells.get(coord), '.' | '+');
+        let mut openings = Vec::new();
+        for coord in doors {
+            let (left, right) = (coord + Coord::new(-1, 0), coord + Coord::new(1, 0));
+            let (up, down) = (coord + Coord::new(0, -1), coord + Coord::new(0, 1));
+            let across = is_open(self, left)
+                && is_open(self, right)
+                && is_wall(self, up)
+                && is_wall(self, down);
+            let along = is_open(self, up)
+                && is_open(self, down)
+                && is_wall(self, left)
+                && is_wall(self, right);
+            if !across && !along {
+                openings.push(coord);
+            }
+        }
+        for coord in openings {
+            self.set(coord, '.');
+        }
+    }
+
+    fn into_text(self) -> String {
+        self.rows
+            .into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Splits an area in two along its longer side, over and over, and places a room in each of
+/// the areas left at the end. Each split joins a room on one side to a room on the other, so
+/// every room can be reached. Returns the rooms.
+fn split<R: Rng>(area: Rect, cells: &mut Cells, rng: &mut R) -> Vec<Rect> {
+    let vertical = area.width >= area.height;
+    let length = if vertical { area.width } else { area.height };
+    if length < BSP_MIN_LEAF_LENGTH * 2 {
+        let width = rng.gen_range(BSP_MIN_ROOM_LENGTH..=(area.width - 1).max(BSP_MIN_ROOM_LENGTH));
+        let height =
+            rng.gen_range(BSP_MIN_ROOM_LENGTH..=(area.height - 1).max(BSP_MIN_ROOM_LENGTH));
+        let room = Rect {
+            x: area.x + rng.gen_range(0..=(area.width - width - 1).max(0)),
+            y: area.y + rng.gen_range(0..=(area.height - height - 1).max(0)),
+            width,
+            height,
+        };
+        cells.draw_room(room);
+        return vec![room];
+    }
+    let at = rng.gen_range(BSP_MIN_LEAF_LENGTH..=length - BSP_MIN_LEAF_LENGTH);
+    let (first, second) = if vertical {
+        (
+            Rect { width: at, ..area },
+            Rect {
+                x: area.x + at,
+                width: area.width - at,
+                ..area
+            },
+        )
+    } else {
+        (
+            Rect { height: at, ..area },
+            Rect {
+                y: area.y + at,
+                height: area.height - at,
+                ..area
+            },
+        )
+    };
+    let mut rooms = split(first, cells, rng);
+    let second_rooms = split(second, cells, rng);
+    if let (Some(&from), Some(&to)) = (rooms.choose(rng), second_rooms.choose(rng)) {
+        let corridor = cells.carve_corridor(from.centre(), to.centre(), vertical);
+        cells.wall_in(&corridor);
+    }
+    rooms.extend(second_rooms);
+    rooms
+}
+
+/// Generates a level of rectangular rooms joined by corridors, in the format of the terrain
+/// files. The player starts in one room and the stairs are in the room furthest from it. The
+/// escape pod, terminal and reactor are tucked into the corners of other rooms.
+pub(crate) fn generate_bsp_text<R: Rng>(rng: &mut R) -> String {
+    let mut cells = Cells {
+        rows: vec![vec![','; BSP_LEVEL_SIZE.width() as usize]; BSP_LEVEL_SIZE.height() as usize],
+        paths: HashSet::new(),
+    };
+    let area = Rect {
+        x: 1,
+        y: 1,
+        width: BSP_LEVEL_SIZE.width() as i32 - 2,
+        height: BSP_LEVEL_SIZE.height() as i32 - 2,
+    };
+    let mut rooms = split(area, &mut cells, rng);
+    cells.fix_doors();
+    rooms.shuffle(rng);
+    let start = rooms[0];
+    let player_coord = start.centre();
+    cells.set(player_coord, '@');
+    if let Some(&stairs_room) = rooms[1..]
+        .iter()
+        .max_by_key(|room| (room.centre() - player_coord).magnitude2())
+    {
+        cells.set(stairs_room.centre(), '>');
+    }
+    // solid features go in corners, where they can't block the way through a room
+    let mut corners = rooms
+        .iter()
+        .flat_map(|room| room.interior_corners())
+        .filter(|&corner| {
+            cells.get(corner) == '.'
+                && !cells.paths.contains(&corner)
+                && (-1..=1).all(|y| (-1..=1).all(|x| cells.get(corner + Coord::new(x, y)) != '+'))
+        })
+        .collect::<Vec<_>>();
+    corners.shuffle(rng);
+    for (&corner, ch) in corners.iter().zip(['E', 'T', 'R', 'L', 'L', 'C']) {
+        cells.set(corner, ch);
+    }
+    let floor = rooms[1..]
+        .iter()
+        .flat_map(|room| room.interior())
+        .filter(|&coord| cells.get(coord) == '.')
+        .collect::<Vec<_>>();
+    for ch in ['S', 'a', 'D'] {
+        if let Some(&coord) = floor.choose(rng) {
+            if cells.get(coord) == '.' {
+                cells.set(coord, ch);
+            }
+        }
+    }
+    cells.into_text()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+    use std::collections::VecDeque;
+
+    /// Cells inside the station the player can walk through
+    const WALKABLE: &[char] = &['.', '+', '@', '>', 'E', 'S', 'a', 'D'];
+
+    fn generate(seed: u64) -> Vec<Vec<char>> {
+        generate_bsp_text(&mut Isaac64Rng::seed_from_u64(seed))
+            .split('\n')
+            .map(|row| row.chars().collect())
+            .collect()
+    }
+
+    fn find(rows: &[Vec<char>], ch: char) -> Vec<Coord> {
+        rows.iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(move |&(_, &c)| c == ch)
+                    .map(move |(x, _)| Coord::new(x as i32, y as i32))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_level() {
+        for seed in 0..4 {
+            assert_eq!(generate(seed), generate(seed));
+        }
+    }
+
+    #[test]
+    fn player_and_stairs_are_placed() {
+        for seed in 0..50 {
+            let rows = generate(seed);
+            assert_eq!(find(&rows, '@').len(), 1);
+            assert_eq!(find(&rows, '>').len(), 1);
+        }
+    }
+
+    /// Every room's interior is walkable apart from the features in its corners, so if every
+    /// walkable cell can be reached from the player then so can every room
+    #[test]
+    fn every_room_is_reachable() {
+        for seed in 0..50 {
+            let rows = generate(seed);
+            let start = find(&rows, '@')[0];
+            let mut reached = HashSet::new();
+            reached.insert(start);
+            let mut queue = VecDeque::new();
+            queue.push_back(start);
+            while let Some(coord) = queue.pop_front() {
+                for offset in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
+                    let neighbour = coord + Coord::new(offset.0, offset.1);
+                    let walkable = rows
+                        .get(neighbour.y as usize)
+                        .and_then(|row| row.get(neighbour.x as usize))
+                        .is_some_and(|ch| WALKABLE.contains(ch));
+                    if walkable && reached.insert(neighbour) {
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+            for &ch in WALKABLE {
+                for coord in find(&rows, ch) {
+                    assert!(
+                        reached.contains(&coord),
+                        "seed {}: {:?} unreachable",
+                        seed,
+                        coord
+                    );
+                }
+            }
+        }
+    }
+}
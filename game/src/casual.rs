@@ -0,0 +1,161 @@
+use crate::{
+    score::CONTINUE_PENALTY, timer::Timers, world::World, Entity, Game, GameOverReason, Message,
+    RunFlags,
+};
+use serde::{Deserialize, Serialize};
+
+/// Number of times a casual run can continue after the player dies
+pub const CASUAL_CONTINUES: u32 = 3;
+
+/// The parts of the game which are put back how they were when the player entered the level
+#[derive(Serialize, Deserialize)]
+struct LevelSnapshot {
+    world: World,
+    player_entity: Entity,
+    timers: Timers,
+    power_switched_off: bool,
+    /// Decisions made on the level, such as sabotaging the reactor, are undone along with it
+    #[serde(default)]
+    run_flags: RunFlags,
+}
+
+/// Borrows the same fields as `LevelSnapshot` so the game doesn't need to be cloned to take a
+/// snapshot
+#[derive(Serialize)]
+struct LevelSnapshotRef<'a> {
+    world: &'a World,
+    player_entity: Entity,
+    timers: &'a Timers,
+    power_switched_off: bool,
+    run_flags: RunFlags,
+}
+
+/// State of a run played in casual mode, where dying sends the player back to the start of the
+/// level rather than ending the game
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct Casual {
+    continues_remaining: u32,
+    /// The serialized `LevelSnapshot` taken when the player entered the current level
+    level_start: String,
+}
+
+impl Casual {
+    pub(crate) fn new() -> Self {
+        Self {
+            continues_remaining: CASUAL_CONTINUES,
+            level_start: String::new(),
+        }
+    }
+}
+
+impl Game {
+    pub fn is_casual(&self) -> bool {
+        self.casual.is_some()
+    }
+
+    /// Continues the player has left, if this is a casual run
+    pub fn continues_remaining(&self) -> Option<u32> {
+        self.casual
+            .as_ref()
+            .map(|casual| casual.continues_remaining)
+    }
+
+    /// Remembers the state of the level so a casual run can return to it when the player dies
+    pub(crate) fn snapshot_level(&mut self) {
+        if self.casual.is_none() {
+            return;
+        }
+        let snapshot = LevelSnapshotRef {
+            world: &self.world,
+            player_entity: self.player_entity,
+            timers: &self.timers,
+            power_switched_off: self.power_switched_off,
+            run_flags: self.run_flags,
+        };
+        let level_start = ron::to_string(&snapshot).expect("failed to snapshot level");
+        if let Some(casual) = self.casual.as_mut() {
+            casual.level_start = level_start;
+        }
+    }
+
+    /// Sends the player back to the start of the level if they have a continue left, returning
+    /// whether they did
+    pub(crate) fn try_continue(&mut self, reason: GameOverReason) -> bool {
+        let casual = match self.casual.as_mut() {
+            Some(casual) if casual.continues_remaining > 0 => casual,
+            _ => return false,
+        };
+        casual.continues_remaining -= 1;
+        let continues_remaining = casual.continues_remaining;
+        let LevelSnapshot {
+            world,
+            player_entity,
+            timers,
+            power_switched_off,
+            run_flags,
+        } = ron::from_str(&casual.level_start).expect("failed to restore level snapshot");
+        self.world = world;
+        self.player_entity = player_entity;
+        self.timers = timers;
+        self.power_switched_off = power_switched_off;
+        self.run_flags = run_flags;
+        self.dragging = None;
+        // npcs on the restored level didn't hear anything made before the player died
+        self.noises.clear();
+        self.score = self.score.saturating_sub(CONTINUE_PENALTY);
+        let cause = match reason {
+            GameOverReason::Killed => "message.continue_cause.killed",
+            GameOverReason::SelfDestruct => "message.continue_cause.self_destruct",
+            GameOverReason::Suffocated => "message.continue_cause.suffocated",
+            GameOverReason::RadiationSickness => "message.continue_cause.radiation_sickness",
+        };
+        self.messages.system(Message::with_args(
+            "message.continue_used",
+            &[&cause, &self.level_name, &continues_remaining],
+        ));
+        self.plan_npc_actions();
+        self.update_visibility();
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{world::spatial::Layers, Config, Ending};
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    #[test]
+    fn continuing_undoes_reactor_sabotage() {
+        let config = Config {
+            casual: true,
+            ..Config::default()
+        };
+        let mut game = Game::new(&config, Vec::new(), &mut Isaac64Rng::seed_from_u64(0));
+        let coord = game
+            .world
+            .size()
+            .coord_iter_row_major()
+            .find(|&coord| {
+                matches!(
+                    game.world.spatial_table.layers_at(coord),
+                    Some(&Layers {
+                        floor: Some(_),
+                        feature: None,
+                        character: None,
+                        ..
+                    })
+                )
+            })
+            .expect("level has an empty floor cell");
+        let reactor = game.world.spawn_reactor(coord);
+        game.destroy_reactor(reactor, &config);
+        assert!(game.run_flags.destroyed_reactor);
+        assert!(game.try_continue(GameOverReason::Killed));
+        assert!(!game.run_flags.destroyed_reactor);
+        assert!(game.noises.is_empty());
+        game.run_flags.escaped = true;
+        assert_eq!(game.run_flags.ending(), Ending::Escaped);
+    }
+}
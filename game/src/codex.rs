@@ -0,0 +1,381 @@
+use crate::{
+    identification::INJECTOR_HEALING,
+    radiation::{DAMAGED_REACTOR_RADIATION, RAD_SUIT_PROTECTION},
+    shield::SHIELD_CHARGES,
+    weapon::AMMO_BOX_ROUNDS,
+    world::data::{BAYONET_DAMAGE, EXTENDED_MAG_AMMO, SCOPE_RANGE_SQUARED_BONUS},
+    CellVisibility, Game, Message, NpcType, Tile, WeaponKind,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CodexCategory {
+    Enemy,
+    Item,
+    Terrain,
+}
+
+impl CodexCategory {
+    /// Key of the category's name in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Enemy => "codex.category.enemy",
+            Self::Item => "codex.category.item",
+            Self::Terrain => "codex.category.terrain",
+        }
+    }
+}
+
+/// Something the player can encounter which has an entry in the codex
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CodexEntry {
+    Zombie,
+    Skeleton,
+    Hound,
+    Abomination,
+    ZombieCorpse,
+    Bones,
+    RadSuit,
+    RocketLauncher,
+    ScatterGun,
+    Railgun,
+    RicochetPistol,
+    MissileLauncher,
+    ChaffEmitter,
+    ShieldGenerator,
+    Ammo,
+    Scope,
+    ExtendedMag,
+    Bayonet,
+    Keycard,
+    HealingInjector,
+    OxygenInjector,
+    AntiRadiationInjector,
+    TeleportInjector,
+    NaniteInjector,
+    Door,
+    LockedDoor,
+    Terminal,
+    Reactor,
+    CoolantTank,
+    Locker,
+    Barricade,
+    Trap,
+    Rubble,
+    Drain,
+    Gap,
+    Hull,
+    StairsDown,
+    EscapePod,
+}
+
+impl CodexEntry {
+    pub const ALL: &'static [Self] = &[
+        Self::Zombie,
+        Self::Skeleton,
+        Self::Hound,
+        Self::Abomination,
+        Self::ZombieCorpse,
+        Self::Bones,
+        Self::RadSuit,
+        Self::RocketLauncher,
+        Self::ScatterGun,
+        Self::Railgun,
+        Self::RicochetPistol,
+        Self::MissileLauncher,
+        Self::ChaffEmitter,
+        Self::ShieldGenerator,
+        Self::Ammo,
+        Self::Scope,
+        Self::ExtendedMag,
+        Self::Bayonet,
+        Self::Keycard,
+        Self::HealingInjector,
+        Self::OxygenInjector,
+        Self::AntiRadiationInjector,
+        Self::TeleportInjector,
+        Self::NaniteInjector,
+        Self::Door,
+        Self::LockedDoor,
+        Self::Terminal,
+        Self::Reactor,
+        Self::CoolantTank,
+        Self::Locker,
+        Self::Barricade,
+        Self::Trap,
+        Self::Rubble,
+        Self::Drain,
+        Self::Gap,
+        Self::Hull,
+        Self::StairsDown,
+        Self::EscapePod,
+    ];
+
+    /// The codex entry describing the thing drawn with a given tile, if any
+    pub fn from_tile(tile: Tile) -> Option<Self> {
+        let entry = match tile {
+            Tile::Zombie => Self::Zombie,
+            Tile::Skeleton => Self::Skeleton,
+            Tile::Hound => Self::Hound,
+            Tile::Abomination => Self::Abomination,
+            Tile::Corpse => Self::ZombieCorpse,
+            Tile::Bones => Self::Bones,
+            Tile::RadSuit => Self::RadSuit,
+            Tile::RocketLauncher => Self::RocketLauncher,
+            Tile::ScatterGun => Self::ScatterGun,
+            Tile::Railgun => Self::Railgun,
+            Tile::RicochetPistol => Self::RicochetPistol,
+            Tile::MissileLauncher => Self::MissileLauncher,
+            Tile::ChaffEmitter => Self::ChaffEmitter,
+            Tile::ShieldGenerator => Self::ShieldGenerator,
+            Tile::Ammo => Self::Ammo,
+            Tile::Scope => Self::Scope,
+            Tile::ExtendedMag => Self::ExtendedMag,
+            Tile::Bayonet => Self::Bayonet,
+            Tile::KeycardRed | Tile::KeycardBlue => Self::Keycard,
+            Tile::DoorClosed | Tile::DoorOpen => Self::Door,
+            Tile::DoorLockedRed | Tile::DoorLockedBlue => Self::LockedDoor,
+            Tile::Terminal => Self::Terminal,
+            Tile::Reactor | Tile::ReactorDestroyed => Self::Reactor,
+            Tile::CoolantTank | Tile::CoolantTankBreached => Self::CoolantTank,
+            Tile::Locker | Tile::LockerOpen => Self::Locker,
+            Tile::Barricade => Self::Barricade,
+            Tile::SpikeTrap | Tile::GasTrap | Tile::AlarmTrap => Self::Trap,
+            Tile::Rubble => Self::Rubble,
+            Tile::Drain => Self::Drain,
+            Tile::Gap => Self::Gap,
+            Tile::Hull => Self::Hull,
+            Tile::StairsDown => Self::StairsDown,
+            Tile::EscapePod => Self::EscapePod,
+            // injectors only enter the codex once they've been identified
+            Tile::InjectorRed
+            | Tile::InjectorBlue
+            | Tile::InjectorGreen
+            | Tile::InjectorYellow
+            | Tile::InjectorPurple => return None,
+            Tile::Player
+            | Tile::Floor
+            | Tile::Wall
+            | Tile::FloorMedbay
+            | Tile::FloorEngineering
+            | Tile::FloorHydroponics
+            | Tile::FloorDerelict
+            | Tile::WallMedbay
+            | Tile::WallEngineering
+            | Tile::WallHydroponics
+            | Tile::WallDerelict
+            | Tile::WallHidden => return None,
+        };
+        Some(entry)
+    }
+
+    /// Key of the entry's name in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Zombie => "codex.zombie",
+            Self::Skeleton => "codex.skeleton",
+            Self::Hound => "codex.hound",
+            Self::Abomination => "codex.abomination",
+            Self::ZombieCorpse => "codex.zombie_corpse",
+            Self::Bones => "codex.bones",
+            Self::RadSuit => "codex.rad_suit",
+            Self::RocketLauncher => "codex.rocket_launcher",
+            Self::ScatterGun => "codex.scatter_gun",
+            Self::Railgun => "codex.railgun",
+            Self::RicochetPistol => "codex.ricochet_pistol",
+            Self::MissileLauncher => "codex.missile_launcher",
+            Self::ChaffEmitter => "codex.chaff_emitter",
+            Self::ShieldGenerator => "codex.shield_generator",
+            Self::Ammo => "codex.ammo",
+            Self::Scope => "codex.scope",
+            Self::ExtendedMag => "codex.extended_mag",
+            Self::Bayonet => "codex.bayonet",
+            Self::Keycard => "codex.keycard",
+            Self::HealingInjector => "codex.healing_injector",
+            Self::OxygenInjector => "codex.oxygen_injector",
+            Self::AntiRadiationInjector => "codex.anti_radiation_injector",
+            Self::TeleportInjector => "codex.teleport_injector",
+            Self::NaniteInjector => "codex.nanite_injector",
+            Self::Door => "codex.door",
+            Self::LockedDoor => "codex.locked_door",
+            Self::Terminal => "codex.terminal",
+            Self::Reactor => "codex.reactor",
+            Self::CoolantTank => "codex.coolant_tank",
+            Self::Locker => "codex.locker",
+            Self::Barricade => "codex.barricade",
+            Self::Trap => "codex.trap",
+            Self::Rubble => "codex.rubble",
+            Self::Drain => "codex.drain",
+            Self::Gap => "codex.gap",
+            Self::Hull => "codex.hull",
+            Self::StairsDown => "codex.stairs_down",
+            Self::EscapePod => "codex.escape_pod",
+        }
+    }
+
+    pub fn category(self) -> CodexCategory {
+        match self {
+            Self::Zombie | Self::Skeleton | Self::Hound | Self::Abomination => CodexCategory::Enemy,
+            Self::ZombieCorpse
+            | Self::Bones
+            | Self::RadSuit
+            | Self::RocketLauncher
+            | Self::ScatterGun
+            | Self::Railgun
+            | Self::RicochetPistol
+            | Self::MissileLauncher
+            | Self::ChaffEmitter
+            | Self::ShieldGenerator
+            | Self::Ammo
+            | Self::Scope
+            | Self::ExtendedMag
+            | Self::Bayonet
+            | Self::Keycard
+            | Self::HealingInjector
+            | Self::OxygenInjector
+            | Self::AntiRadiationInjector
+            | Self::TeleportInjector
+            | Self::NaniteInjector => CodexCategory::Item,
+            Self::Door
+            | Self::LockedDoor
+            | Self::Terminal
+            | Self::Reactor
+            | Self::CoolantTank
+            | Self::Locker
+            | Self::Barricade
+            | Self::Trap
+            | Self::Rubble
+            | Self::Drain
+            | Self::Gap
+            | Self::Hull
+            | Self::StairsDown
+            | Self::EscapePod => CodexCategory::Terrain,
+        }
+    }
+
+    /// Game-relevant numbers, if this entry has any
+    pub fn stats(self) -> Option<Message> {
+        let npc_stats = |npc_type: NpcType| {
+            Message::with_args(
+                "codex.stats.npc",
+                &[&npc_type.max_hit_points(), &npc_type.damage()],
+            )
+        };
+        let weapon_stats = |kind: WeaponKind| {
+            let weapon = kind.weapon();
+            Message::with_args("codex.stats.weapon", &[&weapon.damage, &weapon.ammo])
+        };
+        match self {
+            Self::Zombie => Some(npc_stats(NpcType::Zombie)),
+            Self::Skeleton => Some(npc_stats(NpcType::Skeleton)),
+            Self::Hound => Some(npc_stats(NpcType::Hound)),
+            Self::Abomination => Some(npc_stats(NpcType::Abomination)),
+            Self::Bones => NpcType::Skeleton
+                .respawn()
+                .map(|respawn| Message::with_args("codex.stats.respawn", &[&respawn.turns])),
+            Self::RadSuit => Some(Message::with_args(
+                "codex.stats.rad_suit",
+                &[&RAD_SUIT_PROTECTION],
+            )),
+            Self::RocketLauncher => Some(weapon_stats(WeaponKind::RocketLauncher)),
+            Self::ScatterGun => Some(weapon_stats(WeaponKind::ScatterGun)),
+            Self::Railgun => Some(weapon_stats(WeaponKind::Railgun)),
+            Self::RicochetPistol => Some(weapon_stats(WeaponKind::RicochetPistol)),
+            Self::MissileLauncher => Some(weapon_stats(WeaponKind::MissileLauncher)),
+            Self::Scope => Some(Message::with_args(
+                "codex.stats.scope",
+                &[&SCOPE_RANGE_SQUARED_BONUS],
+            )),
+            Self::ExtendedMag => Some(Message::with_args(
+                "codex.stats.extended_mag",
+                &[&EXTENDED_MAG_AMMO],
+            )),
+            Self::HealingInjector => Some(Message::with_args(
+                "codex.stats.healing_injector",
+                &[&INJECTOR_HEALING],
+            )),
+            Self::Bayonet => Some(Message::with_args(
+                "codex.stats.bayonet",
+                &[&BAYONET_DAMAGE],
+            )),
+            Self::ShieldGenerator => Some(Message::with_args(
+                "codex.stats.shield_generator",
+                &[&SHIELD_CHARGES],
+            )),
+            Self::Ammo => Some(Message::with_args("codex.stats.ammo", &[&AMMO_BOX_ROUNDS])),
+            Self::Reactor => Some(Message::with_args(
+                "codex.stats.reactor",
+                &[&DAMAGED_REACTOR_RADIATION],
+            )),
+            _ => None,
+        }
+    }
+
+    /// Key of the entry's description in the app's locale
+    pub fn description_key(self) -> &'static str {
+        match self {
+            Self::Zombie => "codex.zombie.description",
+            Self::Skeleton => "codex.skeleton.description",
+            Self::Hound => "codex.hound.description",
+            Self::Abomination => "codex.abomination.description",
+            Self::ZombieCorpse => "codex.zombie_corpse.description",
+            Self::Bones => "codex.bones.description",
+            Self::RadSuit => "codex.rad_suit.description",
+            Self::RocketLauncher => "codex.rocket_launcher.description",
+            Self::ScatterGun => "codex.scatter_gun.description",
+            Self::Railgun => "codex.railgun.description",
+            Self::RicochetPistol => "codex.ricochet_pistol.description",
+            Self::MissileLauncher => "codex.missile_launcher.description",
+            Self::ChaffEmitter => "codex.chaff_emitter.description",
+            Self::ShieldGenerator => "codex.shield_generator.description",
+            Self::Ammo => "codex.ammo.description",
+            Self::Scope => "codex.scope.description",
+            Self::ExtendedMag => "codex.extended_mag.description",
+            Self::Bayonet => "codex.bayonet.description",
+            Self::Keycard => "codex.keycard.description",
+            Self::HealingInjector => "codex.healing_injector.description",
+            Self::OxygenInjector => "codex.oxygen_injector.description",
+            Self::AntiRadiationInjector => "codex.anti_radiation_injector.description",
+            Self::TeleportInjector => "codex.teleport_injector.description",
+            Self::NaniteInjector => "codex.nanite_injector.description",
+            Self::Door => "codex.door.description",
+            Self::LockedDoor => "codex.locked_door.description",
+            Self::Terminal => "codex.terminal.description",
+            Self::Reactor => "codex.reactor.description",
+            Self::CoolantTank => "codex.coolant_tank.description",
+            Self::Locker => "codex.locker.description",
+            Self::Barricade => "codex.barricade.description",
+            Self::Trap => "codex.trap.description",
+            Self::Rubble => "codex.rubble.description",
+            Self::Drain => "codex.drain.description",
+            Self::Gap => "codex.gap.description",
+            Self::Hull => "codex.hull.description",
+            Self::StairsDown => "codex.stairs_down.description",
+            Self::EscapePod => "codex.escape_pod.description",
+        }
+    }
+}
+
+impl Game {
+    /// Records codex entries for everything currently visible to the player
+    pub(crate) fn discover_visible(&mut self) {
+        let discovered = &mut self.discovered;
+        for coord in self.world.spatial_table.grid_size().coord_iter_row_major() {
+            if let CellVisibility::Current { data, .. } = self.visibility_grid.get_visibility(coord)
+            {
+                data.tiles.for_each_enumerate(|tile, _layer| {
+                    if let Some(entry) = tile.and_then(CodexEntry::from_tile) {
+                        if !discovered.contains(&entry) {
+                            discovered.push(entry);
+                        }
+                    }
+                });
+            }
+        }
+    }
+
+    /// Codex entries for everything the player has seen during this game, in order of discovery
+    pub fn discovered(&self) -> &[CodexEntry] {
+        &self.discovered
+    }
+}
@@ -0,0 +1,230 @@
+use coord_2d::Coord;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Cells the player can walk through. Doors open when walked into, and the hull can be walked on
+/// outside the station.
+const PASSABLE: &[char] = &['.', ',', '+', '@', '>', 'E', 'S', 'a', 'z', 'r', 'D'];
+/// Features the player must be able to reach for the level to be completable, and which must
+/// be stood on rather than bumped into
+const MANDATORY_ON: &[char] = &['>', 'E'];
+/// Solid features the player must be able to reach, by standing next to them
+const MANDATORY_NEXT_TO: &[char] = &['R', 'T'];
+/// Cells a repair corridor may be dug through
+const DIGGABLE: &[char] = &['#', ':'];
+
+const CARDINALS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+fn neighbours(coord: Coord) -> impl Iterator<Item = Coord> {
+    CARDINALS
+        .into_iter()
+        .map(move |(dx, dy)| coord + Coord::new(dx, dy))
+}
+
+/// What the connectivity check found when generating a level, for debugging
+#[derive(Debug, Clone, Default)]
+pub struct TerrainReport {
+    /// Number of layouts generated, including the one that was kept
+    pub attempts: u32,
+    /// Mandatory features of the kept layout which couldn't be reached from where the player
+    /// starts, before any repairs
+    pub unreachable: Vec<(char, Coord)>,
+    /// Cells dug out of the kept layout to connect unreachable features
+    pub carved: Vec<Coord>,
+    /// Whether every mandatory feature of the kept layout can be reached after repairs
+    pub connected: bool,
+}
+
+struct Cells {
+    rows: Vec<Vec<char>>,
+}
+
+impl Cells {
+    fn get(&self, coord: Coord) -> Option<char> {
+        if coord.x < 0 || coord.y < 0 {
+            return None;
+        }
+        self.rows
+            .get(coord.y as usize)?
+            .get(coord.x as usize)
+            .copied()
+    }
+
+    fn set(&mut self, coord: Coord, ch: char) {
+        self.rows[coord.y as usize][coord.x as usize] = ch;
+    }
+
+    fn coords(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| (0..row.len()).map(move |x| Coord::new(x as i32, y as i32)))
+    }
+
+    fn is_passable(&self, coord: Coord) -> bool {
+        self.get(coord).is_some_and(|ch| PASSABLE.contains(&ch))
+    }
+
+    fn is_on_edge(&self, coord: Coord) -> bool {
+        coord.x <= 0
+            || coord.y <= 0
+            || coord.y as usize >= self.rows.len() - 1
+            || coord.x as usize >= self.rows[coord.y as usize].len() - 1
+    }
+
+    /// Digging out a wall next to the hull would let the station's air out
+    fn is_diggable(&self, coord: Coord) -> bool {
+        let ch = match self.get(coord) {
+            Some(ch) => ch,
+            None => return false,
+        };
+        DIGGABLE.contains(&ch)
+            && !self.is_on_edge(coord)
+            && (-1..=1)
+                .all(|dy| (-1..=1).all(|dx| self.get(coord + Coord::new(dx, dy)) != Some(',')))
+    }
+
+    /// Cells the player can walk to from `start`
+    fn reachable(&self, start: Coord) -> HashSet<Coord> {
+        let mut seen = HashSet::new();
+        seen.insert(start);
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(coord) = queue.pop_front() {
+            for neighbour in neighbours(coord) {
+                if self.is_passable(neighbour) && seen.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        seen
+    }
+
+    fn is_feature_reached(&self, coord: Coord, reachable: &HashSet<Coord>) -> bool {
+        match self.get(coord) {
+            Some(ch) if MANDATORY_NEXT_TO.contains(&ch) => {
+                neighbours(coord).any(|neighbour| reachable.contains(&neighbour))
+            }
+            _ => reachable.contains(&coord),
+        }
+    }
+
+    /// Digs the shortest corridor from the reachable cells to the feature at `coord`, through
+    /// walls and gaps. Returns the cells that were dug out, or `None` if there's no way to
+    /// reach the feature.
+    fn carve_to(&mut self, coord: Coord, reachable: &HashSet<Coord>) -> Option<Vec<Coord>> {
+        let stand_next_to = self
+            .get(coord)
+            .is_some_and(|ch| MANDATORY_NEXT_TO.contains(&ch));
+        let mut previous = HashMap::new();
+        let mut queue = reachable.iter().copied().collect::<VecDeque<_>>();
+        let mut seen = reachable.clone();
+        let mut end = None;
+        while let Some(current) = queue.pop_front() {
+            let arrived = if stand_next_to {
+                neighbours(current).any(|neighbour| neighbour == coord)
+            } else {
+                current == coord
+            };
+            if arrived {
+                end = Some(current);
+                break;
+            }
+            for neighbour in neighbours(current) {
+                let enterable = neighbour == coord
+                    || self.is_passable(neighbour)
+                    || self.is_diggable(neighbour);
+                if enterable && seen.insert(neighbour) {
+                    previous.insert(neighbour, current);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        let mut current = end?;
+        let mut carved = Vec::new();
+        while let Some(&before) = previous.get(&current) {
+            if self.is_diggable(current) {
+                self.set(current, '.');
+                carved.push(current);
+            }
+            current = before;
+        }
+        carved.reverse();
+        Some(carved)
+    }
+}
+
+/// Checks that the stairs and every other mandatory feature of a level can be reached from
+/// where the player starts, digging corridors to any that can't. The returned report's attempt
+/// count is left for the caller to fill in.
+pub(crate) fn repair_connectivity(txt: &str) -> (String, TerrainReport) {
+    let mut cells = Cells {
+        rows: txt.split('\n').map(|row| row.chars().collect()).collect(),
+    };
+    let mut report = TerrainReport::default();
+    let start = match cells.coords().find(|&coord| cells.get(coord) == Some('@')) {
+        Some(start) => start,
+        None => return (txt.to_string(), report),
+    };
+    let features = cells
+        .coords()
+        .filter_map(|coord| {
+            let ch = cells.get(coord)?;
+            (MANDATORY_ON.contains(&ch) || MANDATORY_NEXT_TO.contains(&ch)).then_some((ch, coord))
+        })
+        .collect::<Vec<_>>();
+    let mut reachable = cells.reachable(start);
+    report.connected = true;
+    for (ch, coord) in features {
+        if cells.is_feature_reached(coord, &reachable) {
+            continue;
+        }
+        report.unreachable.push((ch, coord));
+        match cells.carve_to(coord, &reachable) {
+            Some(carved) => {
+                report.carved.extend(carved);
+                reachable = cells.reachable(start);
+            }
+            None => report.connected = false,
+        }
+    }
+    let txt = cells
+        .rows
+        .into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n");
+    (txt, report)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn connected_level_is_unchanged() {
+        let txt = "######\n#@..>#\n######";
+        let (repaired, report) = repair_connectivity(txt);
+        assert_eq!(repaired, txt);
+        assert!(report.unreachable.is_empty());
+        assert!(report.carved.is_empty());
+        assert!(report.connected);
+    }
+
+    #[test]
+    fn corridor_is_dug_to_walled_off_stairs() {
+        let txt = "#######\n#@.#>.#\n#..#..#\n#######";
+        let (repaired, report) = repair_connectivity(txt);
+        assert_eq!(report.unreachable, vec![('>', Coord::new(4, 1))]);
+        assert_eq!(report.carved.len(), 1);
+        assert!(report.connected);
+        let cells = Cells {
+            rows: repaired
+                .split('\n')
+                .map(|row| row.chars().collect())
+                .collect(),
+        };
+        assert!(cells
+            .reachable(Coord::new(1, 1))
+            .contains(&Coord::new(4, 1)));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{
+    world::data::{EntityData, Layer, Location, Tile},
+    Direction, Entity, Game, Message,
+};
+use serde::{Deserialize, Serialize};
+
+/// Items stored inside a feature such as a locker. Like carried items they aren't in the world
+/// until they come out of the container.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Container {
+    contents: Vec<EntityData>,
+}
+
+impl Container {
+    pub fn new(contents: Vec<EntityData>) -> Self {
+        Self { contents }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contents.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.contents.len()
+    }
+
+    pub fn add(&mut self, entity_data: EntityData) {
+        self.contents.push(entity_data);
+    }
+}
+
+impl Game {
+    /// Spills the contents of a container onto the player's cell and the cells around the
+    /// container. Anything without room to land stays inside for next time.
+    pub(crate) fn open_container(&mut self, entity: Entity) {
+        let coord = match self.world.spatial_table.coord_of(entity) {
+            Some(coord) => coord,
+            None => return,
+        };
+        let mut contents = match self.world.components.container.get_mut(entity) {
+            Some(container) => std::mem::take(&mut container.contents),
+            None => return,
+        };
+        if contents.is_empty() {
+            self.messages.items(
+                Some(self.player_entity),
+                Message::new("message.locker_empty"),
+            );
+            return;
+        }
+        let player_coord = self.player_coord();
+        let player_cell_free = self
+            .world
+            .spatial_table
+            .layers_at_checked(player_coord)
+            .item
+            .is_none();
+        let spill_coords = player_cell_free
+            .then_some(player_coord)
+            .into_iter()
+            .chain(
+                Direction::all()
+                    .map(|direction| coord + direction.coord())
+                    .filter(|&neighbour| {
+                        neighbour != player_coord && self.world.can_spawn_item_at(neighbour)
+                    }),
+            )
+            .collect::<Vec<_>>();
+        self.messages.items(
+            Some(self.player_entity),
+            Message::new("message.locker_opened"),
+        );
+        for spill_coord in spill_coords {
+            let entity_data = match contents.pop() {
+                Some(entity_data) => entity_data,
+                None => break,
+            };
+            self.world.insert_entity_data(
+                Location {
+                    layer: Some(Layer::Item),
+                    coord: spill_coord,
+                },
+                entity_data,
+            );
+        }
+        if contents.is_empty() {
+            self.world.components.container.remove(entity);
+            self.world.components.tile.insert(entity, Tile::LockerOpen);
+        } else {
+            self.messages.items(
+                Some(self.player_entity),
+                Message::new("message.locker_no_room"),
+            );
+            if let Some(container) = self.world.components.container.get_mut(entity) {
+                container.contents = contents;
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{world::spatial::Layers, CardinalDirection, Coord, Direction, Entity, Game};
+
+/// Percentage chance that a low feature stops a shot at a character crouched next to it
+pub(crate) const LOW_FEATURE_COVER: u32 = 50;
+
+impl Game {
+    /// The best cover a character at `coord` has against shots coming from `from`. Only
+    /// features on the side facing the shooter count.
+    pub(crate) fn cover_at(&self, coord: Coord, from: Coord) -> u32 {
+        let incoming = from - coord;
+        Direction::all()
+            .map(|direction| direction.coord())
+            .filter(|offset| offset.x * incoming.x + offset.y * incoming.y > 0)
+            .filter_map(|offset| {
+                let feature = self
+                    .world
+                    .spatial_table
+                    .layers_at(coord + offset)?
+                    .feature?;
+                self.world.components.cover.get(feature).copied()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn is_free_for_npc(&self, coord: Coord) -> bool {
+        matches!(
+            self.world.spatial_table.layers_at(coord),
+            Some(&Layers {
+                character: None,
+                floor: Some(_),
+                ..
+            })
+        ) && !self.is_solid_at(coord)
+    }
+
+    /// When the player has a gun, npcs prefer to advance from one piece of cover to the next.
+    /// Returns a step into cover that doesn't take the npc further from the player, if one
+    /// exists and the npc isn't already in cover.
+    pub(crate) fn npc_cover_direction(
+        &self,
+        coord: Coord,
+        player: Entity,
+        player_coord: Coord,
+    ) -> Option<CardinalDirection> {
+        if !self.world.components.weapon.contains(player) || self.cover_at(coord, player_coord) > 0
+        {
+            return None;
+        }
+        let distance = (player_coord - coord).magnitude2();
+        CardinalDirection::all()
+            .filter(|direction| {
+                let neighbour = coord + direction.coord();
+                self.is_free_for_npc(neighbour)
+                    && (player_coord - neighbour).magnitude2() <= distance
+                    && self.cover_at(neighbour, player_coord) > 0
+            })
+            .max_by_key(|direction| self.cover_at(coord + direction.coord(), player_coord))
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{
+    world::data::Weapon, ActionError, Config, Entity, Game, GameControlFlow, GameOverReason,
+    Message,
+};
+
+/// One in this many weapons found lying around the station is cursed
+pub(crate) const CURSED_WEAPON_CHANCE: u32 = 4;
+/// Damage dealt to the player each time they fire a cursed weapon
+pub(crate) const CURSE_DAMAGE: u32 = 1;
+
+impl Game {
+    fn weapon_at_player(&self) -> Option<(Entity, Weapon)> {
+        let item_entity = self
+            .world
+            .spatial_table
+            .layers_at(self.player_coord())?
+            .item?;
+        let weapon = *self.world.components.weapon.get(item_entity)?;
+        Some((item_entity, weapon))
+    }
+
+    /// Weapons under the player whose curse hasn't been revealed can be scanned at a terminal
+    pub(crate) fn can_scan_weapon(&self) -> bool {
+        self.weapon_at_player()
+            .map(|(_, weapon)| !weapon.curse_known)
+            .unwrap_or(false)
+    }
+
+    pub(crate) fn scan_weapon(&mut self) {
+        let (item_entity, weapon) = match self.weapon_at_player() {
+            Some(weapon_at_player) => weapon_at_player,
+            None => return,
+        };
+        if let Some(weapon) = self.world.components.weapon.get_mut(item_entity) {
+            weapon.curse_known = true;
+        }
+        let key = if weapon.cursed {
+            "message.curse_scanned_cursed"
+        } else {
+            "message.curse_scanned_clean"
+        };
+        self.messages.items(
+            Some(item_entity),
+            Message::with_args(key, &[&weapon.kind.name()]),
+        );
+    }
+
+    /// Called when the player takes hold of a weapon. Curses always reveal themselves once it's
+    /// too late.
+    pub(crate) fn reveal_player_weapon_curse(&mut self) {
+        if let Some(weapon) = self.world.components.weapon.get_mut(self.player_entity) {
+            if weapon.cursed && !weapon.curse_known {
+                weapon.curse_known = true;
+                let name = weapon.kind.name();
+                self.messages.items(
+                    Some(self.player_entity),
+                    Message::with_args("message.curse_revealed", &[&name]),
+                );
+            }
+        }
+    }
+
+    pub(crate) fn is_player_weapon_cursed(&self) -> bool {
+        self.player_weapon()
+            .map(|weapon| weapon.cursed)
+            .unwrap_or(false)
+    }
+
+    /// A terminal can only remove a curse the player knows about
+    pub(crate) fn can_remove_curse_at_terminal(&self) -> bool {
+        let known_curse = self
+            .player_weapon()
+            .map(|weapon| weapon.cursed && weapon.curse_known)
+            .unwrap_or(false);
+        known_curse && self.is_powered_terminal_adjacent()
+    }
+
+    pub(crate) fn player_remove_curse(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        if !self.can_remove_curse_at_terminal() {
+            return Err(ActionError::InvalidInteraction);
+        }
+        self.messages.system(Message::new("message.curse_terminal"));
+        self.remove_player_curse();
+        Ok(None)
+    }
+
+    pub(crate) fn remove_player_curse(&mut self) {
+        if let Some(weapon) = self.world.components.weapon.get_mut(self.player_entity) {
+            if weapon.cursed {
+                weapon.cursed = false;
+                weapon.curse_known = true;
+                let name = weapon.kind.name();
+                self.messages.items(
+                    Some(self.player_entity),
+                    Message::with_args("message.curse_lifted", &[&name]),
+                );
+            }
+        }
+    }
+
+    /// Cursed weapons hurt whoever fires them
+    pub(crate) fn curse_drawback(&mut self, config: &Config) -> Option<GameControlFlow> {
+        let weapon = self.player_weapon()?;
+        if !weapon.cursed {
+            return None;
+        }
+        self.messages.combat(
+            Some(self.player_entity),
+            Message::with_args("message.curse_bite", &[&weapon.kind.name()]),
+        );
+        if self.damage_character(self.player_entity, CURSE_DAMAGE, config) {
+            return Some(GameControlFlow::GameOver(GameOverReason::Killed));
+        }
+        None
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    terrain::Terrain,
+    world::{
+        data::{Atmosphere, Faction, Tile},
+        spatial::Layers,
+        World,
+    },
+    CardinalDirection, Coord, Entity, Game,
+};
+use std::fmt::Write;
+
+/// Marks pressurised cells next to open space, which air would escape from
+const AIR_BOUNDARY_CHAR: char = '~';
+
+fn is_solid_at(world: &World, coord: Coord) -> bool {
+    world
+        .spatial_table
+        .layers_at(coord)
+        .and_then(|layers| layers.feature)
+        .is_some_and(|feature| world.components.solid.contains(feature))
+}
+
+fn is_air_boundary(world: &World, coord: Coord) -> bool {
+    world.atmosphere_at(coord) == Atmosphere::Pressurised
+        && !is_solid_at(world, coord)
+        && CardinalDirection::all().any(|direction| {
+            let neighbour = coord + direction.coord();
+            world.atmosphere_at(neighbour) == Atmosphere::Vacuum && !is_solid_at(world, neighbour)
+        })
+}
+
+fn debug_char(world: &World, entity: Entity) -> Option<char> {
+    let ch = world.components.tile.get(entity)?.debug_char();
+    if world.components.faction.get(entity) == Some(&Faction::Renegade) {
+        Some(ch.to_ascii_uppercase())
+    } else {
+        Some(ch)
+    }
+}
+
+fn cell_char(world: &World, coord: Coord) -> char {
+    let &Layers {
+        floor,
+        feature,
+        character,
+        item,
+    } = match world.spatial_table.layers_at(coord) {
+        Some(layers) => layers,
+        None => return ' ',
+    };
+    if let Some(ch) = [character, item, feature]
+        .into_iter()
+        .flatten()
+        .find_map(|entity| debug_char(world, entity))
+    {
+        return ch;
+    }
+    if world.is_gap(coord) {
+        ':'
+    } else if world.atmosphere_at(coord) == Atmosphere::Vacuum {
+        ','
+    } else if is_air_boundary(world, coord) {
+        AIR_BOUNDARY_CHAR
+    } else {
+        floor
+            .and_then(|floor| debug_char(world, floor))
+            .unwrap_or(' ')
+    }
+}
+
+fn describe(world: &World, entity: Entity) -> Option<String> {
+    let tile = world.components.tile.get(entity)?;
+    let mut description = match world.components.npc_type.get(entity) {
+        Some(npc_type) => npc_type.name().to_string(),
+        None => tile.id().replace('_', " "),
+    };
+    if world.components.faction.get(entity) == Some(&Faction::Renegade) {
+        description.push_str(", renegade");
+    }
+    if let Some(name) = world.components.name.get(entity) {
+        write!(description, ", named {}", name).unwrap();
+    }
+    if let Some(container) = world.components.container.get(entity) {
+        write!(description, ", holding {} items", container.len()).unwrap();
+    }
+    Some(description)
+}
+
+/// Renders a level as plain text, one character per cell, followed by notes on everything the
+/// map alone can't describe. Each cell shows its topmost character, item or feature. Npcs are
+/// lower case letters, or upper case if they're renegades. Floor open to space is ',' and
+/// pressurised floor next to it is '~'.
+pub(crate) fn world_to_debug_string(world: &World) -> String {
+    let size = world.spatial_table.grid_size();
+    let mut out = String::new();
+    for y in 0..size.height() as i32 {
+        let row = (0..size.width() as i32)
+            .map(|x| cell_char(world, Coord::new(x, y)))
+            .collect::<String>();
+        writeln!(out, "{}", row.trim_end()).unwrap();
+    }
+    let mut characters = Vec::new();
+    let mut items = Vec::new();
+    let mut features = Vec::new();
+    for coord in size.coord_iter_row_major() {
+        let layers = match world.spatial_table.layers_at(coord) {
+            Some(layers) => layers,
+            None => continue,
+        };
+        if let Some(character) = layers.character {
+            characters.push((coord, character));
+        }
+        if let Some(item) = layers.item {
+            items.push((coord, item));
+        }
+        if let Some(feature) = layers.feature {
+            let tile = world.components.tile.get(feature).copied();
+            let plain = tile.is_none_or(|tile| {
+                tile.is_wall() || tile == Tile::DoorClosed || tile == Tile::DoorOpen
+            });
+            if !plain {
+                features.push((coord, feature));
+            }
+        }
+    }
+    for (heading, entities) in [
+        ("characters", characters),
+        ("items", items),
+        ("features", features),
+    ] {
+        writeln!(out, "{}:", heading).unwrap();
+        for (coord, entity) in entities {
+            if let (Some(ch), Some(description)) =
+                (debug_char(world, entity), describe(world, entity))
+            {
+                writeln!(out, "  {} ({}, {}) {}", ch, coord.x, coord.y, description).unwrap();
+            }
+        }
+    }
+    out
+}
+
+impl Terrain {
+    /// Annotated text rendering of the level, for tuning level generation without the app
+    pub fn to_debug_string(&self) -> String {
+        let mut out = world_to_debug_string(&self.world);
+        writeln!(out, "gravity: {:?}", self.gravity).unwrap();
+        writeln!(out, "{:?}", self.report).unwrap();
+        out
+    }
+}
+
+impl Game {
+    /// Annotated text rendering of the current level as it is now, including the npcs and items
+    /// the player hasn't seen
+    pub fn dump_level_ascii(&self) -> String {
+        let mut out = format!("level {}: {}\n", self.level, self.level_name);
+        if let Some(theme) = self.level_theme {
+            writeln!(out, "theme: {}", theme.name()).unwrap();
+        }
+        writeln!(out, "gravity: {:?}", self.gravity).unwrap();
+        writeln!(out, "turn: {}", self.turn).unwrap();
+        out.push_str(&world_to_debug_string(&self.world));
+        out
+    }
+}
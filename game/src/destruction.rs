@@ -0,0 +1,101 @@
+use crate::{
+    world::data::{Blast, Tile},
+    Coord, Direction, Entity, Game, Message, SoundEffect,
+};
+
+/// Hit points of a wall. Explosions and shots which pass through walls wear them down.
+pub(crate) const WALL_HIT_POINTS: u32 = 5;
+/// Barricades are worn down the same way as walls, but don't hold out as long
+pub(crate) const BARRICADE_HIT_POINTS: u32 = 3;
+
+impl Game {
+    /// The wall at a cell, if it can be destroyed
+    fn destructible_wall_at(&self, coord: Coord) -> Option<Entity> {
+        let feature = self.world.spatial_table.layers_at(coord)?.feature?;
+        let components = &self.world.components;
+        if components.solid.contains(feature) && components.hit_points.contains(feature) {
+            Some(feature)
+        } else {
+            None
+        }
+    }
+
+    /// Walls caught in an explosion. Explosions don't spread through walls, so these are the
+    /// walls within the blast radius which border the area it reaches.
+    pub(crate) fn explosion_walls(&self, centre: Coord, blast: Blast) -> Vec<Coord> {
+        let mut walls = Vec::new();
+        for coord in self.explosion_area(centre, blast) {
+            for direction in Direction::all() {
+                let neighbour = coord + direction.coord();
+                if (neighbour - centre).magnitude2() <= blast.radius_squared
+                    && !walls.contains(&neighbour)
+                    && self.destructible_wall_at(neighbour).is_some()
+                {
+                    walls.push(neighbour);
+                }
+            }
+        }
+        walls
+    }
+
+    /// Damages the wall at a cell, if there is one. A destroyed wall leaves rubble behind which
+    /// no longer blocks movement or vision. Hidden walls are shaken loose rather than damaged,
+    /// exposing the door behind them.
+    pub(crate) fn damage_wall(&mut self, coord: Coord, damage: u32) {
+        let wall = match self.destructible_wall_at(coord) {
+            Some(wall) => wall,
+            None => return,
+        };
+        if self.world.reveal_hidden_wall(coord) {
+            self.messages
+                .system(Message::new("message.hidden_door_exposed"));
+            return;
+        }
+        let destroyed = match self.world.components.hit_points.get_mut(wall) {
+            Some(hit_points) => {
+                hit_points.decrease(damage);
+                hit_points.is_empty()
+            }
+            None => false,
+        };
+        if !destroyed {
+            return;
+        }
+        let message = if self.world.components.tile.get(wall) == Some(&Tile::Barricade) {
+            "message.barricade_smashed"
+        } else {
+            "message.wall_collapses"
+        };
+        self.world.remove_entity(wall);
+        if let Some(floor) = self.world.spatial_table.layers_at_checked(coord).floor {
+            self.world.components.tile.insert(floor, Tile::Rubble);
+            self.world.components.rubble.insert(floor, ());
+        }
+        self.emit_sound(SoundEffect::Breach, coord);
+        self.messages.system(Message::new(message));
+    }
+
+    pub(crate) fn is_rubble_at(&self, coord: Coord) -> bool {
+        self.world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.floor)
+            .is_some_and(|floor| self.world.components.rubble.contains(floor))
+    }
+
+    /// Characters which enter a cell full of rubble or deep liquid spend their next action
+    /// making their way through it
+    pub(crate) fn enter_difficult_terrain(&mut self, entity: Entity) {
+        if let Some(coord) = self.world.spatial_table.coord_of(entity) {
+            if self.is_rubble_at(coord) || self.is_wading_depth_at(coord) {
+                self.world.components.clambering.insert(entity, ());
+            }
+        }
+    }
+
+    /// Returns whether the character is making its way through difficult terrain, which uses up
+    /// its action
+    pub(crate) fn lose_turn_to_difficult_terrain(&mut self, entity: Entity) -> bool {
+        self.world.components.clambering.remove(entity).is_some()
+    }
+}
@@ -0,0 +1,57 @@
+use crate::Game;
+use serde::{Deserialize, Serialize};
+
+/// Chosen when starting a new game. Unlike the run modifiers, every game has exactly one.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: &'static [Self] = &[Self::Easy, Self::Normal, Self::Hard];
+
+    /// Key of the difficulty's name in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Easy => "difficulty.easy",
+            Self::Normal => "difficulty.normal",
+            Self::Hard => "difficulty.hard",
+        }
+    }
+
+    /// The damage dealt by an npc attack which would deal `damage` on normal difficulty
+    pub(crate) fn npc_damage(self, damage: u32) -> u32 {
+        match self {
+            Self::Easy => (damage / 2).max(1),
+            Self::Normal => damage,
+            Self::Hard => damage + damage / 2,
+        }
+    }
+
+    /// Percentage of the usual budget spent on spawning npcs on each level
+    pub(crate) fn spawn_budget_percent(self) -> u32 {
+        match self {
+            Self::Easy => 75,
+            Self::Normal => 100,
+            Self::Hard => 150,
+        }
+    }
+
+    /// Percentage of a unit of oxygen lost each turn the player can't breathe
+    pub(crate) fn oxygen_drain_percent(self) -> u32 {
+        match self {
+            Self::Easy => 50,
+            Self::Normal => 100,
+            Self::Hard => 200,
+        }
+    }
+}
+
+impl Game {
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{Entity, Game};
+
+/// Speed of a character which acts once per turn. The player moves at this speed unless hasted
+/// or slowed.
+pub const NORMAL_SPEED: u32 = 100;
+/// Energy spent by a character each time it acts
+pub const ENERGY_PER_ACTION: u32 = 100;
+
+impl Game {
+    /// A character's speed after any haste or slow. Characters without a speed (e.g. from older
+    /// save files) move at normal speed.
+    pub(crate) fn speed(&self, entity: Entity) -> u32 {
+        let speed = self
+            .world
+            .components
+            .speed
+            .get(entity)
+            .copied()
+            .unwrap_or(NORMAL_SPEED);
+        let percent = self
+            .world
+            .components
+            .status_effects
+            .get(entity)
+            .map_or(100, |status_effects| status_effects.speed_percent());
+        speed * percent / 100
+    }
+
+    /// Gives a character a turn's worth of energy according to its speed, and spends as much of
+    /// it as possible. Returns the number of actions the character can take this turn. Slow
+    /// characters build up energy over several turns, and fast characters may act more than once
+    /// in a turn. A turn lasts as long as the player takes to act, so everything else gets less
+    /// done in the turns of a hasted player.
+    pub(crate) fn gain_energy(&mut self, entity: Entity) -> u32 {
+        let speed = self.speed(entity) * NORMAL_SPEED / self.speed(self.player_entity).max(1);
+        let energy = self
+            .world
+            .components
+            .energy
+            .get(entity)
+            .copied()
+            .unwrap_or(0)
+            + speed;
+        self.world
+            .components
+            .energy
+            .insert(entity, energy % ENERGY_PER_ACTION);
+        energy / ENERGY_PER_ACTION
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{ActionError, Coord, Entity, Game, Input};
+use std::collections::VecDeque;
+
+/// Maximum number of events kept. Older events are discarded to make room for new ones.
+const EVENT_LOG_CAPACITY: usize = 1000;
+
+/// What a character tried to do on their turn
+#[derive(Debug, Clone, Copy)]
+pub enum EventAction {
+    Input(Input),
+    Attack {
+        target: Entity,
+    },
+    /// Started a heavy attack which lands on the given cell next turn
+    WindUp {
+        target: Coord,
+    },
+    /// Threw or fired something at the given cell
+    RangedAttack {
+        target: Coord,
+    },
+    Approach,
+    Drift,
+}
+
+/// What happened as a result of an action
+#[derive(Debug, Clone, Copy)]
+pub enum EventOutcome {
+    Done,
+    Failed(ActionError),
+    Moved(Coord),
+    Blocked,
+    NoPath,
+    Damaged {
+        damage: u32,
+        killed: bool,
+    },
+    /// An attack struck a cell that the target had left
+    Missed,
+    /// The action caused the game to leave regular play, e.g. by winning or opening a menu
+    ControlFlow,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GameEvent {
+    pub turn: u64,
+    pub actor: Entity,
+    pub actor_name: &'static str,
+    pub action: EventAction,
+    pub outcome: EventOutcome,
+}
+
+/// A record of the recent decisions made by characters, to help explain surprising behaviour
+/// after the fact. This isn't saved, and is dumped to the log if the game panics.
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: VecDeque<GameEvent>,
+}
+
+impl EventLog {
+    fn push(&mut self, event: GameEvent) {
+        if self.events.len() >= EVENT_LOG_CAPACITY {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    pub fn events(&self) -> impl Iterator<Item = &GameEvent> {
+        self.events.iter()
+    }
+
+    /// One line per event, oldest first
+    pub fn dump(&self) -> String {
+        let mut dump = String::new();
+        for event in self.events.iter() {
+            dump.push_str(&format!(
+                "turn {}: {} ({:?}) {:?} -> {:?}\n",
+                event.turn, event.actor_name, event.actor, event.action, event.outcome
+            ));
+        }
+        dump
+    }
+}
+
+impl Drop for EventLog {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            log::error!("Game panicked. Recent events:\n{}", self.dump());
+        }
+    }
+}
+
+impl Game {
+    pub(crate) fn record_event(
+        &mut self,
+        actor: Entity,
+        action: EventAction,
+        outcome: EventOutcome,
+    ) {
+        let actor_name = if self.is_player(actor) {
+            "player"
+        } else {
+            self.npc_name(actor)
+        };
+        self.event_log.push(GameEvent {
+            turn: self.turn,
+            actor,
+            actor_name,
+            action,
+            outcome,
+        });
+    }
+
+    pub fn event_log(&self) -> &EventLog {
+        &self.event_log
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    names,
+    status_effect::BURNING_DURATION,
+    world::{
+        data::{Blast, GasKind},
+        spatial::Layers,
+    },
+    ActionError, Config, Coord, DecalKind, Direction, Game, GameControlFlow, GameOverReason,
+    Message, SoundEffect, StatusEffect, TutorialStep,
+};
+use std::collections::{HashSet, VecDeque};
+
+pub const PLAYER_STARTING_GRENADES: u32 = 3;
+/// Maximum distance squared from the player to the cell they are aiming at
+const GRENADE_RANGE_SQUARED: u32 = 49;
+pub(crate) const GRENADE_BLAST: Blast = Blast {
+    radius_squared: 5,
+    falloff: 0,
+    smoke: 4,
+};
+const GRENADE_CAMERA_SHAKE: u32 = 3;
+
+impl Game {
+    pub fn is_in_grenade_range(&self, target: Coord) -> bool {
+        (target - self.player_coord()).magnitude2() <= GRENADE_RANGE_SQUARED
+    }
+
+    pub fn grenade_landing_coord(&self, target: Coord) -> Coord {
+        self.projectile_path(&self.grenade_projectile(target))
+            .last()
+            .cloned()
+            .unwrap_or_else(|| self.player_coord())
+    }
+
+    /// The cells that would be caught in an explosion centred at `centre`. Explosions spread
+    /// outwards from their centre and can't pass through walls or closed doors.
+    pub fn explosion_area(&self, centre: Coord, blast: Blast) -> Vec<Coord> {
+        let size = self.world.spatial_table.grid_size();
+        let mut area = vec![centre];
+        let mut seen = HashSet::new();
+        seen.insert(centre);
+        let mut queue = VecDeque::new();
+        queue.push_back(centre);
+        while let Some(coord) = queue.pop_front() {
+            for direction in Direction::all() {
+                let neighbour = coord + direction.coord();
+                if !neighbour.is_valid(size)
+                    || (neighbour - centre).magnitude2() > blast.radius_squared
+                    || !seen.insert(neighbour)
+                    || self.is_solid_at(neighbour)
+                {
+                    continue;
+                }
+                area.push(neighbour);
+                queue.push_back(neighbour);
+            }
+        }
+        area
+    }
+
+    pub fn player_grenades(&self) -> u32 {
+        self.world
+            .components
+            .grenades
+            .get(self.player_entity)
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    pub(crate) fn player_throw_grenade(
+        &mut self,
+        target: Coord,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        if !self.is_in_grenade_range(target) {
+            return Err(ActionError::OutOfRange);
+        }
+        let grenades = self
+            .world
+            .components
+            .grenades
+            .get_mut(self.player_entity)
+            .filter(|grenades| **grenades > 0)
+            .ok_or(ActionError::NoGrenades)?;
+        *grenades -= 1;
+        let centre = self.grenade_landing_coord(target);
+        self.messages.combat(
+            Some(self.player_entity),
+            Message::new("message.grenade_thrown"),
+        );
+        self.complete_tutorial_step(TutorialStep::ThrowGrenade);
+        Ok(self.explode(centre, GRENADE_BLAST, self.balance.grenade_damage, config))
+    }
+
+    /// Damage falls off with chebyshev distance from the centre of the blast, so cells on the
+    /// edge of a large blast may not be damaged at all
+    pub(crate) fn explode(
+        &mut self,
+        centre: Coord,
+        blast: Blast,
+        damage: u32,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        self.emit_sound(SoundEffect::Explosion, centre);
+        self.emit_camera_shake(GRENADE_CAMERA_SHAKE);
+        for coord in self.explosion_walls(centre, blast) {
+            let delta = coord - centre;
+            let distance = delta.x.abs().max(delta.y.abs()) as u32;
+            self.damage_wall(coord, damage.saturating_sub(blast.falloff * distance));
+        }
+        for coord in self.explosion_area(centre, blast) {
+            let delta = coord - centre;
+            let distance = delta.x.abs().max(delta.y.abs()) as u32;
+            let damage = damage.saturating_sub(blast.falloff * distance);
+            self.world.add_gas(coord, GasKind::Smoke, blast.smoke);
+            if damage == 0 {
+                continue;
+            }
+            self.add_decal(coord, DecalKind::Scorch, config);
+            self.ignite(coord);
+            let character_entity = match self.world.spatial_table.layers_at(coord) {
+                Some(&Layers {
+                    character: Some(character_entity),
+                    ..
+                }) => character_entity,
+                _ => continue,
+            };
+            if self.is_player(character_entity) {
+                self.messages.combat(
+                    Some(character_entity),
+                    Message::new("message.player_caught_in_blast"),
+                );
+                if self.damage_character(character_entity, damage, config) {
+                    return Some(GameControlFlow::GameOver(GameOverReason::Killed));
+                }
+            } else if self.damage_character(character_entity, damage, config) {
+                let name = self.npc_description(character_entity);
+                self.messages.combat(
+                    Some(character_entity),
+                    Message::with_args("message.npc_blown_apart", &[&names::capitalise(&name)]),
+                );
+                self.npc_die(character_entity);
+                continue;
+            }
+            self.apply_status_effect(character_entity, StatusEffect::Burning, BURNING_DURATION);
+        }
+        None
+    }
+}
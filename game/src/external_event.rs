@@ -0,0 +1,86 @@
+use crate::{BossPhase, Coord, Entity, Game, LevelTheme, Message};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEffect {
+    Explosion,
+    Hit,
+    Door,
+    Breach,
+}
+
+/// Something that happened in the game which the app may want to present to the player, beyond
+/// what can be seen by looking at the game state
+#[derive(Debug, Clone)]
+pub enum ExternalEvent {
+    Sound {
+        effect: SoundEffect,
+        coord: Coord,
+    },
+    Message {
+        source: Option<Entity>,
+        message: Message,
+    },
+    CameraShake {
+        intensity: u32,
+    },
+    /// The player swung at these cells in melee
+    MeleeAttack {
+        cells: Vec<Coord>,
+    },
+    /// An attack on the character in this cell missed
+    Miss {
+        coord: Coord,
+    },
+    /// The player has arrived on a level, including the first level of a new game
+    LevelStart {
+        level: u32,
+        name: String,
+        theme: Option<LevelTheme>,
+    },
+    /// The boss has noticed the player or changed how it fights
+    BossPhaseChange {
+        phase: BossPhase,
+    },
+    BossDefeated,
+}
+
+impl Game {
+    pub(crate) fn emit_sound(&mut self, effect: SoundEffect, coord: Coord) {
+        self.external_events
+            .push(ExternalEvent::Sound { effect, coord });
+        self.make_sound_noise(effect, coord);
+    }
+
+    pub(crate) fn emit_camera_shake(&mut self, intensity: u32) {
+        self.external_events
+            .push(ExternalEvent::CameraShake { intensity });
+    }
+
+    pub(crate) fn emit_level_start(&mut self) {
+        self.external_events.push(ExternalEvent::LevelStart {
+            level: self.level,
+            name: self.level_name.clone(),
+            theme: self.level_theme,
+        });
+    }
+
+    /// Called after loading a game so messages logged before it was saved aren't reported again
+    pub(crate) fn mark_messages_reported(&mut self) {
+        self.num_reported_messages = self.messages.entries().len();
+    }
+
+    /// Removes and returns all the events that happened since the last call, followed by an event
+    /// for each message logged since the last call
+    pub(crate) fn take_external_events(&mut self) -> Vec<ExternalEvent> {
+        let mut events = std::mem::take(&mut self.external_events);
+        events.extend(
+            self.take_new_messages()
+                .into_iter()
+                .map(|entry| ExternalEvent::Message {
+                    source: entry.source,
+                    message: entry.message,
+                }),
+        );
+        events
+    }
+}
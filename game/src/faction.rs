@@ -0,0 +1,140 @@
+use crate::{
+    names,
+    world::{
+        data::{Faction, NpcType},
+        World,
+    },
+    Config, Coord, Entity, EventAction, EventOutcome, Game, GameControlFlow, Message, SoundEffect,
+};
+use rand::{seq::SliceRandom, Rng};
+
+/// Rival factions only turn up from this level onwards
+const RIVAL_FACTION_MIN_LEVEL: u32 = 2;
+/// Npcs within this distance of the first renegade placed on a level join its pack
+const RIVAL_PACK_RADIUS_SQUARED: u32 = 64;
+/// Npcs notice enemies other than the player within this distance
+const RIVAL_SIGHT_DISTANCE_SQUARED: u32 = 64;
+
+/// On later levels, turns the npcs around one of the npcs placed on the level into a rival
+/// pack which fights the rest of them
+pub(crate) fn place_rivals<R: Rng>(world: &mut World, npcs: &[Entity], level: u32, rng: &mut R) {
+    if level < RIVAL_FACTION_MIN_LEVEL {
+        return;
+    }
+    let anchor = match npcs.choose(rng) {
+        Some(&anchor) => anchor,
+        None => return,
+    };
+    let anchor_coord = match world.spatial_table.coord_of(anchor) {
+        Some(coord) => coord,
+        None => return,
+    };
+    for &entity in npcs {
+        if let Some(coord) = world.spatial_table.coord_of(entity) {
+            if (coord - anchor_coord).magnitude2() <= RIVAL_PACK_RADIUS_SQUARED {
+                world.components.faction.insert(entity, Faction::Renegade);
+            }
+        }
+    }
+}
+
+impl Game {
+    /// Leaving renegades alive on a level counts as sparing them, as long as the player has
+    /// never killed one
+    pub(crate) fn record_faction_spared(&mut self) {
+        let renegades_alive = self
+            .world
+            .components
+            .faction
+            .iter()
+            .any(|(_, &faction)| faction == Faction::Renegade);
+        if renegades_alive && !self.run_flags.harmed_faction {
+            self.run_flags.spared_faction = true;
+        }
+    }
+
+    /// Killing a renegade rules out a truce with them
+    pub(crate) fn record_faction_harmed(&mut self, entity: Entity) {
+        if self.world.components.faction.get(entity) == Some(&Faction::Renegade) {
+            self.run_flags.harmed_faction = true;
+            self.run_flags.spared_faction = false;
+        }
+    }
+
+    /// The closest npc hostile to the given npc, if it's nearby and closer than the player
+    pub(crate) fn nearest_rival(
+        &self,
+        entity: Entity,
+        coord: Coord,
+        player_coord: Coord,
+    ) -> Option<(Entity, Coord)> {
+        let faction = *self.world.components.faction.get(entity)?;
+        let limit = (player_coord - coord)
+            .magnitude2()
+            .min(RIVAL_SIGHT_DISTANCE_SQUARED + 1);
+        self.world
+            .components
+            .faction
+            .iter()
+            .filter(|&(other, &other_faction)| {
+                other != entity && !self.is_player(other) && other_faction.is_hostile_to(faction)
+            })
+            .filter_map(|(other, _)| Some((other, self.world.spatial_table.coord_of(other)?)))
+            .filter(|&(_, other_coord)| (other_coord - coord).magnitude2() < limit)
+            .min_by_key(|&(_, other_coord)| (other_coord - coord).magnitude2())
+    }
+
+    /// Resolves a melee attack by one npc on another. The player gets no credit for kills.
+    pub(crate) fn npc_attack_npc(
+        &mut self,
+        entity: Entity,
+        target: Entity,
+        npc_type: NpcType,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        let name = names::capitalise(&self.npc_description(entity));
+        let target_name = self.npc_description(target);
+        if !self.roll_to_hit(Some(entity), target, 0) {
+            self.messages.combat(
+                Some(entity),
+                Message::with_args("message.npc_misses_npc", &[&name, &target_name]),
+            );
+            self.record_event(entity, EventAction::Attack { target }, EventOutcome::Missed);
+            return None;
+        }
+        if self.absorb_hit(target) {
+            self.record_event(
+                entity,
+                EventAction::Attack { target },
+                EventOutcome::Blocked,
+            );
+            return None;
+        }
+        if let Some(coord) = self.world.spatial_table.coord_of(target) {
+            self.emit_sound(SoundEffect::Hit, coord);
+        }
+        let damage = npc_type.damage();
+        let killed = self.damage_character(target, damage, config);
+        self.record_event(
+            entity,
+            EventAction::Attack { target },
+            EventOutcome::Damaged { damage, killed },
+        );
+        if killed {
+            self.messages.combat(
+                Some(entity),
+                Message::with_args("message.npc_destroys_npc", &[&name, &target_name]),
+            );
+            self.remove_dead_npc(target);
+        } else {
+            self.messages.combat(
+                Some(entity),
+                Message::with_args("message.npc_hits_npc", &[&name, &target_name]),
+            );
+            if let Some(effect) = npc_type.melee_status_effect() {
+                self.apply_status_effect(target, effect, effect.default_duration());
+            }
+        }
+        None
+    }
+}
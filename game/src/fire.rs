@@ -0,0 +1,108 @@
+use crate::{
+    status_effect::BURNING_DURATION,
+    world::data::{Atmosphere, DecalKind},
+    CardinalDirection, Config, Coord, Direction, Entity, Game, StatusEffect,
+};
+
+/// Turns a cell burns for with nothing to keep it going
+const FIRE_TURNS: u32 = 2;
+/// Extra turns a cell burns for if there's something flammable in it
+const FUEL_TURNS: u32 = 4;
+
+impl Game {
+    fn floor_at(&self, coord: Coord) -> Option<Entity> {
+        self.world.spatial_table.layers_at(coord)?.floor
+    }
+
+    /// Fire needs air, and can't burn underwater or inside walls
+    fn can_burn_at(&self, coord: Coord) -> bool {
+        self.floor_at(coord).is_some()
+            && !self.is_solid_at(coord)
+            && self.world.liquid_depth_at(coord) == 0
+            && self.world.atmosphere_at(coord) == Atmosphere::Pressurised
+    }
+
+    /// Oil and corpses catch fire from neighbouring flames
+    fn is_flammable_at(&self, coord: Coord) -> bool {
+        let layers = match self.world.spatial_table.layers_at(coord) {
+            Some(layers) => layers,
+            None => return false,
+        };
+        let oil = layers.floor.is_some_and(|floor| {
+            self.world
+                .components
+                .decal
+                .get(floor)
+                .is_some_and(|decal| decal.kind == DecalKind::Oil)
+        });
+        let corpse = layers
+            .item
+            .is_some_and(|item| self.world.components.corpse.contains(item));
+        oil || corpse
+    }
+
+    pub fn is_fire_at(&self, coord: Coord) -> bool {
+        self.floor_at(coord)
+            .is_some_and(|floor| self.world.components.fire.contains(floor))
+    }
+
+    /// Sets a cell alight if it can burn. Cells with something flammable in them burn longer.
+    pub(crate) fn ignite(&mut self, coord: Coord) {
+        if !self.can_burn_at(coord) {
+            return;
+        }
+        let turns = if self.is_flammable_at(coord) {
+            FIRE_TURNS + FUEL_TURNS
+        } else {
+            FIRE_TURNS
+        };
+        if let Some(floor) = self.floor_at(coord) {
+            let fire = self.world.components.fire.get(floor).copied().unwrap_or(0);
+            self.world.components.fire.insert(floor, fire.max(turns));
+        }
+    }
+
+    /// Fire eats up the air around it, so the player can't catch their breath near it
+    pub(crate) fn is_fire_near(&self, coord: Coord) -> bool {
+        std::iter::once(coord)
+            .chain(Direction::all().map(|direction| coord + direction.coord()))
+            .any(|coord| self.is_fire_at(coord))
+    }
+
+    /// Spreads fire to flammable neighbours, sets characters standing in it alight, and burns
+    /// out fires which have run out of fuel
+    pub(crate) fn tick_fire(&mut self, config: &Config) {
+        let burning = self
+            .world
+            .components
+            .fire
+            .iter()
+            .filter_map(|(floor, &turns)| {
+                let coord = self.world.spatial_table.coord_of(floor)?;
+                Some((floor, coord, turns))
+            })
+            .collect::<Vec<_>>();
+        for &(floor, coord, turns) in &burning {
+            // liquid which has flowed in since the fire started puts it out
+            if !self.can_burn_at(coord) || turns <= 1 {
+                self.world.components.fire.remove(floor);
+                self.add_decal(coord, DecalKind::Scorch, config);
+                continue;
+            }
+            self.world.components.fire.insert(floor, turns - 1);
+            for direction in CardinalDirection::all() {
+                let neighbour = coord + direction.coord();
+                if !self.is_fire_at(neighbour) && self.is_flammable_at(neighbour) {
+                    self.ignite(neighbour);
+                }
+            }
+        }
+        let characters = burning
+            .into_iter()
+            .filter_map(|(_, coord, _)| self.world.spatial_table.layers_at(coord)?.character)
+            .collect::<Vec<_>>();
+        for character in characters {
+            self.apply_status_effect(character, StatusEffect::Burning, BURNING_DURATION);
+        }
+    }
+}
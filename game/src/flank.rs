@@ -0,0 +1,99 @@
+use crate::{world::data::Alertness, CardinalDirection, Coord, Entity, Game};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Npcs at most this many steps from the player coordinate their approach with each other
+const FLANK_DISTANCE: u32 = 8;
+/// Longest detour an npc will take to reach the side of the player it was assigned
+const FLANK_MAX_PATH: usize = 16;
+
+impl Game {
+    /// Shares out the cells next to each player between the npcs closing in on them, so a pack
+    /// surrounds the player rather than queueing behind whichever npc is in front. The closest
+    /// npcs choose first, and each takes the free side nearest to it. Npcs approaching alone
+    /// keep following the distance map.
+    pub(crate) fn assign_flanks(&mut self) {
+        let mut flanks = HashMap::new();
+        for player_coord in self.player_coords() {
+            let mut pack = self
+                .world
+                .components
+                .npc_type
+                .iter()
+                .filter(|&(entity, _)| {
+                    self.npc_alertness(entity) == Alertness::Hostile
+                        && !self.world.components.fleeing.contains(entity)
+                        && !self.is_searching(entity)
+                })
+                .filter_map(|(entity, _)| {
+                    let coord = self.world.spatial_table.coord_of(entity)?;
+                    let distance = self.ai_distance_at(coord)?;
+                    let target = self.nearest_player(coord);
+                    (distance > 1
+                        && distance <= FLANK_DISTANCE
+                        && self.world.spatial_table.coord_of(target) == Some(player_coord))
+                    .then_some((entity, coord, distance))
+                })
+                .collect::<Vec<_>>();
+            if pack.len() < 2 {
+                continue;
+            }
+            pack.sort_by_key(|&(_, _, distance)| distance);
+            let mut sides = CardinalDirection::all()
+                .map(|direction| player_coord + direction.coord())
+                .filter(|&coord| self.is_free_for_npc(coord))
+                .collect::<Vec<_>>();
+            for (entity, coord, _) in pack {
+                let index = match (0..sides.len())
+                    .min_by_key(|&index| (sides[index] - coord).magnitude2())
+                {
+                    Some(index) => index,
+                    None => break,
+                };
+                flanks.insert(entity, sides.swap_remove(index));
+            }
+        }
+        self.ai_ctx.flanks = flanks;
+    }
+
+    /// The first step on the shortest path from `coord` to the side of the player the npc was
+    /// assigned, routing around other characters
+    pub(crate) fn flank_step(&self, entity: Entity, coord: Coord) -> Option<CardinalDirection> {
+        let flank = *self.ai_ctx.flanks.get(&entity)?;
+        self.path_step(coord, flank, FLANK_MAX_PATH)
+    }
+
+    /// The first step on the shortest path from `coord` to `goal` no longer than `max_length`,
+    /// routing around other characters. The goal itself may be occupied.
+    pub(crate) fn path_step(
+        &self,
+        coord: Coord,
+        goal: Coord,
+        max_length: usize,
+    ) -> Option<CardinalDirection> {
+        let mut first_steps = HashMap::new();
+        let mut seen = HashSet::new();
+        seen.insert(coord);
+        let mut queue = VecDeque::new();
+        queue.push_back((coord, 0));
+        while let Some((current, length)) = queue.pop_front() {
+            if current == goal {
+                return first_steps.get(&current).copied();
+            }
+            if length >= max_length {
+                continue;
+            }
+            for direction in CardinalDirection::all() {
+                let neighbour = current + direction.coord();
+                if (neighbour != goal && !self.is_free_for_npc(neighbour))
+                    || !seen.insert(neighbour)
+                {
+                    continue;
+                }
+                let first_step = first_steps.get(&current).copied().unwrap_or(direction);
+                first_steps.insert(neighbour, first_step);
+                queue.push_back((neighbour, length + 1));
+            }
+        }
+        None
+    }
+}
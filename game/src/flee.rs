@@ -0,0 +1,134 @@
+use crate::{names, world::spatial::Layers, CardinalDirection, Coord, Entity, Game, Message};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque},
+};
+
+/// Npcs on this percentage of their hit points or fewer lose their nerve and run
+const FLEE_HIT_POINTS_PERCENT: u32 = 34;
+/// Turns an npc spends running before it rallies and fights to the death
+const FLEE_TURNS: u32 = 6;
+/// Furthest distance from the player that fleeing npcs consider when deciding where to run
+const FLEE_MAP_DISTANCE: i32 = 20;
+/// Distances from the player are scaled by this many tenths before being inverted. Scaling by
+/// more than one makes npcs cornered in a dead end prefer to slip past the player over staying
+/// put.
+const FLEE_MAP_SCALE_TENTHS: i32 = 12;
+
+impl Game {
+    fn is_walkable(&self, coord: Coord) -> bool {
+        match self.world.spatial_table.layers_at(coord) {
+            Some(&Layers {
+                floor: Some(floor), ..
+            }) => !self.world.components.gap.contains(floor) && !self.is_solid_at(coord),
+            _ => false,
+        }
+    }
+
+    /// Builds the map fleeing npcs follow downhill. It starts as the distance to the nearest
+    /// player made negative, then each cell is relaxed to at most one more than its lowest
+    /// neighbour, so dead ends near the player stop looking like safe places to run to.
+    pub(crate) fn update_flee_map(&mut self) {
+        let size = self.world.size();
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        for coord in self.player_coords() {
+            distances.insert(coord, 0);
+            queue.push_back((coord, 0));
+        }
+        while let Some((coord, distance)) = queue.pop_front() {
+            if distance >= FLEE_MAP_DISTANCE {
+                continue;
+            }
+            for direction in CardinalDirection::all() {
+                let neighbour = coord + direction.coord();
+                if !neighbour.is_valid(size)
+                    || distances.contains_key(&neighbour)
+                    || !self.is_walkable(neighbour)
+                {
+                    continue;
+                }
+                distances.insert(neighbour, distance + 1);
+                queue.push_back((neighbour, distance + 1));
+            }
+        }
+        let mut flee_map = distances
+            .into_iter()
+            .map(|(coord, distance)| (coord, -(distance * FLEE_MAP_SCALE_TENTHS) / 10))
+            .collect::<HashMap<_, _>>();
+        let mut heap = flee_map
+            .iter()
+            .map(|(coord, &value)| Reverse((value, coord.x, coord.y)))
+            .collect::<BinaryHeap<_>>();
+        while let Some(Reverse((value, x, y))) = heap.pop() {
+            let coord = Coord::new(x, y);
+            if flee_map.get(&coord) != Some(&value) {
+                continue;
+            }
+            for direction in CardinalDirection::all() {
+                let neighbour = coord + direction.coord();
+                if let Some(neighbour_value) = flee_map.get_mut(&neighbour) {
+                    if value + 1 < *neighbour_value {
+                        *neighbour_value = value + 1;
+                        heap.push(Reverse((value + 1, neighbour.x, neighbour.y)));
+                    }
+                }
+            }
+        }
+        self.ai_ctx.flee_map = flee_map;
+    }
+
+    /// Checks whether a badly hurt npc breaks and runs, and counts down the turns until a
+    /// fleeing npc rallies. Each npc only flees once. Returns true if the npc is fleeing.
+    pub(crate) fn check_morale(&mut self, entity: Entity) -> bool {
+        if let Some(turns) = self.world.components.fleeing.get_mut(entity) {
+            if *turns > 0 {
+                *turns -= 1;
+                return true;
+            }
+            self.world.components.fleeing.remove(entity);
+            self.world.components.rallied.insert(entity, ());
+            let name = names::capitalise(&self.npc_description(entity));
+            self.messages.combat(
+                Some(entity),
+                Message::with_args("message.npc_rallies", &[&name]),
+            );
+            return false;
+        }
+        if self.world.components.rallied.contains(entity) {
+            return false;
+        }
+        let badly_hurt = self
+            .world
+            .components
+            .hit_points
+            .get(entity)
+            .map(|hit_points| {
+                hit_points.current() * 100 <= hit_points.max() * FLEE_HIT_POINTS_PERCENT
+            })
+            .unwrap_or(false);
+        if !badly_hurt {
+            return false;
+        }
+        self.world.components.fleeing.insert(entity, FLEE_TURNS);
+        let name = names::capitalise(&self.npc_description(entity));
+        self.messages.combat(
+            Some(entity),
+            Message::with_args("message.npc_flees", &[&name]),
+        );
+        true
+    }
+
+    /// A step from `coord` further away from the player, following the flee map downhill
+    pub(crate) fn flee_step(&self, coord: Coord) -> Option<CardinalDirection> {
+        let current = *self.ai_ctx.flee_map.get(&coord)?;
+        CardinalDirection::all()
+            .filter_map(|direction| {
+                let neighbour = coord + direction.coord();
+                let value = *self.ai_ctx.flee_map.get(&neighbour)?;
+                (value < current && self.is_free_for_npc(neighbour)).then_some((direction, value))
+            })
+            .min_by_key(|&(_, value)| value)
+            .map(|(direction, _)| direction)
+    }
+}
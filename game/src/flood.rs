@@ -0,0 +1,77 @@
+use crate::{
+    names, CardinalDirection, Config, Coord, Entity, Game, GameControlFlow, GameOverReason,
+    Message, PowerState,
+};
+use std::collections::{HashSet, VecDeque};
+
+/// Characters lose an action for each cell they enter with liquid at least this deep
+const WADING_DEPTH: u8 = 2;
+/// Damage dealt each turn to characters standing in electrified liquid
+const SHOCK_DAMAGE: u32 = 2;
+
+impl Game {
+    pub(crate) fn is_wading_depth_at(&self, coord: Coord) -> bool {
+        self.world.liquid_depth_at(coord) >= WADING_DEPTH
+    }
+
+    /// Cells of liquid connected to a powered terminal. Any liquid touching a terminal carries
+    /// its current through the whole pool.
+    pub fn electrified_cells(&self) -> HashSet<Coord> {
+        let mut electrified = HashSet::new();
+        if self.power_state() != PowerState::On {
+            return electrified;
+        }
+        let has_liquid = |coord: Coord| self.world.liquid_depth_at(coord) > 0;
+        let mut queue = self
+            .world
+            .components
+            .terminal
+            .iter()
+            .filter_map(|(entity, _)| self.world.spatial_table.coord_of(entity))
+            .flat_map(|coord| CardinalDirection::all().map(move |d| coord + d.coord()))
+            .filter(|&coord| has_liquid(coord))
+            .collect::<VecDeque<_>>();
+        while let Some(coord) = queue.pop_front() {
+            if !electrified.insert(coord) {
+                continue;
+            }
+            for direction in CardinalDirection::all() {
+                let neighbour = coord + direction.coord();
+                if has_liquid(neighbour) && !electrified.contains(&neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        electrified
+    }
+
+    /// Shocks every character standing in electrified liquid
+    pub(crate) fn tick_electrified_liquid(&mut self, config: &Config) -> Option<GameControlFlow> {
+        let electrified = self.electrified_cells();
+        if electrified.is_empty() {
+            return None;
+        }
+        let shocked = electrified
+            .into_iter()
+            .filter_map(|coord| self.world.spatial_table.layers_at(coord)?.character)
+            .collect::<Vec<Entity>>();
+        for entity in shocked {
+            let killed = self.damage_character(entity, SHOCK_DAMAGE, config);
+            if self.is_player(entity) {
+                self.messages
+                    .combat(Some(entity), Message::new("message.player_electrocuted"));
+                if killed {
+                    return Some(GameControlFlow::GameOver(GameOverReason::Killed));
+                }
+            } else if killed {
+                let name = names::capitalise(&self.npc_description(entity));
+                self.messages.combat(
+                    Some(entity),
+                    Message::with_args("message.npc_electrocuted", &[&name]),
+                );
+                self.npc_die(entity);
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,22 @@
+use crate::{status_effect::POISON_DURATION, world::data::GasKind, Game, StatusEffect};
+
+impl Game {
+    /// Spreads and thins out gas clouds, then poisons characters breathing toxic gas
+    pub(crate) fn tick_gas(&mut self) {
+        self.world.diffuse_gas();
+        let poisoned = self
+            .world
+            .components
+            .gas
+            .iter()
+            .filter(|(_, gas)| gas.kind == GasKind::Poison)
+            .filter_map(|(floor, _)| {
+                let coord = self.world.spatial_table.coord_of(floor)?;
+                self.world.spatial_table.layers_at(coord)?.character
+            })
+            .collect::<Vec<_>>();
+        for character in poisoned {
+            self.apply_status_effect(character, StatusEffect::Poison, POISON_DURATION);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{Coord, Game};
+use serde::{Deserialize, Serialize};
+
+/// Where the player was at the end of a turn
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GhostStep {
+    pub level: u32,
+    pub coord: Coord,
+}
+
+/// The path the player took through a run, one step per turn. Later runs on the same seed show
+/// it as a ghost.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GhostTrail {
+    pub seed: u64,
+    steps: Vec<GhostStep>,
+}
+
+impl GhostTrail {
+    /// Where the player was at the end of the given turn
+    pub fn at_turn(&self, turn: u64) -> Option<GhostStep> {
+        self.steps.get(turn as usize).copied()
+    }
+}
+
+impl Game {
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn ghost_trail(&self) -> &GhostTrail {
+        &self.ghost_trail
+    }
+
+    /// Records where the player is on the current turn. Turns that ended early (e.g. by taking
+    /// the stairs) don't advance the turn counter, so the latest step for a turn replaces any
+    /// earlier one.
+    pub(crate) fn record_ghost_step(&mut self) {
+        let coord = match self.world.spatial_table.coord_of(self.player_entity) {
+            Some(coord) => coord,
+            None => return,
+        };
+        let step = GhostStep {
+            level: self.level,
+            coord,
+        };
+        let turn = self.turn as usize;
+        let steps = &mut self.ghost_trail.steps;
+        steps.truncate(turn);
+        if let Some(&last) = steps.last() {
+            steps.resize(turn, last);
+        }
+        steps.push(step);
+    }
+}
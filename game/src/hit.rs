@@ -0,0 +1,50 @@
+use crate::{Coord, Entity, ExternalEvent, Game};
+use rand::Rng;
+
+pub(crate) const PLAYER_ACCURACY: u32 = 85;
+pub(crate) const PLAYER_EVASION: u32 = 10;
+/// Every attack has at least this percentage chance to hit, however evasive its target
+const MIN_HIT_CHANCE: u32 = 5;
+/// Every attack has at least this percentage chance to miss, however accurate its attacker
+const MAX_HIT_CHANCE: u32 = 95;
+
+impl Game {
+    /// Percentage chance that an attack by `attacker` hits `target`. Attacks with no attacker,
+    /// or from something without an accuracy, always hit unless the target is evasive.
+    pub(crate) fn hit_chance(&self, attacker: Option<Entity>, target: Entity, bonus: u32) -> u32 {
+        let accuracy = attacker
+            .and_then(|attacker| self.world.components.accuracy.get(attacker).copied())
+            .unwrap_or(100)
+            + bonus;
+        let evasion = self
+            .world
+            .components
+            .evasion
+            .get(target)
+            .copied()
+            .unwrap_or(0);
+        accuracy
+            .saturating_sub(evasion)
+            .clamp(MIN_HIT_CHANCE, MAX_HIT_CHANCE)
+    }
+
+    /// Rolls an attack against its target's evasion. Misses are shown by the app.
+    pub(crate) fn roll_to_hit(
+        &mut self,
+        attacker: Option<Entity>,
+        target: Entity,
+        bonus: u32,
+    ) -> bool {
+        let hit = self.rng.gen_range(0..100) < self.hit_chance(attacker, target, bonus);
+        if !hit {
+            if let Some(coord) = self.world.spatial_table.coord_of(target) {
+                self.emit_miss(coord);
+            }
+        }
+        hit
+    }
+
+    fn emit_miss(&mut self, coord: Coord) {
+        self.external_events.push(ExternalEvent::Miss { coord });
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{world::spatial::Layers, Coord, Entity, Game, Message};
+
+impl Game {
+    /// The cell containing the nearest character to `coord` which a homing projectile could lock
+    /// onto. Characters carrying chaff can't be locked onto.
+    pub(crate) fn homing_target(&self, coord: Coord, source: Option<Entity>) -> Option<Coord> {
+        self.world
+            .components
+            .npc_type
+            .iter()
+            .map(|(entity, _)| entity)
+            .chain(std::iter::once(self.player_entity))
+            .filter(|&entity| {
+                Some(entity) != source && !self.world.components.chaff.contains(entity)
+            })
+            .filter_map(|entity| self.world.spatial_table.coord_of(entity))
+            .min_by_key(|&target| (target - coord).magnitude2())
+    }
+
+    pub(crate) fn pick_up_chaff_emitter(&mut self) {
+        let item_entity = match self.world.spatial_table.layers_at(self.player_coord()) {
+            Some(&Layers {
+                item: Some(item_entity),
+                ..
+            }) => item_entity,
+            _ => return,
+        };
+        if self.world.components.chaff.contains(item_entity) {
+            self.world.remove_entity(item_entity);
+            self.world.components.chaff.insert(self.player_entity, ());
+            self.messages.items(
+                Some(item_entity),
+                Message::new("message.chaff_emitter_worn"),
+            );
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{
+    world::{
+        data::{EntityData, Layer, Location},
+        spawn::make_player,
+    },
+    Coord, Direction, Entity, Game, Message,
+};
+
+impl Game {
+    /// Hot-seat games have several characters controlled from the same keyboard
+    pub fn is_hot_seat(&self) -> bool {
+        self.players.len() > 1
+    }
+
+    /// Hot-seat players are numbered from 1 in the order they take their turns
+    pub fn active_player_number(&self) -> Option<usize> {
+        self.players
+            .iter()
+            .position(|&entity| entity == self.player_entity)
+            .filter(|_| self.is_hot_seat())
+            .map(|index| index + 1)
+    }
+
+    /// True for any character controlled by a player, including ones waiting for their turn
+    pub(crate) fn is_player(&self, entity: Entity) -> bool {
+        entity == self.player_entity || self.players.contains(&entity)
+    }
+
+    /// The player-controlled character standing at a coordinate, if any
+    pub(crate) fn player_at(&self, coord: Coord) -> Option<Entity> {
+        self.world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.character)
+            .filter(|&entity| self.is_player(entity))
+    }
+
+    /// The coordinates of every player-controlled character
+    pub(crate) fn player_coords(&self) -> Vec<Coord> {
+        if self.players.is_empty() {
+            return vec![self.player_coord()];
+        }
+        self.players
+            .iter()
+            .filter_map(|&entity| self.world.spatial_table.coord_of(entity))
+            .collect()
+    }
+
+    /// The player-controlled character closest to a coordinate
+    pub(crate) fn nearest_player(&self, coord: Coord) -> Entity {
+        self.players
+            .iter()
+            .filter_map(|&entity| {
+                let player_coord = self.world.spatial_table.coord_of(entity)?;
+                Some((entity, (player_coord - coord).magnitude2()))
+            })
+            .min_by_key(|&(_, distance)| distance)
+            .map(|(entity, _)| entity)
+            .unwrap_or(self.player_entity)
+    }
+
+    /// Adds a second player-controlled character next to the first
+    pub(crate) fn add_hot_seat_partner(&mut self, partner_data: EntityData) {
+        if self.players.is_empty() {
+            self.players.push(self.player_entity);
+        }
+        if let Some(partner) = self.place_partner(partner_data) {
+            self.players.push(partner);
+        }
+    }
+
+    /// Puts a character in an empty cell next to the active player. Returns `None` if the
+    /// player is completely surrounded.
+    fn place_partner(&mut self, partner_data: EntityData) -> Option<Entity> {
+        let player_coord = self.player_coord();
+        let coord = Direction::all()
+            .map(|direction| player_coord + direction.coord())
+            .find(|&coord| self.world.can_spawn_character_at(coord))?;
+        let location = Location {
+            layer: Some(Layer::Character),
+            coord,
+        };
+        Some(self.world.insert_entity_data(location, partner_data))
+    }
+
+    /// The data of each player-controlled character other than the active one, removed from the
+    /// world so they can be brought along to the next level
+    pub(crate) fn take_partners(&mut self) -> Vec<EntityData> {
+        let partners = self
+            .players
+            .iter()
+            .copied()
+            .filter(|&entity| entity != self.player_entity)
+            .collect::<Vec<_>>();
+        self.players.clear();
+        partners
+            .into_iter()
+            .map(|entity| self.world.components.remove_entity_data(entity))
+            .collect()
+    }
+
+    /// Places characters taken from the previous level alongside the active player
+    pub(crate) fn place_partners(&mut self, partners: Vec<EntityData>) {
+        for partner_data in partners {
+            self.add_hot_seat_partner(partner_data);
+        }
+    }
+
+    /// Hands control to the next player who hasn't acted yet this round. Returns `false` once
+    /// every player has acted, in which case control returns to the first player and the rest
+    /// of the world takes its turn.
+    pub(crate) fn pass_to_next_player(&mut self) -> bool {
+        if !self.is_hot_seat() {
+            return false;
+        }
+        let index = match self
+            .players
+            .iter()
+            .position(|&entity| entity == self.player_entity)
+        {
+            Some(index) => index,
+            None => return false,
+        };
+        let next_index = (index + 1) % self.players.len();
+        self.player_entity = self.players[next_index];
+        // corpses can't be handed between players
+        self.dragging = None;
+        if next_index == 0 {
+            return false;
+        }
+        self.messages.system(Message::with_args(
+            "message.hot_seat_turn",
+            &[&(next_index + 1)],
+        ));
+        true
+    }
+
+    /// A freshly-spawned character for the second player
+    pub(crate) fn new_hot_seat_partner(&self) -> EntityData {
+        let mut partner_data = make_player();
+        self.modifiers.apply_to_player(&mut partner_data);
+        partner_data
+    }
+}
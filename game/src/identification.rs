@@ -0,0 +1,265 @@
+use crate::{
+    codex::CodexEntry, world::spatial::Layers, ActionError, CardinalDirection, Entity, Game,
+    GameControlFlow, Message, PowerState, Tile,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// Hit points restored by a healing injector
+pub(crate) const INJECTOR_HEALING: u32 = 4;
+
+/// What an injector does when it's used
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InjectorEffect {
+    Heal,
+    RestoreOxygen,
+    PurgeRadiation,
+    Teleport,
+    RemoveCurse,
+}
+
+impl InjectorEffect {
+    pub const ALL: &'static [Self] = &[
+        Self::Heal,
+        Self::RestoreOxygen,
+        Self::PurgeRadiation,
+        Self::Teleport,
+        Self::RemoveCurse,
+    ];
+
+    /// Key of the name of an identified injector with this effect in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Heal => "injector.heal",
+            Self::RestoreOxygen => "injector.restore_oxygen",
+            Self::PurgeRadiation => "injector.purge_radiation",
+            Self::Teleport => "injector.teleport",
+            Self::RemoveCurse => "injector.remove_curse",
+        }
+    }
+
+    fn codex_entry(self) -> CodexEntry {
+        match self {
+            Self::Heal => CodexEntry::HealingInjector,
+            Self::RestoreOxygen => CodexEntry::OxygenInjector,
+            Self::PurgeRadiation => CodexEntry::AntiRadiationInjector,
+            Self::Teleport => CodexEntry::TeleportInjector,
+            Self::RemoveCurse => CodexEntry::NaniteInjector,
+        }
+    }
+}
+
+/// How an injector looks before its effect is known
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Appearance {
+    Red,
+    Blue,
+    Green,
+    Yellow,
+    Purple,
+}
+
+impl Appearance {
+    const ALL: &'static [Self] = &[
+        Self::Red,
+        Self::Blue,
+        Self::Green,
+        Self::Yellow,
+        Self::Purple,
+    ];
+
+    /// Key of the name of an unidentified injector which looks like this in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Red => "injector.red",
+            Self::Blue => "injector.blue",
+            Self::Green => "injector.green",
+            Self::Yellow => "injector.yellow",
+            Self::Purple => "injector.purple",
+        }
+    }
+
+    pub fn tile(self) -> Tile {
+        match self {
+            Self::Red => Tile::InjectorRed,
+            Self::Blue => Tile::InjectorBlue,
+            Self::Green => Tile::InjectorGreen,
+            Self::Yellow => Tile::InjectorYellow,
+            Self::Purple => Tile::InjectorPurple,
+        }
+    }
+}
+
+/// Which appearance each injector effect has, shuffled at the start of each game, and which
+/// effects the player has identified so far
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Identification {
+    appearances: Vec<(InjectorEffect, Appearance)>,
+    identified: Vec<InjectorEffect>,
+}
+
+impl Identification {
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        let mut appearances = Appearance::ALL.to_vec();
+        appearances.shuffle(rng);
+        Self {
+            appearances: InjectorEffect::ALL
+                .iter()
+                .copied()
+                .zip(appearances)
+                .collect(),
+            identified: Vec::new(),
+        }
+    }
+
+    pub fn appearance(&self, effect: InjectorEffect) -> Appearance {
+        self.appearances
+            .iter()
+            .find(|&&(e, _)| e == effect)
+            .map(|&(_, appearance)| appearance)
+            .expect("every effect has an appearance")
+    }
+
+    pub fn is_identified(&self, effect: InjectorEffect) -> bool {
+        self.identified.contains(&effect)
+    }
+}
+
+impl Game {
+    /// Key of the name of an injector as far as the player knows in the app's locale
+    pub fn injector_name_key(&self, effect: InjectorEffect) -> &'static str {
+        if self.identification.is_identified(effect) {
+            effect.name_key()
+        } else {
+            self.identification.appearance(effect).name_key()
+        }
+    }
+
+    fn injector_at_player(&self) -> Option<(Entity, InjectorEffect)> {
+        let item_entity = self
+            .world
+            .spatial_table
+            .layers_at(self.player_coord())?
+            .item?;
+        let effect = *self.world.components.injector.get(item_entity)?;
+        Some((item_entity, effect))
+    }
+
+    pub(crate) fn can_use_injector(&self) -> bool {
+        self.injector_at_player().is_some()
+    }
+
+    pub(crate) fn is_powered_terminal_adjacent(&self) -> bool {
+        let terminal_adjacent = CardinalDirection::all().any(|direction| {
+            if let Some(&Layers {
+                feature: Some(feature_entity),
+                ..
+            }) = self
+                .world
+                .spatial_table
+                .layers_at(self.player_coord() + direction.coord())
+            {
+                self.world.components.terminal.contains(feature_entity)
+            } else {
+                false
+            }
+        });
+        terminal_adjacent && self.power_state() == PowerState::On
+    }
+
+    /// Unidentified injectors, and weapons which might be cursed, can be analysed at a powered
+    /// terminal next to the player
+    pub(crate) fn can_analyse_item(&self) -> bool {
+        let unidentified = self
+            .injector_at_player()
+            .map(|(_, effect)| !self.identification.is_identified(effect))
+            .unwrap_or(false);
+        (unidentified || self.can_scan_weapon()) && self.is_powered_terminal_adjacent()
+    }
+
+    fn identify(&mut self, effect: InjectorEffect) {
+        if self.identification.is_identified(effect) {
+            return;
+        }
+        let appearance = self.identification.appearance(effect);
+        self.identification.identified.push(effect);
+        self.messages.items(
+            Some(self.player_entity),
+            Message::with_args(
+                "message.injector_identified",
+                &[&appearance.name_key(), &effect.name_key()],
+            ),
+        );
+        let entry = effect.codex_entry();
+        if !self.discovered.contains(&entry) {
+            self.discovered.push(entry);
+        }
+    }
+
+    pub(crate) fn player_analyse_item(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        if !self.can_analyse_item() {
+            return Err(ActionError::InvalidInteraction);
+        }
+        self.messages.system(Message::new("message.item_analysed"));
+        if let Some((_, effect)) = self.injector_at_player() {
+            self.identify(effect);
+        } else {
+            self.scan_weapon();
+        }
+        Ok(None)
+    }
+
+    pub(crate) fn player_use_injector(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        let (item_entity, effect) = self
+            .injector_at_player()
+            .ok_or(ActionError::InvalidInteraction)?;
+        self.world.remove_entity(item_entity);
+        let name = self.injector_name_key(effect);
+        self.messages.items(
+            Some(self.player_entity),
+            Message::with_args("message.item_used", &[&name]),
+        );
+        self.inject(self.player_entity, effect);
+        self.identify(effect);
+        Ok(None)
+    }
+
+    /// Applies an injector's effect to a character. Effects which only make sense for the
+    /// player do nothing to npcs.
+    pub(crate) fn inject(&mut self, entity: Entity, effect: InjectorEffect) {
+        let components = &mut self.world.components;
+        match effect {
+            InjectorEffect::Heal => {
+                if let Some(hit_points) = components.hit_points.get_mut(entity) {
+                    hit_points.increase(INJECTOR_HEALING);
+                }
+            }
+            InjectorEffect::RestoreOxygen => {
+                if let Some(oxygen) = components.oxygen.get_mut(entity) {
+                    oxygen.fill();
+                }
+            }
+            InjectorEffect::PurgeRadiation => {
+                if let Some(rads) = components.rads.get_mut(entity) {
+                    rads.exposure = 0;
+                    rads.pending_damage.clear();
+                }
+            }
+            InjectorEffect::Teleport => {
+                let size = self.world.spatial_table.grid_size();
+                let candidates = size
+                    .coord_iter_row_major()
+                    .filter(|&coord| self.world.can_spawn_character_at(coord))
+                    .collect::<Vec<_>>();
+                if let Some(&coord) = candidates.choose(&mut self.rng) {
+                    let _ = self.world.spatial_table.update_coord(entity, coord);
+                }
+            }
+            InjectorEffect::RemoveCurse => {
+                if entity == self.player_entity {
+                    self.remove_player_curse();
+                }
+            }
+        }
+    }
+}
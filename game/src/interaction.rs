@@ -0,0 +1,156 @@
+use crate::{
+    prompt::Prompt,
+    world::{data::DoorState, spatial::Layers},
+    ActionError, CardinalDirection, Config, Coord, Game, GameControlFlow,
+};
+use serde::{Deserialize, Serialize};
+
+/// Something the player can do to their own cell or a cell next to them
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interaction {
+    Attack,
+    OpenDoor,
+    CloseDoor,
+    UseTerminal,
+    SabotageReactor,
+    PunctureTank,
+    OpenLocker,
+    SearchCorpse,
+    DestroyCorpse,
+    DragCorpse,
+    FitAttachment,
+    UseInjector,
+    AnalyseItem,
+    RemoveCurse,
+    PickUp,
+}
+
+impl Interaction {
+    /// Key of the interaction's name in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Attack => "interaction.attack",
+            Self::OpenDoor => "interaction.open_door",
+            Self::CloseDoor => "interaction.close_door",
+            Self::UseTerminal => "interaction.use_terminal",
+            Self::SabotageReactor => "interaction.sabotage_reactor",
+            Self::PunctureTank => "interaction.puncture_tank",
+            Self::OpenLocker => "interaction.open_locker",
+            Self::SearchCorpse => "interaction.search_corpse",
+            Self::DestroyCorpse => "interaction.destroy_corpse",
+            Self::DragCorpse => "interaction.drag_corpse",
+            Self::FitAttachment => "interaction.fit_attachment",
+            Self::UseInjector => "interaction.use_injector",
+            Self::AnalyseItem => "interaction.analyse_item",
+            Self::RemoveCurse => "interaction.remove_curse",
+            Self::PickUp => "interaction.pick_up",
+        }
+    }
+}
+
+impl Game {
+    /// The direction from the player to `coord`, or `None` if it isn't next to the player
+    fn direction_to_adjacent_coord(&self, coord: Coord) -> Option<CardinalDirection> {
+        CardinalDirection::all().find(|direction| self.player_coord() + direction.coord() == coord)
+    }
+
+    /// Everything the player could do to the cell at `coord` right now. This is empty unless
+    /// `coord` is the player's own cell or a cell next to them.
+    pub fn available_actions_at(&self, coord: Coord) -> Vec<Interaction> {
+        let mut interactions = Vec::new();
+        if coord == self.player_coord() {
+            if self.corpse_entity_at_coord(coord).is_some() {
+                interactions.push(Interaction::SearchCorpse);
+                interactions.push(Interaction::DestroyCorpse);
+                interactions.push(Interaction::DragCorpse);
+            }
+            if self.fittable_attachment_at_player().is_some() {
+                interactions.push(Interaction::FitAttachment);
+            }
+            if self.can_use_injector() {
+                interactions.push(Interaction::UseInjector);
+            }
+            if self.can_pick_up() {
+                interactions.push(Interaction::PickUp);
+            }
+            if self.can_analyse_item() {
+                interactions.push(Interaction::AnalyseItem);
+            }
+            if self.can_remove_curse_at_terminal() {
+                interactions.push(Interaction::RemoveCurse);
+            }
+            return interactions;
+        }
+        if self.direction_to_adjacent_coord(coord).is_none() {
+            return interactions;
+        }
+        let layers = match self.world.spatial_table.layers_at(coord) {
+            Some(&layers) => layers,
+            None => return interactions,
+        };
+        if layers.character.is_some() {
+            interactions.push(Interaction::Attack);
+        }
+        if let Some(feature_entity) = layers.feature {
+            let components = &self.world.components;
+            match components.door_state.get(feature_entity) {
+                Some(DoorState::Closed) => interactions.push(Interaction::OpenDoor),
+                Some(DoorState::Open) if layers.character.is_none() => {
+                    interactions.push(Interaction::CloseDoor)
+                }
+                _ => (),
+            }
+            if components.terminal.contains(feature_entity) {
+                interactions.push(Interaction::UseTerminal);
+            }
+            if components.reactor.contains(feature_entity) {
+                interactions.push(Interaction::SabotageReactor);
+            }
+            if components.coolant_tank.contains(feature_entity) {
+                interactions.push(Interaction::PunctureTank);
+            }
+            if components.container.contains(feature_entity) {
+                interactions.push(Interaction::OpenLocker);
+            }
+        }
+        interactions
+    }
+
+    pub(crate) fn player_interact(
+        &mut self,
+        coord: Coord,
+        interaction: Interaction,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        if !self.available_actions_at(coord).contains(&interaction) {
+            return Err(ActionError::InvalidInteraction);
+        }
+        let Layers {
+            feature, character, ..
+        } = *self.world.spatial_table.layers_at_checked(coord);
+        match interaction {
+            Interaction::SearchCorpse => return self.player_search(),
+            Interaction::DestroyCorpse => return self.player_destroy_corpse(),
+            Interaction::DragCorpse => return self.player_drag(),
+            Interaction::FitAttachment => return self.player_fit_attachment(),
+            Interaction::UseInjector => return self.player_use_injector(),
+            Interaction::AnalyseItem => return self.player_analyse_item(),
+            Interaction::RemoveCurse => return self.player_remove_curse(),
+            Interaction::PickUp => return self.player_pick_up(),
+            Interaction::Attack => {
+                if let (Some(_), Some(direction)) =
+                    (character, self.direction_to_adjacent_coord(coord))
+                {
+                    self.player_melee(direction, config);
+                }
+            }
+            Interaction::OpenDoor => self.open_door(feature.unwrap()),
+            Interaction::CloseDoor => self.close_door(feature.unwrap()),
+            Interaction::UseTerminal => self.toggle_power(),
+            Interaction::SabotageReactor => return Ok(self.prompt(Prompt::SabotageReactor(coord))),
+            Interaction::PunctureTank => self.breach_coolant_tank(feature.unwrap()),
+            Interaction::OpenLocker => self.open_container(feature.unwrap()),
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,22 @@
+use crate::MenuImage;
+use serde::Deserialize;
+
+const INTERLUDES: &str = include_str!("interludes.ron");
+
+/// Story text shown to the player on their way between levels
+#[derive(Debug, Clone, Deserialize)]
+pub struct Interlude {
+    /// The level the interlude leads into, numbered from 1
+    pub level: u32,
+    /// May use the rich text markup understood by the app
+    pub text: String,
+    pub image: MenuImage,
+}
+
+/// The interlude to show before the player arrives on `level`, if it has one
+pub fn interlude_before_level(level: u32) -> Option<Interlude> {
+    let interludes: Vec<Interlude> = ron::from_str(INTERLUDES).expect("failed to parse interludes");
+    interludes
+        .into_iter()
+        .find(|interlude| interlude.level == level)
+}
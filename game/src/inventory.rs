@@ -0,0 +1,163 @@
+use crate::{
+    world::data::{EntityData, Layer, Location},
+    ActionError, Entity, Game, GameControlFlow, ItemExamination, Message,
+};
+use serde::{Deserialize, Serialize};
+
+/// Number of items the player can carry
+pub const PLAYER_INVENTORY_CAPACITY: usize = 6;
+
+/// Items carried by a character. Carried items aren't in the world, so they're kept as entity
+/// data and travel with their carrier between levels.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Inventory {
+    capacity: usize,
+    pub(crate) items: Vec<EntityData>,
+}
+
+impl Inventory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() >= self.capacity
+    }
+}
+
+impl Game {
+    /// Names of the items carried by the player, in slot order
+    pub fn player_inventory(&self) -> Vec<ItemExamination> {
+        self.world
+            .components
+            .inventory
+            .get(self.player_entity)
+            .map(|inventory| {
+                inventory
+                    .items
+                    .iter()
+                    .map(|data| self.examine_carried_item(data))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn player_inventory_capacity(&self) -> usize {
+        self.world
+            .components
+            .inventory
+            .get(self.player_entity)
+            .map_or(0, Inventory::capacity)
+    }
+
+    pub(crate) fn examine_carried_item(&self, data: &EntityData) -> ItemExamination {
+        let description = if let Some(effect) = data.injector {
+            self.injector_name_key(effect).to_string()
+        } else if let Some(attachment) = data.attachment {
+            attachment.name().to_string()
+        } else {
+            "unknown item".to_string()
+        };
+        ItemExamination::common(description)
+    }
+
+    /// The item the player is standing on, if it can be carried. Weapons are wielded rather
+    /// than carried, and other items are used as soon as they're found.
+    fn carryable_item_at_player(&self) -> Option<Entity> {
+        let item_entity = self
+            .world
+            .spatial_table
+            .layers_at(self.player_coord())?
+            .item?;
+        let components = &self.world.components;
+        if components.injector.contains(item_entity) || components.attachment.contains(item_entity)
+        {
+            Some(item_entity)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn can_pick_up(&self) -> bool {
+        let has_space = self
+            .world
+            .components
+            .inventory
+            .get(self.player_entity)
+            .is_some_and(|inventory| !inventory.is_full());
+        has_space && self.carryable_item_at_player().is_some()
+    }
+
+    pub(crate) fn player_pick_up(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        let item_entity = self
+            .carryable_item_at_player()
+            .ok_or(ActionError::NothingToPickUp)?;
+        let inventory = self
+            .world
+            .components
+            .inventory
+            .get(self.player_entity)
+            .ok_or(ActionError::InventoryFull)?;
+        if inventory.is_full() {
+            return Err(ActionError::InventoryFull);
+        }
+        let data = self.world.components.remove_entity_data(item_entity);
+        self.world.remove_entity(item_entity);
+        let name = self.examine_carried_item(&data).description;
+        if let Some(inventory) = self.world.components.inventory.get_mut(self.player_entity) {
+            inventory.items.push(data);
+        }
+        self.messages.items(
+            Some(self.player_entity),
+            Message::with_args("message.item_picked_up", &[&name]),
+        );
+        Ok(None)
+    }
+
+    /// Puts the item in the given slot on the floor under the player
+    pub(crate) fn player_drop(
+        &mut self,
+        slot: usize,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        let player_coord = self.player_coord();
+        if self
+            .world
+            .spatial_table
+            .layers_at_checked(player_coord)
+            .item
+            .is_some()
+        {
+            return Err(ActionError::NoRoomToDrop);
+        }
+        let inventory = self
+            .world
+            .components
+            .inventory
+            .get_mut(self.player_entity)
+            .ok_or(ActionError::NoSuchSlot)?;
+        if slot >= inventory.items.len() {
+            return Err(ActionError::NoSuchSlot);
+        }
+        let data = inventory.items.remove(slot);
+        let name = self.examine_carried_item(&data).description;
+        self.world.insert_entity_data(
+            Location {
+                layer: Some(Layer::Item),
+                coord: player_coord,
+            },
+            data,
+        );
+        self.messages.items(
+            Some(self.player_entity),
+            Message::with_args("message.item_dropped", &[&name]),
+        );
+        Ok(None)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::Game;
+use rand::SeedableRng;
+use rand_isaac::Isaac64Rng;
+use serde::{Deserialize, Serialize};
+
+/// State of a run played in ironman mode, where the app saves after every action and deletes the
+/// save when the player dies
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) struct Ironman {
+    /// Chosen when the run starts. The rng is reseeded from this before each input.
+    seed: u64,
+}
+
+impl Ironman {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    pub(crate) fn seed(self) -> u64 {
+        self.seed
+    }
+}
+
+impl Game {
+    pub fn is_ironman(&self) -> bool {
+        self.ironman.is_some()
+    }
+
+    /// The rng for each input of an ironman run depends only on the run's seed and how many
+    /// inputs came before it, so reloading an older save and trying again (or waiting around)
+    /// can't change what an input does. The turn count isn't used because it stays the same
+    /// across level changes and hot-seat passes.
+    pub(crate) fn reseed_ironman_rng(&mut self) {
+        if let Some(ironman) = self.ironman {
+            // mix the input count into the seed so consecutive inputs don't get related seeds
+            let seed = ironman.seed.wrapping_add(
+                self.num_committed_inputs
+                    .wrapping_mul(0x9E37_79B9_7F4A_7C15),
+            );
+            self.rng = Isaac64Rng::seed_from_u64(seed);
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{world::data::NpcType, CardinalDirection, Coord, Entity, Game};
+
+impl Game {
+    /// A step away from the target for a ranged npc that's closer than it likes to be. The npc
+    /// retreats down the same map fleeing npcs use, so it backs out of rooms rather than into
+    /// corners. Returns `None` if the npc is happy where it is or has nowhere to go.
+    pub(crate) fn kite_step(
+        &self,
+        npc_type: NpcType,
+        coord: Coord,
+        target_coord: Coord,
+    ) -> Option<CardinalDirection> {
+        let preferred_range_squared = npc_type.ranged_attack()?.preferred_range_squared?;
+        if (target_coord - coord).magnitude2() >= preferred_range_squared {
+            return None;
+        }
+        self.flee_step(coord)
+    }
+
+    /// Where a ranged npc with a preferred range will shoot on its next turn, if it has a clear
+    /// shot at the player. Other ranged npcs only shoot some of the time, so their shots can't
+    /// be planned.
+    pub(crate) fn planned_shot(
+        &self,
+        entity: Entity,
+        npc_type: NpcType,
+        coord: Coord,
+    ) -> Option<Coord> {
+        let attack = npc_type.ranged_attack()?;
+        attack.preferred_range_squared?;
+        self.npc_ranged_projectile(entity, coord, self.player_entity, attack, 0)?;
+        self.world.spatial_table.coord_of(self.player_entity)
+    }
+}
@@ -0,0 +1,144 @@
+use crate::{world::data::Alertness, CardinalDirection, Coord, Entity, Game};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// Turns an npc keeps looking for the player after losing sight of them, before it gives up
+const LAST_SEEN_SEARCH_TURNS: u32 = 12;
+/// Npcs search cells this close to where they last saw the player
+const LAST_SEEN_SEARCH_RADIUS: i32 = 3;
+/// Longest path an npc will take to the cell it's searching
+const LAST_SEEN_MAX_PATH: usize = 24;
+
+/// Where an npc last saw the player, and where it's looking for them now
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct LastSeen {
+    coord: Coord,
+    /// Starts as `coord`, then moves between nearby cells once the npc has looked there
+    search: Coord,
+    turns_unseen: u32,
+}
+
+impl LastSeen {
+    fn new(coord: Coord) -> Self {
+        Self {
+            coord,
+            search: coord,
+            turns_unseen: 0,
+        }
+    }
+}
+
+impl Game {
+    /// Hostile npcs remember where they last saw the player. An npc that loses sight of the
+    /// player heads for that spot, then searches the cells around it for a few turns. If the
+    /// player doesn't turn up, the npc gives up and forgets about them.
+    pub(crate) fn update_last_seen(&mut self) {
+        let player_coords = self.player_coords();
+        let alarm = self.is_alarm_sounding();
+        let npcs = self
+            .world
+            .components
+            .alertness
+            .iter()
+            .filter(|&(_, &alertness)| alertness == Alertness::Hostile)
+            .filter_map(|(entity, _)| Some((entity, self.world.spatial_table.coord_of(entity)?)))
+            .collect::<Vec<_>>();
+        for (entity, coord) in npcs {
+            let seen = player_coords
+                .iter()
+                .copied()
+                .filter(|&player_coord| alarm || self.npc_can_see(coord, player_coord))
+                .min_by_key(|&player_coord| (player_coord - coord).magnitude2());
+            if let Some(player_coord) = seen {
+                self.world
+                    .components
+                    .last_seen
+                    .insert(entity, LastSeen::new(player_coord));
+                continue;
+            }
+            let last_seen = match self.world.components.last_seen.get(entity) {
+                Some(&last_seen) => last_seen,
+                None => continue,
+            };
+            // npcs on the player's trail keep following it for as long as it lasts
+            if last_seen.turns_unseen >= LAST_SEEN_SEARCH_TURNS
+                && !self.smells_player(entity, coord)
+            {
+                self.world.components.last_seen.remove(entity);
+                self.world
+                    .components
+                    .alertness
+                    .insert(entity, Alertness::Unaware);
+                continue;
+            }
+            // move on once the npc has looked somewhere, or if it can't get there
+            let search = if coord == last_seen.search
+                || self
+                    .path_step(coord, last_seen.search, LAST_SEEN_MAX_PATH)
+                    .is_none()
+            {
+                self.nearby_search_coord(last_seen.coord, coord)
+                    .unwrap_or(last_seen.search)
+            } else {
+                last_seen.search
+            };
+            self.world.components.last_seen.insert(
+                entity,
+                LastSeen {
+                    search,
+                    turns_unseen: last_seen.turns_unseen + 1,
+                    ..last_seen
+                },
+            );
+        }
+    }
+
+    /// A random free cell near where the player was last seen, other than the npc's own cell
+    fn nearby_search_coord(&mut self, centre: Coord, coord: Coord) -> Option<Coord> {
+        let candidates = (-LAST_SEEN_SEARCH_RADIUS..=LAST_SEEN_SEARCH_RADIUS)
+            .flat_map(|y| {
+                (-LAST_SEEN_SEARCH_RADIUS..=LAST_SEEN_SEARCH_RADIUS)
+                    .map(move |x| centre + Coord::new(x, y))
+            })
+            .filter(|&candidate| candidate != coord && self.is_free_for_npc(candidate))
+            .collect::<Vec<_>>();
+        candidates.choose(&mut self.rng).copied()
+    }
+
+    /// Being hurt tells an npc where the player is, even if it can't see them
+    pub(crate) fn remember_player(&mut self, entity: Entity) {
+        let coord = match self.world.spatial_table.coord_of(entity) {
+            Some(coord) => coord,
+            None => return,
+        };
+        if let Some(player_coord) = self
+            .world
+            .spatial_table
+            .coord_of(self.nearest_player(coord))
+        {
+            self.world
+                .components
+                .last_seen
+                .insert(entity, LastSeen::new(player_coord));
+        }
+    }
+
+    /// True for hostile npcs that have lost sight of the player and are looking for them
+    pub(crate) fn is_searching(&self, entity: Entity) -> bool {
+        self.world
+            .components
+            .last_seen
+            .get(entity)
+            .map(|last_seen| last_seen.turns_unseen > 0)
+            .unwrap_or(false)
+    }
+
+    /// The next step towards the cell a searching npc is looking in
+    pub(crate) fn search_step(&self, entity: Entity, coord: Coord) -> Option<CardinalDirection> {
+        let last_seen = self.world.components.last_seen.get(entity)?;
+        if last_seen.turns_unseen == 0 {
+            return None;
+        }
+        self.path_step(coord, last_seen.search, LAST_SEEN_MAX_PATH)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::Game;
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// How the levels of a run are laid out. Chosen when starting a new game.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LayoutStyle {
+    /// The hand-made space station layout
+    #[default]
+    Station,
+    /// Rectangular rooms joined by corridors, generated afresh for each level
+    Rooms,
+    /// Interiors pieced together from small hand-made samples, generated afresh for each level
+    Interiors,
+    /// Each level is laid out in one of the other styles at random
+    Mixed,
+}
+
+impl LayoutStyle {
+    pub const ALL: &'static [Self] = &[Self::Station, Self::Rooms, Self::Interiors, Self::Mixed];
+
+    /// Key of the style's name in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Station => "layout.station",
+            Self::Rooms => "layout.rooms",
+            Self::Interiors => "layout.interiors",
+            Self::Mixed => "layout.mixed",
+        }
+    }
+
+    /// Decides which style the next level is laid out in. Never returns `Mixed`.
+    pub(crate) fn choose<R: Rng>(self, rng: &mut R) -> Self {
+        match self {
+            Self::Mixed => *[Self::Station, Self::Rooms, Self::Interiors]
+                .choose(rng)
+                .unwrap(),
+            other => other,
+        }
+    }
+}
+
+impl Game {
+    pub fn layout_style(&self) -> LayoutStyle {
+        self.layout_style
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{
+    terrain::Terrain, world::data::Gravity, Config, EventDirector, Game, Grid, Identification,
+    Level, Size,
+};
+use coord_2d::Coord;
+use rand::{Rng, SeedableRng};
+use rand_isaac::Isaac64Rng;
+
+/// Size of new level designs. This matches the size of the built-in level.
+pub const LEVEL_DESIGN_SIZE: Size = Size::new_u16(23, 18);
+
+/// Something that can be painted onto a level design
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Brush {
+    Floor,
+    Wall,
+    Door,
+    Gap,
+    Hull,
+    Stairs,
+    Zombie,
+    Player,
+}
+
+impl Brush {
+    pub const ALL: &'static [Self] = &[
+        Self::Floor,
+        Self::Wall,
+        Self::Door,
+        Self::Gap,
+        Self::Hull,
+        Self::Stairs,
+        Self::Zombie,
+        Self::Player,
+    ];
+
+    /// The character representing the brush in terrain files
+    pub fn ch(self) -> char {
+        match self {
+            Self::Floor => '.',
+            Self::Wall => '#',
+            Self::Door => '+',
+            Self::Gap => ':',
+            Self::Hull => ',',
+            Self::Stairs => '>',
+            Self::Zombie => 'z',
+            Self::Player => '@',
+        }
+    }
+
+    /// Key of the brush's name in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Floor => "editor.brush.floor",
+            Self::Wall => "editor.brush.wall",
+            Self::Door => "editor.brush.door",
+            Self::Gap => "editor.brush.gap",
+            Self::Hull => "editor.brush.hull",
+            Self::Stairs => "editor.brush.stairs",
+            Self::Zombie => "editor.brush.zombie",
+            Self::Player => "editor.brush.player",
+        }
+    }
+}
+
+/// A hand-made level, stored as a grid of the characters used in terrain files
+#[derive(Debug, Clone)]
+pub struct LevelDesign {
+    cells: Grid<char>,
+}
+
+impl Default for LevelDesign {
+    fn default() -> Self {
+        Self::new(LEVEL_DESIGN_SIZE)
+    }
+}
+
+impl LevelDesign {
+    /// An empty level surrounded by hull
+    pub fn new(size: Size) -> Self {
+        let cells = Grid::new_fn(size, |coord| {
+            let on_edge = coord.x == 0
+                || coord.y == 0
+                || coord.x == size.width() as i32 - 1
+                || coord.y == size.height() as i32 - 1;
+            if on_edge {
+                Brush::Hull.ch()
+            } else {
+                Brush::Floor.ch()
+            }
+        });
+        Self { cells }
+    }
+
+    /// Reads a design in the format of the terrain files. Returns `None` if the rows aren't all
+    /// the same length.
+    pub fn from_text(txt: &str) -> Option<Self> {
+        let rows = txt.trim_end().split('\n').collect::<Vec<_>>();
+        let width = rows[0].chars().count();
+        if width == 0 || rows.iter().any(|row| row.chars().count() != width) {
+            return None;
+        }
+        let size = Size::new(width as u32, rows.len() as u32);
+        let cells = Grid::new_iterator(size, rows.into_iter().flat_map(|row| row.chars()));
+        Some(Self { cells })
+    }
+
+    /// The design in the format of the terrain files
+    pub fn to_text(&self) -> String {
+        self.cells
+            .rows()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn size(&self) -> Size {
+        self.cells.size()
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<char> {
+        self.cells.get(coord).copied()
+    }
+
+    /// There can only be one player start, so painting a new one removes the old one
+    pub fn paint(&mut self, coord: Coord, brush: Brush) {
+        if self.cells.get(coord).is_none() {
+            return;
+        }
+        if brush == Brush::Player {
+            for ch in self.cells.iter_mut() {
+                if *ch == Brush::Player.ch() {
+                    *ch = Brush::Floor.ch();
+                }
+            }
+        }
+        *self.cells.get_checked_mut(coord) = brush.ch();
+    }
+
+    /// A level can only be played if it has somewhere for the player to start
+    pub fn has_player_start(&self) -> bool {
+        self.cells.iter().any(|&ch| ch == Brush::Player.ch())
+    }
+}
+
+impl Game {
+    /// A game starting on a hand-made level. Later levels are generated as normal. Returns
+    /// `None` if the design has nowhere for the player to start.
+    pub fn new_custom_level<R: Rng>(
+        config: &Config,
+        design: &LevelDesign,
+        base_rng: &mut R,
+    ) -> Option<Self> {
+        if !design.has_player_start() {
+            return None;
+        }
+        let seed = base_rng.gen();
+        let mut rng = Isaac64Rng::seed_from_u64(seed);
+        let event_director = EventDirector::new(&mut rng);
+        let identification = Identification::new(&mut rng);
+        let Terrain {
+            world,
+            player_entity,
+            gravity,
+            report,
+        } = Terrain::from_text(
+            &design.to_text(),
+            crate::world::spawn::make_player(),
+            Gravity::Normal,
+        )?;
+        let level = Level {
+            world,
+            player_entity,
+            gravity,
+            name: "Custom Level".to_string(),
+            theme: None,
+            report,
+        };
+        let mut game = Self::with_first_level(level, rng, event_director, identification, config);
+        game.seed = seed;
+        game.ghost_trail.seed = seed;
+        Some(game)
+    }
+}
@@ -3,23 +3,31 @@ pub use entity_table::{entity_data, entity_update, ComponentTable, Entity};
 pub use grid_2d::{Coord, Grid, Size};
 pub use grid_search_cardinal_distance_map as distance_map;
 pub use line_2d::{self, coords_between, coords_between_cardinal};
-use rand::{Rng, SeedableRng};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
 use rand_isaac::Isaac64Rng;
 pub use rgb_int::Rgb24;
 use serde::{Deserialize, Serialize};
 pub use shadowcast::Context as ShadowcastContext;
 pub use spatial_table::UpdateError;
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
 pub mod witness;
 mod world;
 
+use visible_area_detection::Light;
 pub use visible_area_detection::{
     vision_distance::Circle, CellVisibility, VisibilityGrid, World as VisibleWorld,
 };
-pub use world::data::{Layer, Location, Meter, Tile};
+pub use world::air::MAX_LIQUID_DEPTH;
+pub use world::data::{
+    Affix, Alertness, Attachment, Attachments, Blast, Decal, DecalKind, Faction, Gas, GasKind,
+    Gravity, KeyColour, Layer, Location, Meter, NpcType, OnCollision, Rads, Rarity, Respawn,
+    RespawnLocation, Respawnable, Tile, Weapon, WeaponKind, MAX_DECAL_INTENSITY,
+};
+pub use world::gas::MAX_GAS_DENSITY;
 use world::{
-    data::{Components, DoorState, EntityData, EntityUpdate},
+    action::MovementMode,
+    data::{Atmosphere, Components, DoorState, EntityData, EntityUpdate, BAYONET_DAMAGE},
     spatial::{LayerTable, Layers, SpatialTable},
     World,
 };
@@ -27,6 +35,199 @@ use world::{
 mod terrain;
 use terrain::Terrain;
 
+mod connectivity;
+pub use connectivity::TerrainReport;
+
+mod debug_dump;
+
+mod prefab;
+
+mod secret;
+
+mod bsp;
+
+mod wfc;
+
+mod lock;
+
+mod timer;
+use timer::Timers;
+pub use timer::{Timer, TimerKind};
+
+mod station_event;
+use station_event::EventDirector;
+
+mod power;
+pub use power::PowerState;
+
+mod radiation;
+use radiation::DAMAGED_REACTOR_RADIATION;
+
+mod explosion;
+
+mod projectile;
+pub use projectile::{Aim, AimPreview};
+
+mod weapon;
+pub use weapon::ItemExamination;
+
+mod homing;
+
+mod status_effect;
+use status_effect::STUN_DURATION;
+pub use status_effect::{StatusEffect, StatusEffects};
+
+mod throw;
+
+mod container;
+pub use container::Container;
+
+mod destruction;
+
+mod fire;
+
+mod flood;
+
+mod gas;
+
+mod cover;
+
+mod hit;
+
+mod melee;
+pub use melee::AttackShape;
+
+mod shield;
+pub use shield::Shield;
+
+mod noise;
+use noise::{Noise, FOOTSTEP_LOUDNESS};
+
+mod flee;
+
+mod flank;
+
+mod faction;
+
+mod alert;
+
+mod kite;
+
+mod last_seen;
+pub use last_seen::LastSeen;
+
+mod scent;
+
+mod boss;
+pub use boss::{Boss, BossPhase};
+
+mod trap;
+pub use trap::{Trap, TrapKind};
+
+mod inventory;
+pub use inventory::{Inventory, PLAYER_INVENTORY_CAPACITY};
+
+mod autoexplore;
+
+mod travel;
+use travel::Travel;
+
+mod energy;
+pub use energy::{ENERGY_PER_ACTION, NORMAL_SPEED};
+
+mod curse;
+
+mod loot;
+
+mod undo;
+
+mod replay;
+pub use replay::{Replay, ReplayInput};
+
+mod prompt;
+use prompt::Prompt;
+
+mod ironman;
+use ironman::Ironman;
+
+mod score;
+
+mod casual;
+pub use casual::CASUAL_CONTINUES;
+
+mod run_record;
+pub use run_record::{RunMode, RunOutcome, RunRecord};
+
+mod ghost;
+pub use ghost::{GhostStep, GhostTrail};
+
+mod modifiers;
+pub use modifiers::{RunModifier, RunModifiers};
+
+mod difficulty;
+pub use difficulty::Difficulty;
+
+mod layout;
+pub use layout::LayoutStyle;
+
+mod theme;
+pub use theme::LevelTheme;
+
+mod light;
+
+mod new_game_plus;
+
+mod hot_seat;
+
+mod stress;
+
+mod level_design;
+pub use level_design::{Brush, LevelDesign, LEVEL_DESIGN_SIZE};
+
+mod identification;
+use identification::Identification;
+pub use identification::{Appearance, InjectorEffect};
+
+mod interaction;
+pub use interaction::Interaction;
+
+mod message_log;
+pub use message_log::{LogEntry, Message, MessageCategory, MessageLog};
+
+mod codex;
+pub use codex::{CodexCategory, CodexEntry};
+
+mod run_timer;
+pub use run_timer::{RunTimer, Split};
+
+mod external_event;
+pub use external_event::{ExternalEvent, SoundEffect};
+
+mod balance;
+pub use balance::Balance;
+
+mod names;
+
+mod spawn_director;
+pub use spawn_director::Performance;
+use spawn_director::SpawnDirector;
+
+mod telegraph;
+pub use telegraph::{Behaviour, Disposition, IntendedAction, NextAction, NpcExamination};
+
+mod ai_debug;
+pub use ai_debug::AgentIntrospection;
+
+mod event_log;
+pub use event_log::{EventAction, EventLog, EventOutcome, GameEvent};
+
+mod interlude;
+pub use interlude::{interlude_before_level, Interlude};
+
+mod tutorial;
+use tutorial::Tutorial;
+pub use tutorial::TutorialStep;
+
 #[derive(Debug, Clone, Copy)]
 pub struct Omniscient;
 
@@ -35,6 +236,23 @@ pub struct Config {
     pub omniscient: Option<Omniscient>,
     pub demo: bool,
     pub debug: bool,
+    pub gore: bool,
+    pub balance: Balance,
+    pub performance: Performance,
+    /// New games are started in ironman mode
+    pub ironman: bool,
+    /// New games are started in casual mode
+    pub casual: bool,
+    /// Rules applied to new games
+    pub modifiers: RunModifiers,
+    /// Difficulty of new games
+    pub difficulty: Difficulty,
+    /// How the levels of new games are laid out
+    pub layout_style: LayoutStyle,
+    /// New games have two characters taking alternating turns from the same keyboard
+    pub hot_seat: bool,
+    /// The most recent turn can be undone. This has no effect in ironman runs.
+    pub undo: bool,
 }
 impl Config {
     pub const OMNISCIENT: Option<Omniscient> = Some(Omniscient);
@@ -45,53 +263,197 @@ impl Default for Config {
             omniscient: None,
             demo: false,
             debug: false,
+            gore: true,
+            balance: Balance::default(),
+            performance: Performance::default(),
+            ironman: false,
+            casual: false,
+            modifiers: RunModifiers::default(),
+            difficulty: Difficulty::default(),
+            layout_style: LayoutStyle::default(),
+            hot_seat: false,
+            undo: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-pub enum MenuImage {}
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum MenuImage {
+    Placeholder,
+}
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MenuChoice {
-    Dummy,
+    Confirm,
 }
 
+impl MenuChoice {
+    /// Key of the text shown for this choice in a menu in the app's locale
+    pub fn label_key(self) -> &'static str {
+        match self {
+            Self::Confirm => "menu_choice.confirm",
+        }
+    }
+}
+
+/// A menu the game wants to show the player. The player's choice is passed back to the game with
+/// `witness::Menu::commit`, which takes a turn with `Input::Choose`.
 #[derive(Debug, Clone)]
-pub struct Menu {
+pub struct MenuRequest {
     pub choices: Vec<MenuChoice>,
-    pub text: String,
+    /// Key of the menu's text in the app's locale
+    pub text_key: &'static str,
     pub image: MenuImage,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Victory {}
 
-#[derive(Debug, Clone, Copy)]
-pub enum GameOverReason {}
+/// Key decisions made by the player over the course of a run. These determine which ending is
+/// shown when the run is won.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct RunFlags {
+    /// Left a level with renegades still alive, without ever having killed one
+    pub spared_faction: bool,
+    /// Killed a renegade, after which the faction can no longer be spared
+    #[serde(default)]
+    pub harmed_faction: bool,
+    pub destroyed_reactor: bool,
+    pub escaped: bool,
+    /// Made it to the final level before leaving the station
+    #[serde(default)]
+    pub reached_bottom: bool,
+}
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    /// Left the station early in an escape pod with the reactor intact
+    Escaped,
+    /// Left the station early in an escape pod after destroying the reactor
+    Scuttled,
+    /// Pressed on to the bottom of the station having spared the renegades
+    Truce,
+    /// Pressed on to the bottom of the station
+    Confrontation,
+}
+
+impl RunFlags {
+    pub fn ending(&self) -> Ending {
+        if self.escaped && !self.reached_bottom {
+            if self.destroyed_reactor {
+                Ending::Scuttled
+            } else {
+                Ending::Escaped
+            }
+        } else if self.spared_faction {
+            Ending::Truce
+        } else {
+            Ending::Confrontation
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum GameOverReason {
+    Killed,
+    SelfDestruct,
+    Suffocated,
+    RadiationSickness,
+}
+
+/// Returned by the game when the app needs to do something other than keep playing. Modal
+/// interactions are described by the data attached to `OpenMenu` so that adding a new one doesn't
+/// require a new variant here.
 #[derive(Debug)]
 pub enum GameControlFlow {
     GameOver(GameOverReason),
-    Win,
-    Menu(Menu),
+    Win(Ending),
+    OpenMenu(MenuRequest),
+    /// The player has arrived on a new level, numbered from 1
+    LevelChange(u32),
+    TutorialComplete,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
 pub enum Input {
     Walk(CardinalDirection),
     Wait,
+    Search,
+    DestroyCorpse,
+    Drag,
+    Brace,
+    ThrowGrenade(Coord),
+    FireWeapon(Coord),
+    Interact(Coord, Interaction),
+    /// Answers the menu opened by the previous input
+    Choose(MenuChoice),
+    /// Takes a step towards the nearest unexplored part of the level
+    AutoExplore,
+    /// Takes a step towards a cell the player has seen
+    TravelTo(Coord),
+    PickUp,
+    /// Drops the item in the given inventory slot
+    Drop(usize),
+    /// Throws the item in the given inventory slot at a cell
+    Throw {
+        slot: usize,
+        target: Coord,
+    },
+    /// Swaps the held weapon with the holstered one, or holsters or draws the only weapon
+    SwapWeapons,
 }
 
 #[derive(Serialize, Deserialize, Default, Debug)]
 pub struct VisibleCellData {
     pub tiles: LayerTable<Option<Tile>>,
+    pub decal: Option<Decal>,
+    pub respawn_turns: Option<u32>,
+    pub liquid_depth: u8,
+    /// Name of the character in the cell, if it's important enough to have one
+    pub character_name: Option<String>,
+    /// How aware the npc in the cell is of the player
+    pub alertness: Option<Alertness>,
+    pub fire: bool,
+    pub gas: Option<Gas>,
 }
 
 impl VisibleCellData {
-    fn update(&mut self, world: &World, coord: Coord) {
+    /// Hidden walls are seen, and remembered, as `wall_tile` until they're found
+    fn update(&mut self, world: &World, coord: Coord, wall_tile: Tile) {
         let layers = world.spatial_table.layers_at_checked(coord);
         self.tiles = layers.option_and_then(|&entity| world.components.tile.get(entity).cloned());
+        if self.tiles.feature == Some(Tile::WallHidden) {
+            self.tiles.feature = Some(wall_tile);
+        }
+        // traps can't be seen until they've been found
+        if let Some(feature_entity) = layers.feature {
+            if let Some(trap) = world.components.trap.get(feature_entity) {
+                if !trap.revealed {
+                    self.tiles.feature = None;
+                }
+            }
+        }
+        self.decal = layers
+            .floor
+            .and_then(|floor_entity| world.components.decal.get(floor_entity).cloned());
+        self.respawn_turns = layers.item.and_then(|item_entity| {
+            world
+                .components
+                .respawn
+                .get(item_entity)
+                .map(|respawn| respawn.turns)
+        });
+        self.liquid_depth = world.liquid_depth_at(coord);
+        self.fire = layers
+            .floor
+            .is_some_and(|floor_entity| world.components.fire.contains(floor_entity));
+        self.gas = world.gas_at(coord);
+        self.character_name = layers
+            .character
+            .and_then(|character_entity| world.components.name.get(character_entity).cloned());
+        self.alertness = layers
+            .character
+            .and_then(|character_entity| world.components.alertness.get(character_entity).cloned());
     }
 }
 
@@ -102,8 +464,9 @@ impl VisibleWorld for World {
         self.spatial_table.grid_size()
     }
 
+    /// Smoke and gas hanging in a cell add to the opacity of whatever feature is there
     fn get_opacity(&self, coord: Coord) -> u8 {
-        if let Some(&Layers {
+        let feature_opacity = if let Some(&Layers {
             feature: Some(feature_entity),
             ..
         }) = self.spatial_table.layers_at(coord)
@@ -115,15 +478,76 @@ impl VisibleWorld for World {
                 .unwrap_or(0)
         } else {
             0
+        };
+        feature_opacity.saturating_add(self.gas_opacity(coord))
+    }
+
+    fn for_each_light_by_coord<F: FnMut(Coord, &Light<Self::VisionDistance>)>(&self, mut f: F) {
+        for (coord, light) in self.all_lights_by_coord() {
+            f(coord, light);
         }
     }
 }
 
-pub enum ActionError {}
+#[derive(Debug, Clone, Copy)]
+pub enum ActionError {
+    NoCorpse,
+    NothingToBrace,
+    NoGrenades,
+    NoWeapon,
+    NoAmmo,
+    OutOfRange,
+    InvalidInteraction,
+    NoPrompt,
+    InvalidChoice,
+    NpcInView,
+    HazardInView,
+    NothingToExplore,
+    TravelInterrupted,
+    AtDestination,
+    NoPath,
+    NothingToPickUp,
+    InventoryFull,
+    NoRoomToDrop,
+    NoSuchSlot,
+    WeaponCursed,
+}
+
+/// Number of cells the player's melee attacks push enemies back in normal gravity
+const PLAYER_MELEE_KNOCKBACK: u32 = 1;
+const REACTOR_CAMERA_SHAKE: u32 = 2;
+/// Npcs with ranged attacks use them on average once in this many turns when they have a shot
+const NPC_RANGED_ATTACK_CHANCE: u32 = 3;
+const SELF_DESTRUCT_WARNINGS: &[u32] = &[75, 50, 25, 10, 5];
+/// Maximum number of pieces of debris that fall each turn during the self destruct sequence.
+/// This is reached as the countdown approaches zero.
+const MAX_DEBRIS_PER_TURN: u32 = 4;
+const DEBRIS_RADIUS: i32 = 5;
+const VISION_DISTANCE_SQUARED: u32 = 150;
+const POWER_OUTAGE_VISION_DISTANCE_SQUARED: u32 = 20;
+const COOLANT_TANK_VOLUME: u32 = 80;
+/// How many steps away npcs can find a path to the player from
+const NPC_APPROACH_DISTANCE: u32 = 12;
+/// The last level is the abomination's arena, which is only left by escape pod
+const NUM_LEVELS: u32 = 3;
+/// Hidden traps placed on each generated level
+const TRAPS_PER_LEVEL: usize = 3;
+/// Traps are never placed closer than this to where the player arrives on a level
+const TRAP_MIN_DISTANCE_SQUARED: u32 = 9;
+/// Containers are filled with between one and this many items when a level is generated
+const MAX_ITEMS_PER_CONTAINER: u32 = 2;
+/// Each generated level has a one in this many chance of containing a shield generator
+const SHIELD_GENERATOR_CHANCE: u32 = 2;
 
 #[derive(Serialize, Deserialize, Default)]
 struct AiCtx {
     distance_map: distance_map::PopulateContext,
+    /// Rebuilt at the start of each npc turn, so there's no need to save it
+    #[serde(skip)]
+    flee_map: HashMap<Coord, i32>,
+    /// The side of the player each npc in a pack is heading for this turn
+    #[serde(skip)]
+    flanks: HashMap<Entity, Coord>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -132,45 +556,541 @@ pub struct Game {
     rng: Isaac64Rng,
     player_entity: Entity,
     visibility_grid: VisibilityGrid<VisibleCellData>,
-    messages: Vec<String>,
+    messages: MessageLog,
     ai_ctx: AiCtx,
+    run_flags: RunFlags,
+    dragging: Option<Entity>,
+    timers: Timers,
+    event_director: EventDirector,
+    power_switched_off: bool,
+    gravity: Gravity,
+    /// Number of turns that have passed since the start of the game
+    turn: u64,
+    discovered: Vec<CodexEntry>,
+    run_timer: RunTimer,
+    /// Events waiting to be collected by the app. These are only of interest while the game is
+    /// running so they aren't saved.
+    #[serde(skip)]
+    external_events: Vec<ExternalEvent>,
+    #[serde(skip)]
+    num_reported_messages: usize,
+    balance: Balance,
+    /// How the player had fared in previous games when the run started. The app's copy changes
+    /// as the player dies and clears levels, so the run keeps its own to stay reproducible.
+    #[serde(default)]
+    performance: Performance,
+    #[serde(skip)]
+    event_log: EventLog,
+    level_name: String,
+    #[serde(default)]
+    level_theme: Option<LevelTheme>,
+    /// How the current level's layout was checked and repaired when it was generated. Only kept
+    /// for debugging, so it isn't saved.
+    #[serde(skip)]
+    terrain_report: TerrainReport,
+    /// The level the player is currently on, numbered from 1
+    level: u32,
+    /// Only present when playing the tutorial
+    tutorial: Option<Tutorial>,
+    identification: Identification,
+    /// Only present in ironman runs
+    #[serde(default)]
+    ironman: Option<Ironman>,
+    /// Only present in casual runs
+    #[serde(default)]
+    casual: Option<casual::Casual>,
+    #[serde(default)]
+    score: u32,
+    /// The seed of the game's rng when the game started
+    #[serde(default)]
+    seed: u64,
+    #[serde(default)]
+    ghost_trail: GhostTrail,
+    #[serde(default)]
+    modifiers: RunModifiers,
+    #[serde(default)]
+    difficulty: Difficulty,
+    /// Percentage of a unit of oxygen drained but not yet taken from the player, so difficulties
+    /// which drain less than a unit per turn still drain at a steady rate
+    #[serde(default)]
+    oxygen_drain_progress: u32,
+    #[serde(default)]
+    layout_style: LayoutStyle,
+    /// How many times the player has won before starting this run with the same character
+    #[serde(default)]
+    new_game_plus: u32,
+    /// Every player-controlled character in the order they take their turns, in hot-seat games.
+    /// Empty when there is only one player. `player_entity` is whichever of these is acting.
+    #[serde(default)]
+    players: Vec<Entity>,
+    /// The serialized game from before the most recent turn, if undo is enabled
+    #[serde(skip)]
+    undo: Option<String>,
+    /// Number of inputs which have taken effect. Unlike the turn count, this also goes up for
+    /// inputs which change level or pass control to the next hot-seat player.
+    #[serde(default)]
+    num_committed_inputs: u64,
+    /// Every input so far, in runs which can be replayed
+    #[serde(default)]
+    replay: Option<Vec<ReplayInput>>,
+    /// The question asked by the previous input, if it's still waiting for an answer
+    #[serde(default)]
+    prompt: Option<Prompt>,
+    /// Where the player is travelling to. Any other input stops travelling.
+    #[serde(default)]
+    travel: Option<Travel>,
+    /// Noises made since npcs last listened for them
+    #[serde(default)]
+    noises: Vec<Noise>,
+}
+
+/// A newly-generated level with the player placed on it
+struct Level {
+    world: World,
+    player_entity: Entity,
+    gravity: Gravity,
+    name: String,
+    /// Only present on generated levels
+    theme: Option<LevelTheme>,
+    report: TerrainReport,
+}
+
+/// A random empty cell where an item could be left
+fn choose_item_coord<R: Rng>(world: &World, rng: &mut R) -> Option<Coord> {
+    let size = world.spatial_table.grid_size();
+    let candidates = size
+        .coord_iter_row_major()
+        .filter(|&coord| world.can_spawn_item_at(coord))
+        .collect::<Vec<_>>();
+    candidates.choose(rng).copied()
+}
+
+/// Leaves a weapon which can be found on the given level, an attachment and an injector lying
+/// somewhere on it. The last level also has a chaff emitter for dealing with the homing missiles
+/// found there.
+fn place_items<R: Rng>(
+    world: &mut World,
+    level: u32,
+    identification: &Identification,
+    rng: &mut R,
+) {
+    let kinds = WeaponKind::ALL
+        .iter()
+        .filter(|kind| kind.min_level() <= level)
+        .collect::<Vec<_>>();
+    if let (Some(coord), Some(&&kind)) = (choose_item_coord(world, rng), kinds.choose(rng)) {
+        world.spawn_weapon(coord, loot::roll_weapon(kind, level, rng));
+    }
+    if let (Some(coord), Some(&attachment)) =
+        (choose_item_coord(world, rng), Attachment::ALL.choose(rng))
+    {
+        world.spawn_attachment(coord, attachment);
+    }
+    if let (Some(coord), Some(&effect)) = (
+        choose_item_coord(world, rng),
+        InjectorEffect::ALL.choose(rng),
+    ) {
+        world.spawn_injector(coord, effect, identification.appearance(effect).tile());
+    }
+    if level == NUM_LEVELS {
+        if let Some(coord) = choose_item_coord(world, rng) {
+            world.spawn_chaff_emitter(coord);
+        }
+    }
+    if rng.gen_ratio(1, SHIELD_GENERATOR_CHANCE) {
+        if let Some(coord) = choose_item_coord(world, rng) {
+            world.spawn_shield_generator(coord);
+        }
+    }
+    fill_containers(world, identification, rng);
+}
+
+/// Hides traps in empty cells away from the player's starting position
+fn place_traps<R: Rng>(world: &mut World, player_coord: Coord, rng: &mut R) {
+    let size = world.spatial_table.grid_size();
+    let candidates = size
+        .coord_iter_row_major()
+        .filter(|&coord| {
+            (coord - player_coord).magnitude2() >= TRAP_MIN_DISTANCE_SQUARED
+                && world.can_spawn_character_at(coord)
+                && world
+                    .spatial_table
+                    .layers_at_checked(coord)
+                    .feature
+                    .is_none()
+        })
+        .collect::<Vec<_>>();
+    let coords = candidates
+        .choose_multiple(rng, TRAPS_PER_LEVEL)
+        .copied()
+        .collect::<Vec<_>>();
+    for coord in coords {
+        let &kind = TrapKind::ALL.choose(rng).unwrap();
+        world.spawn_trap(coord, kind);
+    }
+}
+
+/// Puts a few injectors and attachments in each of the level's containers
+fn fill_containers<R: Rng>(world: &mut World, identification: &Identification, rng: &mut R) {
+    let containers = world
+        .components
+        .container
+        .iter()
+        .map(|(entity, _)| entity)
+        .collect::<Vec<_>>();
+    for entity in containers {
+        let num_items = rng.gen_range(1..=MAX_ITEMS_PER_CONTAINER);
+        for _ in 0..num_items {
+            let entity_data = if rng.gen() {
+                let &effect = InjectorEffect::ALL.choose(rng).unwrap();
+                entity_data! {
+                    tile: identification.appearance(effect).tile(),
+                    injector: effect,
+                }
+            } else {
+                let &attachment = Attachment::ALL.choose(rng).unwrap();
+                entity_data! {
+                    tile: attachment.tile(),
+                    attachment,
+                }
+            };
+            if let Some(container) = world.components.container.get_mut(entity) {
+                container.add(entity_data);
+            }
+        }
+    }
+}
+
+/// The final level is a fixed arena rather than a generated one. Only its items are random.
+fn arena_level<R: Rng>(
+    player_data: EntityData,
+    identification: &Identification,
+    rng: &mut R,
+) -> Level {
+    let Terrain {
+        mut world,
+        player_entity,
+        gravity,
+        report,
+    } = Terrain::arena(player_data);
+    place_items(&mut world, NUM_LEVELS, identification, rng);
+    Level {
+        world,
+        player_entity,
+        gravity,
+        name: "Quarantine Bay".to_string(),
+        theme: None,
+        report,
+    }
+}
+
+fn generate_level<R: Rng>(
+    mut player_data: EntityData,
+    level: u32,
+    identification: &Identification,
+    new_game_plus: u32,
+    layout_style: LayoutStyle,
+    spawn_director: SpawnDirector,
+    rng: &mut R,
+) -> Level {
+    // keycards only open doors on the level they were found on
+    player_data.keycards = None;
+    if level == NUM_LEVELS {
+        return arena_level(player_data, identification, rng);
+    }
+    let Terrain {
+        mut world,
+        player_entity,
+        gravity,
+        report,
+    } = Terrain::generate_text(player_data, new_game_plus, layout_style, rng);
+    let theme = LevelTheme::choose(rng);
+    theme::apply_theme(&mut world, theme);
+    if let Some(player_coord) = world.spatial_table.coord_of(player_entity) {
+        let spawned = spawn_director.populate(&mut world, player_coord, theme, rng);
+        // one of the npcs on each level is important enough to have a name
+        if let Some(&leader) = spawned.choose(rng) {
+            world.components.name.insert(leader, names::npc_name(rng));
+        }
+        faction::place_rivals(&mut world, &spawned, level, rng);
+        lock::place_locks(&mut world, player_coord, rng);
+        place_traps(&mut world, player_coord, rng);
+    }
+    place_items(&mut world, level, identification, rng);
+    let name = names::level_name(rng);
+    Level {
+        world,
+        player_entity,
+        gravity,
+        name,
+        theme: Some(theme),
+        report,
+    }
 }
 
 impl Game {
-    pub fn new<R: Rng>(_config: &Config, _victories: Vec<Victory>, base_rng: &mut R) -> Self {
-        let rng = Isaac64Rng::seed_from_u64(base_rng.gen());
-        let Terrain {
+    pub fn new<R: Rng>(config: &Config, _victories: Vec<Victory>, base_rng: &mut R) -> Self {
+        let mut player_data = world::spawn::make_player();
+        config.modifiers.apply_to_player(&mut player_data);
+        Self::new_run(config, player_data, 0, base_rng)
+    }
+
+    /// Starts a run with an existing player, which may have come from a previous run
+    fn new_run<R: Rng>(
+        config: &Config,
+        player_data: EntityData,
+        new_game_plus: u32,
+        base_rng: &mut R,
+    ) -> Self {
+        let seed = base_rng.gen();
+        let mut game = Self::new_run_from_seed(config, player_data, new_game_plus, seed);
+        if config.ironman {
+            game.ironman = Some(Ironman::new(base_rng.gen()));
+        }
+        game
+    }
+
+    /// Everything about starting a run except choosing its seeds
+    fn new_run_from_seed(
+        config: &Config,
+        player_data: EntityData,
+        new_game_plus: u32,
+        seed: u64,
+    ) -> Self {
+        let mut rng = Isaac64Rng::seed_from_u64(seed);
+        let event_director = EventDirector::new(&mut rng);
+        let identification = Identification::new(&mut rng);
+        let spawn_director = SpawnDirector::for_run(
+            &config.performance,
+            config.modifiers,
+            config.difficulty,
+            new_game_plus,
+        );
+        let level = generate_level(
+            player_data,
+            1,
+            &identification,
+            new_game_plus,
+            config.layout_style,
+            spawn_director,
+            &mut rng,
+        );
+        let mut game = Self::with_first_level(level, rng, event_director, identification, config);
+        game.seed = seed;
+        game.modifiers = config.modifiers;
+        game.difficulty = config.difficulty;
+        game.layout_style = config.layout_style;
+        game.new_game_plus = new_game_plus;
+        game.ghost_trail.seed = seed;
+        // runs continuing with a character from an earlier run can't be replayed from their seed
+        if new_game_plus == 0 {
+            game.replay = Some(Vec::new());
+        }
+        if config.hot_seat {
+            let partner_data = game.new_hot_seat_partner();
+            game.add_hot_seat_partner(partner_data);
+            game.update_visibility();
+        }
+        if config.casual {
+            game.casual = Some(casual::Casual::new());
+            game.snapshot_level();
+        }
+        game
+    }
+
+    /// A game on a fixed level which walks the player through the controls
+    pub fn new_tutorial<R: Rng>(config: &Config, base_rng: &mut R) -> Self {
+        let mut rng = Isaac64Rng::seed_from_u64(base_rng.gen());
+        let event_director = EventDirector::new(&mut rng);
+        let identification = Identification::new(&mut rng);
+        let level = tutorial::tutorial_level(world::spawn::make_player());
+        let mut game = Self::with_first_level(level, rng, event_director, identification, config);
+        game.tutorial = Some(Tutorial::default());
+        game.prompt_tutorial_step();
+        game
+    }
+
+    fn with_first_level(
+        level: Level,
+        rng: Isaac64Rng,
+        event_director: EventDirector,
+        identification: Identification,
+        config: &Config,
+    ) -> Self {
+        let Level {
             world,
             player_entity,
-        } = Terrain::generate_text(world::spawn::make_player());
+            gravity,
+            name: level_name,
+            theme: level_theme,
+            report: terrain_report,
+        } = level;
         let mut game = Self {
             rng,
             visibility_grid: VisibilityGrid::new(world.spatial_table.grid_size()),
             world,
             player_entity,
-            messages: Vec::new(),
+            messages: Default::default(),
             ai_ctx: Default::default(),
+            run_flags: Default::default(),
+            dragging: None,
+            timers: Default::default(),
+            event_director,
+            power_switched_off: false,
+            gravity,
+            turn: 0,
+            discovered: Vec::new(),
+            run_timer: Default::default(),
+            external_events: Vec::new(),
+            num_reported_messages: 0,
+            balance: config.balance,
+            performance: config.performance,
+            event_log: Default::default(),
+            level_name,
+            level_theme,
+            terrain_report,
+            level: 1,
+            tutorial: None,
+            identification,
+            ironman: None,
+            casual: None,
+            score: 0,
+            seed: 0,
+            ghost_trail: Default::default(),
+            modifiers: Default::default(),
+            difficulty: Default::default(),
+            oxygen_drain_progress: 0,
+            layout_style: Default::default(),
+            new_game_plus: 0,
+            players: Vec::new(),
+            undo: None,
+            num_committed_inputs: 0,
+            replay: None,
+            prompt: None,
+            travel: None,
+            noises: Vec::new(),
         };
+        game.announce_level();
         game.update_visibility();
+        game.record_ghost_step();
         game
     }
 
-    pub fn messages(&self) -> &[String] {
+    fn announce_level(&mut self) {
+        self.emit_level_start();
+        self.messages.system(Message::with_args(
+            "message.level_boarded",
+            &[&self.level_name],
+        ));
+        match self.gravity {
+            Gravity::Normal => (),
+            Gravity::Low => self.messages.system(Message::new("message.gravity_low")),
+            Gravity::Zero => self.messages.system(Message::new("message.gravity_zero")),
+        }
+    }
+
+    /// Replaces the current level with a newly-generated one, bringing the player (and
+    /// everything they are carrying) along. State belonging to the station as a whole, such as
+    /// timers and power, carries over.
+    fn descend(&mut self, config: &Config) {
+        let partners = self.take_partners();
+        let player_data = self.world.components.remove_entity_data(self.player_entity);
+        let spawn_director = SpawnDirector::for_run(
+            &self.performance,
+            self.modifiers,
+            self.difficulty,
+            self.new_game_plus,
+        );
+        let Level {
+            world,
+            player_entity,
+            gravity,
+            name,
+            theme,
+            report,
+        } = generate_level(
+            player_data,
+            self.level + 1,
+            &self.identification,
+            self.new_game_plus,
+            self.layout_style,
+            spawn_director,
+            &mut self.rng,
+        );
+        self.visibility_grid = VisibilityGrid::new(world.spatial_table.grid_size());
+        self.world = world;
+        self.player_entity = player_entity;
+        self.gravity = gravity;
+        self.level_name = name;
+        self.level_theme = theme;
+        self.terrain_report = report;
+        self.dragging = None;
+        self.level += 1;
+        self.place_partners(partners);
+        self.announce_level();
+        self.plan_npc_actions();
+        self.update_visibility();
+        self.snapshot_level();
+    }
+
+    pub fn messages(&self) -> &MessageLog {
         &self.messages
     }
 
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    pub fn num_committed_inputs(&self) -> u64 {
+        self.num_committed_inputs
+    }
+
+    pub fn level_name(&self) -> &str {
+        &self.level_name
+    }
+
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+
+    pub fn terrain_report(&self) -> &TerrainReport {
+        &self.terrain_report
+    }
+
+    pub fn run_flags(&self) -> &RunFlags {
+        &self.run_flags
+    }
+
+    pub fn timer(&self, kind: TimerKind) -> Option<&Timer> {
+        self.timers.get(kind)
+    }
+
+    pub fn gravity(&self) -> Gravity {
+        self.gravity
+    }
+
     pub fn update_visibility(&mut self) {
+        let wall_tile = self.level_theme.map_or(Tile::Wall, LevelTheme::wall_tile);
         let update_fn = |data: &mut VisibleCellData, coord| {
-            data.update(&self.world, coord);
+            data.update(&self.world, coord, wall_tile);
+        };
+        let distance = if self.power_state() == PowerState::Off {
+            Circle::new_squared(POWER_OUTAGE_VISION_DISTANCE_SQUARED)
+        } else {
+            Circle::new_squared(VISION_DISTANCE_SQUARED)
         };
-        let distance = Circle::new_squared(150);
-        self.visibility_grid.update_custom(
-            Rgb24::new_grey(255),
+        // in hot-seat games each player can see everything visible to any of them
+        let eyes = self
+            .player_coords()
+            .into_iter()
+            .map(|coord| (coord, distance));
+        self.visibility_grid.update_custom_multi(
+            light::ambient_light(self.power_state()),
             &self.world,
-            distance,
-            self.player_coord(),
+            eyes,
             update_fn,
         );
+        self.discover_visible();
     }
 
     pub fn cell_visibility_at_coord(&self, coord: Coord) -> CellVisibility<&VisibleCellData> {
@@ -185,7 +1105,31 @@ impl Game {
             .expect("player does not have coord")
     }
 
+    pub fn player_hit_points(&self) -> &Meter {
+        self.world
+            .components
+            .hit_points
+            .get(self.player_entity)
+            .expect("player does not have hit points")
+    }
+
+    pub fn player_oxygen(&self) -> &Meter {
+        self.world
+            .components
+            .oxygen
+            .get(self.player_entity)
+            .expect("player does not have oxygen")
+    }
+
+    /// Characters in deep liquid have to swim
+    pub fn is_player_swimming(&self) -> bool {
+        self.world.is_deep_liquid(self.player_coord())
+    }
+
     fn open_door(&mut self, entity: Entity) {
+        if let Some(coord) = self.world.spatial_table.coord_of(entity) {
+            self.emit_sound(SoundEffect::Door, coord);
+        }
         self.world.components.apply_entity_update(
             entity,
             entity_update! {
@@ -193,6 +1137,7 @@ impl Game {
                 tile: Some(Tile::DoorOpen),
                 solid: None,
                 opacity: None,
+                door_close_countdown: Some(self.balance.door_close_turns),
             },
         );
     }
@@ -215,6 +1160,9 @@ impl Game {
     }
 
     fn close_door(&mut self, entity: Entity) {
+        if let Some(coord) = self.world.spatial_table.coord_of(entity) {
+            self.emit_sound(SoundEffect::Door, coord);
+        }
         self.world.components.insert_entity_data(
             entity,
             entity_data! {
@@ -224,25 +1172,601 @@ impl Game {
                 opacity: 255,
             },
         );
+        self.world.components.door_close_countdown.remove(entity);
+    }
+
+    fn add_decal(&mut self, coord: Coord, kind: DecalKind, config: &Config) {
+        if kind.is_gore() && !config.gore {
+            return;
+        }
+        self.world.add_decal(coord, kind);
+    }
+
+    fn destroy_reactor(&mut self, entity: Entity, config: &Config) {
+        self.world.components.apply_entity_update(
+            entity,
+            entity_update! {
+                reactor: None,
+                tile: Some(Tile::ReactorDestroyed),
+                light: Some(light::glow(light::DAMAGED_REACTOR_LIGHT)),
+                radiation_source: Some(DAMAGED_REACTOR_RADIATION),
+            },
+        );
+        self.run_flags.destroyed_reactor = true;
+        if let Some(coord) = self.world.spatial_table.coord_of(entity) {
+            self.emit_sound(SoundEffect::Explosion, coord);
+            self.emit_camera_shake(REACTOR_CAMERA_SHAKE);
+            self.add_decal(coord, DecalKind::Oil, config);
+            for direction in Direction::all() {
+                self.add_decal(coord + direction.coord(), DecalKind::Scorch, config);
+            }
+        }
+        self.messages
+            .system(Message::new("message.reactor_sabotaged"));
+        self.timers
+            .start(TimerKind::SelfDestruct, self.balance.self_destruct_turns);
+        self.messages.system(Message::with_args(
+            "message.self_destruct_started",
+            &[&self.balance.self_destruct_turns],
+        ));
+    }
+
+    /// Debris falls near the player during the self destruct sequence. The amount of debris
+    /// increases as the countdown approaches zero.
+    fn self_destruct_hazards(&mut self, config: &Config) -> Option<GameControlFlow> {
+        let timer = *self.timers.get(TimerKind::SelfDestruct)?;
+        let num_debris = (timer.elapsed() * MAX_DEBRIS_PER_TURN) / timer.total;
+        for _ in 0..num_debris {
+            let offset = Coord::new(
+                self.rng.gen_range(-DEBRIS_RADIUS..=DEBRIS_RADIUS),
+                self.rng.gen_range(-DEBRIS_RADIUS..=DEBRIS_RADIUS),
+            );
+            let coord = self.player_coord() + offset;
+            if !coord.is_valid(self.world.size()) {
+                continue;
+            }
+            self.add_decal(coord, DecalKind::Scorch, config);
+            if let Some(&Layers {
+                character: Some(character_entity),
+                ..
+            }) = self.world.spatial_table.layers_at(coord)
+            {
+                if self.is_player(character_entity) {
+                    self.messages
+                        .combat(None, Message::new("message.debris_hits_player"));
+                    if self.damage_character(character_entity, self.balance.debris_damage, config) {
+                        return Some(GameControlFlow::GameOver(GameOverReason::SelfDestruct));
+                    }
+                } else if self.damage_character(
+                    character_entity,
+                    self.balance.debris_damage,
+                    config,
+                ) {
+                    // the player isn't to blame for npcs crushed by debris
+                    self.remove_dead_npc(character_entity);
+                }
+            }
+        }
+        None
+    }
+
+    fn tick_timers(&mut self, config: &Config) -> Option<GameControlFlow> {
+        let expired = self.timers.tick();
+        if expired.contains(&TimerKind::SelfDestruct) {
+            return Some(GameControlFlow::GameOver(GameOverReason::SelfDestruct));
+        }
+        if expired.contains(&TimerKind::PowerOutage) {
+            self.messages
+                .system(Message::new("message.lights_restored"));
+        }
+        if expired.contains(&TimerKind::Alarm) {
+            self.messages.system(Message::new("message.alarm_ended"));
+        }
+        if let Some(timer) = self.timers.get(TimerKind::SelfDestruct) {
+            if SELF_DESTRUCT_WARNINGS.contains(&timer.remaining) {
+                self.messages.system(Message::with_args(
+                    "message.self_destruct_warning",
+                    &[&timer.remaining],
+                ));
+            }
+        }
+        self.self_destruct_hazards(config)
+    }
+
+    /// How an npc is referred to in messages, e.g. "the zombie", or "Sgt. Varga" if it has a name
+    fn npc_description(&self, entity: Entity) -> String {
+        if let Some(name) = self.world.components.name.get(entity) {
+            return name.clone();
+        }
+        let adjective = self
+            .world
+            .components
+            .faction
+            .get(entity)
+            .and_then(|faction| faction.adjective());
+        match adjective {
+            Some(adjective) => format!("the {} {}", adjective, self.npc_name(entity)),
+            None => format!("the {}", self.npc_name(entity)),
+        }
+    }
+
+    fn npc_name(&self, entity: Entity) -> &'static str {
+        self.world
+            .components
+            .npc_type
+            .get(entity)
+            .map(|npc_type| npc_type.name())
+            .unwrap_or("thing")
+    }
+
+    /// Deals damage to a character. Returns `true` if the character ran out of hit points.
+    fn damage_character(&mut self, entity: Entity, damage: u32, config: &Config) -> bool {
+        let bleeds = self.is_player(entity)
+            || self
+                .world
+                .components
+                .npc_type
+                .get(entity)
+                .map(|npc_type| npc_type.bleeds())
+                .unwrap_or(false);
+        if bleeds {
+            if let Some(coord) = self.world.spatial_table.coord_of(entity) {
+                self.add_decal(coord, DecalKind::Blood, config);
+            }
+        }
+        self.alert_npc(entity);
+        if let Some(hit_points) = self.world.components.hit_points.get_mut(entity) {
+            hit_points.decrease(damage);
+            hit_points.is_empty()
+        } else {
+            false
+        }
+    }
+
+    fn npc_die(&mut self, entity: Entity) {
+        self.score_kill();
+        self.record_faction_harmed(entity);
+        self.remove_dead_npc(entity);
+    }
+
+    /// Replaces a dead npc with its corpse and any ammo it dropped
+    fn remove_dead_npc(&mut self, entity: Entity) {
+        self.boss_defeated(entity);
+        let coord = self.world.spatial_table.coord_of(entity);
+        let npc_type = self.world.components.npc_type.get(entity).cloned();
+        self.world.remove_entity(entity);
+        if let (Some(coord), Some(npc_type)) = (coord, npc_type) {
+            self.world.spawn_corpse(coord, npc_type);
+            if let Some(rounds) = loot::roll_ammo_drop(npc_type, &mut self.rng) {
+                self.world.spawn_dropped_ammo(coord, rounds);
+            }
+        }
+    }
+
+    fn player_attack(&mut self, entity: Entity, direction: CardinalDirection, config: &Config) {
+        self.complete_tutorial_step(TutorialStep::Melee);
+        let name = self.npc_description(entity);
+        if !self.roll_to_hit(Some(self.player_entity), entity, 0) {
+            self.messages.combat(
+                Some(entity),
+                Message::with_args("message.player_misses", &[&name]),
+            );
+            return;
+        }
+        if let Some(coord) = self.world.spatial_table.coord_of(entity) {
+            self.emit_sound(SoundEffect::Hit, coord);
+        }
+        let mut damage = self.balance.player_melee_damage;
+        let bayonet = self
+            .player_weapon()
+            .is_some_and(|weapon| weapon.attachments.bayonet);
+        if bayonet {
+            damage += BAYONET_DAMAGE;
+        }
+        if self.damage_character(entity, damage, config) {
+            self.messages.combat(
+                Some(entity),
+                Message::with_args("message.player_destroys", &[&name]),
+            );
+            self.npc_die(entity);
+        } else {
+            self.messages.combat(
+                Some(entity),
+                Message::with_args("message.player_hits", &[&name]),
+            );
+            if bayonet {
+                self.apply_status_effect(entity, StatusEffect::Stun, STUN_DURATION);
+            }
+            let distance = PLAYER_MELEE_KNOCKBACK * self.gravity.knockback_multiplier();
+            self.world.knock_back(entity, direction, distance);
+        }
+    }
+
+    fn corpse_entity_at_coord(&self, coord: Coord) -> Option<Entity> {
+        if let Some(&Layers {
+            item: Some(item_entity),
+            ..
+        }) = self.world.spatial_table.layers_at(coord)
+        {
+            if self.world.components.corpse.contains(item_entity) {
+                return Some(item_entity);
+            }
+        }
+        None
+    }
+
+    /// True if there's a corpse under the player which hasn't been searched yet
+    pub fn is_player_on_unsearched_corpse(&self) -> bool {
+        self.corpse_entity_at_coord(self.player_coord())
+            .and_then(|corpse_entity| self.world.components.corpse.get(corpse_entity))
+            .map(|corpse| !corpse.searched)
+            .unwrap_or(false)
+    }
+
+    /// Checks the surrounding walls for hidden doors, then searches any corpse the player is
+    /// standing on
+    fn player_search(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        let player_coord = self.player_coord();
+        let mut found = false;
+        for direction in Direction::all() {
+            if self
+                .world
+                .reveal_hidden_wall(player_coord + direction.coord())
+            {
+                found = true;
+            }
+        }
+        if found {
+            self.messages
+                .system(Message::new("message.hidden_door_found"));
+        }
+        match self.corpse_entity_at_coord(player_coord) {
+            Some(corpse_entity) => self.search_corpse(corpse_entity),
+            None => {
+                if !found {
+                    self.messages
+                        .system(Message::new("message.search_found_nothing"));
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    fn search_corpse(
+        &mut self,
+        corpse_entity: Entity,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        let corpse = self.world.components.corpse.get_mut(corpse_entity).unwrap();
+        let name = corpse.npc_type.corpse_name();
+        if corpse.searched {
+            self.messages.items(
+                Some(corpse_entity),
+                Message::with_args("message.corpse_already_searched", &[&name]),
+            );
+            return Ok(None);
+        }
+        corpse.searched = true;
+        if self.rng.gen_ratio(1, 3) {
+            if let Some(hit_points) = self.world.components.hit_points.get_mut(self.player_entity) {
+                hit_points.increase(self.balance.corpse_medkit_healing);
+            }
+            self.messages.items(
+                Some(corpse_entity),
+                Message::with_args("message.corpse_medkit", &[&name]),
+            );
+        } else {
+            self.messages.items(
+                Some(corpse_entity),
+                Message::with_args("message.corpse_empty", &[&name]),
+            );
+        }
+        Ok(None)
+    }
+
+    fn player_destroy_corpse(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        let corpse_entity = self
+            .corpse_entity_at_coord(self.player_coord())
+            .ok_or(ActionError::NoCorpse)?;
+        let respawning = self.world.components.respawn.contains(corpse_entity);
+        let name = self
+            .world
+            .components
+            .corpse
+            .get(corpse_entity)
+            .map(|corpse| corpse.npc_type.corpse_name())
+            .unwrap_or("corpse");
+        self.world.remove_entity(corpse_entity);
+        if self.dragging == Some(corpse_entity) {
+            self.dragging = None;
+        }
+        if respawning {
+            self.messages.items(
+                None,
+                Message::with_args("message.corpse_destroyed_for_good", &[&name]),
+            );
+        } else {
+            self.messages.items(
+                None,
+                Message::with_args("message.corpse_destroyed", &[&name]),
+            );
+        }
+        Ok(None)
+    }
+
+    fn player_drag(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        if self.dragging.take().is_some() {
+            self.messages
+                .items(None, Message::new("message.drag_released"));
+            return Ok(None);
+        }
+        let corpse_entity = self
+            .corpse_entity_at_coord(self.player_coord())
+            .ok_or(ActionError::NoCorpse)?;
+        let name = self
+            .world
+            .components
+            .corpse
+            .get(corpse_entity)
+            .map(|corpse| corpse.npc_type.corpse_name())
+            .unwrap_or("corpse");
+        self.messages.items(
+            Some(corpse_entity),
+            Message::with_args("message.drag_started", &[&name]),
+        );
+        self.dragging = Some(corpse_entity);
+        Ok(None)
     }
 
-    fn player_walk(&mut self, direction: CardinalDirection) -> Option<GameControlFlow> {
+    /// Moves the corpse being dragged (if any) into the cell the player just left
+    fn drag_corpse(&mut self, to: Coord) {
+        if let Some(corpse_entity) = self.dragging {
+            if !self.world.components.corpse.contains(corpse_entity) {
+                self.dragging = None;
+                return;
+            }
+            if self.world.spatial_table.coord_of(corpse_entity) == Some(to) {
+                return;
+            }
+            if self
+                .world
+                .spatial_table
+                .update_coord(corpse_entity, to)
+                .is_err()
+            {
+                self.messages
+                    .items(Some(corpse_entity), Message::new("message.drag_lost"));
+                self.dragging = None;
+            }
+        }
+    }
+
+    /// Counts down the timer on each respawning entity, replacing it with what it respawns as
+    /// when it reaches zero and there is somewhere for it to appear
+    fn tick_respawn(&mut self) {
+        let mut ready = Vec::new();
+        for (entity, respawn) in self.world.components.respawn.iter_mut() {
+            respawn.turns = respawn.turns.saturating_sub(1);
+            if respawn.turns == 0 {
+                ready.push((entity, respawn.what, respawn.location));
+            }
+        }
+        for (entity, what, location) in ready {
+            let coord = match self.world.spatial_table.coord_of(entity) {
+                Some(coord) => coord,
+                None => continue,
+            };
+            let respawn_coord = match self.world.respawn_coord(coord, location, what) {
+                Some(respawn_coord) => respawn_coord,
+                None => continue,
+            };
+            self.world.remove_entity(entity);
+            if self.dragging == Some(entity) {
+                self.dragging = None;
+            }
+            match what {
+                Respawnable::Npc(npc_type) => {
+                    let npc_entity = self.world.spawn_npc(respawn_coord, npc_type);
+                    if let CellVisibility::Current { .. } =
+                        self.cell_visibility_at_coord(respawn_coord)
+                    {
+                        self.messages.combat(
+                            Some(npc_entity),
+                            Message::with_args("message.npc_respawned", &[&npc_type.name()]),
+                        );
+                    }
+                }
+                // rearmed traps are hidden again, so nothing is said about them
+                Respawnable::Trap(kind) => {
+                    self.world.spawn_trap(respawn_coord, kind);
+                }
+            }
+        }
+    }
+
+    fn breach_coolant_tank(&mut self, entity: Entity) {
+        self.world.components.coolant_tank.remove(entity);
+        self.world
+            .components
+            .tile
+            .insert(entity, Tile::CoolantTankBreached);
+        self.world
+            .components
+            .liquid_source
+            .insert(entity, COOLANT_TANK_VOLUME);
+        if let Some(coord) = self.world.spatial_table.coord_of(entity) {
+            self.emit_sound(SoundEffect::Breach, coord);
+        }
+        self.messages.system(Message::new("message.tank_punctured"));
+    }
+
+    /// Moves liquid around the level and drains the player's oxygen while they can't breathe
+    fn tick_air(&mut self) -> Option<GameControlFlow> {
+        self.world.flow_liquid();
+        let player_coord = self.player_coord();
+        let swimming = self.world.is_deep_liquid(player_coord);
+        if swimming && self.dragging.take().is_some() {
+            self.messages
+                .items(None, Message::new("message.drag_released_to_swim"));
+        }
+        let can_breathe = !swimming
+            && self.world.atmosphere_at(player_coord) == Atmosphere::Pressurised
+            && !self.is_fire_near(player_coord);
+        let oxygen = self.world.components.oxygen.get_mut(self.player_entity)?;
+        if can_breathe {
+            oxygen.increase(self.balance.oxygen_recovery_per_turn);
+            return None;
+        }
+        if !oxygen.is_empty() {
+            self.oxygen_drain_progress += self.difficulty.oxygen_drain_percent();
+            oxygen.decrease(self.oxygen_drain_progress / 100);
+            self.oxygen_drain_progress %= 100;
+            if oxygen.is_empty() {
+                self.messages.system(Message::new("message.out_of_air"));
+            }
+            return None;
+        }
+        self.messages.system(Message::new("message.suffocating"));
+        let hit_points = self
+            .world
+            .components
+            .hit_points
+            .get_mut(self.player_entity)?;
+        hit_points.decrease(self.balance.suffocation_damage);
+        if hit_points.is_empty() {
+            return Some(GameControlFlow::GameOver(GameOverReason::Suffocated));
+        }
+        None
+    }
+
+    /// The direction the player is drifting in, if they are somewhere they can't walk
+    fn player_drift_direction(&self) -> Option<CardinalDirection> {
+        if self.world.movement_mode(self.player_coord(), self.gravity) == MovementMode::Walking {
+            return None;
+        }
+        self.world
+            .components
+            .momentum
+            .get(self.player_entity)
+            .cloned()
+    }
+
+    fn player_walk(
+        &mut self,
+        direction: CardinalDirection,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        let player_coord = self.player_coord();
+        if self.world.movement_mode(player_coord, self.gravity) == MovementMode::Walking {
+            self.world.components.momentum.remove(self.player_entity);
+            return self.player_step(direction, config);
+        }
+        // There's nothing to push off while drifting so the player keeps their current heading
+        let direction = self.player_drift_direction().unwrap_or(direction);
+        let game_control_flow = self.player_step(direction, config);
+        if self.player_coord() == player_coord {
+            if self
+                .world
+                .components
+                .momentum
+                .remove(self.player_entity)
+                .is_some()
+            {
+                self.messages.system(Message::new("message.drift_stopped"));
+            }
+        } else {
+            self.world
+                .components
+                .momentum
+                .insert(self.player_entity, direction);
+        }
+        game_control_flow
+    }
+
+    fn player_brace(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        if !self.world.can_brace_at(self.player_coord()) {
+            return Err(ActionError::NothingToBrace);
+        }
+        if self
+            .world
+            .components
+            .momentum
+            .remove(self.player_entity)
+            .is_some()
+        {
+            self.messages
+                .system(Message::new("message.drift_caught_wall"));
+        }
+        Ok(None)
+    }
+
+    fn player_step(
+        &mut self,
+        direction: CardinalDirection,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
         let player_coord = self.player_coord();
-        let new_player_coord = player_coord + direction.coord();
+        let new_player_coord =
+            match self
+                .world
+                .step_destination(player_coord, direction, self.gravity)
+            {
+                Some(coord) => coord,
+                None => {
+                    self.messages.system(Message::new("message.gap_in_the_way"));
+                    return None;
+                }
+            };
         if !new_player_coord.is_valid(self.world.size()) {
             // player would walk outside bounds of map
             return None;
         }
+        if let Some(&Layers {
+            character: Some(character_entity),
+            ..
+        }) = self.world.spatial_table.layers_at(new_player_coord)
+        {
+            if self.is_player(character_entity) {
+                self.messages
+                    .system(Message::new("message.other_player_in_the_way"));
+            } else {
+                self.player_melee(direction, config);
+            }
+            return None;
+        }
         if let Some(&Layers {
             feature: Some(feature_entity),
             ..
         }) = self.world.spatial_table.layers_at(new_player_coord)
         {
+            // If the player bumps into a locked door, open it if they have the keycard
+            if let Some(&colour) = self.world.components.locked.get(feature_entity) {
+                self.try_unlock_door(feature_entity, colour);
+                return None;
+            }
             // If the player bumps into a door, open the door
             if let Some(DoorState::Closed) = self.world.components.door_state.get(feature_entity) {
                 self.open_door(feature_entity);
                 return None;
             }
+            // If the player bumps into a terminal, toggle the station's power
+            if self.world.components.terminal.contains(feature_entity) {
+                self.toggle_power();
+                return None;
+            }
+            // If the player bumps into a coolant tank, puncture it
+            if self.world.components.coolant_tank.contains(feature_entity) {
+                self.breach_coolant_tank(feature_entity);
+                return None;
+            }
+            // If the player bumps into a locker, open it
+            if self.world.components.container.contains(feature_entity) {
+                self.open_container(feature_entity);
+                return None;
+            }
+            // If the player bumps into a working reactor, ask whether to sabotage it
+            if self.world.components.reactor.contains(feature_entity) {
+                return self.prompt(Prompt::SabotageReactor(new_player_coord));
+            }
             // Don't let the player walk through solid entities
             if self.world.components.solid.contains(feature_entity) {
                 if let Some(open_door_entity) =
@@ -253,18 +1777,282 @@ impl Game {
                 return None;
             }
             // Exercise win logic
+            if self.world.components.escape_pod.contains(feature_entity) {
+                self.run_flags.escaped = true;
+                self.run_flags.reached_bottom = self.level == NUM_LEVELS;
+                self.complete_level();
+                return Some(GameControlFlow::Win(self.run_flags.ending()));
+            }
             if self.world.components.stairs_down.contains(feature_entity) {
-                return Some(GameControlFlow::Win);
+                if self.tutorial.is_some() {
+                    return self.tutorial_take_stairs();
+                }
+                self.complete_level();
+                if self.level < NUM_LEVELS {
+                    self.descend(config);
+                    return Some(GameControlFlow::LevelChange(self.level));
+                }
+                return Some(GameControlFlow::Win(self.run_flags.ending()));
             }
         }
+        if new_player_coord != player_coord + direction.coord() {
+            self.messages.system(Message::new("message.gap_leapt"));
+        }
         self.world
             .spatial_table
             .update_coord(self.player_entity, new_player_coord)
             .unwrap();
+        self.drag_corpse(player_coord);
+        self.complete_tutorial_step(TutorialStep::Move);
+        self.make_noise(new_player_coord, FOOTSTEP_LOUDNESS);
+        self.pick_up_protective_gear();
+        self.pick_up_weapon();
+        self.pick_up_chaff_emitter();
+        self.pick_up_shield_generator();
+        self.pick_up_ammo();
+        self.pick_up_keycard();
+        self.enter_difficult_terrain(self.player_entity);
+        self.trigger_trap(self.player_entity, config)
+    }
+
+    fn npc_hit_player(
+        &mut self,
+        entity: Entity,
+        target: Entity,
+        npc_type: NpcType,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        let name = names::capitalise(&self.npc_description(entity));
+        if self.absorb_hit(target) {
+            self.record_event(
+                entity,
+                EventAction::Attack { target },
+                EventOutcome::Blocked,
+            );
+            return None;
+        }
+        self.messages.combat(
+            Some(entity),
+            Message::with_args("message.npc_hits_player", &[&name]),
+        );
+        if let Some(coord) = self.world.spatial_table.coord_of(target) {
+            self.emit_sound(SoundEffect::Hit, coord);
+        }
+        let damage = self.difficulty.npc_damage(npc_type.damage());
+        let killed = self.damage_character(target, damage, config);
+        self.record_event(
+            entity,
+            EventAction::Attack { target },
+            EventOutcome::Damaged { damage, killed },
+        );
+        if killed {
+            return Some(GameControlFlow::GameOver(GameOverReason::Killed));
+        }
+        if let Some(effect) = npc_type.melee_status_effect() {
+            self.apply_status_effect(target, effect, effect.default_duration());
+        }
+        None
+    }
+
+    fn npc_miss_player(&mut self, entity: Entity, target: Entity) -> Option<GameControlFlow> {
+        let name = names::capitalise(&self.npc_description(entity));
+        self.messages.combat(
+            Some(entity),
+            Message::with_args("message.npc_misses_player", &[&name]),
+        );
+        self.record_event(entity, EventAction::Attack { target }, EventOutcome::Missed);
+        None
+    }
+
+    /// Resolves an attack that an npc wound up on its previous turn. The attack only hits if
+    /// the player is still in the targeted cell, and the npc is still next to it.
+    fn npc_land_heavy_attack(
+        &mut self,
+        entity: Entity,
+        npc_type: NpcType,
+        coord: Coord,
+        target: Coord,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        let delta = target - coord;
+        if delta.x.abs() + delta.y.abs() == 1 {
+            if let Some(player) = self.player_at(target) {
+                if !self.roll_to_hit(Some(entity), player, 0) {
+                    return self.npc_miss_player(entity, player);
+                }
+                let game_control_flow = self.npc_hit_player(entity, player, npc_type, config);
+                if game_control_flow.is_none() {
+                    // the blow leaves the player reeling
+                    self.apply_status_effect(player, StatusEffect::Stun, STUN_DURATION);
+                }
+                return game_control_flow;
+            }
+        }
+        let name = names::capitalise(&self.npc_description(entity));
+        self.messages.combat(
+            Some(entity),
+            Message::with_args("message.npc_heavy_blow_misses", &[&name]),
+        );
+        self.record_event(
+            entity,
+            EventAction::Attack {
+                target: self.player_entity,
+            },
+            EventOutcome::Missed,
+        );
+        None
+    }
+
+    fn npc_act(&mut self, entity: Entity, config: &Config) -> Option<GameControlFlow> {
+        let npc_type = *self.world.components.npc_type.get(entity)?;
+        let coord = self.world.spatial_table.coord_of(entity)?;
+        self.update_boss_phase(entity);
+        if let Some(target) = self.world.components.pending_attack.remove(entity) {
+            return self.npc_land_heavy_attack(entity, npc_type, coord, target, config);
+        }
+        if let Some(phase) = self.boss_special_due(entity) {
+            return self.boss_special(entity, coord, phase, config);
+        }
+        let player = self.nearest_player(coord);
+        let player_coord = self.world.spatial_table.coord_of(player)?;
+        let alertness = self.npc_alertness(entity);
+        let hostile = alertness == Alertness::Hostile;
+        // a fleeing npc with nowhere left to run fights back
+        let flee = if hostile && self.check_morale(entity) {
+            self.flee_step(coord)
+        } else {
+            None
+        };
+        // npcs fight whichever enemy is closest, which isn't always the player
+        let rival = match flee {
+            None if hostile => self.nearest_rival(entity, coord, player_coord),
+            _ => None,
+        };
+        let (target, target_coord) = rival.unwrap_or((player, player_coord));
+        let delta = target_coord - coord;
+        // ranged npcs back off before shooting if the player gets too close
+        let kite = match (flee, rival) {
+            (None, None) if hostile => self.kite_step(npc_type, coord, player_coord),
+            _ => None,
+        };
+        // npcs don't attack anything until they've seen the player
+        let attacking = hostile && flee.is_none() && kite.is_none();
+        if attacking && delta.x.abs() + delta.y.abs() == 1 {
+            if rival.is_some() {
+                return self.npc_attack_npc(entity, target, npc_type, config);
+            }
+            if npc_type.winds_up() {
+                let target = player_coord;
+                let name = names::capitalise(&self.npc_description(entity));
+                self.messages.combat(
+                    Some(entity),
+                    Message::with_args("message.npc_winds_up", &[&name]),
+                );
+                self.world.components.pending_attack.insert(entity, target);
+                self.record_event(entity, EventAction::WindUp { target }, EventOutcome::Done);
+                return None;
+            }
+            if !self.roll_to_hit(Some(entity), player, 0) {
+                return self.npc_miss_player(entity, player);
+            }
+            return self.npc_hit_player(entity, player, npc_type, config);
+        }
+        if let (true, Some(attack)) = (attacking, npc_type.ranged_attack()) {
+            if attack.preferred_range_squared.is_some()
+                || self.rng.gen_ratio(1, NPC_RANGED_ATTACK_CHANCE)
+            {
+                let damage = match rival {
+                    Some(_) => npc_type.damage(),
+                    None => self.difficulty.npc_damage(npc_type.damage()),
+                };
+                if let Some(projectile) =
+                    self.npc_ranged_projectile(entity, coord, target, attack, damage)
+                {
+                    return self.npc_fire(entity, attack, projectile, config);
+                }
+            }
+        }
+        let drifting = self.world.movement_mode(coord, self.gravity) == MovementMode::Drifting;
+        if drifting && self.world.drift(entity) {
+            let outcome = match self.world.spatial_table.coord_of(entity) {
+                Some(coord) => EventOutcome::Moved(coord),
+                None => EventOutcome::Done,
+            };
+            self.record_event(entity, EventAction::Drift, outcome);
+            return None;
+        }
+        if alertness == Alertness::Unaware {
+            return None;
+        }
+        if flee.is_none() && self.world.components.fleeing.contains(entity) {
+            // out of the player's reach, so there's no need to run any further
+            self.record_event(entity, EventAction::Approach, EventOutcome::NoPath);
+            return None;
+        }
+        // suspicious npcs only know where the noise came from, not where the player is
+        let approach = if hostile {
+            flee.or(kite)
+                .or_else(|| {
+                    rival.and_then(|(_, rival_coord)| self.step_towards(coord, rival_coord))
+                })
+                .or_else(|| {
+                    if self.is_searching(entity) {
+                        // the npc has lost sight of the player, so follows their trail if it can
+                        // smell it, and otherwise goes to where it last saw them
+                        self.scent_step(npc_type, coord)
+                            .or_else(|| self.search_step(entity, coord))
+                    } else {
+                        self.npc_cover_direction(coord, player, player_coord)
+                            .or_else(|| self.flank_step(entity, coord))
+                            .or_else(|| self.world.distance_map.direction_to_best_neighbour(coord))
+                            .or_else(|| self.scent_step(npc_type, coord))
+                    }
+                })
+        } else {
+            None
+        };
+        if approach.is_some() {
+            // the npc has found the player, so it no longer cares about what it heard
+            self.world.components.investigating.remove(entity);
+        }
+        let direction = match approach.or_else(|| self.investigation_step(entity, coord)) {
+            Some(direction) => direction,
+            None => {
+                // there's nothing left to investigate once the npc can't get any closer
+                self.world.components.investigating.remove(entity);
+                self.record_event(entity, EventAction::Approach, EventOutcome::NoPath);
+                return None;
+            }
+        };
+        let destination = coord + direction.coord();
+        let mut outcome = EventOutcome::Blocked;
+        let mut moved = false;
+        if let Some(&Layers {
+            character: None, ..
+        }) = self.world.spatial_table.layers_at(destination)
+        {
+            if self
+                .world
+                .spatial_table
+                .update_coord(entity, destination)
+                .is_ok()
+            {
+                outcome = EventOutcome::Moved(destination);
+                moved = true;
+                if drifting {
+                    self.world.components.momentum.insert(entity, direction);
+                }
+            }
+        }
+        self.record_event(entity, EventAction::Approach, outcome);
+        if moved {
+            self.enter_difficult_terrain(entity);
+            return self.trigger_trap(entity, config);
+        }
         None
     }
 
-    fn npc_turn(&mut self) -> Option<GameControlFlow> {
+    fn npc_turn(&mut self, config: &Config) -> Option<GameControlFlow> {
         {
             struct C<'a> {
                 components: &'a Components,
@@ -282,22 +2070,57 @@ impl Game {
                                 return false;
                             }
                         }
-                        if let Layers { floor: None, .. } = layers {
-                            return false;
+                        match layers.floor {
+                            None => return false,
+                            Some(floor) => {
+                                if self.components.gap.contains(floor) {
+                                    return false;
+                                }
+                            }
                         }
                     }
                     true
                 }
             }
             self.ai_ctx.distance_map.clear();
-            self.ai_ctx.distance_map.add(self.player_coord());
+            for coord in self.player_coords() {
+                self.ai_ctx.distance_map.add(coord);
+            }
             let c = C {
                 components: &self.world.components,
                 spatial_table: &self.world.spatial_table,
             };
-            self.ai_ctx
-                .distance_map
-                .populate_approach(&c, 12, &mut self.world.distance_map);
+            // an alarm tells every npc on the level where the player is
+            let approach_distance = if self.is_alarm_sounding() {
+                let size = self.world.size();
+                size.width() + size.height()
+            } else {
+                NPC_APPROACH_DISTANCE
+            };
+            self.ai_ctx.distance_map.populate_approach(
+                &c,
+                approach_distance,
+                &mut self.world.distance_map,
+            );
+        }
+        self.update_flee_map();
+        self.assign_flanks();
+        let npc_entities = self
+            .world
+            .components
+            .npc_type
+            .iter()
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for entity in npc_entities {
+            for _ in 0..self.gain_energy(entity) {
+                if self.lose_turn_to_stun(entity) || self.lose_turn_to_difficult_terrain(entity) {
+                    continue;
+                }
+                if let Some(game_control_flow) = self.npc_act(entity, config) {
+                    return Some(game_control_flow);
+                }
+            }
         }
         None
     }
@@ -305,39 +2128,175 @@ impl Game {
     #[must_use]
     pub(crate) fn handle_tick(
         &mut self,
-        _since_last_tick: Duration,
+        since_last_tick: Duration,
         _config: &Config,
     ) -> Option<GameControlFlow> {
+        self.tick_run_timer(since_last_tick);
         None
     }
 
     fn pass_time(&mut self) {}
 
-    #[must_use]
-    pub(crate) fn handle_input(
+    /// Removes state that isn't meant to survive saving and loading the game
+    pub(crate) fn prepare_for_save(&mut self) {
+        self.world.remove_transient_decals();
+    }
+
+    fn player_act(
         &mut self,
         input: Input,
-        _config: &Config,
+        config: &Config,
     ) -> Result<Option<GameControlFlow>, ActionError> {
+        let prompt = self.prompt.take();
+        let travel = self.travel.take();
+        if self.lose_turn_to_stun(self.player_entity) {
+            self.messages.system(Message::new("message.player_stunned"));
+            return Ok(None);
+        }
+        if self.lose_turn_to_difficult_terrain(self.player_entity) {
+            let key = if self.is_rubble_at(self.player_coord()) {
+                "message.rubble_climbed"
+            } else {
+                "message.liquid_waded"
+            };
+            self.messages.system(Message::new(key));
+            return Ok(None);
+        }
         let game_control_flow = match input {
-            Input::Walk(direction) => self.player_walk(direction),
-            Input::Wait => {
-                self.pass_time();
-                None
+            Input::Walk(direction) => self.player_walk(direction, config),
+            Input::Wait => match self.player_drift_direction() {
+                Some(direction) => self.player_walk(direction, config),
+                None => {
+                    self.pass_time();
+                    None
+                }
+            },
+            Input::Search => self.player_search()?,
+            Input::DestroyCorpse => self.player_destroy_corpse()?,
+            Input::Drag => self.player_drag()?,
+            Input::Brace => self.player_brace()?,
+            Input::ThrowGrenade(target) => self.player_throw_grenade(target, config)?,
+            Input::FireWeapon(target) => self.player_fire_weapon(target, config)?,
+            Input::Interact(coord, interaction) => {
+                self.player_interact(coord, interaction, config)?
+            }
+            Input::Choose(choice) => self.player_choose(prompt, choice, config)?,
+            Input::AutoExplore => self.player_autoexplore(config)?,
+            Input::TravelTo(destination) => self.player_travel(travel, destination, config)?,
+            Input::PickUp => self.player_pick_up()?,
+            Input::Drop(slot) => self.player_drop(slot)?,
+            Input::Throw { slot, target } => self.player_throw(slot, target, config)?,
+            Input::SwapWeapons => self.player_swap_weapons()?,
+        };
+        Ok(game_control_flow)
+    }
+
+    /// Resolves a full turn: the player's action followed by the npcs and the rest of the world.
+    /// This doesn't depend on the passage of real time, so a game driven only by this method
+    /// (e.g. by tests or bots) is deterministic given its rng seed.
+    #[must_use]
+    pub fn step_turn(
+        &mut self,
+        input: Input,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        let undo = self.undo_snapshot(config);
+        let turn = self.turn;
+        self.reseed_ironman_rng();
+        let game_control_flow = match self.resolve_turn(input, config)? {
+            Some(GameControlFlow::GameOver(reason)) if self.try_continue(reason) => None,
+            game_control_flow => game_control_flow,
+        };
+        // actions which fail don't take a turn, so the previous turn can still be undone
+        self.undo = undo;
+        self.num_committed_inputs += 1;
+        self.record_replay_input(turn, input);
+        self.record_ghost_step();
+        Ok(game_control_flow)
+    }
+
+    /// Takes a turn with each input in order, for driving the game from tests and bots without a
+    /// frontend. Arriving on a new level doesn't interrupt the inputs, but anything else which
+    /// needs the app's attention (e.g. the game ending) stops at that input, as does an action
+    /// failing. Nothing in the game is animated, so no time needs to pass between turns.
+    pub fn run_inputs(
+        &mut self,
+        inputs: &[Input],
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        for &input in inputs {
+            match self.step_turn(input, config)? {
+                None | Some(GameControlFlow::LevelChange(_)) => (),
+                game_control_flow => return Ok(game_control_flow),
             }
+        }
+        Ok(None)
+    }
+
+    fn resolve_turn(
+        &mut self,
+        input: Input,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        let result = self.player_act(input, config);
+        let outcome = match &result {
+            Ok(None) => EventOutcome::Done,
+            Ok(Some(_)) => EventOutcome::ControlFlow,
+            Err(error) => EventOutcome::Failed(*error),
         };
+        self.record_event(self.player_entity, EventAction::Input(input), outcome);
+        let game_control_flow = result?;
+        if game_control_flow.is_some() {
+            return Ok(game_control_flow);
+        }
+        // the rest of the world waits until every hot-seat player has acted
+        if self.pass_to_next_player() {
+            self.update_visibility();
+            return Ok(None);
+        }
+        self.propagate_noise();
+        self.tick_scent();
+        self.update_alertness();
+        self.update_last_seen();
+        let game_control_flow = self.npc_turn(config);
         if game_control_flow.is_some() {
             return Ok(game_control_flow);
         }
-        let game_control_flow = self.npc_turn();
+        self.tick_fire(config);
+        self.tick_gas();
+        self.tick_shields();
+        let game_control_flow = self.tick_status_effects(config);
         if game_control_flow.is_some() {
             return Ok(game_control_flow);
         }
+        let game_control_flow = self.tick_timers(config);
+        if game_control_flow.is_some() {
+            return Ok(game_control_flow);
+        }
+        // station events would get in the way of learning the controls
+        if self.tutorial.is_none() {
+            self.tick_station_events(config);
+        }
+        let game_control_flow = self.tick_air();
+        if game_control_flow.is_some() {
+            return Ok(game_control_flow);
+        }
+        let game_control_flow = self.tick_electrified_liquid(config);
+        if game_control_flow.is_some() {
+            return Ok(game_control_flow);
+        }
+        let game_control_flow = self.tick_radiation();
+        if game_control_flow.is_some() {
+            return Ok(game_control_flow);
+        }
+        self.tick_respawn();
+        self.tick_doors();
+        self.detect_traps();
+        self.world.age_decals();
+        self.plan_npc_actions();
+        self.turn += 1;
+        self.messages.set_turn(self.turn);
         self.update_visibility();
         Ok(None)
     }
-
-    pub(crate) fn handle_choice(&mut self, _choice: MenuChoice) -> Option<GameControlFlow> {
-        None
-    }
 }
@@ -0,0 +1,49 @@
+use crate::{world::World, Coord, PowerState};
+use rgb_int::Rgb24;
+use visible_area_detection::{vision_distance::Circle, Light, Rational};
+
+/// Light reaching every visible cell, before any lights nearby are added
+const AMBIENT_LIGHT: u8 = 159;
+/// The emergency lighting left on during a power outage
+const POWER_OUTAGE_AMBIENT_LIGHT: u8 = 79;
+/// Lights stop reaching cells beyond this distance
+const LIGHT_DISTANCE_SQUARED: u32 = 64;
+/// How quickly light fades with distance
+const LIGHT_DIMINISH: Rational = Rational {
+    numerator: 1,
+    denominator: 8,
+};
+
+pub(crate) const REACTOR_LIGHT: Rgb24 = Rgb24::new(0, 255, 127);
+pub(crate) const DAMAGED_REACTOR_LIGHT: Rgb24 = Rgb24::new(191, 63, 0);
+pub(crate) const TERMINAL_LIGHT: Rgb24 = Rgb24::new(191, 191, 63);
+pub(crate) const ESCAPE_POD_LIGHT: Rgb24 = Rgb24::new(63, 127, 255);
+pub(crate) const STAIRS_LIGHT: Rgb24 = Rgb24::new(127, 127, 127);
+
+/// Light given off by a feature, fading with distance from it
+pub(crate) fn glow(colour: Rgb24) -> Light<Circle> {
+    Light {
+        colour,
+        vision_distance: Circle::new_squared(LIGHT_DISTANCE_SQUARED),
+        diminish: LIGHT_DIMINISH,
+    }
+}
+
+/// Light reaching every visible cell, which the lights on the level add to
+pub(crate) fn ambient_light(power_state: PowerState) -> Rgb24 {
+    if power_state == PowerState::Off {
+        Rgb24::new_grey(POWER_OUTAGE_AMBIENT_LIGHT)
+    } else {
+        Rgb24::new_grey(AMBIENT_LIGHT)
+    }
+}
+
+impl World {
+    pub fn all_lights_by_coord(&self) -> impl '_ + Iterator<Item = (Coord, &Light<Circle>)> {
+        self.components.light.iter().filter_map(|(entity, light)| {
+            self.spatial_table
+                .coord_of(entity)
+                .map(|coord| (coord, light))
+        })
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{
+    world::{
+        data::{Atmosphere, KeyColour},
+        spatial::Layers,
+        World,
+    },
+    CardinalDirection, Coord, Entity, Game, Message,
+};
+use rand::{seq::SliceRandom, Rng};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Cells a character can walk through once any doors in the way are opened
+fn is_walkable(world: &World, coord: Coord) -> bool {
+    match world.spatial_table.layers_at(coord) {
+        Some(&Layers {
+            floor: Some(floor),
+            feature,
+            ..
+        }) => {
+            !world.components.gap.contains(floor)
+                && feature.is_none_or(|feature| {
+                    !world.components.solid.contains(feature)
+                        || world.components.door_state.contains(feature)
+                })
+        }
+        _ => false,
+    }
+}
+
+fn is_door(world: &World, coord: Coord) -> bool {
+    world
+        .spatial_table
+        .layers_at(coord)
+        .and_then(|layers| layers.feature)
+        .is_some_and(|feature| world.components.door_state.contains(feature))
+}
+
+/// Splits the walkable cells of a level into zones, which are the areas between doors
+fn zones(world: &World) -> Vec<HashSet<Coord>> {
+    let mut zoned = HashSet::new();
+    let mut zones = Vec::new();
+    for start in world.size().coord_iter_row_major() {
+        if zoned.contains(&start) || !is_walkable(world, start) || is_door(world, start) {
+            continue;
+        }
+        let mut zone = HashSet::new();
+        zone.insert(start);
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(coord) = queue.pop_front() {
+            for direction in CardinalDirection::all() {
+                let neighbour = coord + direction.coord();
+                if is_walkable(world, neighbour)
+                    && !is_door(world, neighbour)
+                    && zone.insert(neighbour)
+                {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        zoned.extend(zone.iter().copied());
+        zones.push(zone);
+    }
+    zones
+}
+
+/// Cells the player could walk to from `start` without passing through a locked door they
+/// don't have the keycard for
+fn reachable(
+    world: &World,
+    start: Coord,
+    locks: &HashMap<Coord, KeyColour>,
+    held: &HashSet<KeyColour>,
+) -> HashSet<Coord> {
+    let mut reachable = HashSet::new();
+    reachable.insert(start);
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+    while let Some(coord) = queue.pop_front() {
+        for direction in CardinalDirection::all() {
+            let neighbour = coord + direction.coord();
+            let unlocked = locks
+                .get(&neighbour)
+                .is_none_or(|colour| held.contains(colour));
+            if unlocked && is_walkable(world, neighbour) && reachable.insert(neighbour) {
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    reachable
+}
+
+/// Cells the player can eventually reach from `start`, picking up each keycard as soon as they
+/// can get to it
+fn solve(
+    world: &World,
+    start: Coord,
+    locks: &HashMap<Coord, KeyColour>,
+    keycards: &HashMap<Coord, KeyColour>,
+) -> HashSet<Coord> {
+    let mut held = HashSet::new();
+    loop {
+        let reachable = reachable(world, start, locks, &held);
+        let count = held.len();
+        held.extend(
+            keycards
+                .iter()
+                .filter(|(coord, _)| reachable.contains(coord))
+                .map(|(_, &colour)| colour),
+        );
+        if held.len() == count {
+            return reachable;
+        }
+    }
+}
+
+/// Seals off some of the level's zones behind locked doors, one colour per zone. Each
+/// zone's keycard is left somewhere the player can reach without going through that zone's
+/// doors. Locks are only kept if the player can still reach every part of the level.
+pub(crate) fn place_locks<R: Rng>(world: &mut World, player_coord: Coord, rng: &mut R) {
+    let full = solve(world, player_coord, &HashMap::new(), &HashMap::new()).len();
+    let mut zones = zones(world);
+    zones.retain(|zone| !zone.contains(&player_coord));
+    zones.shuffle(rng);
+    let mut locks = HashMap::new();
+    let mut keycards = HashMap::new();
+    let mut colours = KeyColour::ALL.iter();
+    let mut colour = match colours.next() {
+        Some(&colour) => colour,
+        None => return,
+    };
+    for zone in zones {
+        let doors = zone
+            .iter()
+            .flat_map(|&coord| {
+                CardinalDirection::all().map(move |direction| coord + direction.coord())
+            })
+            .filter(|&coord| is_door(world, coord) && !locks.contains_key(&coord))
+            .collect::<HashSet<_>>();
+        if doors.is_empty() {
+            continue;
+        }
+        let mut next_locks = locks.clone();
+        next_locks.extend(doors.iter().map(|&coord| (coord, colour)));
+        let before = solve(world, player_coord, &next_locks, &keycards);
+        if before.len() == full {
+            // the zone can be reached some other way, so locking it would achieve nothing
+            continue;
+        }
+        let candidates = before
+            .iter()
+            .copied()
+            .filter(|&coord| {
+                coord != player_coord
+                    && !keycards.contains_key(&coord)
+                    && world.can_spawn_item_at(coord)
+                    && world.atmosphere_at(coord) == Atmosphere::Pressurised
+            })
+            .collect::<Vec<_>>();
+        let keycard_coord = match candidates.choose(rng) {
+            Some(&coord) => coord,
+            None => continue,
+        };
+        let mut next_keycards = keycards.clone();
+        next_keycards.insert(keycard_coord, colour);
+        if solve(world, player_coord, &next_locks, &next_keycards).len() != full {
+            continue;
+        }
+        locks = next_locks;
+        keycards = next_keycards;
+        colour = match colours.next() {
+            Some(&colour) => colour,
+            None => break,
+        };
+    }
+    for (coord, colour) in locks {
+        if let Some(door) = world
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.feature)
+        {
+            world.lock_door(door, colour);
+        }
+    }
+    for (coord, colour) in keycards {
+        world.spawn_keycard(coord, colour);
+    }
+}
+
+impl Game {
+    /// Picks up any keycard the player is standing on
+    pub(crate) fn pick_up_keycard(&mut self) {
+        let item_entity = match self.world.spatial_table.layers_at(self.player_coord()) {
+            Some(&Layers {
+                item: Some(item_entity),
+                ..
+            }) => item_entity,
+            _ => return,
+        };
+        let colour = match self.world.components.keycard.get(item_entity) {
+            Some(&colour) => colour,
+            None => return,
+        };
+        match self.world.components.keycards.get_mut(self.player_entity) {
+            Some(keycards) => keycards.push(colour),
+            None => {
+                self.world
+                    .components
+                    .keycards
+                    .insert(self.player_entity, vec![colour]);
+            }
+        }
+        self.world.remove_entity(item_entity);
+        self.messages.items(
+            Some(item_entity),
+            Message::with_args("message.keycard_picked_up", &[&colour.name()]),
+        );
+    }
+
+    /// Opens a locked door for good if the player has its keycard
+    pub(crate) fn try_unlock_door(&mut self, entity: Entity, colour: KeyColour) {
+        let has_keycard = self
+            .world
+            .components
+            .keycards
+            .get(self.player_entity)
+            .is_some_and(|keycards| keycards.contains(&colour));
+        if !has_keycard {
+            self.messages
+                .system(Message::with_args("message.door_locked", &[&colour.name()]));
+            return;
+        }
+        self.world.components.locked.remove(entity);
+        self.messages.system(Message::with_args(
+            "message.door_unlocked",
+            &[&colour.name()],
+        ));
+        self.open_door(entity);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Size;
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    /// Three rooms in a row, each behind a door from the one before it
+    const ROOMS: &[&str] = &[
+        "###########",
+        "#...+...+.#",
+        "#.@.#...#.#",
+        "#...#...#.#",
+        "###########",
+    ];
+
+    fn world_from_rows(rows: &[&str]) -> (World, Coord) {
+        let size = Size::new(rows[0].len() as u32, rows.len() as u32);
+        let mut world = World::new(size);
+        let mut player_coord = Coord::new(0, 0);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                let coord = Coord::new(x as i32, y as i32);
+                world.spawn_floor(coord);
+                match ch {
+                    '#' => {
+                        world.spawn_wall(coord);
+                    }
+                    '+' => {
+                        world.spawn_door(coord);
+                    }
+                    '@' => player_coord = coord,
+                    _ => (),
+                }
+            }
+        }
+        (world, player_coord)
+    }
+
+    #[test]
+    fn keycards_are_reachable_before_their_doors() {
+        for seed in 0..8 {
+            let (mut world, player_coord) = world_from_rows(ROOMS);
+            let full = solve(&world, player_coord, &HashMap::new(), &HashMap::new()).len();
+            place_locks(
+                &mut world,
+                player_coord,
+                &mut Isaac64Rng::seed_from_u64(seed),
+            );
+            let locks = world
+                .components
+                .locked
+                .iter()
+                .filter_map(|(entity, &colour)| {
+                    Some((world.spatial_table.coord_of(entity)?, colour))
+                })
+                .collect::<HashMap<_, _>>();
+            let keycards = world
+                .components
+                .keycard
+                .iter()
+                .filter_map(|(entity, &colour)| {
+                    Some((world.spatial_table.coord_of(entity)?, colour))
+                })
+                .collect::<HashMap<_, _>>();
+            assert!(!locks.is_empty());
+            assert_eq!(solve(&world, player_coord, &locks, &keycards).len(), full);
+            for (&keycard_coord, &colour) in &keycards {
+                // every other door is open, so only doors of the keycard's own colour are shut
+                let held = KeyColour::ALL
+                    .iter()
+                    .copied()
+                    .filter(|&other| other != colour)
+                    .collect::<HashSet<_>>();
+                let reachable = reachable(&world, player_coord, &locks, &held);
+                assert!(reachable.contains(&keycard_coord));
+            }
+        }
+    }
+}
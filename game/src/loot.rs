@@ -0,0 +1,95 @@
+use crate::{
+    curse::CURSED_WEAPON_CHANCE,
+    world::data::{Affix, NpcType, Rarity, Weapon, WeaponKind, MAX_AFFIXES},
+};
+use rand::Rng;
+
+/// Chance of each rarity, out of the total, on the first level. Rarer items become more common
+/// deeper into the station.
+const RARITY_WEIGHTS: [u32; 4] = [60, 25, 12, 3];
+/// How much the weight of each rarity changes with each level after the first
+const RARITY_WEIGHTS_PER_LEVEL: [i32; 4] = [-10, 5, 3, 2];
+
+fn roll_rarity<R: Rng>(level: u32, rng: &mut R) -> Rarity {
+    let levels_deep = level.saturating_sub(1) as i32;
+    let weights = RARITY_WEIGHTS
+        .iter()
+        .zip(RARITY_WEIGHTS_PER_LEVEL)
+        .map(|(&weight, per_level)| (weight as i32 + per_level * levels_deep).max(0) as u32)
+        .collect::<Vec<_>>();
+    let mut roll = rng.gen_range(0..weights.iter().sum::<u32>());
+    for (&rarity, &weight) in Rarity::ALL.iter().zip(weights.iter()) {
+        if roll < weight {
+            return rarity;
+        }
+        roll -= weight;
+    }
+    Rarity::Common
+}
+
+fn roll_affix<R: Rng>(rng: &mut R) -> Affix {
+    match rng.gen_range(0..5) {
+        0 => Affix::Damage(rng.gen_range(1..=2)),
+        1 => Affix::Ammo(rng.gen_range(2..=4)),
+        2 => Affix::Penetration(1),
+        3 => Affix::Knockback,
+        _ => Affix::Vampiric,
+    }
+}
+
+/// A weapon as found lying around the station on a given level, with a random rarity and
+/// affixes to match. Some weapons are cursed.
+pub(crate) fn roll_weapon<R: Rng>(kind: WeaponKind, level: u32, rng: &mut R) -> Weapon {
+    let mut weapon = kind.weapon();
+    weapon.rarity = roll_rarity(level, rng);
+    let num_affixes = weapon.rarity.num_affixes().min(MAX_AFFIXES);
+    let mut affixes = Vec::new();
+    while affixes.len() < num_affixes {
+        let affix = roll_affix(rng);
+        // each kind of affix appears at most once per weapon
+        if !affixes
+            .iter()
+            .any(|&existing| std::mem::discriminant(&existing) == std::mem::discriminant(&affix))
+        {
+            affixes.push(affix);
+        }
+    }
+    for affix in affixes {
+        weapon.add_affix(affix);
+    }
+    weapon.cursed = rng.gen_ratio(1, CURSED_WEAPON_CHANCE);
+    weapon
+}
+
+/// Ammo an npc may drop when it dies
+struct AmmoDrop {
+    npc_type: NpcType,
+    /// Percentage chance of dropping anything at all
+    chance: u32,
+    max_rounds: u32,
+}
+
+const AMMO_DROPS: &[AmmoDrop] = &[
+    AmmoDrop {
+        npc_type: NpcType::Zombie,
+        chance: 15,
+        max_rounds: 2,
+    },
+    AmmoDrop {
+        npc_type: NpcType::Skeleton,
+        chance: 30,
+        max_rounds: 3,
+    },
+];
+
+/// Rolls on the loot table for a dying npc, returning the number of rounds it drops, if any
+pub(crate) fn roll_ammo_drop<R: Rng>(npc_type: NpcType, rng: &mut R) -> Option<u32> {
+    let ammo_drop = AMMO_DROPS
+        .iter()
+        .find(|ammo_drop| ammo_drop.npc_type == npc_type)?;
+    if rng.gen_range(0..100) < ammo_drop.chance {
+        Some(rng.gen_range(1..=ammo_drop.max_rounds))
+    } else {
+        None
+    }
+}
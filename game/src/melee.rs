@@ -0,0 +1,74 @@
+use crate::{
+    world::data::{Weapon, WeaponKind},
+    CardinalDirection, Config, Coord, ExternalEvent, Game,
+};
+use serde::{Deserialize, Serialize};
+
+/// The cells a melee attack reaches
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackShape {
+    /// Just the cell being attacked
+    Single,
+    /// The cell being attacked and the cells on either side of it
+    Cleave,
+    /// The cell being attacked and the cell behind it
+    Skewer,
+}
+
+impl AttackShape {
+    /// The cells hit by an attack from `coord` in `direction`, nearest first
+    pub fn cells(self, coord: Coord, direction: CardinalDirection) -> Vec<Coord> {
+        let target = coord + direction.coord();
+        match self {
+            Self::Single => vec![target],
+            Self::Cleave => vec![
+                target,
+                target + direction.left90().coord(),
+                target + direction.right90().coord(),
+            ],
+            Self::Skewer => vec![target, target + direction.coord()],
+        }
+    }
+}
+
+impl Weapon {
+    /// A bayonet reaches through to a second enemy. Heavy weapons are swung in an arc.
+    pub fn attack_shape(&self) -> AttackShape {
+        if self.attachments.bayonet {
+            return AttackShape::Skewer;
+        }
+        match self.kind {
+            WeaponKind::RocketLauncher | WeaponKind::ScatterGun => AttackShape::Cleave,
+            WeaponKind::Railgun | WeaponKind::RicochetPistol | WeaponKind::MissileLauncher => {
+                AttackShape::Single
+            }
+        }
+    }
+}
+
+impl Game {
+    pub fn player_attack_shape(&self) -> AttackShape {
+        self.player_weapon()
+            .map_or(AttackShape::Single, |weapon| weapon.attack_shape())
+    }
+
+    /// Attacks every npc in the cells reached by the player's attack shape
+    pub(crate) fn player_melee(&mut self, direction: CardinalDirection, config: &Config) {
+        let cells = self
+            .player_attack_shape()
+            .cells(self.player_coord(), direction);
+        let targets = cells
+            .iter()
+            .filter_map(|&coord| self.world.spatial_table.layers_at(coord)?.character)
+            .filter(|&entity| !self.is_player(entity))
+            .collect::<Vec<_>>();
+        self.external_events
+            .push(ExternalEvent::MeleeAttack { cells });
+        for entity in targets {
+            // an earlier blow may have knocked this target away or killed it
+            if self.world.components.npc_type.contains(entity) {
+                self.player_attack(entity, direction, config);
+            }
+        }
+    }
+}
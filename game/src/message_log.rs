@@ -0,0 +1,90 @@
+use crate::{Entity, Game};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageCategory {
+    Combat,
+    Items,
+    System,
+}
+
+/// A message as a locale key and the values to fill in its "{}"s, in order. The app looks up
+/// each value in the locale too, so values which are keys get translated and anything else is
+/// shown as it is.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub key: String,
+    pub args: Vec<String>,
+}
+
+impl Message {
+    pub(crate) fn new(key: &str) -> Self {
+        Self::with_args(key, &[])
+    }
+
+    pub(crate) fn with_args(key: &str, args: &[&dyn Display]) -> Self {
+        Self {
+            key: key.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LogEntry {
+    pub message: Message,
+    pub category: MessageCategory,
+    /// The turn on which the message was logged
+    pub turn: u64,
+    /// The entity responsible for the message, if any
+    pub source: Option<Entity>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct MessageLog {
+    entries: Vec<LogEntry>,
+    turn: u64,
+}
+
+impl MessageLog {
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+
+    pub(crate) fn set_turn(&mut self, turn: u64) {
+        self.turn = turn;
+    }
+
+    fn push(&mut self, category: MessageCategory, source: Option<Entity>, message: Message) {
+        self.entries.push(LogEntry {
+            message,
+            category,
+            turn: self.turn,
+            source,
+        });
+    }
+
+    pub(crate) fn system(&mut self, message: Message) {
+        self.push(MessageCategory::System, None, message);
+    }
+
+    pub(crate) fn combat(&mut self, source: Option<Entity>, message: Message) {
+        self.push(MessageCategory::Combat, source, message);
+    }
+
+    pub(crate) fn items(&mut self, source: Option<Entity>, message: Message) {
+        self.push(MessageCategory::Items, source, message);
+    }
+}
+
+impl Game {
+    /// Returns copies of the messages logged since the last call. The whole log stays available
+    /// from `messages`. Messages returned here aren't reported again as external events.
+    pub fn take_new_messages(&mut self) -> Vec<LogEntry> {
+        let entries = self.messages.entries();
+        let new_messages = entries[self.num_reported_messages.min(entries.len())..].to_vec();
+        self.num_reported_messages = entries.len();
+        new_messages
+    }
+}
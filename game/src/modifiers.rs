@@ -0,0 +1,88 @@
+use crate::{world::data::EntityData, Game};
+use serde::{Deserialize, Serialize};
+
+/// Optional rules chosen when starting a new game to make it harder
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct RunModifiers {
+    /// The player can't pick up weapons
+    pub no_ranged_weapons: bool,
+    /// The player can hold their breath for half as long
+    pub half_oxygen: bool,
+    /// Twice as many npcs are spawned on each level
+    pub double_enemies: bool,
+    /// Destroying npcs scores nothing but each level completed scores double
+    pub pacifist: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunModifier {
+    NoRangedWeapons,
+    HalfOxygen,
+    DoubleEnemies,
+    Pacifist,
+}
+
+impl RunModifier {
+    pub const ALL: &'static [Self] = &[
+        Self::NoRangedWeapons,
+        Self::HalfOxygen,
+        Self::DoubleEnemies,
+        Self::Pacifist,
+    ];
+
+    /// Key of the modifier's name in the app's locale
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::NoRangedWeapons => "modifier.no_ranged_weapons",
+            Self::HalfOxygen => "modifier.half_oxygen",
+            Self::DoubleEnemies => "modifier.double_enemies",
+            Self::Pacifist => "modifier.pacifist",
+        }
+    }
+}
+
+impl RunModifiers {
+    pub fn contains(&self, modifier: RunModifier) -> bool {
+        match modifier {
+            RunModifier::NoRangedWeapons => self.no_ranged_weapons,
+            RunModifier::HalfOxygen => self.half_oxygen,
+            RunModifier::DoubleEnemies => self.double_enemies,
+            RunModifier::Pacifist => self.pacifist,
+        }
+    }
+
+    pub fn toggle(&mut self, modifier: RunModifier) {
+        let flag = match modifier {
+            RunModifier::NoRangedWeapons => &mut self.no_ranged_weapons,
+            RunModifier::HalfOxygen => &mut self.half_oxygen,
+            RunModifier::DoubleEnemies => &mut self.double_enemies,
+            RunModifier::Pacifist => &mut self.pacifist,
+        };
+        *flag = !*flag;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = RunModifier> + '_ {
+        RunModifier::ALL
+            .iter()
+            .copied()
+            .filter(|&modifier| self.contains(modifier))
+    }
+}
+
+impl Game {
+    pub fn modifiers(&self) -> RunModifiers {
+        self.modifiers
+    }
+}
+
+impl RunModifiers {
+    /// Changes to a newly-created player which last for the whole run
+    pub(crate) fn apply_to_player(&self, player_data: &mut EntityData) {
+        if self.half_oxygen {
+            if let Some(oxygen) = player_data.oxygen.as_mut() {
+                oxygen.set_max(oxygen.max() / 2);
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use rand::{seq::SliceRandom, Rng};
+
+const RANKS: &[&str] = &["Sgt.", "Cpl.", "Lt.", "Capt.", "Dr.", "Chief", "Ensign"];
+const SURNAMES: &[&str] = &[
+    "Varga",
+    "Okafor",
+    "Lindqvist",
+    "Moreau",
+    "Tanaka",
+    "Reyes",
+    "Kowalski",
+    "Achterberg",
+    "Nakamura",
+    "Ferreira",
+    "Dubois",
+    "Halloran",
+    "Petrov",
+    "Adeyemi",
+    "Castellanos",
+];
+const DECK_AREAS: &[&str] = &[
+    "Hydroponics",
+    "Engineering",
+    "Crew Quarters",
+    "Cargo Hold",
+    "Medical Bay",
+    "Observation",
+    "Life Support",
+    "Armoury",
+    "Reactor Control",
+    "Mess Hall",
+];
+const MAX_DECK_NUMBER: u32 = 12;
+
+/// A name for an important npc, e.g. "Sgt. Varga"
+pub fn npc_name<R: Rng>(rng: &mut R) -> String {
+    format!(
+        "{} {}",
+        RANKS.choose(rng).unwrap(),
+        SURNAMES.choose(rng).unwrap()
+    )
+}
+
+/// A name for a level, e.g. "Deck 7: Hydroponics"
+pub fn level_name<R: Rng>(rng: &mut R) -> String {
+    format!(
+        "Deck {}: {}",
+        rng.gen_range(1..=MAX_DECK_NUMBER),
+        DECK_AREAS.choose(rng).unwrap()
+    )
+}
+
+/// Makes a description suitable for the start of a sentence
+pub fn capitalise(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
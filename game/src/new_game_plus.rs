@@ -0,0 +1,34 @@
+use crate::{world::data::EntityData, Config, Game};
+use rand::Rng;
+
+impl Game {
+    /// How many times the player's character has won before this run
+    pub fn new_game_plus(&self) -> u32 {
+        self.new_game_plus
+    }
+
+    /// The player's character as it will start a new game+. Weapons, attachments, grenades and
+    /// protective gear are kept, but the player is healed and their radiation exposure cleared.
+    fn new_game_plus_player_data(&self) -> EntityData {
+        let mut player_data = self.world.components.clone_entity_data(self.player_entity);
+        for meter in [player_data.hit_points.as_mut(), player_data.oxygen.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            meter.set_current(meter.max());
+        }
+        player_data.rads = Some(Default::default());
+        player_data
+    }
+
+    /// A fresh run continuing with the character from a run the player has just won, on harder
+    /// and remixed levels
+    pub fn new_plus<R: Rng>(config: &Config, previous: &Game, base_rng: &mut R) -> Self {
+        Self::new_run(
+            config,
+            previous.new_game_plus_player_data(),
+            previous.new_game_plus + 1,
+            base_rng,
+        )
+    }
+}
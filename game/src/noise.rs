@@ -0,0 +1,105 @@
+use crate::{CardinalDirection, Coord, Entity, Game, SoundEffect};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+/// Number of steps around obstacles that the player's footsteps can be heard from
+pub(crate) const FOOTSTEP_LOUDNESS: u32 = 2;
+pub(crate) const GUNFIRE_LOUDNESS: u32 = 15;
+
+impl SoundEffect {
+    /// Number of steps around obstacles that npcs can hear the sound from
+    fn loudness(self) -> u32 {
+        match self {
+            Self::Explosion => 20,
+            Self::Breach => 12,
+            Self::Hit => 6,
+            Self::Door => 5,
+        }
+    }
+}
+
+/// A sound npcs can hear, waiting to spread out from where it was made
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) struct Noise {
+    coord: Coord,
+    loudness: u32,
+}
+
+impl Game {
+    pub(crate) fn make_noise(&mut self, coord: Coord, loudness: u32) {
+        self.noises.push(Noise { coord, loudness });
+    }
+
+    /// Sound effects the app plays are also heard by npcs
+    pub(crate) fn make_sound_noise(&mut self, effect: SoundEffect, coord: Coord) {
+        self.make_noise(coord, effect.loudness());
+    }
+
+    /// The cells a noise reaches. Noise spreads around walls and closed doors rather than
+    /// through them, so it carries further along corridors than between rooms.
+    fn noise_area(&self, noise: Noise) -> Vec<Coord> {
+        let size = self.world.size();
+        let mut area = vec![noise.coord];
+        let mut seen = HashSet::new();
+        seen.insert(noise.coord);
+        let mut queue = VecDeque::new();
+        queue.push_back((noise.coord, 0));
+        while let Some((coord, distance)) = queue.pop_front() {
+            if distance >= noise.loudness {
+                continue;
+            }
+            for direction in CardinalDirection::all() {
+                let neighbour = coord + direction.coord();
+                if !neighbour.is_valid(size)
+                    || self.is_solid_at(neighbour)
+                    || !seen.insert(neighbour)
+                {
+                    continue;
+                }
+                area.push(neighbour);
+                queue.push_back((neighbour, distance + 1));
+            }
+        }
+        area
+    }
+
+    /// Spreads the noises made since the last turn. Npcs who hear one go to investigate where
+    /// it came from, unless they hear something else first.
+    pub(crate) fn propagate_noise(&mut self) {
+        for noise in std::mem::take(&mut self.noises) {
+            let listeners = self
+                .noise_area(noise)
+                .into_iter()
+                .filter_map(|coord| self.world.spatial_table.layers_at(coord)?.character)
+                .filter(|&entity| self.world.components.npc_type.contains(entity))
+                .collect::<Vec<_>>();
+            for entity in listeners {
+                self.world
+                    .components
+                    .investigating
+                    .insert(entity, noise.coord);
+            }
+        }
+    }
+
+    /// A step from `coord` towards the noise the npc is investigating, if any step gets closer
+    pub(crate) fn investigation_step(
+        &self,
+        entity: Entity,
+        coord: Coord,
+    ) -> Option<CardinalDirection> {
+        let target = *self.world.components.investigating.get(entity)?;
+        self.step_towards(coord, target)
+    }
+
+    /// A free step from `coord` which gets closer to `target` as the crow flies, if there is one
+    pub(crate) fn step_towards(&self, coord: Coord, target: Coord) -> Option<CardinalDirection> {
+        let distance = (target - coord).magnitude2();
+        CardinalDirection::all()
+            .filter(|direction| {
+                let neighbour = coord + direction.coord();
+                self.is_free_for_npc(neighbour) && (target - neighbour).magnitude2() < distance
+            })
+            .min_by_key(|direction| (target - (coord + direction.coord())).magnitude2())
+    }
+}
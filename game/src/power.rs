@@ -0,0 +1,67 @@
+use crate::{world::spatial::Layers, Entity, Game, Message, TimerKind};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerState {
+    On,
+    Off,
+}
+
+impl Game {
+    /// The station loses power either because it was switched off at a terminal or because of
+    /// a power outage
+    pub fn power_state(&self) -> PowerState {
+        if self.power_switched_off || self.timers.get(TimerKind::PowerOutage).is_some() {
+            PowerState::Off
+        } else {
+            PowerState::On
+        }
+    }
+
+    pub(crate) fn toggle_power(&mut self) {
+        self.power_switched_off = !self.power_switched_off;
+        if self.power_switched_off {
+            self.messages
+                .system(Message::new("message.power_switched_off"));
+        } else {
+            self.messages
+                .system(Message::new("message.power_switched_on"));
+        }
+    }
+
+    /// Automatic doors close themselves a few turns after being opened, but only while the
+    /// station has power
+    pub(crate) fn tick_doors(&mut self) {
+        if self.power_state() == PowerState::Off {
+            return;
+        }
+        let mut ready = Vec::new();
+        for (entity, turns) in self.world.components.door_close_countdown.iter_mut() {
+            *turns = turns.saturating_sub(1);
+            if *turns == 0 {
+                ready.push(entity);
+            }
+        }
+        for entity in ready {
+            if self.door_is_obstructed(entity) {
+                // try again next turn
+                self.world.components.door_close_countdown.insert(entity, 1);
+            } else {
+                self.close_door(entity);
+            }
+        }
+    }
+
+    fn door_is_obstructed(&self, entity: Entity) -> bool {
+        match self.world.spatial_table.coord_of(entity) {
+            Some(coord) => matches!(
+                self.world.spatial_table.layers_at(coord),
+                Some(&Layers {
+                    character: Some(_),
+                    ..
+                })
+            ),
+            None => true,
+        }
+    }
+}
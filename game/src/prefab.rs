@@ -0,0 +1,113 @@
+use rand::{seq::SliceRandom, Rng};
+use serde::Deserialize;
+
+const PREFABS: &str = include_str!("prefabs.ron");
+
+/// Most prefabs stamped into a single level
+const PREFABS_PER_LEVEL: usize = 2;
+
+/// Terrain characters which can be walked through. Prefabs are only placed where they're
+/// surrounded by these, so that a prefab never cuts one part of a level off from another.
+const WALKABLE: &[char] = &['.', '@', '>', 'S', 'a', 'z', 'r', 'D'];
+
+/// A hand-made room or vault
+#[derive(Debug, Clone, Deserialize)]
+struct Prefab {
+    /// Relative chance of this prefab being chosen
+    weight: u32,
+    /// Cells in the format of the terrain files
+    rows: Vec<String>,
+}
+
+type Cells = Vec<Vec<char>>;
+
+impl Prefab {
+    fn cells(&self) -> Cells {
+        self.rows.iter().map(|row| row.chars().collect()).collect()
+    }
+}
+
+/// Turns cells a quarter turn clockwise
+fn rotate(cells: &Cells) -> Cells {
+    let height = cells.len();
+    let width = cells.first().map_or(0, |row| row.len());
+    (0..width)
+        .map(|x| (0..height).rev().map(|y| cells[y][x]).collect())
+        .collect()
+}
+
+fn mirror(cells: &Cells) -> Cells {
+    cells
+        .iter()
+        .map(|row| row.iter().rev().copied().collect())
+        .collect()
+}
+
+fn cell_at(cells: &Cells, x: i32, y: i32) -> Option<char> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    cells.get(y as usize)?.get(x as usize).copied()
+}
+
+/// Top-left corners where `shape` covers only bare floor, with a ring of walkable cells
+/// around it
+fn placements(level: &Cells, shape: &Cells) -> Vec<(i32, i32)> {
+    let height = shape.len() as i32;
+    let width = shape.first().map_or(0, |row| row.len()) as i32;
+    let mut placements = Vec::new();
+    for (top, row) in level.iter().enumerate() {
+        for left in 0..row.len() {
+            let (left, top) = (left as i32, top as i32);
+            let fits = (top - 1..=top + height).all(|y| {
+                (left - 1..=left + width).all(|x| {
+                    let inside = x >= left && x < left + width && y >= top && y < top + height;
+                    match cell_at(level, x, y) {
+                        Some(ch) if inside => ch == '.',
+                        Some(ch) => WALKABLE.contains(&ch),
+                        None => false,
+                    }
+                })
+            });
+            if fits {
+                placements.push((left, top));
+            }
+        }
+    }
+    placements
+}
+
+/// Stamps a few randomly chosen prefabs into a level in the format of the terrain files.
+/// Prefabs which don't fit anywhere are left out.
+pub(crate) fn stamp_prefabs<R: Rng>(txt: &str, rng: &mut R) -> String {
+    let prefabs: Vec<Prefab> = ron::from_str(PREFABS).expect("failed to parse prefabs");
+    let mut level = txt
+        .split('\n')
+        .map(|row| row.chars().collect::<Vec<_>>())
+        .collect::<Cells>();
+    for _ in 0..PREFABS_PER_LEVEL {
+        let prefab = match prefabs.choose_weighted(rng, |prefab| prefab.weight) {
+            Ok(prefab) => prefab,
+            Err(_) => break,
+        };
+        let mut shape = prefab.cells();
+        for _ in 0..rng.gen_range(0..4) {
+            shape = rotate(&shape);
+        }
+        if rng.gen() {
+            shape = mirror(&shape);
+        }
+        if let Some(&(left, top)) = placements(&level, &shape).choose(rng) {
+            for (y, row) in shape.iter().enumerate() {
+                for (x, &ch) in row.iter().enumerate() {
+                    level[top as usize + y][left as usize + x] = ch;
+                }
+            }
+        }
+    }
+    level
+        .into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
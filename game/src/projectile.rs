@@ -0,0 +1,623 @@
+use crate::{
+    coords_between,
+    event_log::{EventAction, EventOutcome},
+    explosion::GRENADE_BLAST,
+    names,
+    world::{
+        data::{Affix, Homing, OnCollision, RangedAttack, Weapon, SCOPE_ACCURACY_BONUS},
+        spatial::Layers,
+    },
+    CardinalDirection, Config, Coord, Entity, Game, GameControlFlow, GameOverReason, Message,
+};
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// Number of cells characters are pushed by shots from knockback weapons in normal gravity
+const PROJECTILE_KNOCKBACK: u32 = 1;
+/// Hit points restored to the shooter when a vampiric shot kills a character
+const VAMPIRIC_HEALING: u32 = 1;
+
+/// Directions a projectile can travel in, in clockwise order, so that neighbouring elements are
+/// 45 degrees apart
+const HEADINGS: [Coord; 8] = [
+    Coord { x: 0, y: -1 },
+    Coord { x: 1, y: -1 },
+    Coord { x: 1, y: 0 },
+    Coord { x: 1, y: 1 },
+    Coord { x: 0, y: 1 },
+    Coord { x: -1, y: 1 },
+    Coord { x: -1, y: 0 },
+    Coord { x: -1, y: -1 },
+];
+
+/// A shot in flight. Projectiles are resolved all at once, moving a cell at a time until they
+/// hit something or reach the end of their path.
+#[derive(Debug, Clone)]
+pub struct Projectile {
+    coord: Coord,
+    /// Cells the projectile will pass through if nothing gets in its way, nearest first
+    path: VecDeque<Coord>,
+    damage: u32,
+    on_collision: OnCollision,
+    /// Number of solid obstacles the projectile can still pass through
+    walls_remaining: u32,
+    /// Number of times the projectile can still bounce off solid obstacles
+    bounces_remaining: u32,
+    homing: Option<Homing>,
+    /// The character which fired the projectile. Homing projectiles never turn back on it.
+    source: Option<Entity>,
+    /// Characters hit by the projectile are pushed away from its source
+    knockback: bool,
+    /// Characters killed by the projectile heal its source
+    vampiric: bool,
+    /// Added to the accuracy of the projectile's source
+    accuracy_bonus: u32,
+    /// Percentile roll made when the projectile is fired, compared against the cover of each
+    /// character it reaches. Projectiles which haven't been fired ignore cover, so previews
+    /// show shots reaching their target.
+    cover_roll: Option<u32>,
+    /// Set when the projectile is stopped by a character's cover
+    hit_cover: bool,
+}
+
+impl Projectile {
+    /// A projectile travelling in a straight line from `origin` to `target`
+    pub fn new(origin: Coord, target: Coord, damage: u32, on_collision: OnCollision) -> Self {
+        Self {
+            coord: origin,
+            path: coords_between(origin, target).skip(1).collect(),
+            damage,
+            on_collision,
+            walls_remaining: match on_collision {
+                OnCollision::Pierce { walls } => walls,
+                OnCollision::Stop | OnCollision::Explode(_) => 0,
+            },
+            bounces_remaining: 0,
+            homing: None,
+            source: None,
+            knockback: false,
+            vampiric: false,
+            accuracy_bonus: 0,
+            cover_roll: None,
+            hit_cover: false,
+        }
+    }
+
+    /// Applies the affixes of the weapon which fired the projectile
+    pub fn with_affixes(self, weapon: &Weapon) -> Self {
+        Self {
+            walls_remaining: self.walls_remaining + weapon.penetration(),
+            knockback: weapon.has_affix(|affix| affix == Affix::Knockback),
+            vampiric: weapon.has_affix(|affix| affix == Affix::Vampiric),
+            accuracy_bonus: if weapon.attachments.scope {
+                SCOPE_ACCURACY_BONUS
+            } else {
+                0
+            },
+            ..self
+        }
+    }
+
+    pub fn with_source(self, source: Entity) -> Self {
+        Self {
+            source: Some(source),
+            ..self
+        }
+    }
+
+    pub fn with_homing(self, homing: Option<Homing>) -> Self {
+        Self { homing, ..self }
+    }
+
+    pub fn with_bounces(self, bounces: u32) -> Self {
+        Self {
+            bounces_remaining: bounces,
+            ..self
+        }
+    }
+}
+
+/// The result of moving a projectile by one cell
+enum ProjectileMove {
+    /// Moved into an empty cell
+    Moved,
+    /// Moved into a cell containing a character
+    HitCharacter(Entity),
+    /// Changed direction after hitting a solid obstacle, without moving
+    Bounced,
+    /// Stopped in front of a solid obstacle
+    HitObstacle,
+    /// Stopped by the cover of the character in the next cell
+    HitCover,
+    /// Reached the end of its path
+    Finished,
+}
+
+/// What the player is aiming
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aim {
+    Grenade,
+    Weapon,
+    /// Throwing the item in the given inventory slot
+    Throw(usize),
+}
+
+/// What would happen if the player fired at a cell, for showing while they aim
+#[derive(Debug, Clone, Default)]
+pub struct AimPreview {
+    /// Cells the shot passes through, ending with the cell where it stops
+    pub path: Vec<Coord>,
+    /// Cells which would be caught in an explosion where the shot stops
+    pub blast_area: Vec<Coord>,
+    pub in_range: bool,
+}
+
+impl Game {
+    pub(crate) fn is_solid_at(&self, coord: Coord) -> bool {
+        if let Some(&Layers {
+            feature: Some(feature_entity),
+            ..
+        }) = self.world.spatial_table.layers_at(coord)
+        {
+            self.world.components.solid.contains(feature_entity)
+        } else {
+            false
+        }
+    }
+
+    fn projectile_move(&self, projectile: &mut Projectile) -> ProjectileMove {
+        if let Some(homing) = projectile.homing {
+            self.steer(projectile, homing);
+        }
+        let next = match projectile.path.front() {
+            Some(&next) => next,
+            None => return ProjectileMove::Finished,
+        };
+        if self.is_solid_at(next) {
+            if projectile.walls_remaining > 0 {
+                projectile.walls_remaining -= 1;
+            } else if projectile.bounces_remaining > 0 {
+                projectile.bounces_remaining -= 1;
+                self.bounce(projectile, next);
+                return ProjectileMove::Bounced;
+            } else {
+                return ProjectileMove::HitObstacle;
+            }
+        }
+        if let (
+            Some(roll),
+            Some(&Layers {
+                character: Some(_), ..
+            }),
+        ) = (
+            projectile.cover_roll,
+            self.world.spatial_table.layers_at(next),
+        ) {
+            if roll < self.cover_at(next, projectile.coord) {
+                return ProjectileMove::HitCover;
+            }
+        }
+        projectile.path.pop_front();
+        projectile.coord = next;
+        match self.world.spatial_table.layers_at(next) {
+            Some(&Layers {
+                character: Some(character_entity),
+                ..
+            }) => ProjectileMove::HitCharacter(character_entity),
+            _ => ProjectileMove::Moved,
+        }
+    }
+
+    /// Reflects the rest of a projectile's path off the solid obstacle at `next`. Only the
+    /// component of its movement that runs into a wall is reversed, so shots glance off walls
+    /// at an angle, but a shot into a corner comes straight back.
+    fn bounce(&self, projectile: &mut Projectile, next: Coord) {
+        let coord = projectile.coord;
+        let step = next - coord;
+        let blocked_x = step.x != 0 && self.is_solid_at(coord + Coord::new(step.x, 0));
+        let blocked_y = step.y != 0 && self.is_solid_at(coord + Coord::new(0, step.y));
+        let (flip_x, flip_y) = match (blocked_x, blocked_y) {
+            (true, false) => (true, false),
+            (false, true) => (false, true),
+            _ => (step.x != 0, step.y != 0),
+        };
+        for cell in projectile.path.iter_mut() {
+            let delta = *cell - coord;
+            *cell = coord
+                + Coord::new(
+                    if flip_x { -delta.x } else { delta.x },
+                    if flip_y { -delta.y } else { delta.y },
+                );
+        }
+    }
+
+    /// Turns a homing projectile towards its target, by at most its maximum turn. The rest of
+    /// its path is replaced with a straight line in its new direction, so a homing projectile
+    /// flies as far as it would have without homing.
+    fn steer(&self, projectile: &mut Projectile, homing: Homing) {
+        let coord = projectile.coord;
+        let target = match self.homing_target(coord, projectile.source) {
+            Some(target) => target,
+            None => return,
+        };
+        let heading = match projectile.path.front() {
+            Some(&next) => next - coord,
+            None => return,
+        };
+        let index = match HEADINGS.iter().position(|&h| h == heading) {
+            Some(index) => index as i32,
+            None => return,
+        };
+        let max_turn = homing.max_turn as i32;
+        let heading = (-max_turn..=max_turn)
+            .map(|turn| HEADINGS[(index + turn).rem_euclid(HEADINGS.len() as i32) as usize])
+            .min_by_key(|&heading| (coord + heading - target).magnitude2())
+            .unwrap_or(heading);
+        for (i, cell) in projectile.path.iter_mut().enumerate() {
+            let distance = i as i32 + 1;
+            *cell = coord + Coord::new(heading.x * distance, heading.y * distance);
+        }
+    }
+
+    /// Moves a projectile until it stops, returning the cells it passed through and the
+    /// characters it hit in the order they were hit. Only piercing projectiles can hit more than
+    /// one character.
+    pub(crate) fn projectile_flight(
+        &self,
+        projectile: &mut Projectile,
+    ) -> (Vec<Coord>, Vec<Entity>) {
+        let mut path = Vec::new();
+        let mut hits = Vec::new();
+        loop {
+            match self.projectile_move(projectile) {
+                ProjectileMove::Moved => path.push(projectile.coord),
+                ProjectileMove::Bounced => (),
+                ProjectileMove::HitCharacter(entity) => {
+                    path.push(projectile.coord);
+                    hits.push(entity);
+                    if let OnCollision::Pierce { .. } = projectile.on_collision {
+                        continue;
+                    }
+                    return (path, hits);
+                }
+                ProjectileMove::HitCover => {
+                    projectile.hit_cover = true;
+                    return (path, hits);
+                }
+                ProjectileMove::HitObstacle | ProjectileMove::Finished => return (path, hits),
+            }
+        }
+    }
+
+    /// The cells a projectile passes through, ending with the cell where it stops
+    pub(crate) fn projectile_path(&self, projectile: &Projectile) -> Vec<Coord> {
+        self.projectile_flight(&mut projectile.clone()).0
+    }
+
+    pub(crate) fn fire_projectile(
+        &mut self,
+        mut projectile: Projectile,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        projectile.cover_roll = Some(self.rng.gen_range(0..100));
+        let (path, hits) = self.projectile_flight(&mut projectile);
+        if projectile.hit_cover {
+            self.messages
+                .combat(None, Message::new("message.shot_blocked_by_cover"));
+        }
+        // the only solid cells in a projectile's path are walls it passed through
+        for coord in path {
+            if self.is_solid_at(coord) {
+                self.damage_wall(coord, projectile.damage);
+            }
+        }
+        match projectile.on_collision {
+            OnCollision::Explode(blast) => {
+                self.explode(projectile.coord, blast, projectile.damage, config)
+            }
+            OnCollision::Stop | OnCollision::Pierce { .. } => {
+                for entity in hits {
+                    if let Some(game_control_flow) =
+                        self.projectile_hit(&projectile, entity, config)
+                    {
+                        return Some(game_control_flow);
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    pub(crate) fn projectile_hit(
+        &mut self,
+        projectile: &Projectile,
+        entity: Entity,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        if !self.roll_to_hit(projectile.source, entity, projectile.accuracy_bonus) {
+            let message = if self.is_player(entity) {
+                Message::new("message.shot_misses_player")
+            } else {
+                Message::with_args("message.shot_misses_npc", &[&self.npc_description(entity)])
+            };
+            self.messages.combat(Some(entity), message);
+            return None;
+        }
+        if self.absorb_hit(entity) {
+            return None;
+        }
+        let damage = projectile.damage;
+        let killed = if self.is_player(entity) {
+            self.messages
+                .combat(Some(entity), Message::new("message.player_shot"));
+            if self.damage_character(entity, damage, config) {
+                return Some(GameControlFlow::GameOver(GameOverReason::Killed));
+            }
+            false
+        } else if self.damage_character(entity, damage, config) {
+            let name = names::capitalise(&self.npc_description(entity));
+            self.messages.combat(
+                Some(entity),
+                Message::with_args("message.npc_shot_dead", &[&name]),
+            );
+            // the player only gets credit for npcs that other npcs didn't shoot
+            match projectile.source {
+                Some(source) if !self.is_player(source) => self.remove_dead_npc(entity),
+                _ => self.npc_die(entity),
+            }
+            true
+        } else {
+            false
+        };
+        let source = projectile.source?;
+        if killed && projectile.vampiric {
+            if let Some(hit_points) = self.world.components.hit_points.get_mut(source) {
+                hit_points.increase(VAMPIRIC_HEALING);
+            }
+        }
+        if !killed && projectile.knockback {
+            let source_coord = self.world.spatial_table.coord_of(source)?;
+            let coord = self.world.spatial_table.coord_of(entity)?;
+            let delta = coord - source_coord;
+            let direction = if delta.x.abs() >= delta.y.abs() {
+                if delta.x > 0 {
+                    CardinalDirection::East
+                } else {
+                    CardinalDirection::West
+                }
+            } else if delta.y > 0 {
+                CardinalDirection::South
+            } else {
+                CardinalDirection::North
+            };
+            let distance = PROJECTILE_KNOCKBACK * self.gravity.knockback_multiplier();
+            self.world.knock_back(entity, direction, distance);
+        }
+        None
+    }
+
+    /// Looks for a shot from `coord` which would hit the target, trying a direct shot before
+    /// shots bounced off walls
+    pub(crate) fn npc_ranged_projectile(
+        &self,
+        entity: Entity,
+        coord: Coord,
+        target: Entity,
+        attack: RangedAttack,
+        damage: u32,
+    ) -> Option<Projectile> {
+        let target_coord = self.world.spatial_table.coord_of(target)?;
+        if (target_coord - coord).magnitude2() > attack.range_squared {
+            return None;
+        }
+        let radius = (attack.range_squared as f64).sqrt() as i32;
+        let bank_shots = (-radius..=radius)
+            .flat_map(|y| (-radius..=radius).map(move |x| coord + Coord::new(x, y)))
+            .filter(|&aim| {
+                attack.bounces > 0
+                    && aim != coord
+                    && aim != target_coord
+                    && (aim - coord).magnitude2() <= attack.range_squared
+            });
+        std::iter::once(target_coord)
+            .chain(bank_shots)
+            .map(|aim| {
+                Projectile::new(coord, aim, damage, attack.on_collision)
+                    .with_bounces(attack.bounces)
+                    .with_homing(attack.homing)
+                    .with_source(entity)
+            })
+            .find(|projectile| {
+                let (_, hits) = self.projectile_flight(&mut projectile.clone());
+                hits.first() == Some(&target)
+            })
+    }
+
+    pub(crate) fn npc_fire(
+        &mut self,
+        entity: Entity,
+        attack: RangedAttack,
+        projectile: Projectile,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        let name = names::capitalise(&self.npc_description(entity));
+        self.messages.combat(
+            Some(entity),
+            Message::with_args("message.npc_throws", &[&name, &attack.projectile_name]),
+        );
+        let target = projectile.path.back().copied().unwrap_or(projectile.coord);
+        self.record_event(
+            entity,
+            EventAction::RangedAttack { target },
+            EventOutcome::Done,
+        );
+        self.fire_projectile(projectile, config)
+    }
+
+    pub(crate) fn grenade_projectile(&self, target: Coord) -> Projectile {
+        Projectile::new(
+            self.player_coord(),
+            target,
+            self.balance.grenade_damage,
+            OnCollision::Explode(GRENADE_BLAST),
+        )
+    }
+
+    pub fn aim_preview(&self, aim: Aim, target: Coord) -> AimPreview {
+        let (projectile, in_range) = match aim {
+            Aim::Grenade => (
+                self.grenade_projectile(target),
+                self.is_in_grenade_range(target),
+            ),
+            Aim::Weapon => match self.player_weapon() {
+                Some(weapon) => (
+                    self.weapon_projectile(&weapon, target),
+                    (target - self.player_coord()).magnitude2() <= weapon.range_squared,
+                ),
+                None => return AimPreview::default(),
+            },
+            Aim::Throw(_) => (
+                self.throw_projectile(target),
+                self.is_in_throw_range(target),
+            ),
+        };
+        let path = self.projectile_path(&projectile);
+        let end = path.last().copied().unwrap_or_else(|| self.player_coord());
+        let blast_area = match projectile.on_collision {
+            OnCollision::Explode(blast) => self.explosion_area(end, blast),
+            OnCollision::Stop | OnCollision::Pierce { .. } => Vec::new(),
+        };
+        AimPreview {
+            path,
+            blast_area,
+            in_range,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LevelDesign;
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    fn game_from_rows(rows: &[&str]) -> Game {
+        let design = LevelDesign::from_text(&rows.join("\n")).expect("rows are the same length");
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        Game::new_custom_level(&Config::default(), &design, &mut rng).expect("design has a '@'")
+    }
+
+    /// A room with a pillar at (8, 3)
+    const BOUNCE_ROOM: &[&str] = &[
+        "###########",
+        "#.........#",
+        "#.........#",
+        "#...@...#.#",
+        "#.........#",
+        "#.........#",
+        "###########",
+    ];
+
+    /// The path of a projectile fired from `origin` at `target` after it bounces off whatever is
+    /// in the first cell of its path
+    fn bounced_path(game: &Game, origin: Coord, target: Coord) -> Vec<Coord> {
+        let mut projectile = Projectile::new(origin, target, 1, OnCollision::Stop);
+        let next = projectile.path[0];
+        game.bounce(&mut projectile, next);
+        projectile.path.into_iter().collect()
+    }
+
+    #[test]
+    fn bounce_glances_off_flat_wall() {
+        let game = game_from_rows(BOUNCE_ROOM);
+        let path = bounced_path(&game, Coord::new(4, 1), Coord::new(7, -2));
+        assert_eq!(
+            path,
+            vec![Coord::new(5, 2), Coord::new(6, 3), Coord::new(7, 4)]
+        );
+    }
+
+    #[test]
+    fn bounce_comes_straight_back_out_of_corner() {
+        let game = game_from_rows(BOUNCE_ROOM);
+        let path = bounced_path(&game, Coord::new(1, 1), Coord::new(-2, -2));
+        assert_eq!(
+            path,
+            vec![Coord::new(2, 2), Coord::new(3, 3), Coord::new(4, 4)]
+        );
+    }
+
+    #[test]
+    fn bounce_comes_straight_back_off_pillar_corner() {
+        let game = game_from_rows(BOUNCE_ROOM);
+        let path = bounced_path(&game, Coord::new(7, 4), Coord::new(9, 2));
+        assert_eq!(path, vec![Coord::new(6, 5), Coord::new(5, 6)]);
+    }
+
+    #[test]
+    fn bounce_comes_straight_back_from_head_on_hit() {
+        let game = game_from_rows(BOUNCE_ROOM);
+        let path = bounced_path(&game, Coord::new(4, 5), Coord::new(4, 8));
+        assert_eq!(
+            path,
+            vec![Coord::new(4, 4), Coord::new(4, 3), Coord::new(4, 2)]
+        );
+    }
+
+    /// Number of 45 degree turns between two headings, whichever way round is shorter
+    fn turns_between(a: Coord, b: Coord) -> usize {
+        let index = |heading| HEADINGS.iter().position(|&h| h == heading).unwrap();
+        let turns = index(a).abs_diff(index(b));
+        turns.min(HEADINGS.len() - turns)
+    }
+
+    #[test]
+    fn homing_turns_gradually_onto_target() {
+        let game = game_from_rows(&[
+            "#################",
+            "#...............#",
+            "#...............#",
+            "#...............#",
+            "#...............#",
+            "#...............#",
+            "#...............#",
+            "#...............#",
+            "#...............#",
+            "#...............#",
+            "#.@...........z.#",
+            "#...............#",
+            "#################",
+        ]);
+        let homing = Homing { max_turn: 1 };
+        let origin = game.player_coord();
+        let target = Coord::new(14, 10);
+        let npc = game
+            .world
+            .spatial_table
+            .layers_at(target)
+            .and_then(|layers| layers.character)
+            .expect("npc at target");
+        // fired straight up, with the npc off to the right
+        let mut projectile =
+            Projectile::new(origin, origin + Coord::new(0, -16), 1, OnCollision::Stop)
+                .with_source(game.player_entity)
+                .with_homing(Some(homing));
+        let (path, hits) = game.projectile_flight(&mut projectile);
+        assert_eq!(hits, vec![npc]);
+        assert_eq!(path.last(), Some(&target));
+        let headings = std::iter::once(origin)
+            .chain(path.iter().copied())
+            .collect::<Vec<_>>()
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .collect::<Vec<_>>();
+        let mut previous = Coord::new(0, -1);
+        for heading in headings {
+            assert!(turns_between(previous, heading) <= homing.max_turn as usize);
+            previous = heading;
+        }
+        // heading right would have been closer, but that's two turns from heading up
+        assert_eq!(path[0], origin + Coord::new(1, -1));
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    ActionError, Config, Coord, Game, GameControlFlow, MenuChoice, MenuImage, MenuRequest,
+};
+use serde::{Deserialize, Serialize};
+
+/// A question the game is waiting for the player to answer. The answer arrives as an
+/// `Input::Choose` on the following turn. Cancelling the menu declines the prompt, and any other
+/// input discards it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) enum Prompt {
+    /// Confirm sabotaging the reactor at the given coordinate
+    SabotageReactor(Coord),
+}
+
+impl Prompt {
+    fn text_key(self) -> &'static str {
+        match self {
+            Self::SabotageReactor(_) => "prompt.sabotage_reactor",
+        }
+    }
+
+    fn choices(self) -> Vec<MenuChoice> {
+        match self {
+            Self::SabotageReactor(_) => vec![MenuChoice::Confirm],
+        }
+    }
+}
+
+impl Game {
+    /// Asks the player a question. No time passes until it's answered.
+    pub(crate) fn prompt(&mut self, prompt: Prompt) -> Option<GameControlFlow> {
+        self.prompt = Some(prompt);
+        Some(GameControlFlow::OpenMenu(MenuRequest {
+            choices: prompt.choices(),
+            text_key: prompt.text_key(),
+            image: MenuImage::Placeholder,
+        }))
+    }
+
+    /// Only the prompt opened by the previous input can be answered
+    pub(crate) fn player_choose(
+        &mut self,
+        prompt: Option<Prompt>,
+        choice: MenuChoice,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        let prompt = prompt.ok_or(ActionError::NoPrompt)?;
+        if !prompt.choices().contains(&choice) {
+            return Err(ActionError::InvalidChoice);
+        }
+        match (prompt, choice) {
+            (Prompt::SabotageReactor(coord), MenuChoice::Confirm) => {
+                let reactor = self
+                    .world
+                    .spatial_table
+                    .layers_at_checked(coord)
+                    .feature
+                    .filter(|&entity| self.world.components.reactor.contains(entity))
+                    .ok_or(ActionError::InvalidInteraction)?;
+                self.destroy_reactor(reactor, config);
+            }
+        }
+        Ok(None)
+    }
+}
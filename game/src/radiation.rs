@@ -0,0 +1,119 @@
+use crate::{
+    world::{data::Rads, spatial::Layers},
+    Coord, Entity, Game, GameControlFlow, GameOverReason, Message, TutorialStep,
+};
+
+/// Radiation emitted by a damaged reactor at its own cell. This falls off by one unit for each
+/// cell of distance from the reactor.
+pub const DAMAGED_REACTOR_RADIATION: u32 = 8;
+/// Every time a character's cumulative exposure increases by this much they will eventually take
+/// a point of damage
+const EXPOSURE_PER_DAMAGE: u32 = 40;
+/// Number of turns between being exposed to radiation and feeling its effects
+const RADIATION_DAMAGE_DELAY: u32 = 15;
+/// Radiation suits block this percentage of radiation
+pub const RAD_SUIT_PROTECTION: u32 = 75;
+
+impl Rads {
+    /// Returns the number of points of damage that become due this turn
+    fn tick(&mut self) -> u32 {
+        for turns in self.pending_damage.iter_mut() {
+            *turns = turns.saturating_sub(1);
+        }
+        let before = self.pending_damage.len();
+        self.pending_damage.retain(|&turns| turns > 0);
+        (before - self.pending_damage.len()) as u32
+    }
+
+    /// Returns whether this exposure will cause any damage in the future
+    fn expose(&mut self, amount: u32) -> bool {
+        let damage_before = self.exposure / EXPOSURE_PER_DAMAGE;
+        self.exposure += amount;
+        let damage_after = self.exposure / EXPOSURE_PER_DAMAGE;
+        for _ in damage_before..damage_after {
+            self.pending_damage.push(RADIATION_DAMAGE_DELAY);
+        }
+        damage_after > damage_before
+    }
+}
+
+impl Game {
+    /// Ambient radiation at a coordinate, before taking any protection into account
+    pub fn radiation_at(&self, coord: Coord) -> u32 {
+        self.world
+            .components
+            .radiation_source
+            .iter()
+            .filter_map(|(entity, &strength)| {
+                let delta = self.world.spatial_table.coord_of(entity)? - coord;
+                let distance = delta.x.unsigned_abs().max(delta.y.unsigned_abs());
+                Some(strength.saturating_sub(distance))
+            })
+            .sum()
+    }
+
+    pub fn radiation_at_player(&self) -> u32 {
+        self.radiation_at(self.player_coord())
+    }
+
+    pub fn player_rads(&self) -> Option<&Rads> {
+        self.world.components.rads.get(self.player_entity)
+    }
+
+    /// If there's protective gear where the player is standing, put it on
+    pub(crate) fn pick_up_protective_gear(&mut self) {
+        let item_entity = match self.world.spatial_table.layers_at(self.player_coord()) {
+            Some(&Layers {
+                item: Some(item_entity),
+                ..
+            }) => item_entity,
+            _ => return,
+        };
+        if let Some(&protection) = self.world.components.radiation_protection.get(item_entity) {
+            self.world.remove_entity(item_entity);
+            self.world
+                .components
+                .radiation_protection
+                .insert(self.player_entity, protection);
+            self.messages
+                .items(Some(item_entity), Message::new("message.rad_suit_worn"));
+            self.complete_tutorial_step(TutorialStep::PickUpItem);
+        }
+    }
+
+    fn radiation_protection(&self, entity: Entity) -> u32 {
+        self.world
+            .components
+            .radiation_protection
+            .get(entity)
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Exposes the player to the radiation where they stand, and deals damage from past exposure
+    pub(crate) fn tick_radiation(&mut self) -> Option<GameControlFlow> {
+        let protection = self.radiation_protection(self.player_entity);
+        let amount = (self.radiation_at_player() * (100 - protection)) / 100;
+        let rads = self.world.components.rads.get_mut(self.player_entity)?;
+        let damage = rads.tick();
+        if amount > 0 && rads.expose(amount) {
+            self.messages
+                .system(Message::new("message.radiation_nausea"));
+        }
+        if damage == 0 {
+            return None;
+        }
+        self.messages
+            .system(Message::new("message.radiation_sickness"));
+        let hit_points = self
+            .world
+            .components
+            .hit_points
+            .get_mut(self.player_entity)?;
+        hit_points.decrease(damage);
+        if hit_points.is_empty() {
+            return Some(GameControlFlow::GameOver(GameOverReason::RadiationSickness));
+        }
+        None
+    }
+}
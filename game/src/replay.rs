@@ -0,0 +1,85 @@
+use crate::{
+    ironman::Ironman, world::spawn::make_player, Balance, Config, Difficulty, Game, Input,
+    LayoutStyle, Performance, RunModifiers,
+};
+use serde::{Deserialize, Serialize};
+
+/// An input which took a turn
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct ReplayInput {
+    /// The turn on which the input was given
+    pub turn: u64,
+    pub input: Input,
+}
+
+/// Everything needed to play a run back exactly as it happened. All of the game's randomness
+/// comes from rngs seeded by the run's seeds, and the app's rng (used for animations) is separate,
+/// so giving a new game the same inputs reproduces the original run.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Replay {
+    pub seed: u64,
+    /// Present if the run was played in ironman mode, which reseeds the rng each turn
+    pub ironman_seed: Option<u64>,
+    pub casual: bool,
+    pub hot_seat: bool,
+    pub modifiers: RunModifiers,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub layout_style: LayoutStyle,
+    /// The spawn budget depends on how the player had fared before the run
+    #[serde(default)]
+    pub performance: Performance,
+    #[serde(default)]
+    pub balance: Balance,
+    pub inputs: Vec<ReplayInput>,
+}
+
+impl Game {
+    pub(crate) fn record_replay_input(&mut self, turn: u64, input: Input) {
+        if let Some(replay) = self.replay.as_mut() {
+            replay.push(ReplayInput { turn, input });
+        }
+    }
+
+    /// The information needed to replay the run so far. Returns `None` for runs which didn't
+    /// start from a seed, such as the tutorial, levels from the editor and new game+.
+    pub fn replay_data(&self) -> Option<Replay> {
+        let inputs = self.replay.as_ref()?;
+        Some(Replay {
+            seed: self.seed,
+            ironman_seed: self.ironman.map(Ironman::seed),
+            casual: self.is_casual(),
+            hot_seat: self.is_hot_seat(),
+            modifiers: self.modifiers,
+            difficulty: self.difficulty,
+            layout_style: self.layout_style,
+            performance: self.performance,
+            balance: self.balance,
+            inputs: inputs.clone(),
+        })
+    }
+
+    /// The game as it was at the start of a recorded run. Passing each of the replay's inputs to
+    /// `step_turn` in order steps through the run.
+    pub fn from_replay(config: &Config, replay: &Replay) -> Self {
+        let config = Config {
+            ironman: replay.ironman_seed.is_some(),
+            casual: replay.casual,
+            hot_seat: replay.hot_seat,
+            modifiers: replay.modifiers,
+            difficulty: replay.difficulty,
+            layout_style: replay.layout_style,
+            performance: replay.performance,
+            balance: replay.balance,
+            // undo snapshots would be wasted work as the replay never undoes anything
+            undo: false,
+            ..*config
+        };
+        let mut player_data = make_player();
+        config.modifiers.apply_to_player(&mut player_data);
+        let mut game = Self::new_run_from_seed(&config, player_data, 0, replay.seed);
+        game.ironman = replay.ironman_seed.map(Ironman::new);
+        game
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{Difficulty, Ending, Game, GameOverReason, RunModifiers};
+use serde::{Deserialize, Serialize};
+
+/// The rules a run was played under
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    Normal,
+    Ironman,
+    Casual,
+}
+
+/// How a run ended
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub enum RunOutcome {
+    Won(Ending),
+    Died(GameOverReason),
+}
+
+/// Summary of a completed run, suitable for exporting or submitting to a leaderboard
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunRecord {
+    /// Seed of the run's rng
+    pub seed: u64,
+    pub score: u32,
+    pub turns: u64,
+    /// The deepest level reached, numbered from 1
+    pub level: u32,
+    /// Version of the game the run was played on
+    pub version: String,
+    pub mode: RunMode,
+    #[serde(default)]
+    pub modifiers: RunModifiers,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Number of wins carried into this run with the same character
+    #[serde(default)]
+    pub new_game_plus: u32,
+    pub outcome: RunOutcome,
+}
+
+impl Game {
+    pub fn run_mode(&self) -> RunMode {
+        if self.is_ironman() {
+            RunMode::Ironman
+        } else if self.is_casual() {
+            RunMode::Casual
+        } else {
+            RunMode::Normal
+        }
+    }
+
+    pub fn run_record(&self, outcome: RunOutcome) -> RunRecord {
+        RunRecord {
+            seed: self.seed,
+            score: self.score(),
+            turns: self.turn,
+            level: self.level,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            mode: self.run_mode(),
+            modifiers: self.modifiers,
+            difficulty: self.difficulty,
+            new_game_plus: self.new_game_plus,
+            outcome,
+        }
+    }
+}
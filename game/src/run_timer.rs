@@ -0,0 +1,67 @@
+use crate::Game;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The time taken to complete a single level
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Split {
+    pub level: u32,
+    pub duration: Duration,
+    pub turns: u64,
+    /// Whether the level was completed in an ironman run
+    #[serde(default)]
+    pub ironman: bool,
+}
+
+/// Tracks how long the current game has been played for, in both wall-clock time and turns. Time
+/// only passes while the game is receiving ticks, so time spent in menus isn't counted.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RunTimer {
+    elapsed: Duration,
+    level_start_elapsed: Duration,
+    level_start_turn: u64,
+    splits: Vec<Split>,
+}
+
+impl RunTimer {
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Times for each level completed so far in this game
+    pub fn splits(&self) -> &[Split] {
+        &self.splits
+    }
+
+    fn tick(&mut self, since_last_tick: Duration) {
+        self.elapsed += since_last_tick;
+    }
+
+    fn split(&mut self, turn: u64, ironman: bool) {
+        self.splits.push(Split {
+            level: self.splits.len() as u32 + 1,
+            duration: self.elapsed - self.level_start_elapsed,
+            turns: turn - self.level_start_turn,
+            ironman,
+        });
+        self.level_start_elapsed = self.elapsed;
+        self.level_start_turn = turn;
+    }
+}
+
+impl Game {
+    pub fn run_timer(&self) -> &RunTimer {
+        &self.run_timer
+    }
+
+    pub(crate) fn tick_run_timer(&mut self, since_last_tick: Duration) {
+        self.run_timer.tick(since_last_tick);
+    }
+
+    /// Records the time taken to complete the current level
+    pub(crate) fn complete_level(&mut self) {
+        self.run_timer.split(self.turn, self.is_ironman());
+        self.score_level();
+        self.record_faction_spared();
+    }
+}
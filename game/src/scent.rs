@@ -0,0 +1,41 @@
+use crate::{world::data::NpcType, CardinalDirection, Coord, Entity, Game};
+
+impl Game {
+    /// Fades old trails, then leaves fresh scent wherever the players are standing
+    pub(crate) fn tick_scent(&mut self) {
+        self.world.decay_scent();
+        for coord in self.player_coords() {
+            self.world.lay_scent(coord);
+        }
+    }
+
+    /// Npcs which track by scent pick up the player's trail when standing on or next to it
+    pub(crate) fn smells_player(&self, entity: Entity, coord: Coord) -> bool {
+        let tracks_scent = self
+            .world
+            .components
+            .npc_type
+            .get(entity)
+            .is_some_and(|npc_type| npc_type.tracks_scent());
+        tracks_scent
+            && std::iter::once(coord)
+                .chain(CardinalDirection::all().map(|direction| coord + direction.coord()))
+                .any(|coord| self.world.scent_at(coord) > 0)
+    }
+
+    /// A step onto a fresher part of the player's trail, for npcs which track by scent. Npcs
+    /// following the trail retrace the player's steps, so they find their way around corners
+    /// and through doors without needing to see where the player went.
+    pub(crate) fn scent_step(&self, npc_type: NpcType, coord: Coord) -> Option<CardinalDirection> {
+        if !npc_type.tracks_scent() {
+            return None;
+        }
+        let scent = self.world.scent_at(coord);
+        CardinalDirection::all()
+            .filter(|direction| {
+                let neighbour = coord + direction.coord();
+                self.is_free_for_npc(neighbour) && self.world.scent_at(neighbour) > scent
+            })
+            .max_by_key(|direction| self.world.scent_at(coord + direction.coord()))
+    }
+}
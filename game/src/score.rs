@@ -0,0 +1,28 @@
+use crate::Game;
+
+/// Points for each npc destroyed
+const KILL_SCORE: u32 = 10;
+/// Points for each level completed
+const LEVEL_SCORE: u32 = 100;
+/// Points lost each time a casual run continues after the player dies
+pub(crate) const CONTINUE_PENALTY: u32 = 250;
+
+impl Game {
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub(crate) fn score_kill(&mut self) {
+        if !self.modifiers.pacifist {
+            self.score += KILL_SCORE;
+        }
+    }
+
+    pub(crate) fn score_level(&mut self) {
+        if self.modifiers.pacifist {
+            self.score += LEVEL_SCORE * 2;
+        } else {
+            self.score += LEVEL_SCORE;
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use rand::{seq::SliceRandom, Rng};
+
+/// Chance of a generated level having a secret room, as a ratio
+const SECRET_ROOM_CHANCE: (u32, u32) = (1, 2);
+/// Sizes of the space inside a secret room, from most to least preferred. Smaller rooms fit in
+/// more places.
+const SECRET_ROOM_SIZES: &[(i32, i32)] = &[
+    (3, 3),
+    (3, 2),
+    (2, 3),
+    (2, 2),
+    (3, 1),
+    (1, 3),
+    (2, 1),
+    (1, 2),
+];
+/// Loot left in secret rooms, in the order it's placed. Lockers are filled once the level is
+/// built.
+const SECRET_ROOM_LOOT: &[char] = &['L', 'a'];
+/// Floor a secret room's hidden door may open onto
+const INTERIOR: &[char] = &['.', '@', '>', 'S', 'a', 'z', 'r', 'D'];
+
+type Cells = Vec<Vec<char>>;
+
+fn cell_at(cells: &Cells, x: i32, y: i32) -> Option<char> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    cells.get(y as usize)?.get(x as usize).copied()
+}
+
+/// A place a secret room could go. Rooms are dug out of solid wall or built out onto the hull,
+/// and are entered through a wall of the station.
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    /// Top-left corner of the room's walls
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    door: (i32, i32),
+}
+
+impl Placement {
+    fn is_inside(&self, x: i32, y: i32) -> bool {
+        x > self.left
+            && y > self.top
+            && x < self.left + self.width - 1
+            && y < self.top + self.height - 1
+    }
+}
+
+fn placements(level: &Cells, (inner_width, inner_height): (i32, i32)) -> Vec<Placement> {
+    let (width, height) = (inner_width + 2, inner_height + 2);
+    let mut placements = Vec::new();
+    for (top, row) in level.iter().enumerate() {
+        for left in 0..row.len() {
+            let (left, top) = (left as i32, top as i32);
+            let solid = (top..top + height).all(|y| {
+                (left..left + width).all(|x| matches!(cell_at(level, x, y), Some('#') | Some(',')))
+            });
+            if !solid {
+                continue;
+            }
+            let mut doors = Vec::new();
+            for x in left + 1..left + width - 1 {
+                doors.push(((x, top), (0, -1)));
+                doors.push(((x, top + height - 1), (0, 1)));
+            }
+            for y in top + 1..top + height - 1 {
+                doors.push(((left, y), (-1, 0)));
+                doors.push(((left + width - 1, y), (1, 0)));
+            }
+            for ((x, y), (dx, dy)) in doors {
+                let opens_inside = cell_at(level, x, y) == Some('#')
+                    && cell_at(level, x + dx, y + dy).is_some_and(|ch| INTERIOR.contains(&ch));
+                if opens_inside {
+                    placements.push(Placement {
+                        left,
+                        top,
+                        width,
+                        height,
+                        door: (x, y),
+                    });
+                }
+            }
+        }
+    }
+    placements
+}
+
+/// Sometimes walls off a small room full of loot, reached through a hidden door. The room is
+/// the biggest that fits anywhere on the level, and its door is marked 'H'.
+pub(crate) fn add_secret_room<R: Rng>(txt: &str, rng: &mut R) -> String {
+    let (numerator, denominator) = SECRET_ROOM_CHANCE;
+    if !rng.gen_ratio(numerator, denominator) {
+        return txt.to_string();
+    }
+    let mut level = txt
+        .split('\n')
+        .map(|row| row.chars().collect::<Vec<_>>())
+        .collect::<Cells>();
+    let placement = SECRET_ROOM_SIZES
+        .iter()
+        .map(|&size| placements(&level, size))
+        .find(|placements| !placements.is_empty())
+        .and_then(|placements| placements.choose(rng).copied());
+    if let Some(placement) = placement {
+        let mut interior = Vec::new();
+        for y in placement.top..placement.top + placement.height {
+            for x in placement.left..placement.left + placement.width {
+                let ch = if (x, y) == placement.door {
+                    'H'
+                } else if placement.is_inside(x, y) {
+                    interior.push((x, y));
+                    '.'
+                } else {
+                    '#'
+                };
+                level[y as usize][x as usize] = ch;
+            }
+        }
+        interior.shuffle(rng);
+        for (&(x, y), &loot) in interior.iter().zip(SECRET_ROOM_LOOT) {
+            level[y as usize][x as usize] = loot;
+        }
+    }
+    level
+        .into_iter()
+        .map(|row| row.into_iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
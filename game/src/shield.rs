@@ -0,0 +1,92 @@
+use crate::{world::spatial::Layers, Entity, Game, Message};
+use serde::{Deserialize, Serialize};
+
+/// Hits absorbed by a fully charged shield
+pub(crate) const SHIELD_CHARGES: u32 = 3;
+/// Turns without being hit before a shield regains a charge
+const SHIELD_RECHARGE_TURNS: u32 = 5;
+
+/// Absorbs hits in place of the character wearing it, until it runs out of charges. A shield
+/// which hasn't been hit for a while slowly recharges.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Shield {
+    pub charges: u32,
+    pub max_charges: u32,
+    turns_since_hit: u32,
+}
+
+impl Shield {
+    pub fn new(max_charges: u32) -> Self {
+        Self {
+            charges: max_charges,
+            max_charges,
+            turns_since_hit: 0,
+        }
+    }
+}
+
+impl Game {
+    pub fn player_shield(&self) -> Option<Shield> {
+        self.world
+            .components
+            .shield
+            .get(self.player_entity)
+            .copied()
+    }
+
+    pub(crate) fn pick_up_shield_generator(&mut self) {
+        let item_entity = match self.world.spatial_table.layers_at(self.player_coord()) {
+            Some(&Layers {
+                item: Some(item_entity),
+                ..
+            }) => item_entity,
+            _ => return,
+        };
+        if let Some(&shield) = self.world.components.shield.get(item_entity) {
+            self.world.remove_entity(item_entity);
+            self.world
+                .components
+                .shield
+                .insert(self.player_entity, shield);
+            self.messages
+                .items(Some(item_entity), Message::new("message.shield_worn"));
+        }
+    }
+
+    /// Spends one of the character's shield charges to absorb a hit, if it has any left.
+    /// Returns true if the hit was absorbed.
+    pub(crate) fn absorb_hit(&mut self, entity: Entity) -> bool {
+        let shield = match self.world.components.shield.get_mut(entity) {
+            Some(shield) => shield,
+            None => return false,
+        };
+        shield.turns_since_hit = 0;
+        if shield.charges == 0 {
+            return false;
+        }
+        shield.charges -= 1;
+        let broken = shield.charges == 0;
+        let key = match (self.is_player(entity), broken) {
+            (true, false) => "message.player_shield_absorbs",
+            (true, true) => "message.player_shield_fails",
+            (false, false) => "message.npc_shield_absorbs",
+            (false, true) => "message.npc_shield_fails",
+        };
+        self.messages.combat(Some(entity), Message::new(key));
+        true
+    }
+
+    /// Shields regain a charge for every few turns their wearer goes without being hit
+    pub(crate) fn tick_shields(&mut self) {
+        for (_, shield) in self.world.components.shield.iter_mut() {
+            if shield.charges == shield.max_charges {
+                continue;
+            }
+            shield.turns_since_hit += 1;
+            if shield.turns_since_hit >= SHIELD_RECHARGE_TURNS {
+                shield.charges += 1;
+                shield.turns_since_hit = 0;
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    world::{
+        data::{Atmosphere, NpcType},
+        World,
+    },
+    Coord, Difficulty, Entity, LevelTheme, RunModifiers,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// Total cost of npcs placed on a level by a player with no history
+const BASE_SPAWN_BUDGET: u32 = 10;
+/// Maximum amount the budget changes in response to the player's performance
+const MAX_BUDGET_ADJUSTMENT: i32 = 4;
+/// Npcs aren't placed within this distance of the player's starting position
+const MIN_SPAWN_DISTANCE_SQUARED: i32 = 36;
+
+/// How the player has fared in previous games, used to make levels easier or harder
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct Performance {
+    pub deaths: u32,
+    /// Levels completed with a quarter or less of the player's hit points remaining
+    pub low_hp_clears: u32,
+    pub clean_clears: u32,
+}
+
+impl Performance {
+    pub fn record_death(&mut self) {
+        self.deaths += 1;
+    }
+
+    pub fn record_clear(&mut self, hit_points: u32, max_hit_points: u32) {
+        if hit_points * 4 <= max_hit_points {
+            self.low_hp_clears += 1;
+        } else {
+            self.clean_clears += 1;
+        }
+    }
+
+    /// Players who clear levels comfortably get a bigger budget, and players who struggle get a
+    /// smaller one
+    fn budget_adjustment(&self) -> i32 {
+        (self.clean_clears as i32 - self.deaths as i32 - self.low_hp_clears as i32)
+            .clamp(-MAX_BUDGET_ADJUSTMENT, MAX_BUDGET_ADJUSTMENT)
+    }
+}
+
+/// Decides which npcs to place on a level by spending a budget across npc types
+pub struct SpawnDirector {
+    budget: u32,
+}
+
+impl SpawnDirector {
+    pub fn new(performance: &Performance) -> Self {
+        Self {
+            budget: (BASE_SPAWN_BUDGET as i32 + performance.budget_adjustment()) as u32,
+        }
+    }
+
+    /// The director for a level of a run played with the given rules
+    pub fn for_run(
+        performance: &Performance,
+        modifiers: RunModifiers,
+        difficulty: Difficulty,
+        new_game_plus: u32,
+    ) -> Self {
+        let mut spawn_director = Self::new(performance);
+        if modifiers.double_enemies {
+            spawn_director.double_budget();
+        }
+        spawn_director.scale_budget_for_new_game_plus(new_game_plus);
+        spawn_director.budget = spawn_director.budget * difficulty.spawn_budget_percent() / 100;
+        spawn_director
+    }
+
+    fn double_budget(&mut self) {
+        self.budget *= 2;
+    }
+
+    /// Each win carried into a new game adds half the budget again
+    fn scale_budget_for_new_game_plus(&mut self, new_game_plus: u32) {
+        self.budget += self.budget * new_game_plus / 2;
+    }
+
+    fn candidate_coords(world: &World, player_coord: Coord) -> Vec<Coord> {
+        world
+            .size()
+            .coord_iter_row_major()
+            .filter(|&coord| {
+                (coord - player_coord).magnitude2() as i32 >= MIN_SPAWN_DISTANCE_SQUARED
+                    && world.can_spawn_character_at(coord)
+                    && world.atmosphere_at(coord) == Atmosphere::Pressurised
+            })
+            .collect()
+    }
+
+    /// Places npcs until the budget runs out or there is nowhere left to put them, returning the
+    /// npcs that were placed. Npcs common on levels of the given theme are chosen more often.
+    pub fn populate<R: Rng>(
+        &self,
+        world: &mut World,
+        player_coord: Coord,
+        theme: LevelTheme,
+        rng: &mut R,
+    ) -> Vec<Entity> {
+        let mut candidates = Self::candidate_coords(world, player_coord);
+        let mut remaining = self.budget;
+        let mut spawned = Vec::new();
+        while !candidates.is_empty() {
+            let affordable = NpcType::ALL
+                .iter()
+                .filter(|&&npc_type| {
+                    npc_type.spawn_cost() <= remaining && theme.spawn_weight(npc_type) > 0
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            let npc_type =
+                match affordable.choose_weighted(rng, |&npc_type| theme.spawn_weight(npc_type)) {
+                    Ok(&npc_type) => npc_type,
+                    Err(_) => break,
+                };
+            let coord = candidates.swap_remove(rng.gen_range(0..candidates.len()));
+            spawned.push(world.spawn_npc(coord, npc_type));
+            remaining -= npc_type.spawn_cost();
+        }
+        spawned
+    }
+}
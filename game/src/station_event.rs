@@ -0,0 +1,131 @@
+use crate::{
+    world::data::Tile, CellVisibility, Config, Coord, DecalKind, Direction, Game, Message, NpcType,
+    SoundEffect, TimerKind,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+const MIN_TURNS_BETWEEN_EVENTS: u32 = 40;
+const MAX_TURNS_BETWEEN_EVENTS: u32 = 80;
+const DOCKING_PARTY_SIZE: usize = 3;
+/// Number of random cells to try when looking for somewhere for hostiles to dock
+const DOCKING_ATTEMPTS: usize = 100;
+const METEOR_CAMERA_SHAKE: u32 = 4;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StationEvent {
+    PowerOutage,
+    MeteorStrike,
+    HostilesDocking,
+}
+
+impl StationEvent {
+    const ALL: &'static [Self] = &[Self::PowerOutage, Self::MeteorStrike, Self::HostilesDocking];
+}
+
+/// Decides when level-wide events happen to break up the pacing of long levels
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EventDirector {
+    turns_until_next_event: u32,
+}
+
+impl EventDirector {
+    pub fn new<R: Rng>(rng: &mut R) -> Self {
+        Self {
+            turns_until_next_event: rng
+                .gen_range(MIN_TURNS_BETWEEN_EVENTS..=MAX_TURNS_BETWEEN_EVENTS),
+        }
+    }
+
+    /// Advances the director by a turn, returning the event that should happen this turn if any
+    pub fn tick<R: Rng>(&mut self, rng: &mut R) -> Option<StationEvent> {
+        self.turns_until_next_event = self.turns_until_next_event.saturating_sub(1);
+        if self.turns_until_next_event > 0 {
+            return None;
+        }
+        self.turns_until_next_event =
+            rng.gen_range(MIN_TURNS_BETWEEN_EVENTS..=MAX_TURNS_BETWEEN_EVENTS);
+        StationEvent::ALL.choose(rng).cloned()
+    }
+}
+
+impl Game {
+    pub(crate) fn tick_station_events(&mut self, config: &Config) {
+        match self.event_director.tick(&mut self.rng) {
+            Some(StationEvent::PowerOutage) => self.power_outage(),
+            Some(StationEvent::MeteorStrike) => self.meteor_strike(config),
+            Some(StationEvent::HostilesDocking) => self.hostiles_docking(),
+            None => (),
+        }
+    }
+
+    fn power_outage(&mut self) {
+        self.timers
+            .start(TimerKind::PowerOutage, self.balance.power_outage_turns);
+        self.messages.system(Message::new("message.power_failure"));
+    }
+
+    fn meteor_strike(&mut self, config: &Config) {
+        let walls = self
+            .world
+            .components
+            .tile
+            .iter()
+            .filter(|(_, tile)| tile.is_wall())
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        let wall = match walls.choose(&mut self.rng) {
+            Some(&wall) => wall,
+            None => return,
+        };
+        let coord = match self.world.spatial_table.coord_of(wall) {
+            Some(coord) => coord,
+            None => return,
+        };
+        self.world.remove_entity(wall);
+        self.emit_sound(SoundEffect::Explosion, coord);
+        self.emit_camera_shake(METEOR_CAMERA_SHAKE);
+        self.add_decal(coord, DecalKind::Scorch, config);
+        for direction in Direction::all() {
+            self.add_decal(coord + direction.coord(), DecalKind::Scorch, config);
+        }
+        self.messages.system(Message::new("message.meteor_strike"));
+    }
+
+    fn random_hidden_spawn_coord(&mut self) -> Option<Coord> {
+        let size = self.world.spatial_table.grid_size();
+        for _ in 0..DOCKING_ATTEMPTS {
+            let coord = Coord::new(
+                self.rng.gen_range(0..size.width() as i32),
+                self.rng.gen_range(0..size.height() as i32),
+            );
+            if let CellVisibility::Current { .. } = self.cell_visibility_at_coord(coord) {
+                continue;
+            }
+            if self.world.can_spawn_character_at(coord) {
+                return Some(coord);
+            }
+        }
+        None
+    }
+
+    fn hostiles_docking(&mut self) {
+        let coord = match self.random_hidden_spawn_coord() {
+            Some(coord) => coord,
+            None => return,
+        };
+        let mut num_spawned = 0;
+        for spawn_coord in std::iter::once(coord).chain(Direction::all().map(|d| coord + d.coord()))
+        {
+            if num_spawned >= DOCKING_PARTY_SIZE {
+                break;
+            }
+            if self.world.can_spawn_character_at(spawn_coord) {
+                self.world.spawn_npc(spawn_coord, NpcType::Zombie);
+                num_spawned += 1;
+            }
+        }
+        self.messages
+            .system(Message::new("message.boarders_docked"));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{names, Config, Entity, Game, GameControlFlow, GameOverReason, Message};
+use serde::{Deserialize, Serialize};
+
+/// Turns a character burns for after being caught in an explosion
+pub(crate) const BURNING_DURATION: u32 = 2;
+/// Turns a character is poisoned for after being hit by an npc with a poisonous attack
+pub(crate) const POISON_DURATION: u32 = 3;
+/// Turns a character is stunned for after being hit by a heavy blow or a bayonet
+pub(crate) const STUN_DURATION: u32 = 1;
+/// Turns a character is slowed for after being caught in the abomination's grip
+const SLOW_DURATION: u32 = 3;
+/// Turns a character is hasted for when nothing says otherwise
+const HASTE_DURATION: u32 = 3;
+/// Percentage of its usual speed a slowed character moves at
+const SLOWED_SPEED_PERCENT: u32 = 50;
+/// Percentage of its usual speed a hasted character moves at
+const HASTED_SPEED_PERCENT: u32 = 150;
+const POISON_DAMAGE: u32 = 1;
+const BURNING_DAMAGE: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusEffect {
+    /// Deals damage at the end of each turn
+    Poison,
+    /// The character loses their turns
+    Stun,
+    /// Deals damage at the end of each turn
+    Burning,
+    /// The character gains energy more slowly, so acts less often
+    Slowed,
+    /// The character gains energy more quickly, so acts more often
+    Hasted,
+}
+
+impl StatusEffect {
+    pub const ALL: &'static [Self] = &[
+        Self::Poison,
+        Self::Stun,
+        Self::Burning,
+        Self::Slowed,
+        Self::Hasted,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Poison => "poisoned",
+            Self::Stun => "stunned",
+            Self::Burning => "burning",
+            Self::Slowed => "slowed",
+            Self::Hasted => "hasted",
+        }
+    }
+
+    /// Turns the effect lasts when inflicted by an attack
+    pub(crate) fn default_duration(self) -> u32 {
+        match self {
+            Self::Poison => POISON_DURATION,
+            Self::Stun => STUN_DURATION,
+            Self::Burning => BURNING_DURATION,
+            Self::Slowed => SLOW_DURATION,
+            Self::Hasted => HASTE_DURATION,
+        }
+    }
+}
+
+/// Remaining turns of each status effect on a character
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct StatusEffects {
+    poison: u32,
+    stun: u32,
+    burning: u32,
+    #[serde(default)]
+    slowed: u32,
+    #[serde(default)]
+    hasted: u32,
+}
+
+impl StatusEffects {
+    fn duration_mut(&mut self, effect: StatusEffect) -> &mut u32 {
+        match effect {
+            StatusEffect::Poison => &mut self.poison,
+            StatusEffect::Stun => &mut self.stun,
+            StatusEffect::Burning => &mut self.burning,
+            StatusEffect::Slowed => &mut self.slowed,
+            StatusEffect::Hasted => &mut self.hasted,
+        }
+    }
+
+    pub fn duration(&self, effect: StatusEffect) -> u32 {
+        match effect {
+            StatusEffect::Poison => self.poison,
+            StatusEffect::Stun => self.stun,
+            StatusEffect::Burning => self.burning,
+            StatusEffect::Slowed => self.slowed,
+            StatusEffect::Hasted => self.hasted,
+        }
+    }
+
+    /// The effects currently on the character
+    pub fn iter(&self) -> impl Iterator<Item = StatusEffect> + '_ {
+        StatusEffect::ALL
+            .iter()
+            .copied()
+            .filter(|&effect| self.duration(effect) > 0)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Reapplying an effect doesn't stack, but does extend it to the longer duration
+    fn apply(&mut self, effect: StatusEffect, duration: u32) {
+        let current = self.duration_mut(effect);
+        *current = (*current).max(duration);
+    }
+
+    fn damage_per_turn(&self) -> u32 {
+        let mut damage = 0;
+        if self.poison > 0 {
+            damage += POISON_DAMAGE;
+        }
+        if self.burning > 0 {
+            damage += BURNING_DAMAGE;
+        }
+        damage
+    }
+
+    /// Percentage of the character's usual speed it moves at. Being slowed and hasted at once
+    /// cancels out.
+    pub(crate) fn speed_percent(&self) -> u32 {
+        match (self.slowed > 0, self.hasted > 0) {
+            (true, false) => SLOWED_SPEED_PERCENT,
+            (false, true) => HASTED_SPEED_PERCENT,
+            _ => 100,
+        }
+    }
+
+    /// Counts down the effects which last a number of turns. Stun counts down as turns are lost
+    /// instead.
+    fn tick(&mut self) {
+        self.poison = self.poison.saturating_sub(1);
+        self.burning = self.burning.saturating_sub(1);
+        self.slowed = self.slowed.saturating_sub(1);
+        self.hasted = self.hasted.saturating_sub(1);
+    }
+}
+
+impl Game {
+    pub(crate) fn apply_status_effect(
+        &mut self,
+        entity: Entity,
+        effect: StatusEffect,
+        duration: u32,
+    ) {
+        if !self.add_status_effect(entity, effect, duration) {
+            return;
+        }
+        let message = if self.is_player(entity) {
+            Message::with_args("message.player_status_effect", &[&effect.name()])
+        } else {
+            let name = names::capitalise(&self.npc_description(entity));
+            Message::with_args("message.npc_status_effect", &[&name, &effect.name()])
+        };
+        self.messages.combat(Some(entity), message);
+    }
+
+    /// Applies a status effect without announcing it, for effects on many characters at once
+    /// which are announced together. Returns whether the character wasn't already affected.
+    pub(crate) fn add_status_effect(
+        &mut self,
+        entity: Entity,
+        effect: StatusEffect,
+        duration: u32,
+    ) -> bool {
+        if !self.world.components.hit_points.contains(entity) {
+            return false;
+        }
+        let mut status_effects = self
+            .world
+            .components
+            .status_effects
+            .get(entity)
+            .copied()
+            .unwrap_or_default();
+        let already_affected = status_effects.duration(effect) > 0;
+        status_effects.apply(effect, duration);
+        self.world
+            .components
+            .status_effects
+            .insert(entity, status_effects);
+        !already_affected
+    }
+
+    pub fn status_effects(&self, entity: Entity) -> Vec<StatusEffect> {
+        self.world
+            .components
+            .status_effects
+            .get(entity)
+            .map(|status_effects| status_effects.iter().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn player_status_effects(&self) -> Vec<StatusEffect> {
+        self.status_effects(self.player_entity)
+    }
+
+    /// Stunned characters lose their turn. Returns whether the character was stunned, using up a
+    /// turn of the stun.
+    pub(crate) fn lose_turn_to_stun(&mut self, entity: Entity) -> bool {
+        match self.world.components.status_effects.get_mut(entity) {
+            Some(status_effects) if status_effects.stun > 0 => {
+                status_effects.stun -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Damages every poisoned or burning character and counts down their effects
+    pub(crate) fn tick_status_effects(&mut self, config: &Config) -> Option<GameControlFlow> {
+        let affected = self
+            .world
+            .components
+            .status_effects
+            .iter()
+            .map(|(entity, status_effects)| (entity, status_effects.damage_per_turn()))
+            .collect::<Vec<_>>();
+        for (entity, damage) in affected {
+            if let Some(status_effects) = self.world.components.status_effects.get_mut(entity) {
+                status_effects.tick();
+                if status_effects.is_empty() {
+                    self.world.components.status_effects.remove(entity);
+                }
+            }
+            if damage == 0 || !self.world.components.hit_points.contains(entity) {
+                continue;
+            }
+            let killed = self.damage_character(entity, damage, config);
+            if self.is_player(entity) {
+                if killed {
+                    return Some(GameControlFlow::GameOver(GameOverReason::Killed));
+                }
+            } else if killed {
+                let name = names::capitalise(&self.npc_description(entity));
+                self.messages.combat(
+                    Some(entity),
+                    Message::with_args("message.npc_bled_out", &[&name]),
+                );
+                self.npc_die(entity);
+            }
+        }
+        None
+    }
+}
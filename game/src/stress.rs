@@ -0,0 +1,101 @@
+use crate::{
+    explosion::GRENADE_BLAST,
+    world::{
+        data::{Gravity, Layer, Location, NpcType},
+        spawn::make_player,
+        World,
+    },
+    Config, Coord, EventDirector, Game, GameControlFlow, Identification, Input, Level, Size,
+};
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_isaac::Isaac64Rng;
+
+/// Far larger than any real level
+const STRESS_MAP_SIZE: Size = Size::new_u16(200, 200);
+/// The stress test keeps topping the npcs up to this many as they are killed
+const STRESS_NUM_NPCS: usize = 400;
+const STRESS_EXPLOSIONS_PER_TURN: usize = 8;
+/// One in this many cells has a wall, so explosions and npc pathfinding have something to work
+/// around
+const STRESS_WALL_CHANCE: u32 = 12;
+/// Explosions are kept at least this far from the player so the test can run indefinitely
+const STRESS_EXPLOSION_MIN_DISTANCE_SQUARED: u32 = 25;
+
+impl Game {
+    /// A worst-case scene for measuring performance: a huge level crowded with npcs which are
+    /// constantly being blown up by `stress_turn`
+    pub fn new_stress_test<R: Rng>(config: &Config, base_rng: &mut R) -> Self {
+        let mut rng = Isaac64Rng::seed_from_u64(base_rng.gen());
+        let event_director = EventDirector::new(&mut rng);
+        let identification = Identification::new(&mut rng);
+        let mut world = World::new(STRESS_MAP_SIZE);
+        let centre = Coord::new(
+            STRESS_MAP_SIZE.width() as i32 / 2,
+            STRESS_MAP_SIZE.height() as i32 / 2,
+        );
+        for coord in STRESS_MAP_SIZE.coord_iter_row_major() {
+            world.spawn_floor(coord);
+            let on_edge = coord.x == 0
+                || coord.y == 0
+                || coord.x == STRESS_MAP_SIZE.width() as i32 - 1
+                || coord.y == STRESS_MAP_SIZE.height() as i32 - 1;
+            if on_edge || (coord != centre && rng.gen_ratio(1, STRESS_WALL_CHANCE)) {
+                world.spawn_wall(coord);
+            }
+        }
+        let player_location = Location {
+            layer: Some(Layer::Character),
+            coord: centre,
+        };
+        let player_entity = world.insert_entity_data(player_location, make_player());
+        let level = Level {
+            world,
+            player_entity,
+            gravity: Gravity::Normal,
+            name: "Stress Test".to_string(),
+            theme: None,
+            report: Default::default(),
+        };
+        let mut game = Self::with_first_level(level, rng, event_director, identification, config);
+        game.spawn_stress_npcs();
+        game
+    }
+
+    fn spawn_stress_npcs(&mut self) {
+        let num_npcs = self.world.components.npc_type.iter().count();
+        let candidates = STRESS_MAP_SIZE
+            .coord_iter_row_major()
+            .filter(|&coord| self.world.can_spawn_character_at(coord))
+            .collect::<Vec<_>>();
+        let coords = candidates
+            .choose_multiple(&mut self.rng, STRESS_NUM_NPCS.saturating_sub(num_npcs))
+            .copied()
+            .collect::<Vec<_>>();
+        for coord in coords {
+            let &npc_type = NpcType::ALL.choose(&mut self.rng).unwrap();
+            self.world.spawn_npc(coord, npc_type);
+        }
+    }
+
+    /// Sets off explosions around the level, takes a turn, then replaces the npcs that were
+    /// killed. The player is healed after each turn so the test never ends early.
+    pub fn stress_turn(&mut self, config: &Config) -> Option<GameControlFlow> {
+        let player_coord = self.player_coord();
+        for _ in 0..STRESS_EXPLOSIONS_PER_TURN {
+            let centre = Coord::new(
+                self.rng.gen_range(0..STRESS_MAP_SIZE.width() as i32),
+                self.rng.gen_range(0..STRESS_MAP_SIZE.height() as i32),
+            );
+            if (centre - player_coord).magnitude2() < STRESS_EXPLOSION_MIN_DISTANCE_SQUARED {
+                continue;
+            }
+            self.explode(centre, GRENADE_BLAST, self.balance.grenade_damage, config);
+        }
+        let control_flow = self.step_turn(Input::Wait, config).ok().flatten();
+        if let Some(hit_points) = self.world.components.hit_points.get_mut(self.player_entity) {
+            hit_points.set_current(hit_points.max());
+        }
+        self.spawn_stress_npcs();
+        control_flow
+    }
+}
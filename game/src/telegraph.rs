@@ -0,0 +1,231 @@
+use crate::{
+    names, world::data::Alertness, CardinalDirection, CellVisibility, Coord, Entity, Game, NpcType,
+    StatusEffect,
+};
+use serde::{Deserialize, Serialize};
+
+/// What an npc intends to do on its next turn, assuming the player doesn't move
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NextAction {
+    /// Attack whatever is in the cell
+    Attack(Coord),
+    /// Start a heavy attack which will land on the cell the turn after
+    WindUp(Coord),
+    /// Fire a ranged attack at whatever is in the cell
+    Shoot(Coord),
+    Move(Coord),
+}
+
+/// How an npc feels about the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disposition {
+    Hostile,
+}
+
+/// What an npc is currently doing, as far as the player can tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Behaviour {
+    /// Close enough to attack the player
+    Attacking,
+    /// Closing in on the player
+    Hunting,
+    /// Heading towards a noise it heard
+    Investigating,
+    /// Looking for the player where it last saw them
+    Searching,
+    /// Running away from the player
+    Fleeing,
+    /// Has no way of reaching the player
+    Idle,
+}
+
+/// An npc's next action, described relative to the npc
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntendedAction {
+    Move(CardinalDirection),
+    Attack(Coord),
+}
+
+/// Everything the player can learn about an npc by looking at it
+#[derive(Debug, Clone)]
+pub struct NpcExamination {
+    pub description: String,
+    pub npc_type: NpcType,
+    pub disposition: Disposition,
+    pub behaviour: Behaviour,
+    pub intended_action: Option<IntendedAction>,
+    pub status_effects: Vec<StatusEffect>,
+}
+
+/// What an npc's planned action says about what it's doing
+pub(crate) fn behaviour(next_action: Option<NextAction>) -> Behaviour {
+    match next_action {
+        Some(NextAction::Attack(_) | NextAction::WindUp(_) | NextAction::Shoot(_)) => {
+            Behaviour::Attacking
+        }
+        Some(NextAction::Move(_)) => Behaviour::Hunting,
+        None => Behaviour::Idle,
+    }
+}
+
+impl Game {
+    /// Describes the npc at a cell if the player can currently see it
+    pub fn examine_npc(&self, coord: Coord) -> Option<NpcExamination> {
+        if !matches!(
+            self.cell_visibility_at_coord(coord),
+            CellVisibility::Current { .. }
+        ) {
+            return None;
+        }
+        let entity = self.world.spatial_table.layers_at(coord)?.character?;
+        let npc_type = *self.world.components.npc_type.get(entity)?;
+        let next_action = self.world.components.next_action.get(entity).copied();
+        let behaviour = self.npc_behaviour(entity, coord);
+        let intended_action = next_action.and_then(|next_action| match next_action {
+            NextAction::Attack(target) | NextAction::WindUp(target) | NextAction::Shoot(target) => {
+                Some(IntendedAction::Attack(target))
+            }
+            NextAction::Move(destination) => CardinalDirection::all()
+                .find(|direction| coord + direction.coord() == destination)
+                .map(IntendedAction::Move),
+        });
+        Some(NpcExamination {
+            description: names::capitalise(&self.npc_description(entity)),
+            npc_type,
+            disposition: Disposition::Hostile,
+            behaviour,
+            intended_action,
+            status_effects: self.status_effects(entity),
+        })
+    }
+
+    /// What an npc's planned action says about what it's doing. Npcs moving without a path to
+    /// the player, or without having seen the player, must be following up on a noise.
+    pub(crate) fn npc_behaviour(&self, entity: Entity, coord: Coord) -> Behaviour {
+        let next_action = self.world.components.next_action.get(entity).copied();
+        match behaviour(next_action) {
+            Behaviour::Hunting | Behaviour::Idle
+                if self.world.components.fleeing.contains(entity) =>
+            {
+                Behaviour::Fleeing
+            }
+            Behaviour::Hunting | Behaviour::Idle if self.is_searching(entity) => {
+                Behaviour::Searching
+            }
+            Behaviour::Hunting
+                if self.world.components.investigating.contains(entity)
+                    && (self.npc_alertness(entity) == Alertness::Suspicious
+                        || self
+                            .world
+                            .distance_map
+                            .direction_to_best_neighbour(coord)
+                            .is_none()) =>
+            {
+                Behaviour::Investigating
+            }
+            behaviour => behaviour,
+        }
+    }
+
+    /// Decides each npc's next action so it can be shown to the player ahead of time. Must be
+    /// called after `npc_turn` so the distance map reflects the player's current position.
+    pub(crate) fn plan_npc_actions(&mut self) {
+        let player_coord = self.player_coord();
+        let npcs = self
+            .world
+            .components
+            .npc_type
+            .iter()
+            .filter_map(|(entity, &npc_type)| {
+                Some((entity, npc_type, self.world.spatial_table.coord_of(entity)?))
+            })
+            .collect::<Vec<_>>();
+        for (entity, npc_type, coord) in npcs {
+            let delta = player_coord - coord;
+            let retreat = if self.world.components.fleeing.contains(entity) {
+                self.flee_step(coord)
+            } else {
+                self.kite_step(npc_type, coord, player_coord)
+            };
+            let alertness = self.npc_alertness(entity);
+            let next_action =
+                if let Some(&target) = self.world.components.pending_attack.get(entity) {
+                    Some(NextAction::Attack(target))
+                } else if alertness == Alertness::Unaware {
+                    None
+                } else if alertness == Alertness::Suspicious {
+                    self.investigation_step(entity, coord)
+                        .map(|direction| NextAction::Move(coord + direction.coord()))
+                } else if let Some(direction) = retreat {
+                    Some(NextAction::Move(coord + direction.coord()))
+                } else if delta.x.abs() + delta.y.abs() == 1 {
+                    if npc_type.winds_up() {
+                        Some(NextAction::WindUp(player_coord))
+                    } else {
+                        Some(NextAction::Attack(player_coord))
+                    }
+                } else if self.world.components.fleeing.contains(entity) {
+                    None
+                } else if let Some(target) = self.planned_shot(entity, npc_type, coord) {
+                    Some(NextAction::Shoot(target))
+                } else if self.is_searching(entity) {
+                    self.scent_step(npc_type, coord)
+                        .or_else(|| self.search_step(entity, coord))
+                        .map(|direction| NextAction::Move(coord + direction.coord()))
+                } else {
+                    self.flank_step(entity, coord)
+                        .or_else(|| self.world.distance_map.direction_to_best_neighbour(coord))
+                        .or_else(|| self.scent_step(npc_type, coord))
+                        .or_else(|| self.investigation_step(entity, coord))
+                        .map(|direction| NextAction::Move(coord + direction.coord()))
+                };
+            match next_action {
+                Some(next_action) => {
+                    self.world
+                        .components
+                        .next_action
+                        .insert(entity, next_action);
+                }
+                None => {
+                    self.world.components.next_action.remove(entity);
+                }
+            }
+        }
+    }
+
+    /// Cells which npcs the player can currently see will attack on their next turn
+    pub fn telegraphs(&self) -> Vec<Coord> {
+        self.world
+            .components
+            .next_action
+            .iter()
+            .filter_map(|(entity, next_action)| {
+                let target = match next_action {
+                    NextAction::Attack(target) | NextAction::Shoot(target) => *target,
+                    NextAction::WindUp(_) | NextAction::Move(_) => return None,
+                };
+                let coord = self.world.spatial_table.coord_of(entity)?;
+                match self.cell_visibility_at_coord(coord) {
+                    CellVisibility::Current { .. } => Some(target),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Cells where heavy attacks from npcs the player can currently see are about to land
+    pub fn pending_attacks(&self) -> Vec<Coord> {
+        self.world
+            .components
+            .pending_attack
+            .iter()
+            .filter_map(|(entity, &target)| {
+                let coord = self.world.spatial_table.coord_of(entity)?;
+                match self.cell_visibility_at_coord(coord) {
+                    CellVisibility::Current { .. } => Some(target),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+}
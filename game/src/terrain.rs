@@ -1,30 +1,140 @@
 use crate::{
+    boss, bsp,
+    connectivity::{self, TerrainReport},
+    prefab,
+    radiation::RAD_SUIT_PROTECTION,
+    secret,
+    weapon::AMMO_BOX_ROUNDS,
+    wfc,
     world::{
-        data::EntityData,
+        data::{EntityData, Faction, Gravity, NpcType},
         spatial::{Layer, Location},
         World,
     },
-    Entity,
+    Entity, LayoutStyle,
 };
 use coord_2d::{Coord, Size};
+use rand::Rng;
+
+/// Limit on how far new game+ shifts the odds of a level's gravity failing
+const MAX_NEW_GAME_PLUS_GRAVITY_SHIFT: u32 = 4;
+/// Layouts that can't be repaired are thrown away and generated again, up to this many times
+const MAX_TERRAIN_ATTEMPTS: u32 = 5;
 
 pub struct Terrain {
     pub world: World,
     pub player_entity: Entity,
+    pub gravity: Gravity,
+    /// Only filled in for generated levels
+    pub report: TerrainReport,
 }
 
 impl Terrain {
-    pub fn generate_text(player_data: EntityData) -> Self {
+    /// In new game+ the gravity fails more often and the station layout may be mirrored. A few
+    /// prefab rooms and vaults are stamped into the layout, and sometimes a secret room is hidden
+    /// in it. Generated interiors fall back to the station layout on the rare occasions
+    /// generation fails. Corridors are dug to any of the stairs or other mandatory features the
+    /// player can't reach, and layouts where that isn't possible are generated again.
+    pub fn generate_text<R: Rng>(
+        player_data: EntityData,
+        new_game_plus: u32,
+        layout_style: LayoutStyle,
+        rng: &mut R,
+    ) -> Self {
+        let unstable = new_game_plus.min(MAX_NEW_GAME_PLUS_GRAVITY_SHIFT);
+        let gravity = match rng.gen_range(0..10) + unstable {
+            0..=5 => Gravity::Normal,
+            6..=8 => Gravity::Low,
+            _ => Gravity::Zero,
+        };
+        let mut txt = String::new();
+        let mut report = TerrainReport::default();
+        for attempt in 1..=MAX_TERRAIN_ATTEMPTS {
+            let layout = Self::generate_layout(new_game_plus, layout_style, rng);
+            let layout = prefab::stamp_prefabs(&layout, rng);
+            let layout = secret::add_secret_room(&layout, rng);
+            let (repaired, attempt_report) = connectivity::repair_connectivity(&layout);
+            txt = repaired;
+            report = TerrainReport {
+                attempts: attempt,
+                ..attempt_report
+            };
+            if report.connected {
+                break;
+            }
+        }
+        let mut terrain = Self::parse(&txt, player_data, gravity);
+        terrain.report = report;
+        if terrain.report.connected {
+            log::debug!("{:?}", terrain.report);
+        } else {
+            log::warn!(
+                "generated a level that can't be completed:\n{}",
+                terrain.to_debug_string()
+            );
+        }
+        terrain
+    }
+
+    fn generate_layout<R: Rng>(
+        new_game_plus: u32,
+        layout_style: LayoutStyle,
+        rng: &mut R,
+    ) -> String {
+        let generated = match layout_style.choose(rng) {
+            LayoutStyle::Rooms => Some(bsp::generate_bsp_text(rng)),
+            LayoutStyle::Interiors => wfc::generate_wfc_text(rng),
+            LayoutStyle::Station | LayoutStyle::Mixed => None,
+        };
+        if let Some(txt) = generated {
+            txt
+        } else if new_game_plus > 0 && rng.gen() {
+            include_str!("terrain.txt")
+                .split('\n')
+                .map(|row| row.chars().rev().collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            include_str!("terrain.txt").to_string()
+        }
+    }
+
+    /// The fixed layout of the tutorial level
+    pub fn tutorial(player_data: EntityData) -> Self {
+        Self::parse(include_str!("tutorial.txt"), player_data, Gravity::Normal)
+    }
+
+    /// The fixed layout of the final level, where the abomination guards the escape pod
+    pub fn arena(player_data: EntityData) -> Self {
+        Self::parse(include_str!("arena.txt"), player_data, Gravity::Normal)
+    }
+
+    fn parse(txt: &str, player_data: EntityData, gravity: Gravity) -> Self {
+        Self::from_text(txt, player_data, gravity).expect("no player in terrain file")
+    }
+
+    /// Builds a level from text where each character describes a cell. Returns `None` if the
+    /// text has no '@' marking where the player starts.
+    pub fn from_text(txt: &str, player_data: EntityData, gravity: Gravity) -> Option<Self> {
         let mut player_entity: Option<Entity> = None;
-        let txt = include_str!("terrain.txt");
         let rows = txt.split('\n').collect::<Vec<_>>();
         let mut world = World::new(Size::new(rows[0].len() as u32, rows.len() as u32));
         for (y, row) in rows.into_iter().enumerate() {
             for (x, ch) in row.chars().enumerate() {
                 let coord = Coord::new(x as i32, y as i32);
-                world.spawn_floor(coord);
                 match ch {
-                    '.' => (),
+                    ':' => {
+                        world.spawn_gap(coord);
+                    }
+                    ',' => {
+                        world.spawn_hull(coord);
+                    }
+                    _ => {
+                        world.spawn_floor(coord);
+                    }
+                }
+                match ch {
+                    '.' | ':' | ',' => (),
                     '#' => {
                         world.spawn_wall(coord);
                     }
@@ -34,6 +144,46 @@ impl Terrain {
                     '>' => {
                         world.spawn_stairs_down(coord);
                     }
+                    'R' => {
+                        world.spawn_reactor(coord);
+                    }
+                    'E' => {
+                        world.spawn_escape_pod(coord);
+                    }
+                    'T' => {
+                        world.spawn_terminal(coord);
+                    }
+                    'C' => {
+                        world.spawn_coolant_tank(coord);
+                    }
+                    'D' => {
+                        world.spawn_drain(coord);
+                    }
+                    'L' => {
+                        world.spawn_locker(coord, Vec::new());
+                    }
+                    'B' => {
+                        world.spawn_barricade(coord);
+                    }
+                    'H' => {
+                        world.spawn_hidden_wall(coord);
+                    }
+                    'S' => {
+                        world.spawn_rad_suit(coord, RAD_SUIT_PROTECTION);
+                    }
+                    'a' => {
+                        world.spawn_ammo(coord, AMMO_BOX_ROUNDS);
+                    }
+                    'z' => {
+                        world.spawn_npc(coord, NpcType::Zombie);
+                    }
+                    'r' => {
+                        let npc = world.spawn_npc(coord, NpcType::Zombie);
+                        world.components.faction.insert(npc, Faction::Renegade);
+                    }
+                    'A' => {
+                        boss::spawn_boss(&mut world, coord);
+                    }
                     '@' => {
                         let player_location = Location {
                             layer: Some(Layer::Character),
@@ -46,10 +196,11 @@ impl Terrain {
                 }
             }
         }
-        let player_entity = player_entity.expect("no player in terrain file");
-        Self {
+        Some(Self {
             world,
-            player_entity,
-        }
+            player_entity: player_entity?,
+            gravity,
+            report: Default::default(),
+        })
     }
 }
@@ -0,0 +1,108 @@
+use crate::{
+    world::{
+        data::{NpcType, Tile},
+        World,
+    },
+    Game,
+};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+/// The part of the station a level belongs to. Decides which npcs are likely to be found there,
+/// and how the level looks and sounds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LevelTheme {
+    Medbay,
+    Engineering,
+    Hydroponics,
+    /// Long abandoned, and overrun by whatever found its way in
+    Derelict,
+}
+
+impl LevelTheme {
+    pub const ALL: &'static [Self] = &[
+        Self::Medbay,
+        Self::Engineering,
+        Self::Hydroponics,
+        Self::Derelict,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Medbay => "Medbay",
+            Self::Engineering => "Engineering",
+            Self::Hydroponics => "Hydroponics",
+            Self::Derelict => "Derelict",
+        }
+    }
+
+    /// Identifies the theme's colours in the app's palettes
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Medbay => "medbay",
+            Self::Engineering => "engineering",
+            Self::Hydroponics => "hydroponics",
+            Self::Derelict => "derelict",
+        }
+    }
+
+    pub(crate) fn choose<R: Rng>(rng: &mut R) -> Self {
+        *Self::ALL.choose(rng).unwrap()
+    }
+
+    /// How likely each type of npc is to be chosen when populating a level, relative to the
+    /// others. Npcs with a weight of 0 are never placed on levels of this theme.
+    pub(crate) fn spawn_weight(self, npc_type: NpcType) -> u32 {
+        match (self, npc_type) {
+            (_, NpcType::Abomination) => 0,
+            (Self::Medbay, NpcType::Zombie) => 3,
+            (Self::Medbay, NpcType::Skeleton) => 1,
+            (Self::Medbay, NpcType::Hound) => 1,
+            (Self::Engineering, NpcType::Zombie) => 1,
+            (Self::Engineering, NpcType::Skeleton) => 3,
+            (Self::Engineering, NpcType::Hound) => 1,
+            (Self::Hydroponics, NpcType::Zombie) => 1,
+            (Self::Hydroponics, NpcType::Skeleton) => 0,
+            (Self::Hydroponics, NpcType::Hound) => 3,
+            (Self::Derelict, NpcType::Zombie) => 1,
+            (Self::Derelict, NpcType::Skeleton) => 2,
+            (Self::Derelict, NpcType::Hound) => 2,
+        }
+    }
+
+    pub fn floor_tile(self) -> Tile {
+        match self {
+            Self::Medbay => Tile::FloorMedbay,
+            Self::Engineering => Tile::FloorEngineering,
+            Self::Hydroponics => Tile::FloorHydroponics,
+            Self::Derelict => Tile::FloorDerelict,
+        }
+    }
+
+    pub fn wall_tile(self) -> Tile {
+        match self {
+            Self::Medbay => Tile::WallMedbay,
+            Self::Engineering => Tile::WallEngineering,
+            Self::Hydroponics => Tile::WallHydroponics,
+            Self::Derelict => Tile::WallDerelict,
+        }
+    }
+}
+
+/// Replaces the plain floor and wall tiles of a level with the theme's variants
+pub(crate) fn apply_theme(world: &mut World, theme: LevelTheme) {
+    for (_, tile) in world.components.tile.iter_mut() {
+        *tile = match *tile {
+            Tile::Floor => theme.floor_tile(),
+            Tile::Wall => theme.wall_tile(),
+            other => other,
+        };
+    }
+}
+
+impl Game {
+    /// Levels that weren't generated, such as the tutorial, have no theme
+    pub fn level_theme(&self) -> Option<LevelTheme> {
+        self.level_theme
+    }
+}
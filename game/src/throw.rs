@@ -0,0 +1,105 @@
+use crate::{
+    projectile::Projectile,
+    world::data::{EntityData, Layer, Location, OnCollision},
+    ActionError, Config, Coord, Game, GameControlFlow, Message,
+};
+
+/// Maximum distance squared from the player to the cell they are throwing an item at
+const THROW_RANGE_SQUARED: u32 = 25;
+/// Damage dealt to a character hit by a thrown item that doesn't shatter
+const THROW_DAMAGE: u32 = 1;
+
+impl Game {
+    pub fn is_in_throw_range(&self, target: Coord) -> bool {
+        (target - self.player_coord()).magnitude2() <= THROW_RANGE_SQUARED
+    }
+
+    /// Thrown items fly like any other projectile, stopping at the first character or obstacle
+    /// in their way
+    pub(crate) fn throw_projectile(&self, target: Coord) -> Projectile {
+        Projectile::new(self.player_coord(), target, THROW_DAMAGE, OnCollision::Stop)
+            .with_source(self.player_entity)
+    }
+
+    /// Throws the item in the given inventory slot. Injectors shatter where they land, applying
+    /// their effect to any character they hit. Other items hurt whoever they hit and then fall
+    /// to the floor.
+    pub(crate) fn player_throw(
+        &mut self,
+        slot: usize,
+        target: Coord,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        if !self.is_in_throw_range(target) {
+            return Err(ActionError::OutOfRange);
+        }
+        let inventory = self
+            .world
+            .components
+            .inventory
+            .get_mut(self.player_entity)
+            .ok_or(ActionError::NoSuchSlot)?;
+        if slot >= inventory.items.len() {
+            return Err(ActionError::NoSuchSlot);
+        }
+        let data = inventory.items.remove(slot);
+        let name = self.examine_carried_item(&data).description;
+        self.messages.items(
+            Some(self.player_entity),
+            Message::with_args("message.item_thrown", &[&name]),
+        );
+        let mut projectile = self.throw_projectile(target);
+        let (path, hits) = self.projectile_flight(&mut projectile);
+        let hit = hits.first().copied();
+        if let Some(effect) = data.injector {
+            self.messages.items(
+                Some(self.player_entity),
+                Message::with_args("message.item_shattered", &[&name]),
+            );
+            if let Some(entity) = hit {
+                self.inject(entity, effect);
+            }
+            return Ok(None);
+        }
+        if let Some(entity) = hit {
+            if let Some(game_control_flow) = self.projectile_hit(&projectile, entity, config) {
+                return Ok(Some(game_control_flow));
+            }
+        }
+        self.land_thrown_item(&path, data, name);
+        Ok(None)
+    }
+
+    /// Leaves the item in the cell nearest to where it stopped, back along its path, which
+    /// doesn't already have an item in it
+    fn land_thrown_item(&mut self, path: &[Coord], data: EntityData, name: String) {
+        let player_coord = self.player_coord();
+        let coord = path
+            .iter()
+            .rev()
+            .copied()
+            .chain(std::iter::once(player_coord))
+            .find(|&coord| {
+                self.world
+                    .spatial_table
+                    .layers_at_checked(coord)
+                    .item
+                    .is_none()
+            });
+        match coord {
+            Some(coord) => {
+                self.world.insert_entity_data(
+                    Location {
+                        layer: Some(Layer::Item),
+                        coord,
+                    },
+                    data,
+                );
+            }
+            None => self.messages.items(
+                Some(self.player_entity),
+                Message::with_args("message.item_lost", &[&name]),
+            ),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerKind {
+    SelfDestruct,
+    PowerOutage,
+    /// Npcs can find the player from anywhere on the level
+    Alarm,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Timer {
+    pub kind: TimerKind,
+    pub remaining: u32,
+    pub total: u32,
+}
+
+impl Timer {
+    pub fn elapsed(&self) -> u32 {
+        self.total - self.remaining
+    }
+}
+
+/// Countdowns measured in turns which aren't associated with any particular entity
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Timers {
+    timers: Vec<Timer>,
+}
+
+impl Timers {
+    /// Starts a timer of the given kind unless one is already running
+    pub fn start(&mut self, kind: TimerKind, turns: u32) {
+        if self.get(kind).is_none() {
+            self.timers.push(Timer {
+                kind,
+                remaining: turns,
+                total: turns,
+            });
+        }
+    }
+
+    pub fn get(&self, kind: TimerKind) -> Option<&Timer> {
+        self.timers.iter().find(|timer| timer.kind == kind)
+    }
+
+    /// Advances each timer by a turn, removing and returning the kinds of timers which expired
+    pub fn tick(&mut self) -> Vec<TimerKind> {
+        let mut expired = Vec::new();
+        for timer in self.timers.iter_mut() {
+            timer.remaining = timer.remaining.saturating_sub(1);
+            if timer.remaining == 0 {
+                expired.push(timer.kind);
+            }
+        }
+        self.timers.retain(|timer| timer.remaining > 0);
+        expired
+    }
+}
@@ -0,0 +1,217 @@
+use crate::{
+    names,
+    status_effect::POISON_DURATION,
+    timer::TimerKind,
+    world::data::{Blast, GasKind, Tile},
+    Config, Coord, Direction, Entity, Game, GameControlFlow, GameOverReason, Message, SoundEffect,
+    StatusEffect,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+const SPIKE_TRAP_DAMAGE: u32 = 3;
+/// Area filled with poison gas when a gas trap goes off
+const GAS_TRAP_BLAST: Blast = Blast {
+    radius_squared: 2,
+    falloff: 0,
+    smoke: 0,
+};
+const GAS_TRAP_DENSITY: u8 = 6;
+/// Turns for which every npc on the level knows where the player is after an alarm goes off
+const ALARM_TURNS: u32 = 10;
+/// Each turn, the player has a one in this many chance to spot each hidden trap next to them
+const TRAP_DETECT_CHANCE: u32 = 3;
+/// Turns after going off before a gas trap has refilled and is hidden again
+const GAS_TRAP_REFILL_TURNS: u32 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrapKind {
+    Spike,
+    Gas,
+    Alarm,
+}
+
+impl TrapKind {
+    pub const ALL: &'static [Self] = &[Self::Spike, Self::Gas, Self::Alarm];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Spike => "spike trap",
+            Self::Gas => "gas trap",
+            Self::Alarm => "alarm trap",
+        }
+    }
+
+    pub fn tile(self) -> Tile {
+        match self {
+            Self::Spike => Tile::SpikeTrap,
+            Self::Gas => Tile::GasTrap,
+            Self::Alarm => Tile::AlarmTrap,
+        }
+    }
+
+    /// Spikes reset after they're triggered, but gas and alarm traps are used up
+    fn is_reusable(self) -> bool {
+        matches!(self, Self::Spike)
+    }
+
+    /// Turns before a used up trap is rearmed, or `None` if it only goes off once
+    fn refill_turns(self) -> Option<u32> {
+        match self {
+            Self::Gas => Some(GAS_TRAP_REFILL_TURNS),
+            Self::Spike | Self::Alarm => None,
+        }
+    }
+}
+
+/// Goes off when a character enters its cell. Hidden traps aren't drawn until they're spotted
+/// or triggered.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct Trap {
+    pub kind: TrapKind,
+    pub revealed: bool,
+}
+
+impl Trap {
+    pub fn hidden(kind: TrapKind) -> Self {
+        Self {
+            kind,
+            revealed: false,
+        }
+    }
+}
+
+impl Game {
+    fn trap_at(&self, coord: Coord) -> Option<(Entity, Trap)> {
+        let feature = self.world.spatial_table.layers_at(coord)?.feature?;
+        let trap = *self.world.components.trap.get(feature)?;
+        Some((feature, trap))
+    }
+
+    /// Sets off any trap in the cell the character has just entered
+    pub(crate) fn trigger_trap(
+        &mut self,
+        entity: Entity,
+        config: &Config,
+    ) -> Option<GameControlFlow> {
+        let coord = self.world.spatial_table.coord_of(entity)?;
+        let (trap_entity, trap) = self.trap_at(coord)?;
+        if trap.kind.is_reusable() {
+            if let Some(trap) = self.world.components.trap.get_mut(trap_entity) {
+                trap.revealed = true;
+            }
+        } else {
+            self.world.remove_entity(trap_entity);
+            if let Some(turns) = trap.kind.refill_turns() {
+                self.world.spawn_spent_trap(coord, trap.kind, turns);
+            }
+        }
+        let message = if self.is_player(entity) {
+            Message::with_args("message.player_triggers_trap", &[&trap.kind.name()])
+        } else {
+            let name = names::capitalise(&self.npc_description(entity));
+            Message::with_args("message.npc_triggers_trap", &[&name, &trap.kind.name()])
+        };
+        self.messages.combat(Some(entity), message);
+        match trap.kind {
+            TrapKind::Spike => {
+                self.emit_sound(SoundEffect::Hit, coord);
+                if self.damage_character(entity, SPIKE_TRAP_DAMAGE, config) {
+                    if self.is_player(entity) {
+                        return Some(GameControlFlow::GameOver(GameOverReason::Killed));
+                    }
+                    let name = names::capitalise(&self.npc_description(entity));
+                    self.messages.combat(
+                        Some(entity),
+                        Message::with_args("message.npc_impaled", &[&name]),
+                    );
+                    self.npc_die(entity);
+                }
+            }
+            TrapKind::Gas => {
+                for coord in self.explosion_area(coord, GAS_TRAP_BLAST) {
+                    self.world.add_gas(coord, GasKind::Poison, GAS_TRAP_DENSITY);
+                }
+                // the character who set it off is right in the thick of it
+                self.apply_status_effect(entity, StatusEffect::Poison, POISON_DURATION);
+            }
+            TrapKind::Alarm => {
+                self.messages
+                    .system(Message::new("message.alarm_triggered"));
+                self.timers.start(TimerKind::Alarm, ALARM_TURNS);
+                // the noise drives every npc on the level into a frenzy
+                let npcs = self
+                    .world
+                    .components
+                    .npc_type
+                    .iter()
+                    .map(|(npc, _)| npc)
+                    .collect::<Vec<_>>();
+                for npc in npcs {
+                    self.add_status_effect(npc, StatusEffect::Hasted, ALARM_TURNS);
+                }
+            }
+        }
+        None
+    }
+
+    /// Gives the player a chance to spot each hidden trap next to them
+    pub(crate) fn detect_traps(&mut self) {
+        let player_coord = self.player_coord();
+        for direction in Direction::all() {
+            let coord = player_coord + direction.coord();
+            let (trap_entity, trap) = match self.trap_at(coord) {
+                Some((trap_entity, trap)) if !trap.revealed => (trap_entity, trap),
+                _ => continue,
+            };
+            if !self.rng.gen_ratio(1, TRAP_DETECT_CHANCE) {
+                continue;
+            }
+            if let Some(trap) = self.world.components.trap.get_mut(trap_entity) {
+                trap.revealed = true;
+            }
+            self.messages.system(Message::with_args(
+                "message.trap_spotted",
+                &[&trap.kind.name()],
+            ));
+        }
+    }
+
+    /// While an alarm is sounding, npcs can find the player from anywhere on the level
+    pub(crate) fn is_alarm_sounding(&self) -> bool {
+        self.timers.get(TimerKind::Alarm).is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LevelDesign;
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    #[test]
+    fn gas_trap_refills_after_going_off() {
+        let design = LevelDesign::from_text("#####\n#@..#\n#####").unwrap();
+        let config = Config::default();
+        let mut rng = Isaac64Rng::seed_from_u64(0);
+        let mut game = Game::new_custom_level(&config, &design, &mut rng).unwrap();
+        let coord = game.player_coord();
+        game.world.spawn_trap(coord, TrapKind::Gas);
+        assert!(game.trigger_trap(game.player_entity, &config).is_none());
+        assert!(game.trap_at(coord).is_none());
+        // the trap waits for the cell to be empty before it comes back
+        game.world
+            .spatial_table
+            .update_coord(game.player_entity, coord + Coord::new(1, 0))
+            .unwrap();
+        for _ in 1..GAS_TRAP_REFILL_TURNS {
+            game.tick_respawn();
+        }
+        assert!(game.trap_at(coord).is_none());
+        game.tick_respawn();
+        let (_, trap) = game.trap_at(coord).expect("gas trap has refilled");
+        assert_eq!(trap.kind, TrapKind::Gas);
+        assert!(!trap.revealed);
+    }
+}
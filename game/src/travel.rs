@@ -0,0 +1,92 @@
+use crate::{
+    autoexplore::Explorable,
+    distance_map::{DistanceMap, PopulateContext},
+    ActionError, CellVisibility, Config, Coord, Game, GameControlFlow,
+};
+use serde::{Deserialize, Serialize};
+
+/// Where the player is travelling to, and how healthy they were when they took their last step
+/// there. Taking damage along the way interrupts travel.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub(crate) struct Travel {
+    destination: Coord,
+    hit_points: u32,
+}
+
+impl Game {
+    fn travel_distance_map(&self, destination: Coord) -> DistanceMap {
+        let size = self.world.size();
+        let explorable = Explorable {
+            world: &self.world,
+            visibility_grid: &self.visibility_grid,
+        };
+        let mut ctx = PopulateContext::default();
+        ctx.add(destination);
+        let mut distance_map = DistanceMap::new(size);
+        ctx.populate_approach(&explorable, size.width() + size.height(), &mut distance_map);
+        distance_map
+    }
+
+    /// The cells the player would walk through on the way to a destination, ending with the
+    /// destination. Empty if the player doesn't know a way there.
+    pub fn travel_path(&self, destination: Coord) -> Vec<Coord> {
+        if let CellVisibility::Never = self.cell_visibility_at_coord(destination) {
+            return Vec::new();
+        }
+        let distance_map = self.travel_distance_map(destination);
+        let mut path = Vec::new();
+        let mut coord = self.player_coord();
+        while coord != destination {
+            match distance_map.direction_to_best_neighbour(coord) {
+                Some(direction) => {
+                    coord = coord + direction.coord();
+                    path.push(coord);
+                }
+                None => return Vec::new(),
+            }
+        }
+        path
+    }
+
+    /// The rest of the route the player is currently travelling, if they're travelling
+    pub fn current_travel_path(&self) -> Vec<Coord> {
+        self.travel
+            .map(|travel| self.travel_path(travel.destination))
+            .unwrap_or_default()
+    }
+
+    /// Takes a step towards a destination. Travel is interrupted if the player was hurt since the
+    /// previous step, or if there are npcs in view.
+    pub(crate) fn player_travel(
+        &mut self,
+        travel: Option<Travel>,
+        destination: Coord,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        let hit_points = self.player_hit_points().current();
+        if let Some(travel) = travel {
+            if travel.destination == destination && hit_points < travel.hit_points {
+                return Err(ActionError::TravelInterrupted);
+            }
+        }
+        if self.is_npc_visible() {
+            return Err(ActionError::NpcInView);
+        }
+        let player_coord = self.player_coord();
+        if player_coord == destination {
+            return Err(ActionError::AtDestination);
+        }
+        if let CellVisibility::Never = self.cell_visibility_at_coord(destination) {
+            return Err(ActionError::NoPath);
+        }
+        let direction = self
+            .travel_distance_map(destination)
+            .direction_to_best_neighbour(player_coord)
+            .ok_or(ActionError::NoPath)?;
+        self.travel = Some(Travel {
+            destination,
+            hit_points,
+        });
+        Ok(self.player_walk(direction, config))
+    }
+}
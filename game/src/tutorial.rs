@@ -0,0 +1,117 @@
+use crate::{terrain::Terrain, world::data::EntityData, Game, GameControlFlow, Level, Message};
+use serde::{Deserialize, Serialize};
+
+/// Something the player is asked to do during the tutorial. Steps must be completed in order.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialStep {
+    Move,
+    Melee,
+    ThrowGrenade,
+    PickUpItem,
+    Stairs,
+}
+
+impl TutorialStep {
+    pub const ALL: &'static [Self] = &[
+        Self::Move,
+        Self::Melee,
+        Self::ThrowGrenade,
+        Self::PickUpItem,
+        Self::Stairs,
+    ];
+
+    fn next(self) -> Option<Self> {
+        match self {
+            Self::Move => Some(Self::Melee),
+            Self::Melee => Some(Self::ThrowGrenade),
+            Self::ThrowGrenade => Some(Self::PickUpItem),
+            Self::PickUpItem => Some(Self::Stairs),
+            Self::Stairs => None,
+        }
+    }
+
+    /// Key of the step's prompt in the app's locale. The app fills in the keys the player has
+    /// bound to the inputs the step asks for.
+    pub fn prompt_key(self) -> &'static str {
+        match self {
+            Self::Move => "tutorial.move",
+            Self::Melee => "tutorial.melee",
+            Self::ThrowGrenade => "tutorial.throw_grenade",
+            Self::PickUpItem => "tutorial.pick_up_item",
+            Self::Stairs => "tutorial.stairs",
+        }
+    }
+}
+
+/// Progress through the tutorial
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Tutorial {
+    step: Option<TutorialStep>,
+}
+
+impl Default for Tutorial {
+    fn default() -> Self {
+        Self {
+            step: Some(TutorialStep::Move),
+        }
+    }
+}
+
+pub(crate) fn tutorial_level(player_data: EntityData) -> Level {
+    let Terrain {
+        world,
+        player_entity,
+        gravity,
+        report,
+    } = Terrain::tutorial(player_data);
+    Level {
+        world,
+        player_entity,
+        gravity,
+        name: "Training Deck".to_string(),
+        theme: None,
+        report,
+    }
+}
+
+impl Game {
+    pub fn is_tutorial(&self) -> bool {
+        self.tutorial.is_some()
+    }
+
+    /// The step of the tutorial the player is currently on, if they are playing the tutorial
+    pub fn tutorial_step(&self) -> Option<TutorialStep> {
+        self.tutorial.as_ref().and_then(|tutorial| tutorial.step)
+    }
+
+    pub(crate) fn prompt_tutorial_step(&mut self) {
+        if let Some(step) = self.tutorial_step() {
+            self.messages.system(Message::new(step.prompt_key()));
+        }
+    }
+
+    /// Called when the player does something taught by the tutorial. This only has an effect if
+    /// it's the thing the tutorial is currently asking for.
+    pub(crate) fn complete_tutorial_step(&mut self, step: TutorialStep) {
+        let tutorial = match self.tutorial.as_mut() {
+            Some(tutorial) => tutorial,
+            None => return,
+        };
+        if tutorial.step == Some(step) {
+            tutorial.step = step.next();
+            self.prompt_tutorial_step();
+        }
+    }
+
+    /// The stairs only lead out of the tutorial once everything else has been done
+    pub(crate) fn tutorial_take_stairs(&mut self) -> Option<GameControlFlow> {
+        if self.tutorial_step() == Some(TutorialStep::Stairs) {
+            self.complete_tutorial_step(TutorialStep::Stairs);
+            Some(GameControlFlow::TutorialComplete)
+        } else {
+            self.messages
+                .system(Message::new("message.tutorial_unfinished"));
+            None
+        }
+    }
+}
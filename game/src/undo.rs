@@ -0,0 +1,37 @@
+use crate::{Config, Game, Message};
+
+impl Game {
+    /// Serializes the game so the turn about to be taken can be undone. Returns `None` if undo
+    /// is disabled, which it always is in ironman runs.
+    pub(crate) fn undo_snapshot(&self, config: &Config) -> Option<String> {
+        if !config.undo || self.is_ironman() {
+            return None;
+        }
+        // the previous snapshot isn't serialized, so only a single turn can be undone
+        Some(ron::to_string(self).expect("failed to snapshot game for undo"))
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undo.is_some()
+    }
+
+    /// Puts the game back how it was before the most recent turn. Returns whether there was a
+    /// turn to undo. The run timer keeps counting, and messages logged during the undone turn
+    /// aren't reported again.
+    pub fn undo(&mut self) -> bool {
+        let snapshot = match self.undo.take() {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+        let mut previous: Game = ron::from_str(&snapshot).expect("failed to restore undo snapshot");
+        previous.run_timer = std::mem::take(&mut self.run_timer);
+        previous.external_events = std::mem::take(&mut self.external_events);
+        previous.event_log = std::mem::take(&mut self.event_log);
+        previous.mark_messages_reported();
+        previous
+            .messages
+            .system(Message::new("message.turn_undone"));
+        *self = previous;
+        true
+    }
+}
@@ -0,0 +1,306 @@
+use crate::{
+    noise::GUNFIRE_LOUDNESS,
+    projectile::Projectile,
+    world::{
+        data::{Attachment, Rarity, Weapon},
+        spatial::Layers,
+    },
+    ActionError, CellVisibility, Config, Coord, Entity, Game, GameControlFlow, Message,
+};
+
+/// Rounds in each box of ammo found lying around the station
+pub(crate) const AMMO_BOX_ROUNDS: u32 = 3;
+
+/// What the player can tell about an item by looking at it
+#[derive(Debug, Clone)]
+pub struct ItemExamination {
+    /// Either the description itself or its key in the app's locale
+    pub description: String,
+    pub rarity: Rarity,
+}
+
+impl ItemExamination {
+    pub(crate) fn common(description: String) -> Self {
+        Self {
+            description,
+            rarity: Rarity::Common,
+        }
+    }
+}
+
+impl Game {
+    pub fn player_weapon(&self) -> Option<Weapon> {
+        self.world
+            .components
+            .weapon
+            .get(self.player_entity)
+            .copied()
+    }
+
+    /// Loads any ammo the player is standing on into their weapon. Ammo is left where it is if
+    /// the player has nothing to load it into.
+    pub(crate) fn pick_up_ammo(&mut self) {
+        let item_entity = match self.world.spatial_table.layers_at(self.player_coord()) {
+            Some(&Layers {
+                item: Some(item_entity),
+                ..
+            }) => item_entity,
+            _ => return,
+        };
+        let rounds = match self.world.components.ammo.get(item_entity) {
+            Some(&rounds) => rounds,
+            None => return,
+        };
+        let weapon = match self.world.components.weapon.get_mut(self.player_entity) {
+            Some(weapon) => weapon,
+            None => {
+                self.messages.items(
+                    Some(item_entity),
+                    Message::new("message.ammo_without_weapon"),
+                );
+                return;
+            }
+        };
+        weapon.ammo += rounds;
+        let name = weapon.kind.name();
+        self.world.remove_entity(item_entity);
+        self.messages.items(
+            Some(item_entity),
+            Message::with_args("message.ammo_loaded", &[&rounds, &name]),
+        );
+    }
+
+    pub fn player_holstered_weapon(&self) -> Option<Weapon> {
+        self.world
+            .components
+            .holstered
+            .get(self.player_entity)
+            .copied()
+    }
+
+    /// Swaps the weapon in the player's hands with the one in their holster. With only one
+    /// weapon, this holsters it or draws it again.
+    pub(crate) fn player_swap_weapons(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        if self.is_player_weapon_cursed() {
+            return Err(ActionError::WeaponCursed);
+        }
+        let held = self.world.components.weapon.remove(self.player_entity);
+        let holstered = self.world.components.holstered.remove(self.player_entity);
+        if let Some(drawn) = holstered {
+            self.world
+                .components
+                .weapon
+                .insert(self.player_entity, drawn);
+        }
+        if let Some(held) = held {
+            self.world
+                .components
+                .holstered
+                .insert(self.player_entity, held);
+        }
+        let message = match (held, holstered) {
+            (Some(held), Some(drawn)) => Message::with_args(
+                "message.weapon_swapped",
+                &[&held.kind.name(), &drawn.kind.name()],
+            ),
+            (Some(held), None) => {
+                Message::with_args("message.weapon_holstered", &[&held.kind.name()])
+            }
+            (None, Some(drawn)) => {
+                Message::with_args("message.weapon_drawn", &[&drawn.kind.name()])
+            }
+            (None, None) => return Err(ActionError::NoWeapon),
+        };
+        self.messages.items(Some(self.player_entity), message);
+        self.reveal_player_weapon_curse();
+        Ok(None)
+    }
+
+    /// Picks up any weapon the player is standing on. Their current weapon (if any) goes in
+    /// their holster if it's empty, and is otherwise left in its place.
+    pub(crate) fn pick_up_weapon(&mut self) {
+        let player_coord = self.player_coord();
+        let item_entity = match self.world.spatial_table.layers_at(player_coord) {
+            Some(&Layers {
+                item: Some(item_entity),
+                ..
+            }) => item_entity,
+            _ => return,
+        };
+        let weapon = match self.world.components.weapon.get(item_entity) {
+            Some(&weapon) => weapon,
+            None => return,
+        };
+        if self.modifiers.no_ranged_weapons {
+            self.messages.items(
+                Some(self.player_entity),
+                Message::with_args("message.weapon_left_by_vow", &[&weapon.kind.name()]),
+            );
+            return;
+        }
+        if self.is_player_weapon_cursed() {
+            if let Some(held) = self.player_weapon() {
+                self.messages.items(
+                    Some(self.player_entity),
+                    Message::with_args(
+                        "message.weapon_swap_cursed",
+                        &[&held.kind.name(), &weapon.kind.name()],
+                    ),
+                );
+            }
+            return;
+        }
+        self.world.remove_entity(item_entity);
+        let previous = self
+            .world
+            .components
+            .weapon
+            .insert(self.player_entity, weapon);
+        let holster_is_empty = !self.world.components.holstered.contains(self.player_entity);
+        if let (Some(previous), true) = (previous, holster_is_empty) {
+            self.world
+                .components
+                .holstered
+                .insert(self.player_entity, previous);
+            self.messages.items(
+                Some(self.player_entity),
+                Message::with_args(
+                    "message.weapon_swap_holstered",
+                    &[&previous.kind.name(), &weapon.kind.name()],
+                ),
+            );
+        } else if let Some(dropped) = previous {
+            self.world.spawn_weapon(player_coord, dropped);
+            self.messages.items(
+                Some(self.player_entity),
+                Message::with_args(
+                    "message.weapon_swap_dropped",
+                    &[&dropped.kind.name(), &weapon.kind.name()],
+                ),
+            );
+        } else {
+            self.messages.items(
+                Some(self.player_entity),
+                Message::with_args("message.item_picked_up", &[&weapon.kind.name()]),
+            );
+        }
+        self.reveal_player_weapon_curse();
+    }
+
+    /// The attachment lying under the player, if it can be fitted to the weapon they're holding
+    pub(crate) fn fittable_attachment_at_player(&self) -> Option<(Entity, Attachment)> {
+        let item_entity = self
+            .world
+            .spatial_table
+            .layers_at(self.player_coord())?
+            .item?;
+        let attachment = *self.world.components.attachment.get(item_entity)?;
+        if self.player_weapon()?.can_fit(attachment) {
+            Some((item_entity, attachment))
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn player_fit_attachment(&mut self) -> Result<Option<GameControlFlow>, ActionError> {
+        let (item_entity, attachment) = self
+            .fittable_attachment_at_player()
+            .ok_or(ActionError::InvalidInteraction)?;
+        self.world.remove_entity(item_entity);
+        let weapon = self
+            .world
+            .components
+            .weapon
+            .get_mut(self.player_entity)
+            .ok_or(ActionError::NoWeapon)?;
+        weapon.fit(attachment);
+        let message = Message::with_args(
+            "message.attachment_fitted",
+            &[&attachment.name(), &weapon.kind.name()],
+        );
+        self.messages.items(Some(self.player_entity), message);
+        Ok(None)
+    }
+
+    /// Describes the weapon, attachment, injector or ammo lying at a cell if the player can currently see it
+    pub fn examine_item(&self, coord: Coord) -> Option<ItemExamination> {
+        if !matches!(
+            self.cell_visibility_at_coord(coord),
+            CellVisibility::Current { .. }
+        ) {
+            return None;
+        }
+        let item_entity = self.world.spatial_table.layers_at(coord)?.item?;
+        let components = &self.world.components;
+        if let Some(weapon) = components.weapon.get(item_entity) {
+            return Some(ItemExamination {
+                description: format!("{}, {} ammo", weapon.description(), weapon.ammo),
+                rarity: weapon.rarity,
+            });
+        }
+        if let Some(&effect) = components.injector.get(item_entity) {
+            return Some(ItemExamination::common(
+                self.injector_name_key(effect).to_string(),
+            ));
+        }
+        if let Some(&rounds) = components.ammo.get(item_entity) {
+            return Some(ItemExamination::common(format!(
+                "{} rounds of ammo",
+                rounds
+            )));
+        }
+        let attachment = components.attachment.get(item_entity)?;
+        let fits = match self.player_weapon() {
+            Some(weapon) if weapon.can_fit(*attachment) => "fits your weapon",
+            Some(_) => "already fitted to your weapon",
+            None => "needs a weapon",
+        };
+        Some(ItemExamination::common(format!(
+            "{}, {}",
+            attachment.name(),
+            fits
+        )))
+    }
+
+    pub(crate) fn weapon_projectile(&self, weapon: &Weapon, target: Coord) -> Projectile {
+        Projectile::new(
+            self.player_coord(),
+            target,
+            weapon.damage,
+            weapon.on_collision,
+        )
+        .with_bounces(weapon.bounces)
+        .with_homing(weapon.homing)
+        .with_affixes(weapon)
+        .with_source(self.player_entity)
+    }
+
+    pub(crate) fn player_fire_weapon(
+        &mut self,
+        target: Coord,
+        config: &Config,
+    ) -> Result<Option<GameControlFlow>, ActionError> {
+        let weapon = self.player_weapon().ok_or(ActionError::NoWeapon)?;
+        if (target - self.player_coord()).magnitude2() > weapon.range_squared {
+            return Err(ActionError::OutOfRange);
+        }
+        let held = self
+            .world
+            .components
+            .weapon
+            .get_mut(self.player_entity)
+            .filter(|weapon| weapon.ammo > 0)
+            .ok_or(ActionError::NoAmmo)?;
+        held.ammo -= 1;
+        self.messages.combat(
+            Some(self.player_entity),
+            Message::with_args("message.weapon_fired", &[&weapon.kind.name()]),
+        );
+        self.make_noise(self.player_coord(), GUNFIRE_LOUDNESS);
+        if let Some(game_control_flow) = self.curse_drawback(config) {
+            return Ok(Some(game_control_flow));
+        }
+        let projectile = self.weapon_projectile(&weapon, target);
+        Ok(self.fire_projectile(projectile, config))
+    }
+}
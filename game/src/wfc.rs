@@ -0,0 +1,372 @@
+use coord_2d::Coord;
+use rand::{seq::SliceRandom, Rng};
+use std::collections::{HashMap, VecDeque};
+
+/// Hand-made interiors which generated interiors are built to resemble, separated by blank
+/// lines
+const WFC_SAMPLES: &str = include_str!("wfc_samples.txt");
+
+/// Width and height of the windows of sample cells which generated interiors are pieced
+/// together from. Bigger windows copy larger structures from the samples.
+const WFC_WINDOW: usize = 3;
+const WFC_WIDTH: usize = 30;
+const WFC_HEIGHT: usize = 20;
+/// Generation starts over if it paints itself into a corner this many times, and gives up
+/// after that
+const WFC_ATTEMPTS: usize = 10;
+/// Interiors where less of the map than this is connected to the rest are thrown away
+const WFC_MIN_FLOOR_PERCENT: usize = 30;
+
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+type Window = Vec<Vec<char>>;
+
+fn rotate(window: &Window) -> Window {
+    let size = window.len();
+    (0..size)
+        .map(|x| (0..size).rev().map(|y| window[y][x]).collect())
+        .collect()
+}
+
+fn mirror(window: &Window) -> Window {
+    window
+        .iter()
+        .map(|row| row.iter().rev().copied().collect())
+        .collect()
+}
+
+/// Every window in the samples, in all eight orientations, with how often each one appears
+fn learn_windows() -> Vec<(Window, u32)> {
+    let mut counts: HashMap<Window, u32> = HashMap::new();
+    let mut order = Vec::new();
+    for sample in WFC_SAMPLES.split("\n\n") {
+        let rows = sample
+            .lines()
+            .filter(|row| !row.is_empty())
+            .map(|row| row.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        for top in 0..(height + 1).saturating_sub(WFC_WINDOW) {
+            for left in 0..(width + 1).saturating_sub(WFC_WINDOW) {
+                let mut window = rows[top..top + WFC_WINDOW]
+                    .iter()
+                    .map(|row| row[left..left + WFC_WINDOW].to_vec())
+                    .collect::<Window>();
+                for _ in 0..4 {
+                    window = rotate(&window);
+                    for orientation in [window.clone(), mirror(&window)] {
+                        let count = counts.entry(orientation.clone()).or_insert_with(|| {
+                            order.push(orientation);
+                            0
+                        });
+                        *count += 1;
+                    }
+                }
+            }
+        }
+    }
+    order
+        .into_iter()
+        .map(|window| {
+            let count = counts[&window];
+            (window, count)
+        })
+        .collect()
+}
+
+/// Whether `b` can sit at an offset of `(dx, dy)` from `a`, which is when the two windows agree
+/// everywhere they overlap
+fn agrees(a: &Window, b: &Window, (dx, dy): (i32, i32)) -> bool {
+    let size = WFC_WINDOW as i32;
+    (0..size).all(|y| {
+        (0..size).all(|x| {
+            let (bx, by) = (x - dx, y - dy);
+            bx < 0
+                || by < 0
+                || bx >= size
+                || by >= size
+                || a[y as usize][x as usize] == b[by as usize][bx as usize]
+        })
+    })
+}
+
+fn neighbour(index: usize, (dx, dy): (i32, i32)) -> Option<usize> {
+    let (x, y) = (
+        (index % WFC_WIDTH) as i32 + dx,
+        (index / WFC_WIDTH) as i32 + dy,
+    );
+    if x < 0 || y < 0 || x >= WFC_WIDTH as i32 || y >= WFC_HEIGHT as i32 {
+        return None;
+    }
+    Some(y as usize * WFC_WIDTH + x as usize)
+}
+
+/// Pieces a grid together from overlapping windows of the samples, so that every part of it
+/// looks like part of a sample. Each cell keeps count of how many of the windows still possible
+/// in each neighbouring cell allow each of its own windows, so ruling out a window only has to
+/// revisit the windows it allowed. Returns `None` if it ran into a cell no window fits.
+fn collapse<R: Rng>(
+    windows: &[(Window, u32)],
+    compatible: &[Vec<Vec<usize>>],
+    rng: &mut R,
+) -> Option<Vec<Vec<char>>> {
+    let num_cells = WFC_WIDTH * WFC_HEIGHT;
+    let mut wave = vec![vec![true; windows.len()]; num_cells];
+    let mut remaining = vec![windows.len(); num_cells];
+    // `support[cell][window][direction]` counts the windows in the cell in the opposite
+    // direction which allow `window` in `cell`
+    let initial_support = (0..windows.len())
+        .map(|window| {
+            let mut counts = [0; DIRECTIONS.len()];
+            for (direction, count) in counts.iter_mut().enumerate() {
+                *count = compatible[(direction + 2) % DIRECTIONS.len()][window].len();
+            }
+            counts
+        })
+        .collect::<Vec<_>>();
+    let mut support = vec![initial_support; num_cells];
+    let mut removed = Vec::new();
+    loop {
+        // the cell with the fewest possibilities left is the least likely to cause trouble later
+        let fewest = match remaining.iter().copied().filter(|&count| count > 1).min() {
+            Some(fewest) => fewest,
+            None => break,
+        };
+        let candidates = (0..num_cells)
+            .filter(|&index| remaining[index] == fewest)
+            .collect::<Vec<_>>();
+        let &index = candidates.choose(rng)?;
+        let options = (0..windows.len())
+            .filter(|&window| wave[index][window])
+            .collect::<Vec<_>>();
+        let &chosen = options
+            .choose_weighted(rng, |&window| windows[window].1)
+            .ok()?;
+        for window in options {
+            if window != chosen {
+                wave[index][window] = false;
+                removed.push((index, window));
+            }
+        }
+        remaining[index] = 1;
+        while let Some((index, window)) = removed.pop() {
+            for (direction, &offset) in DIRECTIONS.iter().enumerate() {
+                let neighbour = match neighbour(index, offset) {
+                    Some(neighbour) => neighbour,
+                    None => continue,
+                };
+                for &other in &compatible[direction][window] {
+                    let count = &mut support[neighbour][other][direction];
+                    *count -= 1;
+                    if *count == 0 && wave[neighbour][other] {
+                        wave[neighbour][other] = false;
+                        remaining[neighbour] -= 1;
+                        if remaining[neighbour] == 0 {
+                            return None;
+                        }
+                        removed.push((neighbour, other));
+                    }
+                }
+            }
+        }
+    }
+    let cells = (0..WFC_HEIGHT)
+        .map(|y| {
+            (0..WFC_WIDTH)
+                .map(|x| {
+                    let window = wave[y * WFC_WIDTH + x]
+                        .iter()
+                        .position(|&possible| possible);
+                    window.map_or('#', |window| windows[window].0[0][0])
+                })
+                .collect()
+        })
+        .collect();
+    Some(cells)
+}
+
+fn is_walkable(ch: char) -> bool {
+    matches!(ch, '.' | '+')
+}
+
+fn get(cells: &[Vec<char>], coord: Coord) -> char {
+    if coord.x < 0 || coord.y < 0 {
+        return '#';
+    }
+    cells
+        .get(coord.y as usize)
+        .and_then(|row| row.get(coord.x as usize))
+        .copied()
+        .unwrap_or('#')
+}
+
+fn coords() -> impl Iterator<Item = Coord> {
+    (0..WFC_HEIGHT as i32).flat_map(|y| (0..WFC_WIDTH as i32).map(move |x| Coord::new(x, y)))
+}
+
+/// Steps from `start` to each walkable cell connected to it
+fn distances(cells: &[Vec<char>], start: Coord) -> HashMap<Coord, usize> {
+    let mut distances = HashMap::new();
+    distances.insert(start, 0);
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+    while let Some(coord) = queue.pop_front() {
+        let distance = distances[&coord];
+        for (dx, dy) in DIRECTIONS {
+            let neighbour = coord + Coord::new(dx, dy);
+            if is_walkable(get(cells, neighbour)) && !distances.contains_key(&neighbour) {
+                distances.insert(neighbour, distance + 1);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+    distances
+}
+
+/// A solid feature can go in a cell without blocking the way between any of the cells around
+/// it. The cells around it are walked in a ring, and all the walkable cells next to it must be
+/// part of one unbroken stretch of walkable cells.
+fn is_out_of_the_way(cells: &[Vec<char>], coord: Coord) -> bool {
+    const RING: [(i32, i32); 8] = [
+        (0, -1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (0, 1),
+        (-1, 1),
+        (-1, 0),
+        (-1, -1),
+    ];
+    let ring = RING.map(|(dx, dy)| get(cells, coord + Coord::new(dx, dy)));
+    if ring.contains(&'+') {
+        return false;
+    }
+    let open = ring.map(|ch| matches!(ch, '.' | '@' | '>' | 'E' | 'S' | 'a' | 'D'));
+    let stretches = (0..RING.len())
+        .filter(|&index| open[index] && !open[(index + RING.len() - 1) % RING.len()])
+        .count();
+    let all_open = open.iter().all(|&open| open);
+    stretches <= 1 && !all_open
+}
+
+/// Generates a station interior in the format of the terrain files, pieced together from the
+/// sample interiors. Only the largest connected part of the interior is kept. Returns `None`
+/// if generation keeps failing.
+pub(crate) fn generate_wfc_text<R: Rng>(rng: &mut R) -> Option<String> {
+    let windows = learn_windows();
+    let compatible = DIRECTIONS
+        .iter()
+        .map(|&offset| {
+            windows
+                .iter()
+                .map(|(a, _)| {
+                    (0..windows.len())
+                        .filter(|&b| agrees(a, &windows[b].0, offset))
+                        .collect()
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<Vec<usize>>>>();
+    for _ in 0..WFC_ATTEMPTS {
+        let mut cells = match collapse(&windows, &compatible, rng) {
+            Some(cells) => cells,
+            None => continue,
+        };
+        for coord in coords() {
+            let on_edge = coord.x == 0
+                || coord.y == 0
+                || coord.x == WFC_WIDTH as i32 - 1
+                || coord.y == WFC_HEIGHT as i32 - 1;
+            if on_edge {
+                cells[coord.y as usize][coord.x as usize] = '#';
+            }
+        }
+        let mut largest = HashMap::new();
+        for coord in coords() {
+            let reached = largest.contains_key(&coord);
+            if get(&cells, coord) == '.' && !reached {
+                let region = distances(&cells, coord);
+                if region.len() > largest.len() {
+                    largest = region;
+                }
+            }
+        }
+        if largest.len() * 100 < WFC_MIN_FLOOR_PERCENT * WFC_WIDTH * WFC_HEIGHT {
+            continue;
+        }
+        // wall up anything which can't be reached
+        for coord in coords() {
+            if !largest.contains_key(&coord) {
+                cells[coord.y as usize][coord.x as usize] = '#';
+            }
+        }
+        let mut floor = largest
+            .keys()
+            .copied()
+            .filter(|&coord| get(&cells, coord) == '.')
+            .collect::<Vec<_>>();
+        floor.sort_by_key(|coord| (coord.y, coord.x));
+        let player_coord = *floor.choose(rng)?;
+        let stairs_coord = distances(&cells, player_coord)
+            .into_iter()
+            .filter(|&(coord, _)| get(&cells, coord) == '.')
+            .max_by_key(|&(coord, distance)| (distance, coord.y, coord.x))
+            .map(|(coord, _)| coord)?;
+        cells[player_coord.y as usize][player_coord.x as usize] = '@';
+        cells[stairs_coord.y as usize][stairs_coord.x as usize] = '>';
+        for ch in ['E', 'T', 'R', 'L', 'L', 'C', 'S', 'a', 'D'] {
+            let solid = matches!(ch, 'T' | 'R' | 'L' | 'C');
+            let spots = floor
+                .iter()
+                .copied()
+                .filter(|&coord| {
+                    get(&cells, coord) == '.' && (!solid || is_out_of_the_way(&cells, coord))
+                })
+                .collect::<Vec<_>>();
+            if let Some(&coord) = spots.choose(rng) {
+                cells[coord.y as usize][coord.x as usize] = ch;
+            }
+        }
+        // the interior floats in space
+        let hull = ",".repeat(WFC_WIDTH + 2);
+        let rows = std::iter::once(hull.clone())
+            .chain(
+                cells
+                    .into_iter()
+                    .map(|row| format!(",{},", row.into_iter().collect::<String>())),
+            )
+            .chain(std::iter::once(hull))
+            .collect::<Vec<_>>();
+        return Some(rows.join("\n"));
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_isaac::Isaac64Rng;
+
+    fn generate(seed: u64) -> Option<String> {
+        generate_wfc_text(&mut Isaac64Rng::seed_from_u64(seed))
+    }
+
+    #[test]
+    fn same_seed_gives_same_interior() {
+        for seed in 0..4 {
+            assert_eq!(generate(seed), generate(seed));
+        }
+    }
+
+    #[test]
+    fn interior_has_start_and_stairs() {
+        let interiors = (0..4).filter_map(generate).collect::<Vec<_>>();
+        assert!(!interiors.is_empty());
+        for interior in interiors {
+            assert_eq!(interior.matches('@').count(), 1);
+            assert_eq!(interior.matches('>').count(), 1);
+        }
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{ActionError, Config, GameControlFlow, GameOverReason, Input, Menu as GameMenu};
+use crate::{
+    ActionError, Config, Ending, GameControlFlow, GameOverReason, Input, Interaction, MenuRequest,
+};
+use coord_2d::Coord;
 use direction::CardinalDirection;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -16,18 +19,15 @@ pub struct RunningGame {
 impl RunningGame {
     pub fn new(game: Game, running: Running) -> Self {
         let _ = running;
-        Self {
-            game: game.inner_game,
-        }
+        let mut game = game.inner_game;
+        game.prepare_for_save();
+        Self { game }
     }
 
     pub fn into_game(self) -> (Game, Running) {
-        (
-            Game {
-                inner_game: self.game,
-            },
-            Running(Private),
-        )
+        let mut game = self.game;
+        game.mark_messages_reported();
+        (Game { inner_game: game }, Running(Private))
     }
 }
 
@@ -38,12 +38,22 @@ struct Private;
 pub struct Running(Private);
 
 #[derive(Debug)]
-pub struct Win(Private);
+pub struct Win {
+    private: Private,
+    pub ending: Ending,
+}
+
+/// The player has just arrived on a new level
+#[derive(Debug)]
+pub struct LevelChange {
+    private: Private,
+    pub level: u32,
+}
 
 #[derive(Debug)]
 pub struct Menu {
     private: Private,
-    pub menu: GameMenu,
+    pub request: MenuRequest,
 }
 
 #[derive(Debug)]
@@ -52,6 +62,8 @@ pub enum Witness {
     GameOver(GameOverReason),
     Win(Win),
     Menu(Menu),
+    LevelChange(LevelChange),
+    TutorialComplete,
 }
 
 impl Witness {
@@ -65,9 +77,12 @@ impl Menu {
         let Self { private, .. } = self;
         Witness::running(private)
     }
-    pub fn commit(self, game: &mut Game, choice: crate::MenuChoice) -> Witness {
+    /// Answers the menu, which takes a turn. If the answer is rejected the game carries on as if
+    /// the menu had been cancelled.
+    pub fn commit(self, game: &mut Game, choice: crate::MenuChoice, config: &Config) -> Witness {
         let Self { private, .. } = self;
-        game.witness_handle_choice(choice, private)
+        let (witness, _) = game.witness_handle_input(Input::Choose(choice), config, private);
+        witness
     }
 }
 
@@ -87,9 +102,52 @@ pub fn new_game<R: Rng>(
     (g, Running(Private))
 }
 
+/// Continues with the character from a game the player has just won
+pub fn new_game_plus<R: Rng>(
+    config: &Config,
+    previous: &Game,
+    base_rng: &mut R,
+) -> (Game, Running) {
+    let g = Game {
+        inner_game: crate::Game::new_plus(config, &previous.inner_game, base_rng),
+    };
+    (g, Running(Private))
+}
+
+pub fn new_tutorial<R: Rng>(config: &Config, base_rng: &mut R) -> (Game, Running) {
+    let g = Game {
+        inner_game: crate::Game::new_tutorial(config, base_rng),
+    };
+    (g, Running(Private))
+}
+
+/// Starts on a hand-made level. Returns `None` if the level has nowhere for the player to start.
+pub fn new_custom_level<R: Rng>(
+    config: &Config,
+    design: &crate::LevelDesign,
+    base_rng: &mut R,
+) -> Option<(Game, Running)> {
+    let inner_game = crate::Game::new_custom_level(config, design, base_rng)?;
+    Some((Game { inner_game }, Running(Private)))
+}
+
+/// A worst-case scene for measuring performance
+pub fn new_stress_test<R: Rng>(config: &Config, base_rng: &mut R) -> (Game, Running) {
+    let g = Game {
+        inner_game: crate::Game::new_stress_test(config, base_rng),
+    };
+    (g, Running(Private))
+}
+
 impl Win {
     pub fn into_running(self) -> Running {
-        Running(self.0)
+        Running(self.private)
+    }
+}
+
+impl LevelChange {
+    pub fn into_running(self) -> Running {
+        Running(self.private)
     }
 }
 
@@ -112,19 +170,138 @@ impl Running {
         game.witness_handle_tick(since_last_tick, config, private)
     }
 
+    /// Takes a turn with an arbitrary input. The other methods taking a turn are shorthands for
+    /// this.
+    pub fn step_turn(
+        self,
+        game: &mut Game,
+        input: Input,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        let Self(private) = self;
+        game.witness_handle_input(input, config, private)
+    }
+
+    /// Puts the game back how it was before the most recent turn, if undo is enabled
+    pub fn undo(self, game: &mut Game) -> Witness {
+        let Self(private) = self;
+        game.inner_game.undo();
+        Witness::running(private)
+    }
+
     pub fn walk(
         self,
         game: &mut Game,
         direction: CardinalDirection,
         config: &Config,
     ) -> (Witness, Result<(), ActionError>) {
-        let Self(private) = self;
-        game.witness_handle_input(Input::Walk(direction), config, private)
+        self.step_turn(game, Input::Walk(direction), config)
     }
 
     pub fn wait(self, game: &mut Game, config: &Config) -> (Witness, Result<(), ActionError>) {
-        let Self(private) = self;
-        game.witness_handle_input(Input::Wait, config, private)
+        self.step_turn(game, Input::Wait, config)
+    }
+
+    pub fn search(self, game: &mut Game, config: &Config) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::Search, config)
+    }
+
+    pub fn destroy_corpse(
+        self,
+        game: &mut Game,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::DestroyCorpse, config)
+    }
+
+    pub fn drag(self, game: &mut Game, config: &Config) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::Drag, config)
+    }
+
+    pub fn brace(self, game: &mut Game, config: &Config) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::Brace, config)
+    }
+
+    pub fn throw_grenade(
+        self,
+        game: &mut Game,
+        target: Coord,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::ThrowGrenade(target), config)
+    }
+
+    pub fn fire_weapon(
+        self,
+        game: &mut Game,
+        target: Coord,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::FireWeapon(target), config)
+    }
+
+    /// Takes a step towards somewhere unexplored. Fails if there are npcs in view or nowhere left
+    /// to explore.
+    pub fn autoexplore(
+        self,
+        game: &mut Game,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::AutoExplore, config)
+    }
+
+    /// Takes a step towards a destination. Fails if there are npcs in view, the player was hurt
+    /// since their last step towards the same destination, or there's no known path.
+    pub fn travel_to(
+        self,
+        game: &mut Game,
+        destination: Coord,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::TravelTo(destination), config)
+    }
+
+    pub fn pick_up(self, game: &mut Game, config: &Config) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::PickUp, config)
+    }
+
+    /// Drops the item in the given inventory slot onto the player's cell
+    pub fn drop_item(
+        self,
+        game: &mut Game,
+        slot: usize,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::Drop(slot), config)
+    }
+
+    /// Throws the item in the given inventory slot at a cell
+    pub fn throw(
+        self,
+        game: &mut Game,
+        slot: usize,
+        target: Coord,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::Throw { slot, target }, config)
+    }
+
+    pub fn swap_weapons(
+        self,
+        game: &mut Game,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::SwapWeapons, config)
+    }
+
+    pub fn interact(
+        self,
+        game: &mut Game,
+        coord: Coord,
+        interaction: Interaction,
+        config: &Config,
+    ) -> (Witness, Result<(), ActionError>) {
+        self.step_turn(game, Input::Interact(coord, interaction), config)
     }
 }
 
@@ -135,14 +312,9 @@ impl Game {
         config: &Config,
         private: Private,
     ) -> (Witness, Result<(), ActionError>) {
-        match self.inner_game.handle_input(input, config) {
+        match self.inner_game.step_turn(input, config) {
             Err(e) => (Witness::running(private), Err(e)),
-            Ok(None) => (Witness::running(private), Ok(())),
-            Ok(Some(GameControlFlow::GameOver(reason))) => (Witness::GameOver(reason), Ok(())),
-            Ok(Some(GameControlFlow::Menu(menu))) => {
-                (Witness::Menu(Menu { private, menu }), Ok(()))
-            }
-            Ok(Some(GameControlFlow::Win)) => (Witness::Win(Win(Private)), Ok(())),
+            Ok(control_flow) => (self.handle_control_flow(control_flow, private), Ok(())),
         }
     }
 
@@ -154,8 +326,12 @@ impl Game {
         match control_flow {
             None => Witness::running(private),
             Some(GameControlFlow::GameOver(reason)) => Witness::GameOver(reason),
-            Some(GameControlFlow::Win) => Witness::Win(Win(private)),
-            Some(GameControlFlow::Menu(menu)) => Witness::Menu(Menu { private, menu }),
+            Some(GameControlFlow::Win(ending)) => Witness::Win(Win { private, ending }),
+            Some(GameControlFlow::OpenMenu(request)) => Witness::Menu(Menu { private, request }),
+            Some(GameControlFlow::LevelChange(level)) => {
+                Witness::LevelChange(LevelChange { private, level })
+            }
+            Some(GameControlFlow::TutorialComplete) => Witness::TutorialComplete,
         }
     }
 
@@ -169,15 +345,24 @@ impl Game {
         self.handle_control_flow(control_flow, private)
     }
 
-    fn witness_handle_choice(&mut self, choice: crate::MenuChoice, private: Private) -> Witness {
-        let control_flow = self.inner_game.handle_choice(choice);
-        self.handle_control_flow(control_flow, private)
-    }
-
     pub fn inner_ref(&self) -> &crate::Game {
         &self.inner_game
     }
 
+    /// Advances the stress test by a turn. The stress test never ends, so this doesn't produce a
+    /// witness.
+    pub fn stress_turn(&mut self, config: &Config) {
+        let _ = self.inner_game.stress_turn(config);
+    }
+
+    pub fn take_external_events(&mut self) -> Vec<crate::ExternalEvent> {
+        self.inner_game.take_external_events()
+    }
+
+    pub fn take_new_messages(&mut self) -> Vec<crate::LogEntry> {
+        self.inner_game.take_new_messages()
+    }
+
     pub fn into_running_game(self, running: Running) -> RunningGame {
         RunningGame::new(self, running)
     }
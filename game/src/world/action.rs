@@ -0,0 +1,160 @@
+use crate::{
+    world::{
+        data::{Atmosphere, Gravity, Tile},
+        spatial::Layers,
+        World,
+    },
+    CardinalDirection, Entity,
+};
+use coord_2d::Coord;
+use direction::Direction;
+
+/// How a character gets around from a particular cell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovementMode {
+    Walking,
+    /// Moving imparts momentum, and the character keeps moving until they collide with something
+    /// or brace against a wall
+    Drifting,
+}
+
+impl Gravity {
+    /// Multiplier applied to the distance characters are knocked back
+    pub fn knockback_multiplier(self) -> u32 {
+        match self {
+            Self::Low => 2,
+            Self::Normal | Self::Zero => 1,
+        }
+    }
+
+    pub fn can_jump_gaps(self) -> bool {
+        self == Self::Low
+    }
+
+    /// In zero gravity characters keep moving in the direction they last moved until they hit
+    /// something
+    pub fn drifts(self) -> bool {
+        self == Self::Zero
+    }
+}
+
+impl World {
+    pub fn movement_mode(&self, coord: Coord, gravity: Gravity) -> MovementMode {
+        if gravity.drifts() || self.atmosphere_at(coord) == Atmosphere::Vacuum {
+            MovementMode::Drifting
+        } else {
+            MovementMode::Walking
+        }
+    }
+
+    /// Whether there is something solid next to `coord` that a drifting character could grab
+    pub fn can_brace_at(&self, coord: Coord) -> bool {
+        Direction::all().any(|direction| {
+            if let Some(&Layers {
+                feature: Some(feature_entity),
+                ..
+            }) = self.spatial_table.layers_at(coord + direction.coord())
+            {
+                self.components.solid.contains(feature_entity)
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Moves a character one cell in the direction of its momentum. If something is in the way
+    /// the character stops and loses its momentum. Returns whether the character moved.
+    pub fn drift(&mut self, entity: Entity) -> bool {
+        let direction = match self.components.momentum.get(entity) {
+            Some(&direction) => direction,
+            None => return false,
+        };
+        let next_coord = match self.spatial_table.coord_of(entity) {
+            Some(coord) => coord + direction.coord(),
+            None => return false,
+        };
+        if self.can_spawn_character_at(next_coord) {
+            self.spatial_table.update_coord(entity, next_coord).unwrap();
+            true
+        } else {
+            self.components.momentum.remove(entity);
+            false
+        }
+    }
+
+    pub fn is_gap(&self, coord: Coord) -> bool {
+        if let Some(&Layers {
+            floor: Some(floor_entity),
+            ..
+        }) = self.spatial_table.layers_at(coord)
+        {
+            self.components.gap.contains(floor_entity)
+        } else {
+            false
+        }
+    }
+
+    /// The cell a character at `coord` ends up in after taking a step in `direction` under the
+    /// given gravity, or `None` if the step would leave them over a gap. This doesn't check for
+    /// anything occupying the destination.
+    pub fn step_destination(
+        &self,
+        coord: Coord,
+        direction: CardinalDirection,
+        gravity: Gravity,
+    ) -> Option<Coord> {
+        let next_coord = coord + direction.coord();
+        if !self.is_gap(next_coord) {
+            return Some(next_coord);
+        }
+        if gravity.can_jump_gaps() {
+            let landing_coord = next_coord + direction.coord();
+            if self.can_spawn_character_at(landing_coord) {
+                return Some(landing_coord);
+            }
+        }
+        None
+    }
+
+    /// Pushes a character up to `distance` cells in `direction`, stopping early if something is
+    /// in the way. Returns the number of cells the character moved.
+    pub fn knock_back(
+        &mut self,
+        entity: Entity,
+        direction: CardinalDirection,
+        distance: u32,
+    ) -> u32 {
+        let mut moved = 0;
+        while moved < distance {
+            let coord = match self.spatial_table.coord_of(entity) {
+                Some(coord) => coord,
+                None => break,
+            };
+            let next_coord = coord + direction.coord();
+            if !self.can_spawn_character_at(next_coord) {
+                break;
+            }
+            self.spatial_table.update_coord(entity, next_coord).unwrap();
+            moved += 1;
+        }
+        moved
+    }
+
+    /// Turns the hidden wall at `coord`, if there is one, into the closed door it was disguising.
+    /// Returns whether a hidden wall was found.
+    pub fn reveal_hidden_wall(&mut self, coord: Coord) -> bool {
+        let feature = match self.spatial_table.layers_at(coord) {
+            Some(&Layers {
+                feature: Some(feature),
+                ..
+            }) => feature,
+            _ => return false,
+        };
+        if self.components.tile.get(feature) != Some(&Tile::WallHidden) {
+            return false;
+        }
+        self.remove_entity(feature);
+        self.spawn_door(coord);
+        true
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{
+    world::{data::Atmosphere, spatial::Layers, World},
+    CardinalDirection, Entity,
+};
+use coord_2d::Coord;
+
+impl World {
+    /// Cells default to being pressurised unless their floor says otherwise. Cells with no floor
+    /// (including those outside the map) are open to space.
+    pub fn atmosphere_at(&self, coord: Coord) -> Atmosphere {
+        if let Some(&Layers {
+            floor: Some(floor_entity),
+            ..
+        }) = self.spatial_table.layers_at(coord)
+        {
+            self.components
+                .atmosphere
+                .get(floor_entity)
+                .cloned()
+                .unwrap_or(Atmosphere::Pressurised)
+        } else {
+            Atmosphere::Vacuum
+        }
+    }
+}
+
+pub const MAX_LIQUID_DEPTH: u8 = 6;
+/// Characters have to swim in liquid at least this deep
+pub const DEEP_LIQUID_DEPTH: u8 = 3;
+/// Amount of liquid removed from a drain's cell each turn
+const DRAIN_RATE: u8 = 2;
+
+impl World {
+    fn floor_entity_at(&self, coord: Coord) -> Option<Entity> {
+        self.spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.floor)
+    }
+
+    pub fn liquid_depth_at(&self, coord: Coord) -> u8 {
+        self.floor_entity_at(coord)
+            .and_then(|floor_entity| self.components.liquid_depth.get(floor_entity).cloned())
+            .unwrap_or(0)
+    }
+
+    pub fn is_deep_liquid(&self, coord: Coord) -> bool {
+        self.liquid_depth_at(coord) >= DEEP_LIQUID_DEPTH
+    }
+
+    /// Liquid can flow into any cell with a floor that isn't blocked by something solid. Closed
+    /// doors are solid so they hold liquid back.
+    fn liquid_can_enter(&self, coord: Coord) -> bool {
+        if let Some(&Layers {
+            floor: Some(_),
+            feature,
+            ..
+        }) = self.spatial_table.layers_at(coord)
+        {
+            !feature.is_some_and(|feature| self.components.solid.contains(feature))
+        } else {
+            false
+        }
+    }
+
+    fn set_liquid_depth(&mut self, coord: Coord, depth: u8) {
+        if let Some(floor_entity) = self.floor_entity_at(coord) {
+            if depth == 0 {
+                self.components.liquid_depth.remove(floor_entity);
+            } else {
+                self.components
+                    .liquid_depth
+                    .insert(floor_entity, depth.min(MAX_LIQUID_DEPTH));
+            }
+        }
+    }
+
+    /// Returns the amount of liquid actually added, which may be less than `amount` if the cell
+    /// is full or can't hold liquid
+    pub fn add_liquid(&mut self, coord: Coord, amount: u8) -> u8 {
+        if !self.liquid_can_enter(coord) {
+            return 0;
+        }
+        let depth = self.liquid_depth_at(coord);
+        let new_depth = depth.saturating_add(amount).min(MAX_LIQUID_DEPTH);
+        self.set_liquid_depth(coord, new_depth);
+        new_depth - depth
+    }
+
+    /// Advances liquid by a turn. Sources release liquid into neighbouring cells, liquid spreads
+    /// from deeper cells into shallower ones, and drains and breaches to space carry it
+    /// away.
+    pub fn flow_liquid(&mut self) {
+        let sources = self
+            .components
+            .liquid_source
+            .iter()
+            .map(|(entity, &remaining)| (entity, remaining))
+            .collect::<Vec<_>>();
+        for (entity, mut remaining) in sources {
+            if let Some(coord) = self.spatial_table.coord_of(entity) {
+                for direction in CardinalDirection::all() {
+                    if remaining == 0 {
+                        break;
+                    }
+                    remaining -= self.add_liquid(coord + direction.coord(), 1) as u32;
+                }
+            }
+            if remaining == 0 {
+                self.components.liquid_source.remove(entity);
+            } else {
+                self.components.liquid_source.insert(entity, remaining);
+            }
+        }
+        let wet_cells = self
+            .components
+            .liquid_depth
+            .iter()
+            .filter_map(|(floor_entity, &depth)| {
+                self.spatial_table
+                    .coord_of(floor_entity)
+                    .map(|coord| (coord, depth))
+            })
+            .collect::<Vec<_>>();
+        let mut transfers = Vec::new();
+        for &(coord, depth) in &wet_cells {
+            let mut remaining = depth;
+            for direction in CardinalDirection::all() {
+                let neighbour = coord + direction.coord();
+                if remaining == 0 {
+                    break;
+                }
+                if self.liquid_can_enter(neighbour)
+                    && self.liquid_depth_at(neighbour) + 1 < remaining
+                {
+                    transfers.push((coord, neighbour));
+                    remaining -= 1;
+                }
+            }
+        }
+        for (from, to) in transfers {
+            self.set_liquid_depth(from, self.liquid_depth_at(from).saturating_sub(1));
+            self.set_liquid_depth(to, self.liquid_depth_at(to) + 1);
+        }
+        for (coord, _) in wet_cells {
+            if self.atmosphere_at(coord) == Atmosphere::Vacuum {
+                self.set_liquid_depth(coord, 0);
+            } else if self.is_drain(coord) {
+                self.set_liquid_depth(
+                    coord,
+                    self.liquid_depth_at(coord).saturating_sub(DRAIN_RATE),
+                );
+            }
+        }
+    }
+
+    fn is_drain(&self, coord: Coord) -> bool {
+        if let Some(&Layers {
+            feature: Some(feature_entity),
+            ..
+        }) = self.spatial_table.layers_at(coord)
+        {
+            self.components.drain.contains(feature_entity)
+        } else {
+            false
+        }
+    }
+}
@@ -1,6 +1,13 @@
 pub use crate::world::spatial::{Layer, Location};
+use crate::{
+    energy::NORMAL_SPEED, identification::InjectorEffect, Boss, Container, Inventory, LastSeen,
+    NextAction, Shield, StatusEffect, StatusEffects, Trap, TrapKind,
+};
+use coord_2d::Coord;
+use direction::CardinalDirection;
 use entity_table::declare_entity_module;
 use serde::{Deserialize, Serialize};
+use visible_area_detection::{vision_distance::Circle, Light};
 
 declare_entity_module! {
     components {
@@ -9,6 +16,95 @@ declare_entity_module! {
         door_state: DoorState,
         opacity: u8,
         stairs_down: (),
+        reactor: (),
+        escape_pod: (),
+        decal: Decal,
+        npc_type: NpcType,
+        hit_points: Meter,
+        corpse: Corpse,
+        respawn: Respawn,
+        door_close_countdown: u32,
+        terminal: (),
+        gap: (),
+        atmosphere: Atmosphere,
+        momentum: CardinalDirection,
+        liquid_depth: u8,
+        /// Remaining volume of liquid that this entity will release
+        liquid_source: u32,
+        coolant_tank: (),
+        drain: (),
+        oxygen: Meter,
+        radiation_source: u32,
+        /// Percentage of radiation blocked
+        radiation_protection: u32,
+        rads: Rads,
+        grenades: u32,
+        next_action: NextAction,
+        /// Cell which an npc will strike at the start of its next turn
+        pending_attack: Coord,
+        /// Important npcs have names
+        name: String,
+        /// A ranged weapon, either held by a character or lying on the floor
+        weapon: Weapon,
+        /// Hides the entity from homing projectiles
+        chaff: (),
+        /// An item which can be fitted to a weapon
+        attachment: Attachment,
+        injector: InjectorEffect,
+        /// Energy gained by a character each turn. Characters act once for each
+        /// `ENERGY_PER_ACTION` energy they have.
+        speed: u32,
+        /// Energy accumulated towards a character's next action
+        energy: u32,
+        inventory: Inventory,
+        status_effects: StatusEffects,
+        /// Items stored inside a feature, which come out when it's opened
+        container: Container,
+        trap: Trap,
+        /// Floor covered in the remains of a destroyed wall
+        rubble: (),
+        /// The character is climbing over rubble or wading and will lose its next action
+        clambering: (),
+        /// Turns until the fire on this floor burns out
+        fire: u32,
+        /// A door which won't open without a keycard of this colour
+        locked: KeyColour,
+        /// A keycard lying on the floor
+        keycard: KeyColour,
+        /// Keycards the player has picked up on the current level
+        keycards: Vec<KeyColour>,
+        /// Smoke or toxic gas hanging over this floor
+        gas: Gas,
+        /// How recently a character walked over this floor, fading to nothing over time
+        scent: u32,
+        /// Percentage chance that a shot at a character next to this feature is stopped by it
+        cover: u32,
+        /// Percentage chance for this character's attacks to hit a target with no evasion
+        accuracy: u32,
+        /// Subtracted from the chance of attacks against this character hitting
+        evasion: u32,
+        /// Absorbs hits for a character, or is picked up from the floor as a shield generator
+        shield: Shield,
+        /// Rounds in a box of ammo, loaded into the player's weapon when they pick it up
+        ammo: u32,
+        /// The player's second weapon, which they can swap with the one they're holding
+        holstered: Weapon,
+        /// Where an npc heard a noise it's going to check out
+        investigating: Coord,
+        /// Which side a character fights for
+        faction: Faction,
+        /// Npcs only attack once they've noticed the player
+        alertness: Alertness,
+        /// Where a hostile npc last saw the player, so it can go looking for them
+        last_seen: LastSeen,
+        /// Marks the npc waiting at the end of the final level, and tracks how the fight is going
+        boss: Boss,
+        /// Turns a badly hurt npc will keep running from the player before it rallies
+        fleeing: u32,
+        /// Marks an npc that has fled and rallied, and won't flee again
+        rallied: (),
+        /// Coloured light cast on the cells around the entity
+        light: Light<Circle>,
     }
 }
 pub use components::{Components, EntityData, EntityUpdate};
@@ -18,9 +114,194 @@ pub enum Tile {
     Player,
     Floor,
     Wall,
+    FloorMedbay,
+    FloorEngineering,
+    FloorHydroponics,
+    FloorDerelict,
+    WallMedbay,
+    WallEngineering,
+    WallHydroponics,
+    WallDerelict,
+    /// A door disguised as a wall, sealing off a secret room
+    WallHidden,
+    Barricade,
+    Rubble,
     DoorClosed,
     DoorOpen,
+    DoorLockedRed,
+    DoorLockedBlue,
     StairsDown,
+    Reactor,
+    ReactorDestroyed,
+    EscapePod,
+    Terminal,
+    Zombie,
+    Skeleton,
+    Hound,
+    Abomination,
+    Corpse,
+    Bones,
+    Gap,
+    Hull,
+    CoolantTank,
+    CoolantTankBreached,
+    Locker,
+    LockerOpen,
+    SpikeTrap,
+    GasTrap,
+    AlarmTrap,
+    Drain,
+    RadSuit,
+    RocketLauncher,
+    ScatterGun,
+    Railgun,
+    RicochetPistol,
+    MissileLauncher,
+    ChaffEmitter,
+    ShieldGenerator,
+    Ammo,
+    Scope,
+    ExtendedMag,
+    Bayonet,
+    KeycardRed,
+    KeycardBlue,
+    InjectorRed,
+    InjectorBlue,
+    InjectorGreen,
+    InjectorYellow,
+    InjectorPurple,
+}
+
+impl Tile {
+    /// Identifies the tile's definition in the app's tile registry
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Player => "player",
+            Self::Floor => "floor",
+            Self::Wall => "wall",
+            Self::FloorMedbay => "floor_medbay",
+            Self::FloorEngineering => "floor_engineering",
+            Self::FloorHydroponics => "floor_hydroponics",
+            Self::FloorDerelict => "floor_derelict",
+            Self::WallMedbay => "wall_medbay",
+            Self::WallEngineering => "wall_engineering",
+            Self::WallHydroponics => "wall_hydroponics",
+            Self::WallDerelict => "wall_derelict",
+            Self::WallHidden => "wall_hidden",
+            Self::Barricade => "barricade",
+            Self::Rubble => "rubble",
+            Self::DoorClosed => "door_closed",
+            Self::DoorOpen => "door_open",
+            Self::DoorLockedRed => "door_locked_red",
+            Self::DoorLockedBlue => "door_locked_blue",
+            Self::StairsDown => "stairs_down",
+            Self::Reactor => "reactor",
+            Self::ReactorDestroyed => "reactor_destroyed",
+            Self::EscapePod => "escape_pod",
+            Self::Terminal => "terminal",
+            Self::Zombie => "zombie",
+            Self::Skeleton => "skeleton",
+            Self::Hound => "hound",
+            Self::Abomination => "abomination",
+            Self::Corpse => "corpse",
+            Self::Bones => "bones",
+            Self::Gap => "gap",
+            Self::Hull => "hull",
+            Self::CoolantTank => "coolant_tank",
+            Self::CoolantTankBreached => "coolant_tank_breached",
+            Self::Locker => "locker",
+            Self::LockerOpen => "locker_open",
+            Self::SpikeTrap => "spike_trap",
+            Self::GasTrap => "gas_trap",
+            Self::AlarmTrap => "alarm_trap",
+            Self::Drain => "drain",
+            Self::RadSuit => "rad_suit",
+            Self::RocketLauncher => "rocket_launcher",
+            Self::ScatterGun => "scatter_gun",
+            Self::Railgun => "railgun",
+            Self::RicochetPistol => "ricochet_pistol",
+            Self::MissileLauncher => "missile_launcher",
+            Self::ChaffEmitter => "chaff_emitter",
+            Self::ShieldGenerator => "shield_generator",
+            Self::Ammo => "ammo",
+            Self::Scope => "scope",
+            Self::ExtendedMag => "extended_mag",
+            Self::Bayonet => "bayonet",
+            Self::KeycardRed => "keycard_red",
+            Self::KeycardBlue => "keycard_blue",
+            Self::InjectorRed => "injector_red",
+            Self::InjectorBlue => "injector_blue",
+            Self::InjectorGreen => "injector_green",
+            Self::InjectorYellow => "injector_yellow",
+            Self::InjectorPurple => "injector_purple",
+        }
+    }
+
+    /// Plain walls and the walls of each level theme
+    pub fn is_wall(self) -> bool {
+        matches!(
+            self,
+            Self::Wall
+                | Self::WallMedbay
+                | Self::WallEngineering
+                | Self::WallHydroponics
+                | Self::WallDerelict
+        )
+    }
+
+    /// Stands in for the tile in plain text dumps of levels, where the app's tile registry isn't
+    /// available. Npcs are letters and the player is '@', so they stand out from everything else.
+    pub fn debug_char(self) -> char {
+        match self {
+            Self::Player => '@',
+            Self::Floor
+            | Self::FloorMedbay
+            | Self::FloorEngineering
+            | Self::FloorHydroponics
+            | Self::FloorDerelict => '.',
+            Self::Wall
+            | Self::WallMedbay
+            | Self::WallEngineering
+            | Self::WallHydroponics
+            | Self::WallDerelict => '#',
+            Self::WallHidden => '?',
+            Self::Barricade => '|',
+            Self::Rubble => '%',
+            Self::DoorClosed => '+',
+            Self::DoorOpen => '-',
+            Self::DoorLockedRed | Self::DoorLockedBlue => '=',
+            Self::StairsDown => '>',
+            Self::Reactor | Self::ReactorDestroyed => 'R',
+            Self::EscapePod => 'E',
+            Self::Terminal => 'T',
+            Self::Zombie => 'z',
+            Self::Skeleton => 's',
+            Self::Hound => 'h',
+            Self::Abomination => 'A',
+            Self::Corpse | Self::Bones => '&',
+            Self::Gap => ':',
+            Self::Hull => ',',
+            Self::CoolantTank | Self::CoolantTankBreached => 'C',
+            Self::Locker | Self::LockerOpen => 'L',
+            Self::SpikeTrap | Self::GasTrap | Self::AlarmTrap => '^',
+            Self::Drain => 'D',
+            Self::RadSuit => 'S',
+            Self::RocketLauncher
+            | Self::ScatterGun
+            | Self::Railgun
+            | Self::RicochetPistol
+            | Self::MissileLauncher => ')',
+            Self::ChaffEmitter | Self::ShieldGenerator => '[',
+            Self::Ammo => 'a',
+            Self::Scope | Self::ExtendedMag | Self::Bayonet => '(',
+            Self::KeycardRed | Self::KeycardBlue => 'k',
+            Self::InjectorRed
+            | Self::InjectorBlue
+            | Self::InjectorGreen
+            | Self::InjectorYellow
+            | Self::InjectorPurple => '!',
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +310,331 @@ pub enum DoorState {
     Closed,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NpcType {
+    Zombie,
+    Skeleton,
+    Hound,
+    /// Waits for the player on the final level
+    Abomination,
+}
+
+impl NpcType {
+    /// Npcs that can be placed anywhere. The abomination is placed separately.
+    pub const ALL: &'static [Self] = &[Self::Zombie, Self::Skeleton, Self::Hound];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Zombie => "zombie",
+            Self::Skeleton => "skeleton",
+            Self::Hound => "hound",
+            Self::Abomination => "abomination",
+        }
+    }
+
+    pub fn tile(self) -> Tile {
+        match self {
+            Self::Zombie => Tile::Zombie,
+            Self::Skeleton => Tile::Skeleton,
+            Self::Hound => Tile::Hound,
+            Self::Abomination => Tile::Abomination,
+        }
+    }
+
+    pub fn corpse_name(self) -> &'static str {
+        match self {
+            Self::Zombie => "zombie corpse",
+            Self::Skeleton => "pile of bones",
+            Self::Hound => "hound carcass",
+            Self::Abomination => "abomination carcass",
+        }
+    }
+
+    pub fn corpse_tile(self) -> Tile {
+        match self {
+            Self::Zombie => Tile::Corpse,
+            Self::Skeleton => Tile::Bones,
+            Self::Hound => Tile::Corpse,
+            Self::Abomination => Tile::Corpse,
+        }
+    }
+
+    pub fn max_hit_points(self) -> u32 {
+        match self {
+            Self::Zombie => 4,
+            Self::Skeleton => 3,
+            Self::Hound => 3,
+            Self::Abomination => 30,
+        }
+    }
+
+    /// How much of a level's spawn budget it costs to place one of these
+    pub fn spawn_cost(self) -> u32 {
+        match self {
+            Self::Zombie => 3,
+            Self::Skeleton => 2,
+            Self::Hound => 3,
+            Self::Abomination => 20,
+        }
+    }
+
+    /// Energy gained each turn. At `NORMAL_SPEED` an npc acts once per turn, the same as the
+    /// player.
+    pub fn speed(self) -> u32 {
+        match self {
+            Self::Zombie => NORMAL_SPEED * 3 / 4,
+            Self::Skeleton => NORMAL_SPEED,
+            Self::Hound => NORMAL_SPEED * 3 / 2,
+            Self::Abomination => NORMAL_SPEED * 3 / 4,
+        }
+    }
+
+    pub fn damage(self) -> u32 {
+        match self {
+            Self::Zombie => 2,
+            Self::Skeleton => 1,
+            Self::Hound => 1,
+            Self::Abomination => 3,
+        }
+    }
+
+    pub fn accuracy(self) -> u32 {
+        match self {
+            Self::Zombie => 75,
+            Self::Skeleton => 80,
+            Self::Hound => 85,
+            Self::Abomination => 80,
+        }
+    }
+
+    pub fn evasion(self) -> u32 {
+        match self {
+            Self::Zombie => 0,
+            Self::Skeleton => 15,
+            Self::Hound => 20,
+            Self::Abomination => 5,
+        }
+    }
+
+    /// Applied for its default duration to whatever the npc hits in melee
+    pub fn melee_status_effect(self) -> Option<StatusEffect> {
+        match self {
+            Self::Zombie => Some(StatusEffect::Poison),
+            Self::Skeleton => None,
+            Self::Hound => None,
+            Self::Abomination => Some(StatusEffect::Slowed),
+        }
+    }
+
+    /// Npcs with heavy attacks spend a turn winding up before each attack, giving the player a
+    /// chance to get out of the way
+    pub fn winds_up(self) -> bool {
+        match self {
+            Self::Zombie => true,
+            Self::Skeleton => false,
+            Self::Hound => false,
+            Self::Abomination => true,
+        }
+    }
+
+    /// Npcs with ranged attacks sometimes attack the player from a distance, if they can find a
+    /// way to hit them
+    pub fn ranged_attack(self) -> Option<RangedAttack> {
+        match self {
+            Self::Zombie => None,
+            // skeletons hurl bones, which can be bounced off walls to reach around corners
+            Self::Skeleton => Some(RangedAttack {
+                projectile_name: "a bone",
+                range_squared: 36,
+                on_collision: OnCollision::Stop,
+                bounces: 2,
+                homing: None,
+                preferred_range_squared: Some(9),
+            }),
+            Self::Hound => None,
+            // the abomination's bursts are fired on a schedule of their own
+            Self::Abomination => None,
+        }
+    }
+
+    /// Npcs which track by scent follow the player's trail, even once they've lost sight of
+    /// them
+    pub fn tracks_scent(self) -> bool {
+        match self {
+            Self::Zombie => false,
+            Self::Skeleton => false,
+            Self::Hound => true,
+            Self::Abomination => false,
+        }
+    }
+
+    pub fn bleeds(self) -> bool {
+        match self {
+            Self::Zombie => true,
+            Self::Skeleton => false,
+            Self::Hound => true,
+            Self::Abomination => true,
+        }
+    }
+
+    /// How the remains of this npc come back to life, if at all
+    pub fn respawn(self) -> Option<Respawn> {
+        match self {
+            Self::Zombie | Self::Hound | Self::Abomination => None,
+            // standing on the bones only delays a skeleton until it can climb out beside you
+            Self::Skeleton => Some(Respawn {
+                turns: 8,
+                what: Respawnable::Npc(Self::Skeleton),
+                location: RespawnLocation::Nearest,
+            }),
+        }
+    }
+}
+
+/// How aware an npc is of the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Alertness {
+    /// Hasn't noticed the player, and won't attack anything
+    Unaware,
+    /// Heard something and is going to take a look, but won't attack yet
+    Suspicious,
+    /// Has seen the player
+    Hostile,
+}
+
+/// Characters attack anything belonging to a different faction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Faction {
+    /// The player
+    Crew,
+    /// The npcs infesting the station
+    Horde,
+    /// A rival pack that turns up on later levels and fights the horde as well as the player
+    Renegade,
+}
+
+impl Faction {
+    pub fn is_hostile_to(self, other: Self) -> bool {
+        self != other
+    }
+
+    /// Distinguishes npcs of this faction in messages, e.g. "the renegade zombie"
+    pub fn adjective(self) -> Option<&'static str> {
+        match self {
+            Self::Crew | Self::Horde => None,
+            Self::Renegade => Some("renegade"),
+        }
+    }
+}
+
+/// Locked doors only open for a player carrying a keycard of the same colour
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyColour {
+    Red,
+    Blue,
+}
+
+impl KeyColour {
+    pub const ALL: &'static [Self] = &[Self::Red, Self::Blue];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Red => "red",
+            Self::Blue => "blue",
+        }
+    }
+
+    pub fn door_tile(self) -> Tile {
+        match self {
+            Self::Red => Tile::DoorLockedRed,
+            Self::Blue => Tile::DoorLockedBlue,
+        }
+    }
+
+    pub fn keycard_tile(self) -> Tile {
+        match self {
+            Self::Red => Tile::KeycardRed,
+            Self::Blue => Tile::KeycardBlue,
+        }
+    }
+}
+
+/// Where a respawning entity comes back
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum RespawnLocation {
+    /// In the same cell, waiting for it to be unoccupied
+    InPlace,
+    /// In the same cell or an adjacent one, whichever is unoccupied
+    Nearest,
+}
+
+/// What a respawning entity comes back as
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Respawnable {
+    Npc(NpcType),
+    /// A hidden trap, such as a gas trap which has refilled after going off
+    Trap(TrapKind),
+}
+
+/// Counts down each turn and replaces its entity with something new when it reaches zero
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Respawn {
+    pub turns: u32,
+    pub what: Respawnable,
+    pub location: RespawnLocation,
+}
+
+/// The remains of a dead npc
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Corpse {
+    pub npc_type: NpcType,
+    pub searched: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecalKind {
+    Blood,
+    Scorch,
+    Oil,
+}
+
+impl DecalKind {
+    /// Number of turns a decal of this kind lasts, or `None` if it never fades
+    pub fn lifetime(self) -> Option<u32> {
+        match self {
+            Self::Blood | Self::Scorch => None,
+            Self::Oil => Some(100),
+        }
+    }
+
+    /// Whether decals of this kind are kept when the game is saved
+    pub fn persists_across_saves(self) -> bool {
+        match self {
+            Self::Blood | Self::Scorch => true,
+            Self::Oil => false,
+        }
+    }
+
+    /// Whether decals of this kind are hidden when gore is disabled
+    pub fn is_gore(self) -> bool {
+        match self {
+            Self::Blood => true,
+            Self::Scorch | Self::Oil => false,
+        }
+    }
+}
+
+/// Repeatedly adding the same kind of decal to a cell intensifies it up to this value
+pub const MAX_DECAL_INTENSITY: u8 = 4;
+
+/// Decals are stored on floor entities so there is at most one per cell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decal {
+    pub kind: DecalKind,
+    pub intensity: u8,
+    pub age: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Meter {
     current: u32,
@@ -71,3 +677,455 @@ impl Meter {
         self.current = self.max;
     }
 }
+
+/// Gravity affects how characters move around a level
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gravity {
+    Normal,
+    Low,
+    Zero,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Atmosphere {
+    Pressurised,
+    Vacuum,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasKind {
+    /// Blocks vision but is otherwise harmless
+    Smoke,
+    /// Poisons characters who breathe it
+    Poison,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gas {
+    pub kind: GasKind,
+    pub density: u8,
+}
+
+/// A character's cumulative exposure to radiation
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Rads {
+    pub exposure: u32,
+    /// Turns until each point of damage caused by past exposure is dealt
+    pub pending_damage: Vec<u32>,
+}
+
+/// How far an explosion spreads and how quickly it weakens
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blast {
+    pub radius_squared: u32,
+    /// Damage lost for each cell of distance from the centre of the blast
+    pub falloff: u32,
+    /// Density of the smoke left in each cell of the blast
+    #[serde(default)]
+    pub smoke: u8,
+}
+
+/// What happens when a projectile hits something or reaches the end of its path
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnCollision {
+    /// Damage the character that was hit, if any
+    Stop,
+    Explode(Blast),
+    /// Damage every character in the projectile's path, passing through up to `walls` solid
+    /// obstacles along the way
+    Pierce {
+        walls: u32,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaponKind {
+    RocketLauncher,
+    ScatterGun,
+    Railgun,
+    RicochetPistol,
+    MissileLauncher,
+}
+
+impl WeaponKind {
+    pub const ALL: &'static [Self] = &[
+        Self::RocketLauncher,
+        Self::ScatterGun,
+        Self::Railgun,
+        Self::RicochetPistol,
+        Self::MissileLauncher,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RocketLauncher => "rocket launcher",
+            Self::ScatterGun => "scatter gun",
+            Self::Railgun => "railgun",
+            Self::RicochetPistol => "ricochet pistol",
+            Self::MissileLauncher => "missile launcher",
+        }
+    }
+
+    pub fn tile(self) -> Tile {
+        match self {
+            Self::RocketLauncher => Tile::RocketLauncher,
+            Self::ScatterGun => Tile::ScatterGun,
+            Self::Railgun => Tile::Railgun,
+            Self::RicochetPistol => Tile::RicochetPistol,
+            Self::MissileLauncher => Tile::MissileLauncher,
+        }
+    }
+
+    /// The first level on which the weapon can be found
+    pub fn min_level(self) -> u32 {
+        match self {
+            Self::MissileLauncher => 3,
+            Self::RocketLauncher | Self::ScatterGun | Self::Railgun | Self::RicochetPistol => 1,
+        }
+    }
+
+    /// The weapon as it's found lying around the station
+    pub fn weapon(self) -> Weapon {
+        match self {
+            Self::RocketLauncher => Weapon {
+                kind: self,
+                damage: 5,
+                range_squared: 100,
+                ammo: 3,
+                on_collision: OnCollision::Explode(Blast {
+                    radius_squared: 5,
+                    falloff: 1,
+                    smoke: 3,
+                }),
+                bounces: 0,
+                homing: None,
+                attachments: Attachments::default(),
+                cursed: false,
+                curse_known: false,
+                rarity: Rarity::Common,
+                affixes: [None; MAX_AFFIXES],
+            },
+            // fires a burst of shrapnel which spreads widely but weakens quickly
+            Self::ScatterGun => Weapon {
+                kind: self,
+                damage: 3,
+                range_squared: 25,
+                ammo: 6,
+                on_collision: OnCollision::Explode(Blast {
+                    radius_squared: 8,
+                    falloff: 1,
+                    smoke: 0,
+                }),
+                bounces: 0,
+                homing: None,
+                attachments: Attachments::default(),
+                cursed: false,
+                curse_known: false,
+                rarity: Rarity::Common,
+                affixes: [None; MAX_AFFIXES],
+            },
+            Self::Railgun => Weapon {
+                kind: self,
+                damage: 4,
+                range_squared: 144,
+                ammo: 2,
+                on_collision: OnCollision::Pierce { walls: 1 },
+                bounces: 0,
+                homing: None,
+                attachments: Attachments::default(),
+                cursed: false,
+                curse_known: false,
+                rarity: Rarity::Common,
+                affixes: [None; MAX_AFFIXES],
+            },
+            // weak, but its shots bounce off walls so it can hit targets around corners
+            Self::RicochetPistol => Weapon {
+                kind: self,
+                damage: 2,
+                range_squared: 49,
+                ammo: 8,
+                on_collision: OnCollision::Stop,
+                bounces: 3,
+                homing: None,
+                attachments: Attachments::default(),
+                cursed: false,
+                curse_known: false,
+                rarity: Rarity::Common,
+                affixes: [None; MAX_AFFIXES],
+            },
+            Self::MissileLauncher => Weapon {
+                kind: self,
+                damage: 4,
+                range_squared: 144,
+                ammo: 3,
+                on_collision: OnCollision::Explode(Blast {
+                    radius_squared: 2,
+                    falloff: 1,
+                    smoke: 2,
+                }),
+                bounces: 0,
+                homing: Some(Homing { max_turn: 1 }),
+                attachments: Attachments::default(),
+                cursed: false,
+                curse_known: false,
+                rarity: Rarity::Common,
+                affixes: [None; MAX_AFFIXES],
+            },
+        }
+    }
+}
+
+/// A ranged weapon
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    pub damage: u32,
+    /// Maximum distance squared to the cell the weapon can be aimed at
+    pub range_squared: u32,
+    pub ammo: u32,
+    pub on_collision: OnCollision,
+    /// Number of times the weapon's shots bounce off solid obstacles
+    pub bounces: u32,
+    pub homing: Option<Homing>,
+    /// Saves from before attachments were added won't have this field
+    #[serde(default)]
+    pub attachments: Attachments,
+    /// Cursed weapons can't be put down, and hurt whoever fires them
+    #[serde(default)]
+    pub cursed: bool,
+    /// Whether the player knows if the weapon is cursed
+    #[serde(default)]
+    pub curse_known: bool,
+    #[serde(default)]
+    pub rarity: Rarity,
+    #[serde(default)]
+    pub affixes: [Option<Affix>; MAX_AFFIXES],
+}
+
+impl Weapon {
+    /// The weapon's name followed by the names of any attachments fitted to it and its
+    /// affixes. Curses are only mentioned once they're known.
+    pub fn description(&self) -> String {
+        let mut name = self.kind.name().to_string();
+        if self.rarity != Rarity::Common {
+            name = format!("{} {}", self.rarity.name(), name);
+        }
+        if self.cursed && self.curse_known {
+            name = format!("cursed {}", name);
+        }
+        let attachments = self
+            .attachments
+            .iter()
+            .map(Attachment::name)
+            .collect::<Vec<_>>();
+        if !attachments.is_empty() {
+            name = format!("{} ({})", name, attachments.join(", "));
+        }
+        let affixes = self.affixes().map(Affix::name).collect::<Vec<_>>();
+        if !affixes.is_empty() {
+            name = format!("{} [{}]", name, affixes.join(", "));
+        }
+        name
+    }
+
+    pub fn affixes(&self) -> impl Iterator<Item = Affix> + '_ {
+        self.affixes.iter().flatten().copied()
+    }
+
+    pub fn has_affix(&self, f: impl Fn(Affix) -> bool) -> bool {
+        self.affixes().any(f)
+    }
+
+    /// Number of extra solid obstacles the weapon's shots can pass through
+    pub fn penetration(&self) -> u32 {
+        self.affixes()
+            .map(|affix| match affix {
+                Affix::Penetration(walls) => walls,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Adds an affix to the first free slot, applying its effect on the weapon's stats
+    pub(crate) fn add_affix(&mut self, affix: Affix) {
+        if let Some(slot) = self.affixes.iter_mut().find(|slot| slot.is_none()) {
+            *slot = Some(affix);
+            match affix {
+                Affix::Damage(damage) => self.damage += damage,
+                Affix::Ammo(ammo) => self.ammo += ammo,
+                Affix::Penetration(_) | Affix::Knockback | Affix::Vampiric => (),
+            }
+        }
+    }
+
+    pub fn can_fit(&self, attachment: Attachment) -> bool {
+        !self.attachments.contains(attachment)
+    }
+
+    /// Fits an attachment, applying its effect on the weapon's stats. Each attachment can only
+    /// be fitted once.
+    pub fn fit(&mut self, attachment: Attachment) {
+        if !self.can_fit(attachment) {
+            return;
+        }
+        match attachment {
+            Attachment::Scope => self.range_squared += SCOPE_RANGE_SQUARED_BONUS,
+            Attachment::ExtendedMag => self.ammo += EXTENDED_MAG_AMMO,
+            // bayonets affect melee attacks rather than the weapon itself
+            Attachment::Bayonet => (),
+        }
+        self.attachments.insert(attachment);
+    }
+}
+
+pub const MAX_AFFIXES: usize = 3;
+
+/// How unusual an item is. Rarer items have more affixes.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+impl Rarity {
+    pub const ALL: &'static [Self] = &[Self::Common, Self::Uncommon, Self::Rare, Self::Legendary];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Common => "common",
+            Self::Uncommon => "uncommon",
+            Self::Rare => "rare",
+            Self::Legendary => "legendary",
+        }
+    }
+
+    pub fn num_affixes(self) -> usize {
+        match self {
+            Self::Common => 0,
+            Self::Uncommon => 1,
+            Self::Rare => 2,
+            Self::Legendary => 3,
+        }
+    }
+}
+
+/// A randomly-rolled bonus on a weapon
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Affix {
+    Damage(u32),
+    Ammo(u32),
+    /// Shots pass through this many extra solid obstacles
+    Penetration(u32),
+    /// Shots push characters they hit away from the shooter
+    Knockback,
+    /// Killing a character with a shot heals the shooter
+    Vampiric,
+}
+
+impl Affix {
+    pub fn name(self) -> String {
+        match self {
+            Self::Damage(damage) => format!("+{} damage", damage),
+            Self::Ammo(ammo) => format!("+{} ammo", ammo),
+            Self::Penetration(walls) => format!("+{} pen", walls),
+            Self::Knockback => "knockback".to_string(),
+            Self::Vampiric => "vampiric".to_string(),
+        }
+    }
+}
+
+pub const SCOPE_RANGE_SQUARED_BONUS: u32 = 64;
+/// Added to the accuracy of shots from a weapon with a scope
+pub const SCOPE_ACCURACY_BONUS: u32 = 10;
+pub const EXTENDED_MAG_AMMO: u32 = 4;
+/// Extra damage dealt by melee attacks while holding a weapon with a bayonet
+pub const BAYONET_DAMAGE: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Attachment {
+    Scope,
+    ExtendedMag,
+    Bayonet,
+}
+
+impl Attachment {
+    pub const ALL: &'static [Self] = &[Self::Scope, Self::ExtendedMag, Self::Bayonet];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Scope => "scope",
+            Self::ExtendedMag => "extended mag",
+            Self::Bayonet => "bayonet",
+        }
+    }
+
+    pub fn tile(self) -> Tile {
+        match self {
+            Self::Scope => Tile::Scope,
+            Self::ExtendedMag => Tile::ExtendedMag,
+            Self::Bayonet => Tile::Bayonet,
+        }
+    }
+}
+
+/// The attachments fitted to a weapon. A weapon can have one of each.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Attachments {
+    pub scope: bool,
+    pub extended_mag: bool,
+    pub bayonet: bool,
+}
+
+impl Attachments {
+    fn slot(&mut self, attachment: Attachment) -> &mut bool {
+        match attachment {
+            Attachment::Scope => &mut self.scope,
+            Attachment::ExtendedMag => &mut self.extended_mag,
+            Attachment::Bayonet => &mut self.bayonet,
+        }
+    }
+
+    pub fn contains(&self, attachment: Attachment) -> bool {
+        match attachment {
+            Attachment::Scope => self.scope,
+            Attachment::ExtendedMag => self.extended_mag,
+            Attachment::Bayonet => self.bayonet,
+        }
+    }
+
+    fn insert(&mut self, attachment: Attachment) {
+        *self.slot(attachment) = true;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Attachment> + '_ {
+        Attachment::ALL
+            .iter()
+            .copied()
+            .filter(|&attachment| self.contains(attachment))
+    }
+}
+
+/// Projectiles which home in on the nearest character, other than whoever fired them
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Homing {
+    /// Number of 45 degree turns the projectile can make each time it moves
+    pub max_turn: u32,
+}
+
+/// An attack npcs can make from a distance by throwing or firing something
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangedAttack {
+    pub projectile_name: &'static str,
+    /// Maximum distance squared to the cell the npc can aim at
+    pub range_squared: u32,
+    pub on_collision: OnCollision,
+    /// Number of times the projectile bounces off solid obstacles
+    pub bounces: u32,
+    pub homing: Option<Homing>,
+    /// Npcs with a preferred range back away from the player when they're closer than this,
+    /// and shoot whenever they have a clear shot rather than only some of the time
+    pub preferred_range_squared: Option<u32>,
+}
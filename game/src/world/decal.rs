@@ -0,0 +1,87 @@
+use crate::world::{
+    data::{Decal, DecalKind, MAX_DECAL_INTENSITY},
+    spatial::Layers,
+    World,
+};
+use coord_2d::Coord;
+
+/// Upper bound on the number of decals in the world at once. When adding a decal would exceed
+/// this, the oldest decal is removed to make room.
+const MAX_DECALS: usize = 256;
+
+impl World {
+    /// Adds a decal to the floor at the given coord. If the cell already has a decal of the same
+    /// kind it becomes more intense, otherwise the new decal replaces the old one.
+    pub fn add_decal(&mut self, coord: Coord, kind: DecalKind) {
+        let floor_entity = match self.spatial_table.layers_at(coord) {
+            Some(&Layers {
+                floor: Some(floor_entity),
+                ..
+            }) => floor_entity,
+            _ => return,
+        };
+        if let Some(decal) = self.components.decal.get_mut(floor_entity) {
+            if decal.kind == kind {
+                decal.intensity = (decal.intensity + 1).min(MAX_DECAL_INTENSITY);
+            } else {
+                decal.kind = kind;
+                decal.intensity = 1;
+            }
+            decal.age = 0;
+            return;
+        }
+        if self.components.decal.len() >= MAX_DECALS {
+            self.remove_oldest_decal();
+        }
+        self.components.decal.insert(
+            floor_entity,
+            Decal {
+                kind,
+                intensity: 1,
+                age: 0,
+            },
+        );
+    }
+
+    fn remove_oldest_decal(&mut self) {
+        let oldest = self
+            .components
+            .decal
+            .iter()
+            .max_by_key(|(_, decal)| decal.age)
+            .map(|(entity, _)| entity);
+        if let Some(entity) = oldest {
+            self.components.decal.remove(entity);
+        }
+    }
+
+    /// Ages each decal by a turn, removing those that have faded
+    pub fn age_decals(&mut self) {
+        let mut faded = Vec::new();
+        for (entity, decal) in self.components.decal.iter_mut() {
+            decal.age += 1;
+            if let Some(lifetime) = decal.kind.lifetime() {
+                if decal.age >= lifetime {
+                    faded.push(entity);
+                }
+            }
+        }
+        for entity in faded {
+            self.components.decal.remove(entity);
+        }
+    }
+
+    /// Removes all decals which don't persist across saves
+    pub fn remove_transient_decals(&mut self) {
+        let transient = self
+            .components
+            .decal
+            .iter()
+            .filter(|(_, decal)| !decal.kind.persists_across_saves())
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for entity in transient {
+            self.components.decal.remove(entity);
+        }
+    }
+}
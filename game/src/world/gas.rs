@@ -0,0 +1,140 @@
+use crate::{
+    world::{
+        data::{Atmosphere, Gas, GasKind},
+        spatial::Layers,
+        World,
+    },
+    CardinalDirection,
+};
+use coord_2d::Coord;
+use std::collections::HashMap;
+
+pub const MAX_GAS_DENSITY: u8 = 8;
+/// Opacity added to a cell by each unit of gas density
+const SMOKE_OPACITY_PER_DENSITY: u8 = 48;
+const POISON_GAS_OPACITY_PER_DENSITY: u8 = 16;
+
+impl GasKind {
+    fn opacity_per_density(self) -> u8 {
+        match self {
+            Self::Smoke => SMOKE_OPACITY_PER_DENSITY,
+            Self::Poison => POISON_GAS_OPACITY_PER_DENSITY,
+        }
+    }
+}
+
+impl World {
+    /// Gas can spread into any cell with a floor that isn't blocked by something solid
+    fn gas_can_enter(&self, coord: Coord) -> bool {
+        if let Some(&Layers {
+            floor: Some(_),
+            feature,
+            ..
+        }) = self.spatial_table.layers_at(coord)
+        {
+            !feature.is_some_and(|feature| self.components.solid.contains(feature))
+        } else {
+            false
+        }
+    }
+
+    pub fn gas_at(&self, coord: Coord) -> Option<Gas> {
+        let floor_entity = self.spatial_table.layers_at(coord)?.floor?;
+        self.components.gas.get(floor_entity).copied()
+    }
+
+    fn set_gas(&mut self, coord: Coord, gas: Option<Gas>) {
+        let floor_entity = match self.spatial_table.layers_at(coord).and_then(|l| l.floor) {
+            Some(floor_entity) => floor_entity,
+            None => return,
+        };
+        match gas {
+            Some(gas) if gas.density > 0 => {
+                self.components.gas.insert(floor_entity, gas);
+            }
+            _ => {
+                self.components.gas.remove(floor_entity);
+            }
+        }
+    }
+
+    /// Fills a cell with gas. The cell takes on the kind of the new gas if it's at least as
+    /// dense as what's already there.
+    pub fn add_gas(&mut self, coord: Coord, kind: GasKind, density: u8) {
+        if density == 0 || !self.gas_can_enter(coord) {
+            return;
+        }
+        let density = density.min(MAX_GAS_DENSITY);
+        let gas = match self.gas_at(coord) {
+            Some(existing) if existing.density > density => existing,
+            _ => Gas { kind, density },
+        };
+        self.set_gas(coord, Some(gas));
+    }
+
+    /// How much the gas in a cell blocks vision
+    pub fn gas_opacity(&self, coord: Coord) -> u8 {
+        self.gas_at(coord).map_or(0, |gas| {
+            gas.density.saturating_mul(gas.kind.opacity_per_density())
+        })
+    }
+
+    /// Advances gas by a turn. Each cell of gas thins out, and drifts into neighbouring cells
+    /// which have less of it, so clouds spread out as they fade. Gas is lost to space.
+    pub fn diffuse_gas(&mut self) {
+        let clouds = self
+            .components
+            .gas
+            .iter()
+            .filter_map(|(floor_entity, &gas)| {
+                self.spatial_table
+                    .coord_of(floor_entity)
+                    .map(|coord| (coord, gas))
+            })
+            .collect::<Vec<_>>();
+        // each cell ends up as dense as the densest gas reaching it
+        let mut next: HashMap<Coord, Gas> = HashMap::new();
+        let mut contribute = |coord: Coord, gas: Gas| {
+            if gas.density == 0 {
+                return;
+            }
+            let entry = next.entry(coord).or_insert(gas);
+            if gas.density > entry.density {
+                *entry = gas;
+            }
+        };
+        for &(coord, gas) in &clouds {
+            if self.atmosphere_at(coord) == Atmosphere::Vacuum {
+                continue;
+            }
+            contribute(
+                coord,
+                Gas {
+                    density: gas.density - 1,
+                    ..gas
+                },
+            );
+            if gas.density <= 2 {
+                continue;
+            }
+            for direction in CardinalDirection::all() {
+                let neighbour = coord + direction.coord();
+                if self.gas_can_enter(neighbour) {
+                    contribute(
+                        neighbour,
+                        Gas {
+                            density: gas.density - 2,
+                            ..gas
+                        },
+                    );
+                }
+            }
+        }
+        for (coord, _) in clouds {
+            self.set_gas(coord, None);
+        }
+        for (coord, gas) in next {
+            self.set_gas(coord, Some(gas));
+        }
+    }
+}
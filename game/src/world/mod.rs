@@ -1,5 +1,5 @@
 use coord_2d::Size;
-use entity_table::EntityAllocator;
+use entity_table::{Entity, EntityAllocator};
 use grid_search_cardinal_distance_map::DistanceMap;
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +11,16 @@ use data::Components;
 
 pub mod spawn;
 
+pub mod decal;
+
+pub mod action;
+
+pub mod air;
+
+pub mod gas;
+
+pub mod scent;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct World {
     pub entity_allocator: EntityAllocator,
@@ -31,4 +41,10 @@ impl World {
             distance_map: DistanceMap::new(size),
         }
     }
+
+    pub fn remove_entity(&mut self, entity: Entity) {
+        self.components.remove_entity(entity);
+        self.spatial_table.remove(entity);
+        self.entity_allocator.free(entity);
+    }
 }
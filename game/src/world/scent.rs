@@ -0,0 +1,43 @@
+use crate::world::World;
+use coord_2d::Coord;
+
+/// Scent left on a floor by a character walking over it. It fades by one each turn.
+pub const MAX_SCENT: u32 = 40;
+
+impl World {
+    pub fn scent_at(&self, coord: Coord) -> u32 {
+        self.spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.floor)
+            .and_then(|floor_entity| self.components.scent.get(floor_entity).copied())
+            .unwrap_or(0)
+    }
+
+    /// Marks the floor under a character as freshly walked over
+    pub fn lay_scent(&mut self, coord: Coord) {
+        if let Some(floor_entity) = self
+            .spatial_table
+            .layers_at(coord)
+            .and_then(|layers| layers.floor)
+        {
+            self.components.scent.insert(floor_entity, MAX_SCENT);
+        }
+    }
+
+    /// Every trail fades a little each turn, and is gone once it has gone cold
+    pub fn decay_scent(&mut self) {
+        let trails = self
+            .components
+            .scent
+            .iter()
+            .map(|(floor_entity, &scent)| (floor_entity, scent))
+            .collect::<Vec<_>>();
+        for (floor_entity, scent) in trails {
+            if scent <= 1 {
+                self.components.scent.remove(floor_entity);
+            } else {
+                self.components.scent.insert(floor_entity, scent - 1);
+            }
+        }
+    }
+}
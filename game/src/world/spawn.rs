@@ -1,9 +1,23 @@
 use crate::{
+    cover::LOW_FEATURE_COVER,
+    destruction::{BARRICADE_HIT_POINTS, WALL_HIT_POINTS},
+    energy::NORMAL_SPEED,
+    explosion::PLAYER_STARTING_GRENADES,
+    hit::{PLAYER_ACCURACY, PLAYER_EVASION},
+    identification::InjectorEffect,
+    light::{self, ESCAPE_POD_LIGHT, REACTOR_LIGHT, STAIRS_LIGHT, TERMINAL_LIGHT},
+    shield::SHIELD_CHARGES,
     world::{
-        data::{DoorState, EntityData, Layer, Location, Tile},
+        data::{
+            Alertness, Atmosphere, Attachment, Corpse, DoorState, EntityData, Faction, KeyColour,
+            Layer, Location, Meter, NpcType, Rads, Respawn, RespawnLocation, Respawnable, Tile,
+            Weapon,
+        },
+        spatial::Layers,
         World,
     },
-    Entity,
+    CardinalDirection, Container, Entity, Inventory, Shield, Trap, TrapKind,
+    PLAYER_INVENTORY_CAPACITY,
 };
 use coord_2d::Coord;
 use entity_table::entity_data;
@@ -11,6 +25,15 @@ use entity_table::entity_data;
 pub fn make_player() -> EntityData {
     EntityData {
         tile: Some(Tile::Player),
+        hit_points: Some(Meter::new(20, 20)),
+        oxygen: Some(Meter::new(10, 10)),
+        rads: Some(Rads::default()),
+        grenades: Some(PLAYER_STARTING_GRENADES),
+        inventory: Some(Inventory::new(PLAYER_INVENTORY_CAPACITY)),
+        accuracy: Some(PLAYER_ACCURACY),
+        evasion: Some(PLAYER_EVASION),
+        faction: Some(Faction::Crew),
+        speed: Some(NORMAL_SPEED),
         ..Default::default()
     }
 }
@@ -33,45 +56,434 @@ impl World {
         entity
     }
 
+    /// Starts describing a new entity. The entity is added to the world when `build` is called.
+    pub fn spawn(&mut self) -> SpawnBuilder {
+        SpawnBuilder {
+            world: self,
+            coord: None,
+            layer: None,
+            entity_data: EntityData::default(),
+        }
+    }
+
     pub fn spawn_wall(&mut self, coord: Coord) -> Entity {
-        self.spawn_entity(
-            (coord, Layer::Feature),
-            entity_data! {
-                tile: Tile::Wall,
-                solid: (),
-                opacity: 255,
-            },
-        )
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::Wall)
+            .solid()
+            .opacity(255)
+            .with(|data| data.hit_points = Some(Meter::new(WALL_HIT_POINTS, WALL_HIT_POINTS)))
+            .build()
+    }
+
+    /// Looks and behaves like a wall until it's found, when it turns out to be a door
+    pub fn spawn_hidden_wall(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::WallHidden)
+            .solid()
+            .opacity(255)
+            .with(|data| data.hit_points = Some(Meter::new(WALL_HIT_POINTS, WALL_HIT_POINTS)))
+            .build()
     }
 
     pub fn spawn_floor(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Floor)
+            .tile(Tile::Floor)
+            .build()
+    }
+
+    /// The outside of the station's hull, exposed to space
+    pub fn spawn_hull(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Floor)
+            .tile(Tile::Hull)
+            .with(|data| data.atmosphere = Some(Atmosphere::Vacuum))
+            .build()
+    }
+
+    /// A hole in the floor that characters can't walk across
+    pub fn spawn_gap(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Floor)
+            .tile(Tile::Gap)
+            .with(|data| data.gap = Some(()))
+            .build()
+    }
+
+    pub fn spawn_door(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::DoorClosed)
+            .solid()
+            .opacity(255)
+            .with(|data| data.door_state = Some(DoorState::Closed))
+            .build()
+    }
+
+    /// Locks a door until the player brings a keycard of the same colour
+    pub fn lock_door(&mut self, entity: Entity, colour: KeyColour) {
+        self.components.locked.insert(entity, colour);
+        self.components.tile.insert(entity, colour.door_tile());
+    }
+
+    pub fn spawn_stairs_down(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::StairsDown)
+            .with(|data| data.stairs_down = Some(()))
+            .with(|data| data.light = Some(light::glow(STAIRS_LIGHT)))
+            .build()
+    }
+
+    pub fn spawn_reactor(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::Reactor)
+            .solid()
+            .with(|data| data.reactor = Some(()))
+            .with(|data| data.light = Some(light::glow(REACTOR_LIGHT)))
+            .build()
+    }
+
+    pub fn spawn_terminal(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::Terminal)
+            .solid()
+            .with(|data| data.terminal = Some(()))
+            .with(|data| data.light = Some(light::glow(TERMINAL_LIGHT)))
+            .with(|data| data.cover = Some(LOW_FEATURE_COVER))
+            .build()
+    }
+
+    pub fn spawn_coolant_tank(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::CoolantTank)
+            .solid()
+            .with(|data| data.coolant_tank = Some(()))
+            .with(|data| data.cover = Some(LOW_FEATURE_COVER))
+            .build()
+    }
+
+    /// Low cover which explosions and piercing shots wear down, like a wall
+    pub fn spawn_barricade(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::Barricade)
+            .solid()
+            .with(|data| data.cover = Some(LOW_FEATURE_COVER))
+            .with(|data| {
+                data.hit_points = Some(Meter::new(BARRICADE_HIT_POINTS, BARRICADE_HIT_POINTS))
+            })
+            .build()
+    }
+
+    /// A locker holding the given items. Lockers in generated levels are filled with loot after
+    /// the terrain is generated.
+    pub fn spawn_locker(&mut self, coord: Coord, contents: Vec<EntityData>) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::Locker)
+            .solid()
+            .with(|data| data.container = Some(Container::new(contents)))
+            .with(|data| data.cover = Some(LOW_FEATURE_COVER))
+            .build()
+    }
+
+    /// A hidden trap which goes off when a character walks into its cell
+    pub fn spawn_trap(&mut self, coord: Coord, kind: TrapKind) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(kind.tile())
+            .with(|data| data.trap = Some(Trap::hidden(kind)))
+            .build()
+    }
+
+    /// Marks where a spent trap will be rearmed after `turns`. The marker takes up no layer, so
+    /// it doesn't get in the way while it counts down.
+    pub fn spawn_spent_trap(&mut self, coord: Coord, kind: TrapKind, turns: u32) -> Entity {
         self.spawn_entity(
-            (coord, Layer::Floor),
+            Location { layer: None, coord },
             entity_data! {
-                tile: Tile::Floor,
+                respawn: Respawn {
+                    turns,
+                    what: Respawnable::Trap(kind),
+                    location: RespawnLocation::InPlace,
+                },
             },
         )
     }
 
-    pub fn spawn_door(&mut self, coord: Coord) -> Entity {
+    pub fn spawn_drain(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::Drain)
+            .with(|data| data.drain = Some(()))
+            .build()
+    }
+
+    pub fn spawn_rad_suit(&mut self, coord: Coord, protection: u32) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Item)
+            .tile(Tile::RadSuit)
+            .with(|data| data.radiation_protection = Some(protection))
+            .build()
+    }
+
+    pub fn spawn_chaff_emitter(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Item)
+            .tile(Tile::ChaffEmitter)
+            .with(|data| data.chaff = Some(()))
+            .build()
+    }
+
+    pub fn spawn_ammo(&mut self, coord: Coord, rounds: u32) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Item)
+            .tile(Tile::Ammo)
+            .with(|data| data.ammo = Some(rounds))
+            .build()
+    }
+
+    /// Ammo dropped by a dying npc lands in or next to the cell where it died. Returns `None`
+    /// if there was nowhere to put it.
+    pub fn spawn_dropped_ammo(&mut self, coord: Coord, rounds: u32) -> Option<Entity> {
+        let coord = self.free_item_coord_near(coord)?;
+        Some(self.spawn_ammo(coord, rounds))
+    }
+
+    pub fn spawn_keycard(&mut self, coord: Coord, colour: KeyColour) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Item)
+            .tile(colour.keycard_tile())
+            .with(|data| data.keycard = Some(colour))
+            .build()
+    }
+
+    pub fn spawn_shield_generator(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Item)
+            .tile(Tile::ShieldGenerator)
+            .with(|data| data.shield = Some(Shield::new(SHIELD_CHARGES)))
+            .build()
+    }
+
+    pub fn spawn_injector(&mut self, coord: Coord, effect: InjectorEffect, tile: Tile) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Item)
+            .tile(tile)
+            .with(|data| data.injector = Some(effect))
+            .build()
+    }
+
+    pub fn spawn_attachment(&mut self, coord: Coord, attachment: Attachment) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Item)
+            .tile(attachment.tile())
+            .with(|data| data.attachment = Some(attachment))
+            .build()
+    }
+
+    pub fn spawn_weapon(&mut self, coord: Coord, weapon: Weapon) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Item)
+            .tile(weapon.kind.tile())
+            .with(|data| data.weapon = Some(weapon))
+            .build()
+    }
+
+    pub fn spawn_escape_pod(&mut self, coord: Coord) -> Entity {
+        self.spawn()
+            .at(coord)
+            .layer(Layer::Feature)
+            .tile(Tile::EscapePod)
+            .with(|data| data.escape_pod = Some(()))
+            .with(|data| data.light = Some(light::glow(ESCAPE_POD_LIGHT)))
+            .build()
+    }
+
+    pub fn spawn_npc(&mut self, coord: Coord, npc_type: NpcType) -> Entity {
+        let max_hit_points = npc_type.max_hit_points();
         self.spawn_entity(
-            (coord, Layer::Feature),
-            entity_data! {
-                tile: Tile::DoorClosed,
-                solid: (),
-                door_state: DoorState::Closed,
-                opacity: 255,
+            (coord, Layer::Character),
+            EntityData {
+                tile: Some(npc_type.tile()),
+                npc_type: Some(npc_type),
+                hit_points: Some(Meter::new(max_hit_points, max_hit_points)),
+                speed: Some(npc_type.speed()),
+                accuracy: Some(npc_type.accuracy()),
+                evasion: Some(npc_type.evasion()),
+                faction: Some(Faction::Horde),
+                alertness: Some(Alertness::Unaware),
+                ..Default::default()
             },
         )
     }
 
-    pub fn spawn_stairs_down(&mut self, coord: Coord) -> Entity {
-        self.spawn_entity(
-            (coord, Layer::Feature),
+    /// The given cell if it has room for an item, otherwise an adjacent cell that does
+    fn free_item_coord_near(&self, coord: Coord) -> Option<Coord> {
+        let item_is_free = |coord| {
+            matches!(
+                self.spatial_table.layers_at(coord),
+                Some(&Layers {
+                    item: None,
+                    floor: Some(_),
+                    ..
+                })
+            )
+        };
+        std::iter::once(coord)
+            .chain(CardinalDirection::all().map(|direction| coord + direction.coord()))
+            .find(|&coord| item_is_free(coord))
+    }
+
+    /// Corpses are items so if the cell where the npc died already contains an item, the corpse
+    /// is placed in an adjacent cell instead. Returns `None` if there was nowhere to put it.
+    pub fn spawn_corpse(&mut self, coord: Coord, npc_type: NpcType) -> Option<Entity> {
+        let coord = self.free_item_coord_near(coord)?;
+        let entity = self.spawn_entity(
+            (coord, Layer::Item),
             entity_data! {
-                tile: Tile::StairsDown,
-                stairs_down: (),
+                tile: npc_type.corpse_tile(),
+                corpse: Corpse {
+                    npc_type,
+                    searched: false,
+                },
             },
-        )
+        );
+        if let Some(respawn) = npc_type.respawn() {
+            self.components.respawn.insert(entity, respawn);
+        }
+        Some(entity)
+    }
+
+    pub fn can_spawn_character_at(&self, coord: Coord) -> bool {
+        if let Some(&Layers {
+            floor: Some(floor),
+            character: None,
+            feature,
+            ..
+        }) = self.spatial_table.layers_at(coord)
+        {
+            !self.components.gap.contains(floor)
+                && !feature.is_some_and(|feature| self.components.solid.contains(feature))
+        } else {
+            false
+        }
+    }
+
+    /// Items can only be placed where a character could stand, and only one per cell
+    pub fn can_spawn_item_at(&self, coord: Coord) -> bool {
+        self.can_spawn_character_at(coord)
+            && matches!(
+                self.spatial_table.layers_at(coord),
+                Some(&Layers { item: None, .. })
+            )
+    }
+
+    /// Traps are features, so they can only come back in a cell with no feature. A trap
+    /// appearing under a character wouldn't go off, so they wait for the cell to be empty too.
+    fn can_respawn_at(&self, coord: Coord, what: Respawnable) -> bool {
+        match what {
+            Respawnable::Npc(_) => self.can_spawn_character_at(coord),
+            Respawnable::Trap(_) => {
+                self.can_spawn_character_at(coord)
+                    && matches!(
+                        self.spatial_table.layers_at(coord),
+                        Some(&Layers { feature: None, .. })
+                    )
+            }
+        }
+    }
+
+    /// Where an entity at `coord` with the given respawn location policy would respawn right
+    /// now, or `None` if it has to wait for its location to be unoccupied
+    pub fn respawn_coord(
+        &self,
+        coord: Coord,
+        location: RespawnLocation,
+        what: Respawnable,
+    ) -> Option<Coord> {
+        match location {
+            RespawnLocation::InPlace => Some(coord).filter(|&c| self.can_respawn_at(c, what)),
+            RespawnLocation::Nearest => std::iter::once(coord)
+                .chain(CardinalDirection::all().map(|direction| coord + direction.coord()))
+                .find(|&c| self.can_respawn_at(c, what)),
+        }
+    }
+}
+
+/// Describes an entity to add to the world, one component at a time. Components without a
+/// dedicated method can be set with `with`.
+pub struct SpawnBuilder<'a> {
+    world: &'a mut World,
+    coord: Option<Coord>,
+    layer: Option<Layer>,
+    entity_data: EntityData,
+}
+
+impl<'a> SpawnBuilder<'a> {
+    pub fn at(mut self, coord: Coord) -> Self {
+        self.coord = Some(coord);
+        self
+    }
+
+    pub fn layer(mut self, layer: Layer) -> Self {
+        self.layer = Some(layer);
+        self
+    }
+
+    pub fn tile(mut self, tile: Tile) -> Self {
+        self.entity_data.tile = Some(tile);
+        self
+    }
+
+    pub fn solid(mut self) -> Self {
+        self.entity_data.solid = Some(());
+        self
+    }
+
+    pub fn opacity(mut self, opacity: u8) -> Self {
+        self.entity_data.opacity = Some(opacity);
+        self
+    }
+
+    pub fn with<F: FnOnce(&mut EntityData)>(mut self, f: F) -> Self {
+        f(&mut self.entity_data);
+        self
+    }
+
+    /// Adds the entity to the world. Panics if the location wasn't specified or is already
+    /// occupied.
+    pub fn build(self) -> Entity {
+        let coord = self.coord.expect("spawned entity has no coord");
+        let layer = self.layer.expect("spawned entity has no layer");
+        self.world.spawn_entity((coord, layer), self.entity_data)
     }
 }
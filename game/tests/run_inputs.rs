@@ -0,0 +1,68 @@
+use game::{CardinalDirection, Config, Game, Input, Performance};
+use rand::SeedableRng;
+use rand_isaac::Isaac64Rng;
+
+const SEED: u64 = 0x5eed;
+
+/// Wanders around the first level, bumping into whatever is in the way
+fn inputs() -> Vec<Input> {
+    let mut inputs = Vec::new();
+    for direction in CardinalDirection::all() {
+        for _ in 0..3 {
+            inputs.push(Input::Walk(direction));
+        }
+        inputs.push(Input::Wait);
+    }
+    inputs
+}
+
+fn new_game(config: &Config) -> Game {
+    let mut rng = Isaac64Rng::seed_from_u64(SEED);
+    Game::new(config, Vec::new(), &mut rng)
+}
+
+/// Everything about a game worth comparing after running it
+fn summary(game: &Game) -> (String, u64, u32) {
+    (
+        game.dump_level_ascii(),
+        game.turn(),
+        game.player_hit_points().current(),
+    )
+}
+
+#[test]
+fn same_seed_and_inputs_give_same_game() {
+    let config = Config::default();
+    let inputs = inputs();
+    let mut first = new_game(&config);
+    let first_result = format!("{:?}", first.run_inputs(&inputs, &config));
+    let mut second = new_game(&config);
+    let second_result = format!("{:?}", second.run_inputs(&inputs, &config));
+    assert_eq!(first_result, second_result);
+    assert_eq!(summary(&first), summary(&second));
+}
+
+#[test]
+fn replay_ignores_the_app_config_it_is_played_with() {
+    // a player with a history of deaths gets a different spawn budget from a new player
+    let config = Config {
+        performance: Performance {
+            deaths: 5,
+            low_hp_clears: 0,
+            clean_clears: 0,
+        },
+        ..Config::default()
+    };
+    let mut original = new_game(&config);
+    let _ = original.run_inputs(&inputs(), &config);
+    let replay = original.replay_data().expect("seeded runs can be replayed");
+    let playback_config = Config::default();
+    let mut playback = Game::from_replay(&playback_config, &replay);
+    let replay_inputs = replay
+        .inputs
+        .iter()
+        .map(|replay_input| replay_input.input)
+        .collect::<Vec<_>>();
+    let _ = playback.run_inputs(&replay_inputs, &playback_config);
+    assert_eq!(summary(&original), summary(&playback));
+}
@@ -7,12 +7,19 @@ const DEFAULT_SAVE_FILE: &str = "save";
 const DEFAULT_NEXT_TO_EXE_STORAGE_DIR: &str = "save";
 const DEFAULT_CONFIG_FILE: &str = "config.json";
 const DEFAULT_CONTROLS_FILE: &str = "controls.json";
+const DEFAULT_BALANCE_FILE: &str = "balance.ron";
+const DEFAULT_TILES_FILE: &str = "tiles.ron";
+const DEFAULT_RUNS_FILE: &str = "runs.json";
+const DEFAULT_GHOSTS_FILE: &str = "ghosts";
+const DEFAULT_LEVEL_FILE: &str = "level.txt";
 
 pub struct NativeCommon {
     pub storage: AppStorage,
     pub initial_rng_seed: InitialRngSeed,
     pub omniscient: bool,
     pub new_game: bool,
+    pub stress: bool,
+    pub wizard: bool,
 }
 impl NativeCommon {
     pub fn parser() -> impl meap::Parser<Item = Self> {
@@ -25,6 +32,21 @@ impl NativeCommon {
                     .with_default(DEFAULT_CONFIG_FILE.to_string());
                 controls_file = opt_opt("PATH", "controls-file").desc("controls file")
                     .with_default(DEFAULT_CONTROLS_FILE.to_string());
+                balance_file = opt_opt("PATH", "balance-file")
+                    .desc("file overriding the game's balance numbers")
+                    .with_default(DEFAULT_BALANCE_FILE.to_string());
+                tiles_file = opt_opt("PATH", "tiles-file")
+                    .desc("file adding or replacing tile definitions")
+                    .with_default(DEFAULT_TILES_FILE.to_string());
+                runs_file = opt_opt("PATH", "runs-file")
+                    .desc("file which records of completed runs are exported to")
+                    .with_default(DEFAULT_RUNS_FILE.to_string());
+                ghosts_file = opt_opt("PATH", "ghosts-file")
+                    .desc("file containing the paths taken in recent runs")
+                    .with_default(DEFAULT_GHOSTS_FILE.to_string());
+                level_file = opt_opt("PATH", "level-file")
+                    .desc("file where the level made in the level editor is saved")
+                    .with_default(DEFAULT_LEVEL_FILE.to_string());
                 storage_dir = opt_opt("PATH", 'd').name("storage-dir")
                     .desc("directory that will contain state")
                     .with_default(DEFAULT_NEXT_TO_EXE_STORAGE_DIR.to_string());
@@ -32,7 +54,9 @@ impl NativeCommon {
                 delete_config = flag("delete-config").desc("delete config file");
                 delete_controls = flag("delete-controls").desc("delete controls file");
                 new_game = flag("new-game").desc("start a new game, skipping the menu");
+                stress = flag("stress").desc("run a worst-case scene and print frame and tick timings");
                 omniscient = flag("omniscient").desc("enable omniscience");
+                wizard = flag("wizard").desc("enable debugging tools such as the ai overlay");
             } in {{
                 let initial_rng_seed = rng_seed.map(InitialRngSeed::U64).unwrap_or(InitialRngSeed::Random);
                 let mut file_storage = StaticStorage::new(
@@ -62,12 +86,19 @@ impl NativeCommon {
                     save_game_key: save_file,
                     config_key: config_file,
                     controls_key: controls_file,
+                    balance_key: balance_file,
+                    tiles_key: tiles_file,
+                    runs_key: runs_file,
+                    ghosts_key: ghosts_file,
+                    level_key: level_file,
                 };
                 Self {
                     initial_rng_seed,
                     storage,
                     omniscient,
                     new_game,
+                    stress,
+                    wizard,
                 }
             }}
         }
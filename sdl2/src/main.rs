@@ -1,5 +1,5 @@
 #![windows_subsystem = "windows"]
-use app::{app, AppArgs, NAME};
+use app::{app, AppArgs, NoLeaderboard, NAME};
 use chargrid_sdl2::*;
 use native::{meap, NativeCommon};
 
@@ -11,6 +11,8 @@ fn main() {
         initial_rng_seed,
         omniscient,
         new_game,
+        stress,
+        wizard,
     } = NativeCommon::parser()
         .with_help_default()
         .parse_env_or_exit();
@@ -42,5 +44,8 @@ fn main() {
         initial_rng_seed,
         omniscient,
         new_game,
+        stress,
+        wizard,
+        leaderboard: Box::new(NoLeaderboard),
     }));
 }
@@ -1,4 +1,4 @@
-use app::{app, AppArgs, AppStorage, InitialRngSeed};
+use app::{app, AppArgs, AppStorage, InitialRngSeed, NoLeaderboard};
 use chargrid_web::{Context, LoopMethod, Size};
 use general_storage_static::StaticStorage;
 use general_storage_web::LocalStorage;
@@ -7,6 +7,11 @@ use wasm_bindgen::prelude::*;
 const SAVE_KEY: &str = "save";
 const CONFIG_KEY: &str = "config";
 const CONTROLS_KEY: &str = "controls";
+const BALANCE_KEY: &str = "balance";
+const TILES_KEY: &str = "tiles";
+const RUNS_KEY: &str = "runs";
+const GHOSTS_KEY: &str = "ghosts";
+const LEVEL_KEY: &str = "level";
 
 #[wasm_bindgen(start)]
 pub fn run() -> Result<(), JsValue> {
@@ -22,10 +27,18 @@ pub fn run() -> Result<(), JsValue> {
             save_game_key: SAVE_KEY.to_string(),
             config_key: CONFIG_KEY.to_string(),
             controls_key: CONTROLS_KEY.to_string(),
+            balance_key: BALANCE_KEY.to_string(),
+            tiles_key: TILES_KEY.to_string(),
+            runs_key: RUNS_KEY.to_string(),
+            ghosts_key: GHOSTS_KEY.to_string(),
+            level_key: LEVEL_KEY.to_string(),
         },
         initial_rng_seed: InitialRngSeed::Random,
         omniscient: false,
         new_game: false,
+        stress: false,
+        wizard: false,
+        leaderboard: Box::new(NoLeaderboard),
     };
     context.run_with_loop_method(app(args), LoopMethod::SetTimeoutMs(1000 / 60));
     Ok(())
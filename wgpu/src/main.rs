@@ -1,5 +1,5 @@
 #![windows_subsystem = "windows"]
-use app::{app, AppArgs, NAME};
+use app::{app, AppArgs, NoLeaderboard, NAME};
 use chargrid_wgpu::*;
 use native::{meap, NativeCommon};
 
@@ -31,6 +31,8 @@ fn main() {
                 initial_rng_seed,
                 omniscient,
                 new_game,
+                stress,
+                wizard,
             },
         force_opengl,
     } = Args::parser().with_help_default().parse_env_or_exit();
@@ -62,5 +64,8 @@ fn main() {
         initial_rng_seed,
         omniscient,
         new_game,
+        stress,
+        wizard,
+        leaderboard: Box::new(NoLeaderboard),
     }));
 }